@@ -0,0 +1,356 @@
+//! Conversion of query results into [arrow](https://arrow.apache.org) record batches, for
+//! analytics pipelines moving results into columnar tools
+//!
+//! ```no_run
+//! use neo4rs::*;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!    let graph = Graph::new("127.0.0.1:7687", "neo4j", "neo").await.unwrap();
+//!    let mut rows = graph
+//!        .execute(query("MATCH (p:Person) RETURN p.name AS name, p.born AS born"))
+//!        .await
+//!        .unwrap();
+//!    for batch in rows.to_record_batches(8192).await.unwrap() {
+//!        println!("{} rows", batch.num_rows());
+//!    }
+//! }
+//! ```
+use crate::errors::{Error, Result};
+use crate::row::Row;
+use crate::stream::RowStream;
+use crate::types::BoltType;
+use arrow_array::{
+    ArrayRef, BinaryArray, BooleanArray, Date32Array, Float64Array, Int64Array, RecordBatch,
+    StringArray, TimestampNanosecondArray,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+/// The arrow type of a column, inferred from its values
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Null,
+    Boolean,
+    Integer,
+    Float,
+    String,
+    Bytes,
+    Date,
+    LocalDateTime,
+    DateTime,
+    /// Lists, maps, entities and the other values, as JSON strings
+    Json,
+}
+
+impl Kind {
+    fn of(value: &BoltType) -> Kind {
+        match value {
+            BoltType::Null(_) => Kind::Null,
+            BoltType::Boolean(_) => Kind::Boolean,
+            BoltType::Integer(_) => Kind::Integer,
+            BoltType::Float(_) => Kind::Float,
+            BoltType::String(_) => Kind::String,
+            BoltType::Bytes(_) => Kind::Bytes,
+            BoltType::Date(_) => Kind::Date,
+            BoltType::LocalDateTime(_) => Kind::LocalDateTime,
+            BoltType::DateTime(_) => Kind::DateTime,
+            _ => Kind::Json,
+        }
+    }
+
+    /// Integers and floats make a float column, other mixed values a JSON one
+    fn merge(self, other: Kind) -> Kind {
+        match (self, other) {
+            (kind, Kind::Null) | (Kind::Null, kind) => kind,
+            (a, b) if a == b => a,
+            (Kind::Integer, Kind::Float) | (Kind::Float, Kind::Integer) => Kind::Float,
+            _ => Kind::Json,
+        }
+    }
+
+    fn data_type(self) -> DataType {
+        match self {
+            Kind::Boolean => DataType::Boolean,
+            Kind::Integer => DataType::Int64,
+            Kind::Float => DataType::Float64,
+            Kind::Null | Kind::String | Kind::Json => DataType::Utf8,
+            Kind::Bytes => DataType::Binary,
+            Kind::Date => DataType::Date32,
+            Kind::LocalDateTime => DataType::Timestamp(TimeUnit::Nanosecond, None),
+            Kind::DateTime => DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into())),
+        }
+    }
+
+    fn from_data_type(data_type: &DataType) -> Kind {
+        match data_type {
+            DataType::Boolean => Kind::Boolean,
+            DataType::Int64 => Kind::Integer,
+            DataType::Float64 => Kind::Float,
+            DataType::Binary => Kind::Bytes,
+            DataType::Date32 => Kind::Date,
+            DataType::Timestamp(_, None) => Kind::LocalDateTime,
+            DataType::Timestamp(_, Some(_)) => Kind::DateTime,
+            _ => Kind::Json,
+        }
+    }
+}
+
+/// Infers the schema of rows of the same query, with a nullable field per column.
+///
+/// Integers, floats, booleans, strings and bytes map to `Int64`, `Float64`, `Boolean`, `Utf8`
+/// and `Binary`, dates to `Date32`, local date times to nanosecond timestamps and date times with
+/// an offset to nanosecond timestamps in UTC. Columns mixing integers and floats are `Float64`,
+/// columns holding only nulls are `Utf8` and every other value, like lists, maps, nodes or
+/// durations, is stored as a JSON string, see the `json` feature.
+pub fn infer_schema(rows: &[Row]) -> SchemaRef {
+    let keys = rows.first().map(Row::keys).unwrap_or_default();
+    let mut kinds = vec![Kind::Null; keys.len()];
+    for row in rows {
+        // columns that can't be decoded are reported by to_record_batch
+        for (kind, value) in kinds.iter_mut().zip(row.values()) {
+            if let Ok(value) = value {
+                *kind = kind.merge(Kind::of(value));
+            }
+        }
+    }
+    let fields: Vec<_> = keys
+        .iter()
+        .zip(kinds)
+        .map(|(key, kind)| Field::new(key, kind.data_type(), true))
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+/// Converts rows into a record batch of the schema, like one inferred with [`infer_schema`].
+/// Fails with [`Error::ArrowError`] when a value doesn't fit the type of its column, except for
+/// `Utf8` columns which store any value as JSON.
+pub fn to_record_batch(schema: SchemaRef, rows: &[Row]) -> Result<RecordBatch> {
+    let rows: Vec<Vec<&BoltType>> = rows
+        .iter()
+        .map(|row| row.values().collect())
+        .collect::<Result<_>>()?;
+    let columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let values: Vec<&BoltType> = rows
+                .iter()
+                .map(|row| row.get(index).copied().unwrap_or(&NULL))
+                .collect();
+            column(field, &values)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    RecordBatch::try_new(schema, columns).map_err(|e| Error::ArrowError(e.to_string()))
+}
+
+const NULL: BoltType = BoltType::Null(crate::types::BoltNull);
+
+fn column(field: &Field, values: &[&BoltType]) -> Result<ArrayRef> {
+    let mismatch = |value: &BoltType| {
+        Error::ArrowError(format!(
+            "the column {} of type {} can't hold the value {:?}",
+            field.name(),
+            field.data_type(),
+            value
+        ))
+    };
+    macro_rules! collect {
+        ($($pattern:pat => $value:expr),+) => {
+            values
+                .iter()
+                .map(|value| match value {
+                    BoltType::Null(_) => Ok(None),
+                    $($pattern => Ok(Some($value)),)+
+                    value => Err(mismatch(value)),
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+    }
+    let array: ArrayRef = match Kind::from_data_type(field.data_type()) {
+        Kind::Boolean => Arc::new(BooleanArray::from(collect!(
+            BoltType::Boolean(b) => b.value
+        ))),
+        Kind::Integer => Arc::new(Int64Array::from(collect!(
+            BoltType::Integer(i) => i.value
+        ))),
+        Kind::Float => Arc::new(Float64Array::from(collect!(
+            BoltType::Float(f) => f.value,
+            BoltType::Integer(i) => i.value as f64
+        ))),
+        Kind::Bytes => Arc::new(BinaryArray::from_opt_vec(collect!(
+            BoltType::Bytes(b) => b.value.as_ref()
+        ))),
+        Kind::Date => {
+            let days = collect!(BoltType::Date(d) => d.days());
+            let days = days
+                .into_iter()
+                .map(|day| day.map(i32::try_from).transpose())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| Error::ArrowError(e.to_string()))?;
+            Arc::new(Date32Array::from(days))
+        }
+        Kind::LocalDateTime => {
+            let nanos = collect!(BoltType::LocalDateTime(d) => d.local_nanos());
+            Arc::new(TimestampNanosecondArray::from(
+                nanos.into_iter().map(Option::flatten).collect::<Vec<_>>(),
+            ))
+        }
+        Kind::DateTime => {
+            let nanos = collect!(BoltType::DateTime(d) => d.utc_nanos());
+            Arc::new(
+                TimestampNanosecondArray::from(
+                    nanos.into_iter().map(Option::flatten).collect::<Vec<_>>(),
+                )
+                .with_timezone("UTC"),
+            )
+        }
+        Kind::Null | Kind::String | Kind::Json => {
+            let strings = values
+                .iter()
+                .map(|value| match value {
+                    BoltType::Null(_) => Ok(None),
+                    BoltType::String(s) => Ok(Some(s.as_str().to_owned())),
+                    value => serde_json::Value::try_from((*value).clone())
+                        .map(|json| Some(json.to_string())),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Arc::new(StringArray::from(strings))
+        }
+    };
+    Ok(array)
+}
+
+impl RowStream {
+    /// Fetches all the rows left in the stream and converts them into arrow record batches of
+    /// up to `batch_size` rows. The schema is inferred from the first batch, see
+    /// [`infer_schema`], and an empty stream has no batches.
+    pub async fn to_record_batches(&mut self, batch_size: usize) -> Result<Vec<RecordBatch>> {
+        let batch_size = batch_size.max(1);
+        let mut batches = Vec::new();
+        let mut schema = None;
+        let mut rows = Vec::with_capacity(batch_size);
+        loop {
+            let row = self.next().await?;
+            let done = row.is_none();
+            rows.extend(row);
+            if rows.len() == batch_size || (done && !rows.is_empty()) {
+                let schema = schema.get_or_insert_with(|| infer_schema(&rows)).clone();
+                batches.push(to_record_batch(schema, &rows)?);
+                rows.clear();
+            }
+            if done {
+                return Ok(batches);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BoltList, BoltMap, BoltNull};
+    use arrow_array::Array;
+
+    #[test]
+    fn should_convert_rows_into_record_batches() {
+        let fields: BoltList = vec![
+            "name".into(),
+            "age".into(),
+            "score".into(),
+            "tags".into(),
+            "none".into(),
+        ]
+        .into();
+        let rows = vec![
+            Row::new(
+                fields.clone(),
+                vec![
+                    "Alice".into(),
+                    42.into(),
+                    1.into(),
+                    BoltType::List(vec!["a".into()].into()),
+                    BoltType::Null(BoltNull),
+                ]
+                .into(),
+            ),
+            Row::new(
+                fields,
+                vec![
+                    BoltType::Null(BoltNull),
+                    7.into(),
+                    2.5.into(),
+                    BoltType::Map(BoltMap::default()),
+                    BoltType::Null(BoltNull),
+                ]
+                .into(),
+            ),
+        ];
+
+        let schema = infer_schema(&rows);
+        let types: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                DataType::Utf8,
+                DataType::Int64,
+                DataType::Float64,
+                DataType::Utf8,
+                DataType::Utf8
+            ]
+        );
+
+        let batch = to_record_batch(schema.clone(), &rows).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        let names = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(names.value(0), "Alice");
+        assert!(names.is_null(1));
+        let scores = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(scores.values(), &[1.0, 2.5]);
+        let tags = batch
+            .column(3)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(tags.value(0), r#"["a"]"#);
+        assert_eq!(tags.value(1), "{}");
+
+        let fields: BoltList = vec![
+            "name".into(),
+            "age".into(),
+            "score".into(),
+            "tags".into(),
+            "none".into(),
+        ]
+        .into();
+        let mismatch = Row::new(
+            fields,
+            vec![
+                "Bob".into(),
+                "old".into(),
+                1.into(),
+                BoltType::Null(BoltNull),
+                1.into(),
+            ]
+            .into(),
+        );
+        assert!(matches!(
+            to_record_batch(schema, &[mismatch]),
+            Err(Error::ArrowError(_))
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::errors::*;
+use crate::types::*;
+use async_trait::async_trait;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// The credentials connections authenticate with
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthToken {
+    /// A user name and password
+    Basic { user: String, password: String },
+    /// A token issued by an identity provider, for servers using single sign-on
+    Bearer(String),
+    /// A base64 encoded kerberos ticket
+    Kerberos(String),
+    /// Credentials of a custom authentication plugin of the server
+    Custom {
+        scheme: String,
+        principal: String,
+        credentials: String,
+        parameters: BoltMap,
+    },
+}
+
+/// Provides the credentials of expiring tokens or rotated passwords, see
+/// [`ConfigBuilder::auth_provider`]
+///
+/// The token is requested once and shared by every connection until the server reports it as
+/// expired, the next connection then requests a new one.
+///
+/// [`ConfigBuilder::auth_provider`]: crate::ConfigBuilder::auth_provider
+#[async_trait]
+pub trait AuthTokenProvider: Send + Sync {
+    async fn token(&self) -> Result<AuthToken>;
+}
+
+/// The credentials of the config, either a fixed token or the current token of a provider
+#[derive(Clone)]
+pub(crate) enum Auth {
+    Token(AuthToken),
+    Provider(Arc<ProvidedToken>),
+}
+
+pub(crate) struct ProvidedToken {
+    provider: Box<dyn AuthTokenProvider>,
+    current: Mutex<Option<AuthToken>>,
+}
+
+impl Auth {
+    pub(crate) fn provider(provider: impl AuthTokenProvider + 'static) -> Auth {
+        Auth::Provider(Arc::new(ProvidedToken {
+            provider: Box::new(provider),
+            current: Mutex::new(None),
+        }))
+    }
+
+    /// The token new connections authenticate with
+    pub(crate) async fn token(&self) -> Result<AuthToken> {
+        match self {
+            Auth::Token(token) => Ok(token.clone()),
+            Auth::Provider(provided) => {
+                let mut current = provided.current.lock().await;
+                match &*current {
+                    Some(token) => Ok(token.clone()),
+                    None => {
+                        let token = provided.provider.token().await?;
+                        *current = Some(token.clone());
+                        Ok(token)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drops `token` once the server reported it as expired, so the provider is asked for a new
+    /// one. Tokens that were already replaced are ignored.
+    pub(crate) async fn expire(&self, token: &AuthToken) {
+        if let Auth::Provider(provided) = self {
+            let mut current = provided.current.lock().await;
+            if current.as_ref() == Some(token) {
+                *current = None;
+            }
+        }
+    }
+}
+
+impl PartialEq for Auth {
+    fn eq(&self, other: &Auth) -> bool {
+        match (self, other) {
+            (Auth::Token(a), Auth::Token(b)) => a == b,
+            (Auth::Provider(a), Auth::Provider(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Auth::Token(token) => f.debug_tuple("Token").field(token).finish(),
+            Auth::Provider(_) => f.write_str("Provider"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Counter(AtomicUsize);
+
+    #[async_trait]
+    impl AuthTokenProvider for Counter {
+        async fn token(&self) -> Result<AuthToken> {
+            let count = self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(AuthToken::Bearer(format!("token-{}", count)))
+        }
+    }
+
+    #[tokio::test]
+    async fn should_request_a_new_token_once_expired() {
+        let auth = Auth::provider(Counter(AtomicUsize::new(0)));
+
+        let first = auth.token().await.unwrap();
+        assert_eq!(auth.token().await.unwrap(), first);
+
+        auth.expire(&first).await;
+        let second = auth.token().await.unwrap();
+        auth.expire(&first).await;
+
+        assert_eq!(second, AuthToken::Bearer("token-1".into()));
+        assert_eq!(auth.token().await.unwrap(), second);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::pool::PoolStatus;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A cluster member able to serve a unit of work, along with the statistics of its connection
+/// pool, see [`LoadBalancingStrategy`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member<'a> {
+    /// The address of the member, as listed by the routing table
+    pub address: &'a str,
+    /// The statistics of the connection pool of the member
+    pub status: PoolStatus,
+}
+
+/// Picks the cluster member connections are acquired from among the readers or the writers of
+/// the routing table, see
+/// [`ConfigBuilder::load_balancing`](crate::ConfigBuilder::load_balancing)
+///
+/// The driver provides [`RoundRobin`], which is the default, [`LeastConnected`] and
+/// [`Random`]. Members left out by the circuit breaker are not among the candidates.
+pub trait LoadBalancingStrategy: Send + Sync {
+    /// The index of the member to pick among `members`, which is never empty
+    fn select(&self, members: &[Member<'_>]) -> usize;
+}
+
+/// Picks the members in turn
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    next: AtomicUsize,
+}
+
+impl LoadBalancingStrategy for RoundRobin {
+    fn select(&self, members: &[Member<'_>]) -> usize {
+        self.next.fetch_add(1, Ordering::Relaxed) % members.len()
+    }
+}
+
+/// Picks the member with the fewest connections in use, so slow members get less work
+#[derive(Debug, Default)]
+pub struct LeastConnected;
+
+impl LoadBalancingStrategy for LeastConnected {
+    fn select(&self, members: &[Member<'_>]) -> usize {
+        members
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, member)| member.status.in_use)
+            .map_or(0, |(index, _)| index)
+    }
+}
+
+/// Picks a member at random
+#[derive(Debug, Default)]
+pub struct Random;
+
+impl LoadBalancingStrategy for Random {
+    fn select(&self, members: &[Member<'_>]) -> usize {
+        // every RandomState is seeded with new keys
+        let random = RandomState::new().build_hasher().finish();
+        (random % members.len() as u64) as usize
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Balancer(pub(crate) Arc<dyn LoadBalancingStrategy>);
+
+impl Balancer {
+    /// Picks one of `members`, an index out of bounds picks the first one
+    pub(crate) fn select(&self, members: &[Member<'_>]) -> usize {
+        match self.0.select(members) {
+            index if index < members.len() => index,
+            _ => 0,
+        }
+    }
+}
+
+impl Default for Balancer {
+    fn default() -> Self {
+        Balancer(Arc::new(RoundRobin::default()))
+    }
+}
+
+impl fmt::Debug for Balancer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Balancer")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(in_use: &[usize]) -> Vec<Member<'static>> {
+        in_use
+            .iter()
+            .map(|&in_use| Member {
+                address: "reader:7687",
+                status: PoolStatus {
+                    in_use,
+                    ..PoolStatus::default()
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_pick_members_in_turn() {
+        let members = members(&[0, 0, 0]);
+        let strategy = RoundRobin::default();
+
+        let picked: Vec<_> = (0..4).map(|_| strategy.select(&members)).collect();
+
+        assert_eq!(picked, vec![0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn should_pick_the_least_connected_member() {
+        assert_eq!(LeastConnected.select(&members(&[4, 1, 3])), 1);
+        assert_eq!(LeastConnected.select(&members(&[2, 2])), 0);
+    }
+
+    #[test]
+    fn should_pick_random_members_among_the_candidates() {
+        let members = members(&[0, 0, 0]);
+
+        assert!((0..20).all(|_| Random.select(&members) < 3));
+    }
+
+    #[test]
+    fn should_ignore_indexes_out_of_bounds() {
+        struct Broken;
+        impl LoadBalancingStrategy for Broken {
+            fn select(&self, _: &[Member<'_>]) -> usize {
+                7
+            }
+        }
+
+        assert_eq!(Balancer(Arc::new(Broken)).select(&members(&[0, 0])), 0);
+    }
+}
@@ -0,0 +1,190 @@
+//! A synchronous api for applications that don't use async/await, like command line tools
+//!
+//! [`Graph`] owns a runtime and blocks the calling thread until every operation is done,
+//! so it must not be used from within an async runtime, where blocking panics.
+use crate::config::{config, Config};
+use crate::connection::ServerInfo;
+use crate::errors::Result;
+use crate::query::Query;
+use crate::row::Row;
+use crate::rt::Runtime;
+use crate::stream::EagerResult;
+use crate::summary::ResultSummary;
+use crate::txn::{TxOptions, TxState};
+use crate::{Bookmark, PoolStatus};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A blocking neo4j database abstraction, see [`crate::Graph`]
+///
+/// Clones share the same runtime and connection pool.
+#[derive(Clone)]
+pub struct Graph {
+    runtime: Arc<Runtime>,
+    graph: crate::Graph,
+}
+
+impl Graph {
+    /// Connects to the database with configurations provided, you can build a config using
+    /// [`config`]
+    pub fn connect(config: Config) -> Result<Self> {
+        let runtime = Arc::new(Runtime::new()?);
+        let graph = runtime.block_on(crate::Graph::connect(config))?;
+        Ok(Graph { runtime, graph })
+    }
+
+    /// Connects to the database with default configurations
+    pub fn new(uri: &str, user: &str, password: &str) -> Result<Self> {
+        let config = config().uri(uri).user(user).password(password).build()?;
+        Self::connect(config)
+    }
+
+    /// Checks that a connection to the server can be opened and authenticated, see
+    /// [`crate::Graph::verify_connectivity`]
+    pub fn verify_connectivity(&self) -> Result<ServerInfo> {
+        self.runtime.block_on(self.graph.verify_connectivity())
+    }
+
+    /// The time the server took to answer a RESET, see [`crate::Graph::ping`]
+    pub fn ping(&self) -> Result<Duration> {
+        self.runtime.block_on(self.graph.ping())
+    }
+
+    /// The server a connection of the pool is established with, see [`crate::Graph::server_info`]
+    pub fn server_info(&self) -> Result<ServerInfo> {
+        self.runtime.block_on(self.graph.server_info())
+    }
+
+    /// Closes every connection of the pool, see [`crate::Graph::close`]
+    pub fn close(&self) {
+        self.runtime.block_on(self.graph.close())
+    }
+
+    /// Statistics of the connection pool
+    pub fn pool_status(&self) -> PoolStatus {
+        self.graph.pool_status()
+    }
+
+    /// Starts a new transaction, see [`crate::Graph::start_txn`]
+    pub fn start_txn(&self) -> Result<Txn> {
+        self.start_txn_with(TxOptions::default())
+    }
+
+    /// Starts a new transaction with a timeout, metadata, in read mode or impersonating another
+    /// user, see [`TxOptions`]
+    pub fn start_txn_with(&self, options: TxOptions) -> Result<Txn> {
+        let txn = self.runtime.block_on(self.graph.start_txn_with(options))?;
+        Ok(Txn {
+            runtime: self.runtime.clone(),
+            txn,
+        })
+    }
+
+    /// Runs a query and discards its rows, see [`crate::Graph::run`]
+    pub fn run(&self, q: Query) -> Result<()> {
+        self.runtime.block_on(self.graph.run(q))
+    }
+
+    /// Runs independent auto-commit queries over a single connection, returning the outcome of
+    /// every query, see [`crate::Graph::run_all`]
+    pub fn run_all(&self, queries: Vec<Query>) -> Result<Vec<Result<()>>> {
+        self.runtime.block_on(self.graph.run_all(queries))
+    }
+
+    /// Executes a query and returns its [`RowStream`], see [`crate::Graph::execute`]
+    pub fn execute(&self, q: Query) -> Result<RowStream> {
+        let rows = self.runtime.block_on(self.graph.execute(q))?;
+        Ok(RowStream {
+            runtime: self.runtime.clone(),
+            rows,
+        })
+    }
+
+    /// Executes a query and fetches all of its rows at once, see [`crate::Graph::execute_eager`]
+    pub fn execute_eager(&self, q: Query) -> Result<EagerResult> {
+        self.runtime.block_on(self.graph.execute_eager(q))
+    }
+}
+
+/// A blocking transaction, see [`crate::Txn`]
+pub struct Txn {
+    runtime: Arc<Runtime>,
+    txn: crate::Txn,
+}
+
+impl Txn {
+    /// Runs a single query and discards the stream
+    pub fn run(&self, q: Query) -> Result<()> {
+        self.runtime.block_on(self.txn.run(q))
+    }
+
+    /// Executes a query and returns a [`RowStream`]
+    pub fn execute(&self, q: Query) -> Result<RowStream> {
+        let rows = self.runtime.block_on(self.txn.execute(q))?;
+        Ok(RowStream {
+            runtime: self.runtime.clone(),
+            rows,
+        })
+    }
+
+    /// Where the transaction stands, see [`crate::Txn::state`]
+    pub fn state(&self) -> TxState {
+        self.txn.state()
+    }
+
+    /// Whether queries can still run in the transaction
+    pub fn is_open(&self) -> bool {
+        self.txn.is_open()
+    }
+
+    /// Commits the transaction in progress, see [`crate::Txn::commit`]
+    pub fn commit(self) -> Result<Option<Bookmark>> {
+        self.runtime.block_on(self.txn.commit())
+    }
+
+    /// rollback/abort the current transaction
+    pub fn rollback(self) -> Result<()> {
+        self.runtime.block_on(self.txn.rollback())
+    }
+}
+
+/// The rows of a query, fetched in batches as they are iterated, see [`crate::RowStream`]
+pub struct RowStream {
+    runtime: Arc<Runtime>,
+    rows: crate::RowStream,
+}
+
+impl RowStream {
+    /// The bookmark of an auto-commit query once all rows were consumed
+    pub fn bookmark(&self) -> Option<&Bookmark> {
+        self.rows.bookmark()
+    }
+
+    /// The [`ResultSummary`] of the query, available once all rows were consumed
+    pub fn summary(&self) -> Option<&ResultSummary> {
+        self.rows.summary()
+    }
+}
+
+/// Yields the rows one by one, a new batch is fetched from the server once the buffered rows are
+/// consumed
+impl Iterator for RowStream {
+    type Item = Result<Row>;
+
+    fn next(&mut self) -> Option<Result<Row>> {
+        self.runtime.block_on(self.rows.next()).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_block_until_the_operation_is_done() {
+        let graph = Graph::new("bolt://127.0.0.1:1", "neo4j", "neo").unwrap();
+
+        assert!(graph.verify_connectivity().is_err());
+        assert_eq!(graph.pool_status().in_use, 0);
+    }
+}
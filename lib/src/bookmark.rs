@@ -0,0 +1,175 @@
+use crate::types::*;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Identifies the state of the database after a transaction was committed.
+///
+/// Passing the bookmark of a transaction to a later transaction or query, see
+/// [`Graph::start_txn_with_bookmarks`] and [`Query::bookmarks`], ensures that it observes the
+/// changes of the earlier one, even when both run on different connections or cluster members.
+///
+/// [`Graph::start_txn_with_bookmarks`]: crate::Graph::start_txn_with_bookmarks
+/// [`Query::bookmarks`]: crate::Query::bookmarks
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bookmark(String);
+
+impl Bookmark {
+    pub fn new(value: &str) -> Self {
+        Bookmark(value.to_owned())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Bookmark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for Bookmark {
+    fn from(value: String) -> Self {
+        Bookmark(value)
+    }
+}
+
+impl From<&Bookmark> for BoltType {
+    fn from(bookmark: &Bookmark) -> Self {
+        bookmark.as_str().into()
+    }
+}
+
+/// Collects the bookmarks of the transactions and auto-commit queries committed through a
+/// [`Graph`] or a [`Session`] and passes them to the following ones, so every unit of work
+/// observes the changes of the previous ones without passing bookmarks around, see
+/// [`ConfigBuilder::bookmark_manager`] and [`Session::bookmark_manager`]
+///
+/// Bookmarks passed explicitly to a transaction or a query take precedence over the ones of the
+/// manager. [`InMemoryBookmarkManager`] keeps the bookmarks in memory, managers sharing the
+/// bookmarks between processes can be implemented on a shared store.
+///
+/// [`Graph`]: crate::Graph
+/// [`Session`]: crate::Session
+/// [`ConfigBuilder::bookmark_manager`]: crate::ConfigBuilder::bookmark_manager
+/// [`Session::bookmark_manager`]: crate::Session::bookmark_manager
+pub trait BookmarkManager: Send + Sync {
+    /// The bookmarks the next transaction or auto-commit query waits for
+    fn bookmarks(&self) -> Vec<Bookmark>;
+
+    /// Records the `bookmark` of a transaction or query which began with the `previous`
+    /// bookmarks, which it supersedes
+    fn update(&self, previous: &[Bookmark], bookmark: Bookmark);
+}
+
+/// Shares a manager, like between several sessions
+impl<T: BookmarkManager + ?Sized> BookmarkManager for Arc<T> {
+    fn bookmarks(&self) -> Vec<Bookmark> {
+        (**self).bookmarks()
+    }
+
+    fn update(&self, previous: &[Bookmark], bookmark: Bookmark) {
+        (**self).update(previous, bookmark)
+    }
+}
+
+/// A [`BookmarkManager`] keeping the latest bookmarks in memory
+#[derive(Debug, Default)]
+pub struct InMemoryBookmarkManager {
+    bookmarks: Mutex<Vec<Bookmark>>,
+}
+
+impl InMemoryBookmarkManager {
+    /// A manager starting with the `initial` bookmarks, like the ones of another process
+    pub fn new(initial: &[Bookmark]) -> Self {
+        InMemoryBookmarkManager {
+            bookmarks: Mutex::new(initial.to_vec()),
+        }
+    }
+}
+
+impl BookmarkManager for InMemoryBookmarkManager {
+    fn bookmarks(&self) -> Vec<Bookmark> {
+        self.bookmarks.lock().unwrap().clone()
+    }
+
+    fn update(&self, previous: &[Bookmark], bookmark: Bookmark) {
+        let mut bookmarks = self.bookmarks.lock().unwrap();
+        bookmarks.retain(|b| !previous.contains(b));
+        if !bookmarks.contains(&bookmark) {
+            bookmarks.push(bookmark);
+        }
+    }
+}
+
+/// The bookmark manager of the config
+#[derive(Clone)]
+pub(crate) struct ManagedBookmarks(pub(crate) Arc<dyn BookmarkManager>);
+
+impl ManagedBookmarks {
+    /// The `bookmarks` given explicitly, or the ones of the manager without any
+    pub(crate) fn or_managed(
+        manager: Option<&ManagedBookmarks>,
+        bookmarks: &[Bookmark],
+    ) -> Vec<Bookmark> {
+        match manager {
+            Some(manager) if bookmarks.is_empty() => manager.0.bookmarks(),
+            _ => bookmarks.to_vec(),
+        }
+    }
+}
+
+impl fmt::Debug for ManagedBookmarks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ManagedBookmarks")
+    }
+}
+
+/// The `bookmarks` list of the RUN and BEGIN extras
+pub(crate) fn to_bolt_list(bookmarks: &[Bookmark]) -> BoltList {
+    bookmarks
+        .iter()
+        .map(BoltType::from)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_bookmarks_to_bolt_list() {
+        let bookmarks = vec![Bookmark::new("FB:first"), Bookmark::new("FB:second")];
+
+        let list = to_bolt_list(&bookmarks);
+
+        assert_eq!(list.len(), 2);
+        let first: BoltType = "FB:first".into();
+        let second: BoltType = "FB:second".into();
+        assert_eq!(list.get(0), Some(&first));
+        assert_eq!(list.get(1), Some(&second));
+    }
+
+    #[test]
+    fn should_supersede_the_previous_bookmarks() {
+        let (first, second) = (Bookmark::new("FB:first"), Bookmark::new("FB:second"));
+        let manager = InMemoryBookmarkManager::new(std::slice::from_ref(&first));
+
+        manager.update(&[], second.clone());
+        assert_eq!(manager.bookmarks(), vec![first.clone(), second]);
+
+        let previous = manager.bookmarks();
+        manager.update(&previous, Bookmark::new("FB:third"));
+        assert_eq!(manager.bookmarks(), vec![Bookmark::new("FB:third")]);
+
+        let managed = ManagedBookmarks(Arc::new(manager));
+        assert_eq!(
+            ManagedBookmarks::or_managed(Some(&managed), std::slice::from_ref(&first)),
+            vec![first]
+        );
+        assert_eq!(ManagedBookmarks::or_managed(Some(&managed), &[]).len(), 1);
+        assert!(ManagedBookmarks::or_managed(None, &[]).is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+use log::warn;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Keeps cluster members which failed to be connected to several times in a row out of the
+/// selection for a while, so a dead member doesn't delay every query routed to it by a
+/// connection timeout.
+///
+/// The circuit of a member opens once `threshold` connections to it failed in a row. After the
+/// `cooldown` it is half open: a single connection is attempted, which closes the circuit when
+/// it succeeds and opens it again when it fails.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    threshold: usize,
+    cooldown: Duration,
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+#[derive(Debug, Default)]
+struct Circuit {
+    failures: usize,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+impl Circuit {
+    fn is_open(&self, cooldown: Duration) -> bool {
+        match self.opened_at {
+            Some(opened_at) => self.probing || opened_at.elapsed() < cooldown,
+            None => false,
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// A `threshold` of 0 never opens a circuit
+    pub(crate) fn new(threshold: usize, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            threshold,
+            cooldown,
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The `servers` whose circuit is not open, or all of them when every circuit is, so the
+    /// work is still attempted
+    pub(crate) fn available<'a>(&self, servers: &'a [String]) -> Vec<&'a String> {
+        let circuits = self.circuits.lock().unwrap();
+        let available: Vec<_> = servers
+            .iter()
+            .filter(|server| {
+                circuits
+                    .get(*server)
+                    .is_none_or(|circuit| !circuit.is_open(self.cooldown))
+            })
+            .collect();
+        if available.is_empty() {
+            servers.iter().collect()
+        } else {
+            available
+        }
+    }
+
+    /// Records that a connection to `address` is attempted, which probes a half open circuit
+    /// until the connection succeeded or failed
+    pub(crate) fn attempt(&self, address: &str) {
+        if let Some(circuit) = self.circuits.lock().unwrap().get_mut(address) {
+            if circuit.opened_at.is_some() {
+                circuit.probing = true;
+            }
+        }
+    }
+
+    /// Closes the circuit of `address`
+    pub(crate) fn succeeded(&self, address: &str) {
+        self.circuits.lock().unwrap().remove(address);
+    }
+
+    /// Counts a failed connection to `address`, opening its circuit once there were too many
+    pub(crate) fn failed(&self, address: &str) {
+        if self.threshold == 0 {
+            return;
+        }
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits.entry(address.to_owned()).or_default();
+        circuit.failures += 1;
+        circuit.probing = false;
+        if circuit.failures >= self.threshold {
+            if circuit.opened_at.is_none() {
+                warn!(
+                    "not connecting to {} for {:?} after {} failed connections",
+                    address, self.cooldown, circuit.failures
+                );
+            }
+            circuit.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn servers() -> Vec<String> {
+        vec!["reader1:7687".to_owned(), "reader2:7687".to_owned()]
+    }
+
+    #[test]
+    fn should_open_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        let servers = servers();
+
+        breaker.failed("reader1:7687");
+        assert_eq!(breaker.available(&servers).len(), 2);
+        breaker.failed("reader1:7687");
+        assert_eq!(breaker.available(&servers), vec!["reader2:7687"]);
+
+        breaker.failed("reader2:7687");
+        breaker.failed("reader2:7687");
+        assert_eq!(breaker.available(&servers).len(), 2);
+        breaker.succeeded("reader1:7687");
+        assert_eq!(breaker.available(&servers), vec!["reader1:7687"]);
+    }
+
+    #[test]
+    fn should_probe_half_open_circuits_once() {
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+        let servers = servers();
+        breaker.failed("reader1:7687");
+
+        assert_eq!(breaker.available(&servers).len(), 2);
+        breaker.attempt("reader1:7687");
+        assert_eq!(breaker.available(&servers), vec!["reader2:7687"]);
+        breaker.failed("reader1:7687");
+        assert_eq!(breaker.available(&servers).len(), 2);
+        breaker.attempt("reader1:7687");
+        breaker.succeeded("reader1:7687");
+        breaker.attempt("reader1:7687");
+        assert_eq!(breaker.available(&servers).len(), 2);
+    }
+
+    #[test]
+    fn should_never_open_without_threshold() {
+        let breaker = CircuitBreaker::new(0, Duration::from_secs(60));
+        let servers = servers();
+
+        for _ in 0..10 {
+            breaker.failed("reader1:7687");
+        }
+
+        assert_eq!(breaker.available(&servers).len(), 2);
+    }
+}
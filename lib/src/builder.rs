@@ -0,0 +1,611 @@
+//! Builds cypher queries clause by clause, with every value bound as a parameter
+//!
+//! Patterns and conditions are built from [`node`], [`rel`] and [`prop`], their values are never
+//! inserted into the cypher but passed as parameters, and labels, types and property keys are
+//! quoted when needed. Conditions compose, so filters can be added depending on the request:
+//!
+//! ```
+//! use neo4rs::builder::*;
+//!
+//! fn search(name: Option<&str>, min_age: Option<i64>) -> neo4rs::Query {
+//!     let mut filter = Condition::all();
+//!     if let Some(name) = name {
+//!         filter = filter.and(prop("p", "name").starts_with(name));
+//!     }
+//!     if let Some(min_age) = min_age {
+//!         filter = filter.and(prop("p", "age").gte(min_age));
+//!     }
+//!     QueryBuilder::new()
+//!         .match_(node("p").label("Person"))
+//!         .where_(filter)
+//!         .return_(&["p"])
+//!         .order_by("p.name")
+//!         .limit(10)
+//!         .build()
+//! }
+//! ```
+//!
+//! Expressions like the items of `RETURN` or `ORDER BY` are inserted as they are, they must not
+//! be built from untrusted input.
+use crate::query::Query;
+use crate::types::BoltType;
+
+/// A node of a pattern, like `(p:Person {name: $p0})`
+#[derive(Debug, Clone, Default)]
+pub struct Node {
+    var: Option<String>,
+    labels: Vec<String>,
+    props: Vec<(String, BoltType)>,
+}
+
+/// A node bound to the variable `var`
+pub fn node(var: &str) -> Node {
+    Node {
+        var: Some(var.to_owned()),
+        ..Node::default()
+    }
+}
+
+impl Node {
+    /// A node without a variable
+    pub fn anonymous() -> Self {
+        Node::default()
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.labels.push(label.to_owned());
+        self
+    }
+
+    /// Only matches nodes whose property `key` equals `value`, or sets it when creating the node
+    pub fn prop(mut self, key: &str, value: impl Into<BoltType>) -> Self {
+        self.props.push((key.to_owned(), value.into()));
+        self
+    }
+
+    /// A pattern from this node to `node` through `rel`, like `(a)-[r]->(b)`
+    pub fn outgoing(self, rel: Rel, node: Node) -> Pattern {
+        Pattern::start(self).outgoing(rel, node)
+    }
+
+    /// A pattern from `node` to this node through `rel`, like `(a)<-[r]-(b)`
+    pub fn incoming(self, rel: Rel, node: Node) -> Pattern {
+        Pattern::start(self).incoming(rel, node)
+    }
+
+    /// A pattern between this node and `node` in either direction, like `(a)-[r]-(b)`
+    pub fn related(self, rel: Rel, node: Node) -> Pattern {
+        Pattern::start(self).related(rel, node)
+    }
+
+    fn render(&self, params: &mut Params) -> String {
+        let mut rendered = String::from("(");
+        if let Some(var) = &self.var {
+            rendered.push_str(&name(var));
+        }
+        for label in &self.labels {
+            rendered.push(':');
+            rendered.push_str(&name(label));
+        }
+        rendered.push_str(&params.properties(&self.props));
+        rendered.push(')');
+        rendered
+    }
+}
+
+/// A relationship of a pattern, like `[r:KNOWS {since: $p0}]`
+#[derive(Debug, Clone, Default)]
+pub struct Rel {
+    var: Option<String>,
+    types: Vec<String>,
+    props: Vec<(String, BoltType)>,
+}
+
+/// A relationship bound to the variable `var`
+pub fn rel(var: &str) -> Rel {
+    Rel {
+        var: Some(var.to_owned()),
+        ..Rel::default()
+    }
+}
+
+impl Rel {
+    /// A relationship without a variable
+    pub fn anonymous() -> Self {
+        Rel::default()
+    }
+
+    /// Adds a type the relationship may have, several types match any of them
+    pub fn rel_type(mut self, rel_type: &str) -> Self {
+        self.types.push(rel_type.to_owned());
+        self
+    }
+
+    pub fn prop(mut self, key: &str, value: impl Into<BoltType>) -> Self {
+        self.props.push((key.to_owned(), value.into()));
+        self
+    }
+
+    fn render(&self, params: &mut Params) -> String {
+        let mut rendered = String::from("[");
+        if let Some(var) = &self.var {
+            rendered.push_str(&name(var));
+        }
+        let types: Vec<_> = self.types.iter().map(|t| name(t)).collect();
+        if !types.is_empty() {
+            rendered.push(':');
+            rendered.push_str(&types.join("|"));
+        }
+        rendered.push_str(&params.properties(&self.props));
+        rendered.push(']');
+        rendered
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    Outgoing,
+    Incoming,
+    Either,
+}
+
+/// A path of nodes connected by relationships
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    start: Node,
+    steps: Vec<(Direction, Rel, Node)>,
+}
+
+impl From<Node> for Pattern {
+    fn from(start: Node) -> Self {
+        Pattern::start(start)
+    }
+}
+
+impl Pattern {
+    /// A pattern of a single node, which further relationships start from
+    pub fn start(node: Node) -> Self {
+        Pattern {
+            start: node,
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn outgoing(mut self, rel: Rel, node: Node) -> Self {
+        self.steps.push((Direction::Outgoing, rel, node));
+        self
+    }
+
+    pub fn incoming(mut self, rel: Rel, node: Node) -> Self {
+        self.steps.push((Direction::Incoming, rel, node));
+        self
+    }
+
+    pub fn related(mut self, rel: Rel, node: Node) -> Self {
+        self.steps.push((Direction::Either, rel, node));
+        self
+    }
+
+    fn render(&self, params: &mut Params) -> String {
+        let mut rendered = self.start.render(params);
+        for (direction, rel, node) in &self.steps {
+            let rel = rel.render(params);
+            match direction {
+                Direction::Outgoing => rendered.push_str(&format!("-{}->", rel)),
+                Direction::Incoming => rendered.push_str(&format!("<-{}-", rel)),
+                Direction::Either => rendered.push_str(&format!("-{}-", rel)),
+            }
+            rendered.push_str(&node.render(params));
+        }
+        rendered
+    }
+}
+
+/// The property `key` of the node or relationship bound to `var`, to build a [`Condition`]
+pub fn prop(var: &str, key: &str) -> Property {
+    Property(format!("{}.{}", name(var), name(key)))
+}
+
+/// The element id of the node or relationship bound to `var`, to match entities by the
+/// [`Node::element_id`](crate::Node::element_id) of a previous result
+pub fn element_id(var: &str) -> Property {
+    Property(format!("elementId({})", name(var)))
+}
+
+/// A property compared in a [`Condition`], see [`prop`]
+#[derive(Debug, Clone)]
+pub struct Property(String);
+
+impl Property {
+    fn compare(self, operator: &'static str, value: impl Into<BoltType>) -> Condition {
+        Condition(Predicate::Compare(self.0, operator, Box::new(value.into())))
+    }
+
+    pub fn eq(self, value: impl Into<BoltType>) -> Condition {
+        self.compare("=", value)
+    }
+
+    pub fn ne(self, value: impl Into<BoltType>) -> Condition {
+        self.compare("<>", value)
+    }
+
+    pub fn lt(self, value: impl Into<BoltType>) -> Condition {
+        self.compare("<", value)
+    }
+
+    pub fn lte(self, value: impl Into<BoltType>) -> Condition {
+        self.compare("<=", value)
+    }
+
+    pub fn gt(self, value: impl Into<BoltType>) -> Condition {
+        self.compare(">", value)
+    }
+
+    pub fn gte(self, value: impl Into<BoltType>) -> Condition {
+        self.compare(">=", value)
+    }
+
+    pub fn contains(self, value: impl Into<BoltType>) -> Condition {
+        self.compare("CONTAINS", value)
+    }
+
+    pub fn starts_with(self, value: impl Into<BoltType>) -> Condition {
+        self.compare("STARTS WITH", value)
+    }
+
+    pub fn ends_with(self, value: impl Into<BoltType>) -> Condition {
+        self.compare("ENDS WITH", value)
+    }
+
+    /// The property equals one of `values`
+    pub fn is_in<T: Into<BoltType>>(self, values: Vec<T>) -> Condition {
+        self.compare("IN", values)
+    }
+
+    pub fn is_null(self) -> Condition {
+        Condition(Predicate::Raw(format!("{} IS NULL", self.0)))
+    }
+
+    pub fn is_not_null(self) -> Condition {
+        Condition(Predicate::Raw(format!("{} IS NOT NULL", self.0)))
+    }
+}
+
+/// A predicate of a `WHERE` clause, combined with [`Condition::and`] and [`Condition::or`]
+#[derive(Debug, Clone)]
+pub struct Condition(Predicate);
+
+#[derive(Debug, Clone)]
+enum Predicate {
+    Compare(String, &'static str, Box<BoltType>),
+    Raw(String),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// A condition holding when all of the conditions added with [`Condition::and`] hold, and
+    /// always when none were added
+    pub fn all() -> Self {
+        Condition(Predicate::All(Vec::new()))
+    }
+
+    /// A condition holding when any of the conditions added with [`Condition::or`] holds, and
+    /// always when none were added
+    pub fn any() -> Self {
+        Condition(Predicate::Any(Vec::new()))
+    }
+
+    /// A cypher predicate inserted as is, values should be passed as parameters of the query
+    pub fn raw(predicate: &str) -> Self {
+        Condition(Predicate::Raw(predicate.to_owned()))
+    }
+
+    pub fn and(self, other: Condition) -> Self {
+        match self.0 {
+            Predicate::All(mut conditions) => {
+                conditions.push(other);
+                Condition(Predicate::All(conditions))
+            }
+            predicate => Condition(Predicate::All(vec![Condition(predicate), other])),
+        }
+    }
+
+    pub fn or(self, other: Condition) -> Self {
+        match self.0 {
+            Predicate::Any(mut conditions) => {
+                conditions.push(other);
+                Condition(Predicate::Any(conditions))
+            }
+            predicate => Condition(Predicate::Any(vec![Condition(predicate), other])),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Condition(Predicate::Not(Box::new(self)))
+    }
+
+    fn holds_always(&self) -> bool {
+        match &self.0 {
+            Predicate::All(conditions) => conditions.iter().all(Condition::holds_always),
+            Predicate::Any(conditions) => {
+                conditions.is_empty() || conditions.iter().any(Condition::holds_always)
+            }
+            _ => false,
+        }
+    }
+
+    /// The predicate, `None` for a condition that always holds
+    fn render(&self, params: &mut Params) -> Option<String> {
+        if self.holds_always() {
+            return None;
+        }
+        match &self.0 {
+            Predicate::Compare(property, operator, value) => Some(format!(
+                "{} {} {}",
+                property,
+                operator,
+                params.bind(BoltType::clone(value))
+            )),
+            Predicate::Raw(predicate) => Some(predicate.clone()),
+            Predicate::All(conditions) => {
+                let predicates = conditions.iter().filter_map(|c| c.render(params)).collect();
+                join(predicates, " AND ")
+            }
+            Predicate::Any(conditions) => {
+                let predicates = conditions.iter().filter_map(|c| c.render(params)).collect();
+                join(predicates, " OR ")
+            }
+            Predicate::Not(condition) => Some(match condition.render(params) {
+                Some(predicate) => format!("NOT ({})", predicate),
+                None => "false".to_owned(),
+            }),
+        }
+    }
+}
+
+fn join(predicates: Vec<String>, separator: &str) -> Option<String> {
+    match predicates.len() {
+        0 => None,
+        1 => predicates.into_iter().next(),
+        _ => Some(format!("({})", predicates.join(separator))),
+    }
+}
+
+/// The parameters bound while a query is built, named `p0`, `p1` and so on
+#[derive(Debug, Default)]
+struct Params {
+    values: Vec<(String, BoltType)>,
+}
+
+impl Params {
+    fn bind(&mut self, value: BoltType) -> String {
+        let key = format!("p{}", self.values.len());
+        let placeholder = format!("${}", key);
+        self.values.push((key, value));
+        placeholder
+    }
+
+    fn properties(&mut self, props: &[(String, BoltType)]) -> String {
+        if props.is_empty() {
+            return String::new();
+        }
+        let props: Vec<_> = props
+            .iter()
+            .map(|(key, value)| format!("{}: {}", name(key), self.bind(value.clone())))
+            .collect();
+        format!(" {{{}}}", props.join(", "))
+    }
+}
+
+/// Builds a [`Query`] clause by clause, see the [module](self) documentation
+#[derive(Debug, Default)]
+pub struct QueryBuilder {
+    clauses: Vec<String>,
+    params: Params,
+    /// Whether the last clause is a `WHERE`, which further conditions are added to
+    filtering: bool,
+}
+
+impl QueryBuilder {
+    pub fn new() -> Self {
+        QueryBuilder::default()
+    }
+
+    fn clause(mut self, clause: String) -> Self {
+        self.clauses.push(clause);
+        self.filtering = false;
+        self
+    }
+
+    fn patterns(&mut self, keyword: &str, pattern: Pattern) -> String {
+        format!("{} {}", keyword, pattern.render(&mut self.params))
+    }
+
+    pub fn match_(mut self, pattern: impl Into<Pattern>) -> Self {
+        let clause = self.patterns("MATCH", pattern.into());
+        self.clause(clause)
+    }
+
+    pub fn optional_match(mut self, pattern: impl Into<Pattern>) -> Self {
+        let clause = self.patterns("OPTIONAL MATCH", pattern.into());
+        self.clause(clause)
+    }
+
+    pub fn create(mut self, pattern: impl Into<Pattern>) -> Self {
+        let clause = self.patterns("CREATE", pattern.into());
+        self.clause(clause)
+    }
+
+    pub fn merge(mut self, pattern: impl Into<Pattern>) -> Self {
+        let clause = self.patterns("MERGE", pattern.into());
+        self.clause(clause)
+    }
+
+    /// Filters the preceding `MATCH` or `WITH`, conditions of consecutive calls must all hold.
+    /// A condition that always holds adds nothing.
+    pub fn where_(mut self, condition: Condition) -> Self {
+        let predicate = match condition.render(&mut self.params) {
+            Some(predicate) => predicate,
+            None => return self,
+        };
+        match self.clauses.last_mut() {
+            Some(last) if self.filtering => {
+                last.push_str(" AND ");
+                last.push_str(&predicate);
+                self
+            }
+            _ => {
+                let mut builder = self.clause(format!("WHERE {}", predicate));
+                builder.filtering = true;
+                builder
+            }
+        }
+    }
+
+    /// Sets the property `key` of `var` to `value`
+    pub fn set(mut self, var: &str, key: &str, value: impl Into<BoltType>) -> Self {
+        let placeholder = self.params.bind(value.into());
+        let property = prop(var, key).0;
+        match self.clauses.last_mut() {
+            Some(last) if last.starts_with("SET ") => {
+                last.push_str(&format!(", {} = {}", property, placeholder));
+                self
+            }
+            _ => self.clause(format!("SET {} = {}", property, placeholder)),
+        }
+    }
+
+    pub fn delete(self, vars: &[&str]) -> Self {
+        self.clause(format!("DELETE {}", names(vars)))
+    }
+
+    pub fn detach_delete(self, vars: &[&str]) -> Self {
+        self.clause(format!("DETACH DELETE {}", names(vars)))
+    }
+
+    pub fn with(self, items: &[&str]) -> Self {
+        self.clause(format!("WITH {}", items.join(", ")))
+    }
+
+    pub fn return_(self, items: &[&str]) -> Self {
+        self.clause(format!("RETURN {}", items.join(", ")))
+    }
+
+    pub fn order_by(self, item: &str) -> Self {
+        self.clause(format!("ORDER BY {}", item))
+    }
+
+    pub fn order_by_desc(self, item: &str) -> Self {
+        self.clause(format!("ORDER BY {} DESC", item))
+    }
+
+    pub fn skip(mut self, skip: i64) -> Self {
+        let placeholder = self.params.bind(skip.into());
+        self.clause(format!("SKIP {}", placeholder))
+    }
+
+    pub fn limit(mut self, limit: i64) -> Self {
+        let placeholder = self.params.bind(limit.into());
+        self.clause(format!("LIMIT {}", placeholder))
+    }
+
+    /// Adds a clause as is, values should be passed as parameters of the built query
+    pub fn raw(self, clause: &str) -> Self {
+        self.clause(clause.to_owned())
+    }
+
+    /// The cypher of the query built so far
+    pub fn cypher(&self) -> String {
+        self.clauses.join("\n")
+    }
+
+    pub fn build(self) -> Query {
+        let mut query = Query::new(self.cypher());
+        for (key, value) in self.params.values {
+            query = query.param(&key, value);
+        }
+        query
+    }
+}
+
+/// Quotes a variable, label, type or property key unless it is a plain identifier
+fn name(name: &str) -> String {
+    let mut chars = name.chars();
+    let plain = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        name.to_owned()
+    } else {
+        format!("`{}`", name.replace('`', "``"))
+    }
+}
+
+fn names(vars: &[&str]) -> String {
+    vars.iter()
+        .map(|var| name(var))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_bind_values_as_params() {
+        let builder = QueryBuilder::new()
+            .match_(
+                node("p")
+                    .label("Person")
+                    .prop("name", "Mark")
+                    .outgoing(rel("k").rel_type("KNOWS"), node("f")),
+            )
+            .where_(prop("f", "age").gt(30).or(prop("f", "age").is_null()))
+            .where_(prop("f", "name").is_in(vec!["Ann", "Bob"]))
+            .return_(&["f"])
+            .limit(5);
+
+        assert_eq!(
+            builder.cypher(),
+            "MATCH (p:Person {name: $p0})-[k:KNOWS]->(f)\n\
+             WHERE (f.age > $p1 OR f.age IS NULL) AND f.name IN $p2\n\
+             RETURN f\n\
+             LIMIT $p3"
+        );
+        let query = builder.build();
+        assert!(query.has_param_key("p0"));
+        assert!(query.has_param_key("p3"));
+    }
+
+    #[test]
+    fn should_skip_conditions_that_always_hold() {
+        let filter = Condition::all().and(prop("n", "age").gt(1).or(Condition::all()));
+        let cypher = QueryBuilder::new()
+            .merge(node("n").label("Odd `label`"))
+            .where_(filter)
+            .set("n", "first name", "x")
+            .set("n", "age", 1)
+            .cypher();
+
+        assert_eq!(
+            cypher,
+            "MERGE (n:`Odd ``label```)\nSET n.`first name` = $p0, n.age = $p1"
+        );
+    }
+
+    #[test]
+    fn should_match_by_element_id() {
+        let cypher = QueryBuilder::new()
+            .match_(node("n"))
+            .where_(element_id("n").eq("4:abc:7"))
+            .return_(&["n"])
+            .cypher();
+
+        assert_eq!(cypher, "MATCH (n)\nWHERE elementId(n) = $p0\nRETURN n");
+    }
+}
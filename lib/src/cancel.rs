@@ -0,0 +1,145 @@
+use crate::errors::Error;
+use crate::rt;
+use futures::future::{self, Either};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Cancels the queries it is attached to with [`Query::cancellation`], like when the request of
+/// a web server that runs them times out
+///
+/// A cancelled query stops pulling rows and fails with [`Error::Cancelled`], the server is asked
+/// to stop running it with a RESET, after which the connection is returned to the pool. Clones
+/// cancel the same queries.
+///
+/// [`Query::cancellation`]: crate::Query::cancellation
+/// [`Error::Cancelled`]: crate::Error::Cancelled
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<Inner>);
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Cancels the queries attached to the token, they fail as soon as they wait for the server
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Completes once the token is cancelled
+    pub(crate) async fn cancelled(&self) {
+        loop {
+            // registered before the flag is checked, so a cancel in between isn't missed
+            let notified = self.0.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// What stops a query before it completes, its token being cancelled or its timeout elapsing
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Cancel {
+    token: Option<CancellationToken>,
+    deadline: Option<Instant>,
+}
+
+impl Cancel {
+    /// Starts the `timeout` of a query from now
+    pub(crate) fn new(token: Option<CancellationToken>, timeout: Option<Duration>) -> Self {
+        Cancel {
+            token,
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        }
+    }
+
+    /// The error the query fails with once it was stopped
+    pub(crate) fn reason(&self) -> Option<Error> {
+        if self
+            .token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            Some(Error::Cancelled)
+        } else if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Some(Error::Timeout)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the query can be stopped at all
+    pub(crate) fn is_set(&self) -> bool {
+        self.token.is_some() || self.deadline.is_some()
+    }
+
+    /// Completes with the error the query fails with once it is stopped, never when it can't be
+    /// stopped
+    pub(crate) async fn stopped(&self) -> Error {
+        let cancelled = Box::pin(async {
+            match &self.token {
+                Some(token) => token.cancelled().await,
+                None => future::pending().await,
+            }
+        });
+        let elapsed = Box::pin(async {
+            match self.deadline {
+                Some(deadline) => {
+                    rt::sleep(deadline.saturating_duration_since(Instant::now())).await
+                }
+                None => future::pending().await,
+            }
+        });
+        match future::select(cancelled, elapsed).await {
+            Either::Left(_) => Error::Cancelled,
+            Either::Right(_) => Error::Timeout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_wake_up_on_cancel() {
+        let token = CancellationToken::new();
+        let waiting = tokio::spawn({
+            let token = token.clone();
+            async move { token.cancelled().await }
+        });
+
+        token.clone().cancel();
+
+        waiting.await.unwrap();
+        assert!(token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn should_stop_once_the_timeout_elapsed() {
+        let cancel = Cancel::new(None, Some(Duration::from_millis(10)));
+        assert!(cancel.reason().is_none());
+
+        assert!(matches!(cancel.stopped().await, Error::Timeout));
+        assert!(matches!(cancel.reason(), Some(Error::Timeout)));
+        assert!(!Cancel::default().is_set());
+    }
+}
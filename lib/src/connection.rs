@@ -1,45 +1,562 @@
-use crate::errors::{unexpected, Error, Result};
+use crate::auth::AuthToken;
+use crate::cancel::Cancel;
+use crate::config::{Config, Proxy, SocketOptions};
+use crate::errors::{unexpected, Error, Result, AUTHORIZATION_EXPIRED};
+use crate::limits::Limits;
 use crate::messages::*;
-use crate::version::Version;
+use crate::metrics::MetricsHook;
+use crate::rt::{self, TcpStream};
+use crate::trace::traced;
+use crate::types::BoltMap;
+use crate::version::{Feature, Version, MANIFEST_V1};
 use bytes::*;
+use futures::future::{self, Either};
+use log::warn;
+use socket2::TcpKeepalive;
+use std::future::Future;
+use std::io::IoSlice;
 use std::mem;
+use std::time::Duration;
+pub(crate) use tls::SharedConnector;
+use tls::Transport;
 use tokio::io::BufStream;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+mod socks;
+mod tls;
+#[cfg(feature = "websocket")]
+mod websocket;
 
 const MAX_CHUNK_SIZE: usize = 65_535 - mem::size_of::<u16>();
+const END_MARKER: [u8; 2] = [0, 0];
+/// The capacity the buffer messages are read into grows by, small messages are read into the
+/// memory of the ones before them once those were dropped
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+const TOKEN_EXPIRED: &str = "Neo.ClientError.Security.TokenExpired";
+
+/// The server a connection was established with, see [`Graph::server_info`]
+///
+/// [`Graph::server_info`]: crate::Graph::server_info
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerInfo {
+    /// The address the connection was opened to
+    pub address: String,
+    /// The product and version of the server, like `Neo4j/5.12.0`
+    pub agent: String,
+    /// The bolt version negotiated with the server
+    pub protocol_version: Version,
+    /// The id the server assigned to the connection, like `bolt-123`, which appears in its logs
+    /// and in `SHOW TRANSACTIONS`
+    pub connection_id: String,
+    /// Whether the server routes queries to the cluster members serving them itself, which it
+    /// advertises from bolt 5.8
+    pub server_side_routing: bool,
+}
+
+impl ServerInfo {
+    /// Whether the negotiated bolt version supports a feature
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.protocol_version.supports(feature)
+    }
+
+    /// The major, minor and patch version of the server parsed from its agent, like `(5, 12, 0)`
+    /// for `Neo4j/5.12.0`, to check for capabilities of the server which don't depend on the bolt
+    /// version
+    pub fn server_version(&self) -> Option<(u32, u32, u32)> {
+        let (_, version) = self.agent.split_once('/')?;
+        let mut parts = version.split('.').map(|part| {
+            let digits = part
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(part.len());
+            part[..digits].parse().ok()
+        });
+        let major = parts.next()??;
+        let minor = parts.next().flatten().unwrap_or(0);
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some((major, minor, patch))
+    }
+}
 
 #[derive(Debug)]
 pub struct Connection {
     version: Version,
-    stream: BufStream<TcpStream>,
+    /// Whether the 4.4 server accepted the `utc` patch, date times are then encoded like 5.0
+    utc_patch: bool,
+    stream: BufStream<Box<dyn Transport>>,
+    broken: bool,
+    server: ServerInfo,
+    token: AuthToken,
+    token_expired: bool,
+    limits: Limits,
+    metrics: Option<MetricsHook>,
+    /// The qids of the results of the transaction in progress which still have rows to pull
+    open_results: Vec<i64>,
+    /// The qid given to the last result of a bolt 3 transaction, whose results have none
+    legacy_qid: i64,
+    /// The number of messages sent whose response wasn't received yet
+    pending: usize,
+    /// The qid of a stream dropped while it received its rows, the responses still pending are
+    /// skipped before the next message is sent
+    abandoned: Option<i64>,
+    /// The chunk headers of the messages being written, kept for the next messages
+    headers: Vec<[u8; 2]>,
+    /// The buffer messages are read into, split off as each message is received
+    incoming: BytesMut,
+    /// Whether the server asked for telemetry and the config didn't opt out of it
+    telemetry: bool,
+    /// The API of the next query or transaction, reported along with its first message
+    api: Option<TelemetryApi>,
+    /// Whether the response to a TELEMETRY message is still to be received, it is skipped
+    telemetry_pending: bool,
+    /// How long the server may stay silent before a response is considered lost, as hinted by
+    /// the server with `connection.recv_timeout_seconds`
+    recv_timeout: Option<Duration>,
 }
 
-impl Connection {
-    pub async fn new(uri: &str, user: &str, password: &str) -> Result<Connection> {
-        let mut stream = BufStream::new(TcpStream::connect(uri).await?);
-        stream.write_all(&[0x60, 0x60, 0xB0, 0x17]).await?;
-        stream.write_all(&Version::supported_versions()).await?;
-        stream.flush().await?;
-        let mut response = [0, 0, 0, 0];
-        stream.read_exact(&mut response).await?;
-        let version = Version::parse(response)?;
-        let mut connection = Connection { version, stream };
-        let hello = BoltRequest::hello("neo4rs", user.to_owned(), password.to_owned());
-        match connection.send_recv(hello).await? {
-            BoltResponse::SuccessMessage(_msg) => Ok(connection),
-            BoltResponse::FailureMessage(msg) => {
-                Err(Error::AuthenticationError(msg.get("message").unwrap()))
+/// Opens the transport of a connection, encrypted when the uri scheme or config asks for it and
+/// wrapped in a websocket when the config enables it
+async fn connect(address: &str, config: &Config) -> Result<Box<dyn Transport>> {
+    #[cfg(unix)]
+    if config.unix_socket() {
+        return Ok(Box::new(rt::connect_unix(address).await?));
+    }
+    let stream = match &config.resolver {
+        Some(resolver) => {
+            let resolved = resolver.0.resolve(address).await?;
+            connect_any(address, resolved, config.proxy.as_ref()).await?
+        }
+        None => open(address, config.proxy.as_ref()).await?,
+    };
+    tune(&stream, &config.socket)?;
+    let transport: Box<dyn Transport> = if config.encryption {
+        let connector = match &config.tls_connector {
+            Some(shared) => shared.clone(),
+            None => SharedConnector::new(config)?,
+        };
+        connector.0.connect(&config.host(address), stream).await?
+    } else {
+        Box::new(stream)
+    };
+    #[cfg(feature = "websocket")]
+    if config.websocket {
+        return websocket::upgrade(transport, address, config.limits.message_size).await;
+    }
+    Ok(transport)
+}
+
+/// Opens a tcp stream to `address`, through the proxy if there is one
+async fn open(address: &str, proxy: Option<&Proxy>) -> Result<TcpStream> {
+    match proxy {
+        Some(proxy) => socks::connect(proxy, address).await,
+        None => Ok(rt::connect_tcp(address).await?),
+    }
+}
+
+/// Writes all of `slices`, which a single vectored write may only write partly
+async fn write_all_vectored<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    mut slices: &mut [IoSlice<'_>],
+) -> std::io::Result<()> {
+    while !slices.is_empty() {
+        let written = writer.write_vectored(slices).await?;
+        if written == 0 {
+            return Err(std::io::ErrorKind::WriteZero.into());
+        }
+        IoSlice::advance_slices(&mut slices, written);
+    }
+    Ok(())
+}
+
+/// Applies the configured socket options to `stream`
+fn tune(stream: &TcpStream, options: &SocketOptions) -> std::io::Result<()> {
+    let socket = rt::sock_ref(stream);
+    if let Some(nodelay) = options.nodelay {
+        socket.set_tcp_nodelay(nodelay)?;
+    }
+    if let Some(idle) = options.keepalive {
+        socket.set_tcp_keepalive(&TcpKeepalive::new().with_time(idle))?;
+    }
+    if let Some(size) = options.send_buffer_size {
+        socket.set_send_buffer_size(size)?;
+    }
+    if let Some(size) = options.recv_buffer_size {
+        socket.set_recv_buffer_size(size)?;
+    }
+    Ok(())
+}
+
+/// Connects to the first of the resolved addresses accepting the connection
+async fn connect_any(
+    address: &str,
+    resolved: Vec<String>,
+    proxy: Option<&Proxy>,
+) -> Result<TcpStream> {
+    let mut last_error = Error::IOError {
+        detail: format!("{} resolved to no address", address),
+    };
+    for candidate in resolved {
+        match open(&candidate, proxy).await {
+            Ok(stream) => return Ok(stream),
+            Err(e) => {
+                warn!(
+                    "unable to connect to {} resolved for {}: {:?}",
+                    candidate, address, e
+                );
+                last_error = e;
             }
+        }
+    }
+    Err(last_error)
+}
 
+/// The output of a step opening a connection to `address`, failing with an [`Error::IOError`]
+/// once it takes longer than `timeout`
+async fn within<T>(
+    timeout: Duration,
+    address: &str,
+    step: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    rt::timeout(timeout, step).await.unwrap_or_else(|| {
+        Err(Error::IOError {
+            detail: format!("connecting to {} timed out after {:?}", address, timeout),
+        })
+    })
+}
+
+/// Proposes the bolt versions to the server, `None` when it accepted none of them. A server
+/// answering with its manifest gets the chosen version back, without any capabilities.
+async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    proposals: Bytes,
+) -> Result<Option<Version>> {
+    stream.write_all(&[0x60, 0x60, 0xB0, 0x17]).await?;
+    stream.write_all(&proposals).await?;
+    stream.flush().await?;
+    let mut response = [0, 0, 0, 0];
+    stream.read_exact(&mut response).await?;
+    match response {
+        [0, 0, 0, 0] => Ok(None),
+        MANIFEST_V1 => {
+            let count = read_varint(stream).await?;
+            let mut offered = Vec::new();
+            for _ in 0..count {
+                let mut version = [0, 0, 0, 0];
+                stream.read_exact(&mut version).await?;
+                offered.push(version);
+            }
+            // the capabilities of the server, none of which are used yet
+            read_varint(stream).await?;
+            let version = Version::from_manifest(&offered)?;
+            stream.write_all(&version.to_bytes()).await?;
+            stream.write_u8(0).await?;
+            stream.flush().await?;
+            Ok(Some(version))
+        }
+        response => Version::parse(response).map(Some),
+    }
+}
+
+/// Reads an unsigned LEB128 encoded integer of the handshake manifest
+async fn read_varint<S: AsyncRead + Unpin>(stream: &mut S) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = stream.read_u8().await?;
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(Error::UnexpectedMessage("handshake varint too long".into()))
+}
+
+impl Connection {
+    pub async fn new(address: &str, config: &Config) -> Result<Connection> {
+        traced!(
+            "neo4rs.connect",
+            { address = address },
+            Connection::establish(address, config)
+        )
+    }
+
+    async fn establish(address: &str, config: &Config) -> Result<Connection> {
+        let timeout = config.connection_timeout;
+        let mut stream = BufStream::new(within(timeout, address, connect(address, config)).await?);
+        let proposals = handshake(&mut stream, Version::supported_versions());
+        let version = match within(timeout, address, proposals).await? {
+            Some(version) => version,
+            // servers close the connection when they accept none of the proposals
+            None => {
+                stream = BufStream::new(within(timeout, address, connect(address, config)).await?);
+                let fallback = handshake(&mut stream, Version::fallback_versions());
+                within(timeout, address, fallback).await?.ok_or_else(|| {
+                    Error::UnsupportedVersion("no version supported by the server".into())
+                })?
+            }
+        };
+        let token = config.auth.token().await?;
+        let mut connection = Connection {
+            version,
+            utc_patch: false,
+            stream,
+            broken: false,
+            server: ServerInfo {
+                address: address.to_owned(),
+                agent: String::new(),
+                protocol_version: version,
+                connection_id: String::new(),
+                server_side_routing: false,
+            },
+            token,
+            token_expired: false,
+            limits: config.limits,
+            metrics: config.metrics.clone(),
+            open_results: Vec::new(),
+            legacy_qid: 0,
+            pending: 0,
+            abandoned: None,
+            headers: Vec::new(),
+            incoming: BytesMut::new(),
+            telemetry: false,
+            api: None,
+            telemetry_pending: false,
+            recv_timeout: None,
+        };
+        let routing = if config.routes_on_server() && version.supports(Feature::RoutingContext) {
+            Some(config.routing_context())
+        } else {
+            None
+        };
+        let hello = BoltRequest::hello(
+            version,
+            &config.user_agent,
+            routing,
+            &config.notification_filter,
+            &connection.token,
+        );
+        let result = match connection.send_recv(hello).await? {
+            BoltResponse::SuccessMessage(msg) => {
+                connection.server.agent = msg.get("server").unwrap_or_default();
+                connection.server.connection_id = msg.get("connection_id").unwrap_or_default();
+                connection.utc_patch = version == Version::V4_4
+                    && msg
+                        .get::<Vec<String>>("patch_bolt")
+                        .is_some_and(|patches| patches.iter().any(|patch| patch == "utc"));
+                let hints = msg.get::<BoltMap>("hints").unwrap_or_default();
+                connection.telemetry = config.telemetry
+                    && version.supports(Feature::Telemetry)
+                    && hints.get::<bool>("telemetry.enabled").unwrap_or(false);
+                connection.server.server_side_routing =
+                    hints.get::<bool>("ssr.enabled").unwrap_or(false);
+                connection.recv_timeout = hints
+                    .get::<i64>("connection.recv_timeout_seconds")
+                    .filter(|&seconds| seconds > 0)
+                    .map(|seconds| Duration::from_secs(seconds as u64));
+                if version.supports(Feature::ReAuthentication) {
+                    let token = connection.token.clone();
+                    connection.logon(token).await
+                } else {
+                    Ok(())
+                }
+            }
+            BoltResponse::FailureMessage(msg) => Err(Error::AuthenticationError(
+                msg.get("message").unwrap_or_default(),
+            )),
             msg => Err(unexpected(msg, "HELLO")),
+        };
+        if connection.token_expired {
+            config.auth.expire(&connection.token).await;
         }
+        result.map(|_| connection)
     }
 
-    pub async fn reset(&mut self) -> Result<()> {
-        match self.send_recv(BoltRequest::reset()).await? {
+    /// Replaces the credentials the connection authenticated with, requires bolt 5.1 or newer
+    pub async fn reauthenticate(&mut self, token: AuthToken) -> Result<()> {
+        match self.send_recv(BoltRequest::logoff()).await? {
+            BoltResponse::SuccessMessage(_) => {}
+            msg => return Err(msg.into_error("LOGOFF")),
+        }
+        self.logon(token).await
+    }
+
+    async fn logon(&mut self, token: AuthToken) -> Result<()> {
+        let logon = BoltRequest::logon(&token);
+        self.token = token;
+        self.token_expired = false;
+        match self.send_recv(logon).await? {
             BoltResponse::SuccessMessage(_) => Ok(()),
-            msg => Err(unexpected(msg, "RESET")),
+            BoltResponse::FailureMessage(msg) => Err(Error::AuthenticationError(
+                msg.get("message").unwrap_or_default(),
+            )),
+            msg => Err(unexpected(msg, "LOGON")),
+        }
+    }
+
+    /// The credentials the connection authenticated with
+    pub fn token(&self) -> &AuthToken {
+        &self.token
+    }
+
+    /// Whether the server reported the token of the connection as expired
+    pub fn is_token_expired(&self) -> bool {
+        self.token_expired
+    }
+
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The version messages are encoded and decoded for. A 4.4 server which accepted the `utc`
+    /// patch exchanges date times like 5.0 does, which is all 5.0 changed about the values a
+    /// client sends.
+    fn encoding(&self) -> Version {
+        if self.utc_patch {
+            Version::V5_0
+        } else {
+            self.version
+        }
+    }
+
+    pub fn server(&self) -> &ServerInfo {
+        &self.server
+    }
+
+    /// Whether reading or writing a message failed, after which the connection can't be used
+    /// anymore since the stream may be closed or in the middle of a message
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// Keeps the connection from being used again, the pool closes it instead
+    pub(crate) fn mark_broken(&mut self) {
+        self.broken = true;
+    }
+
+    /// Tracks a result of the transaction in progress until all of its rows were pulled or
+    /// discarded, returning the qid its stream pulls the rows with. Results outside of a
+    /// transaction have no qid, the results of a bolt 3 transaction are given one by the
+    /// connection.
+    pub(crate) fn open_result(&mut self, qid: Option<i64>, in_txn: bool) -> i64 {
+        let qid = match qid {
+            Some(qid) => qid,
+            None if in_txn && !self.version.supports(Feature::ResultBatches) => {
+                self.legacy_qid += 1;
+                self.legacy_qid
+            }
+            None => return -1,
+        };
+        self.open_results.push(qid);
+        qid
+    }
+
+    pub(crate) fn close_result(&mut self, qid: i64) {
+        self.open_results.retain(|&open| open != qid);
+    }
+
+    /// Whether the result `qid` still has rows to pull, always true for results outside of a
+    /// transaction
+    pub(crate) fn is_result_open(&self, qid: i64) -> bool {
+        qid < 0 || self.open_results.contains(&qid)
+    }
+
+    /// Discards the rows left in every open result of the transaction, the server only commits
+    /// or rolls back a transaction once all of its results were consumed. The DISCARD messages
+    /// are pipelined with `message`, whose response is returned.
+    pub(crate) async fn close_results_and_send(
+        &mut self,
+        message: BoltRequest,
+    ) -> Result<BoltResponse> {
+        let open_results = std::mem::take(&mut self.open_results);
+        let mut messages: Vec<_> = open_results
+            .iter()
+            .map(|&qid| BoltRequest::discard_all(qid))
+            .collect();
+        messages.push(message);
+        self.send_all(messages).await?;
+        for _ in &open_results {
+            match self.recv().await? {
+                BoltResponse::SuccessMessage(_) => {}
+                msg => return Err(msg.into_error("DISCARD")),
+            }
+        }
+        self.recv().await
+    }
+
+    /// Discards the rows left in the result of a bolt 3 transaction, the server only runs the
+    /// next query of the transaction once the result was consumed. Bolt 4 transactions keep
+    /// several results open.
+    pub(crate) async fn close_legacy_result(&mut self) -> Result<()> {
+        if self.version.supports(Feature::ResultBatches) {
+            return Ok(());
+        }
+        // the result of an abandoned stream is closed once its rows were skipped
+        self.skip_abandoned().await?;
+        match self.open_results.pop() {
+            Some(qid) => match self.send_recv(BoltRequest::discard_all(qid)).await? {
+                BoltResponse::SuccessMessage(_) => Ok(()),
+                msg => Err(msg.into_error("DISCARD")),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Reports `api` to the server along with the next message, when the server asked for
+    /// telemetry
+    pub(crate) fn report(&mut self, api: TelemetryApi) {
+        if self.telemetry {
+            self.api = Some(api);
+        }
+    }
+
+    /// Marks the responses still pending as abandoned by a stream that was dropped while
+    /// receiving its rows
+    pub(crate) fn abandon(&mut self, qid: i64) {
+        if self.pending > 0 {
+            self.abandoned = Some(qid);
+        }
+    }
+
+    /// Skips the rows and the response of the PULL of an abandoned stream, its result stays open
+    /// when the server has more rows left in it
+    async fn skip_abandoned(&mut self) -> Result<()> {
+        if let Some(qid) = self.abandoned {
+            while self.pending > 0 {
+                match self.recv().await? {
+                    BoltResponse::RecordMessage(_) => {}
+                    BoltResponse::SuccessMessage(success)
+                        if success.get("has_more").unwrap_or(false) => {}
+                    _ => self.close_result(qid),
+                }
+            }
+            self.abandoned = None;
+        }
+        Ok(())
+    }
+
+    /// Resets the connection to a clean state, responses still pending are skipped so the
+    /// connection can be used again
+    pub async fn reset(&mut self) -> Result<()> {
+        self.open_results.clear();
+        self.abandoned = None;
+        self.api = None;
+        self.send(BoltRequest::reset()).await?;
+        loop {
+            let response = self.recv().await?;
+            if self.pending > 0 {
+                continue;
+            }
+            return match response {
+                BoltResponse::SuccessMessage(_) => Ok(()),
+                msg => Err(unexpected(msg, "RESET")),
+            };
+        }
+    }
+
+    /// Says goodbye to the server and closes the stream, so the server doesn't log an abrupt
+    /// disconnect. Errors are ignored since the connection is discarded anyway.
+    pub async fn goodbye(mut self) {
+        self.abandoned = None;
+        if self.send(BoltRequest::goodbye()).await.is_ok() {
+            let _ = self.stream.shutdown().await;
         }
     }
 
@@ -49,37 +566,184 @@ impl Connection {
     }
 
     pub async fn send(&mut self, message: BoltRequest) -> Result<()> {
-        let end_marker: [u8; 2] = [0, 0];
-        let bytes: Bytes = message.into_bytes(self.version)?;
-        for c in bytes.chunks(MAX_CHUNK_SIZE) {
-            self.stream.write_u16(c.len() as u16).await?;
-            self.stream.write_all(c).await?;
-        }
-        self.stream.write_all(&end_marker).await?;
-        self.stream.flush().await?;
-        Ok(())
+        self.send_all(vec![message]).await
+    }
+
+    /// Sends all `messages` at once and only then flushes the stream, so they reach the server in
+    /// a single round trip. The responses are read afterwards with [`Connection::recv`], one for
+    /// each message in the same order.
+    pub async fn send_all(&mut self, mut messages: Vec<BoltRequest>) -> Result<()> {
+        self.skip_abandoned().await?;
+        // the response to the TELEMETRY is skipped by recv
+        let telemetry = self.api.take().map(BoltRequest::telemetry);
+        self.telemetry_pending |= telemetry.is_some();
+        messages.splice(0..0, telemetry);
+        // the server doesn't respond to GOODBYE
+        let responses = messages
+            .iter()
+            .filter(|message| !matches!(message, BoltRequest::GoodbyeMessage(_)))
+            .count();
+        // nothing is written unless every message can be serialized
+        let mut serialized = Vec::with_capacity(messages.len());
+        for message in messages {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(message = message.name(), "sending");
+            serialized.push(message.into_bytes(self.encoding())?);
+        }
+        let mut result = self.write(&serialized).await;
+        if result.is_ok() {
+            result = self.stream.flush().await.map_err(Error::from);
+        }
+        self.broken |= result.is_err();
+        self.pending += responses;
+        result
+    }
+
+    /// Sends all `messages` at once and receives the response of the first one, unless the query
+    /// was stopped before, see [`Connection::recv_cancellable`]. The responses of the other
+    /// messages are left to be received.
+    pub(crate) async fn send_all_recv_cancellable(
+        &mut self,
+        messages: Vec<BoltRequest>,
+        cancel: &Cancel,
+    ) -> Result<BoltResponse> {
+        if let Some(reason) = cancel.reason() {
+            self.reset().await?;
+            return Err(reason);
+        }
+        self.send_all(messages).await?;
+        self.recv_cancellable(cancel).await
+    }
+
+    /// Receives the next response like [`Connection::recv`], unless the query is cancelled or
+    /// times out before any of it arrived. The server is then asked to stop with a RESET and the
+    /// request fails with [`Error::Cancelled`] or [`Error::Timeout`].
+    pub(crate) async fn recv_cancellable(&mut self, cancel: &Cancel) -> Result<BoltResponse> {
+        if cancel.is_set() {
+            // waiting for buffered bytes consumes none of them, so the wait can be abandoned
+            let arrived = Box::pin(self.stream.fill_buf());
+            let stopped = Box::pin(cancel.stopped());
+            let reason = match future::select(arrived, stopped).await {
+                Either::Left(_) => None,
+                Either::Right((reason, _)) => Some(reason),
+            };
+            if let Some(reason) = reason {
+                self.reset().await?;
+                return Err(reason);
+            }
+        }
+        self.recv().await
     }
 
     pub async fn recv(&mut self) -> Result<BoltResponse> {
-        let mut bytes = BytesMut::new();
+        if mem::take(&mut self.telemetry_pending) {
+            match self.receive().await? {
+                BoltResponse::SuccessMessage(_) => {}
+                response => return Ok(response),
+            }
+        }
+        self.receive().await
+    }
+
+    async fn receive(&mut self) -> Result<BoltResponse> {
+        let result = self.read_message().await;
+        self.broken |= result.is_err();
+        // a result is streamed as records followed by a single response to its request
+        if let Ok(response) = &result {
+            if !matches!(response, BoltResponse::RecordMessage(_)) {
+                self.pending = self.pending.saturating_sub(1);
+            }
+        }
+        #[cfg(feature = "tracing")]
+        if let Ok(message) = &result {
+            tracing::trace!(message = message.name(), "received");
+        }
+        if let Ok(BoltResponse::FailureMessage(failure)) = &result {
+            let code = failure.code();
+            self.token_expired |= code.as_deref() == Some(TOKEN_EXPIRED);
+            // the server closes connections whose authorization expired
+            self.broken |= code.as_deref() == Some(AUTHORIZATION_EXPIRED);
+            if let (Some(metrics), Some(code)) = (&self.metrics, &code) {
+                metrics.0.error(code);
+            }
+        }
+        result
+    }
+
+    /// Writes messages split into chunks of at most [`MAX_CHUNK_SIZE`] bytes, so messages with
+    /// large parameters can be sent. The chunk headers are written along with the slices of the
+    /// messages in vectored writes, the messages aren't copied into the buffer of the stream
+    /// unless they are small. They are buffered until the stream is flushed.
+    async fn write(&mut self, messages: &[Bytes]) -> Result<()> {
+        let mut headers = mem::take(&mut self.headers);
+        headers.clear();
+        headers.extend(
+            messages
+                .iter()
+                .flat_map(|message| message.chunks(MAX_CHUNK_SIZE))
+                .map(|chunk| (chunk.len() as u16).to_be_bytes()),
+        );
+        let mut slices = Vec::with_capacity(headers.len() * 2 + messages.len());
+        let mut header = headers.iter();
+        for message in messages {
+            for (chunk, header) in message.chunks(MAX_CHUNK_SIZE).zip(&mut header) {
+                slices.push(IoSlice::new(header));
+                slices.push(IoSlice::new(chunk));
+            }
+            slices.push(IoSlice::new(&END_MARKER));
+        }
+        let result = write_all_vectored(&mut self.stream, &mut slices).await;
+        drop(slices);
+        self.headers = headers;
+        Ok(result?)
+    }
+
+    /// Reads the chunks of a message up to its end marker, skipping the empty chunks servers
+    /// send to keep the connection alive. The message is checked against the limits of the
+    /// config before it is parsed.
+    async fn read_message(&mut self) -> Result<BoltResponse> {
         let mut chunk_size = 0;
         while chunk_size == 0 {
-            chunk_size = self.read_u16().await?;
+            chunk_size = self.read_header().await?;
         }
 
+        // the chunks are read right into the buffer of the message, whose strings and byte
+        // arrays are then parsed as slices sharing it
+        let mut bytes = mem::take(&mut self.incoming);
+        if bytes.capacity() < chunk_size as usize {
+            bytes.reserve(READ_BUFFER_SIZE.max(chunk_size as usize));
+        }
         while chunk_size > 0 {
-            let chunk = self.read(chunk_size).await?;
-            bytes.put_slice(&chunk);
+            let start = bytes.len();
+            self.limits.check_size(start + chunk_size as usize)?;
+            bytes.resize(start + chunk_size as usize, 0);
+            self.stream.read_exact(&mut bytes[start..]).await?;
             chunk_size = self.read_u16().await?;
         }
+        let message = bytes.split().freeze();
+        self.incoming = bytes;
 
-        Ok(BoltResponse::parse(self.version, bytes.freeze())?)
+        self.limits.check(&message)?;
+        BoltResponse::parse(self.encoding(), message)
     }
 
-    async fn read(&mut self, size: u16) -> Result<Vec<u8>> {
-        let mut buf = vec![0; size as usize];
-        self.stream.read_exact(&mut buf).await?;
-        Ok(buf)
+    /// Reads the header of the first chunk of a message, failing once the server stayed silent
+    /// for longer than the receive timeout it hinted. Servers hinting one send empty chunks
+    /// while a query runs, each of them starts the wait again.
+    async fn read_header(&mut self) -> Result<u16> {
+        let timeout = match self.recv_timeout {
+            Some(timeout) => timeout,
+            None => return self.read_u16().await,
+        };
+        match rt::timeout(timeout, self.read_u16()).await {
+            Some(header) => header,
+            None => Err(Error::IOError {
+                detail: format!(
+                    "no response from {} within {:?}",
+                    self.server.address, timeout
+                ),
+            }),
+        }
     }
 
     async fn read_u16(&mut self) -> Result<u16> {
@@ -88,3 +752,584 @@ impl Connection {
         Ok(u16::from_be_bytes(data))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_the_server_version() {
+        let (client, _) = tokio::io::duplex(64);
+        let mut info = connection(client).server().clone();
+        assert_eq!(info.server_version(), Some((4, 4, 0)));
+        assert!(!info.supports(Feature::ElementIds));
+
+        info.agent = "Neo4j/5.23-aura".to_owned();
+        assert_eq!(info.server_version(), Some((5, 23, 0)));
+        info.agent = "Memgraph".to_owned();
+        assert_eq!(info.server_version(), None);
+    }
+
+    fn connection(transport: tokio::io::DuplexStream) -> Connection {
+        Connection {
+            version: Version::V4_4,
+            utc_patch: false,
+            stream: BufStream::new(Box::new(transport)),
+            broken: false,
+            server: ServerInfo {
+                address: "127.0.0.1:7687".to_owned(),
+                agent: "Neo4j/4.4.0".to_owned(),
+                protocol_version: Version::V4_4,
+                connection_id: "bolt-1".to_owned(),
+                server_side_routing: false,
+            },
+            token: AuthToken::Bearer("some_token".to_owned()),
+            token_expired: false,
+            limits: Limits {
+                message_size: None,
+                container_length: None,
+                nesting_depth: 8,
+            },
+            metrics: None,
+            open_results: Vec::new(),
+            legacy_qid: 0,
+            pending: 0,
+            abandoned: None,
+            headers: Vec::new(),
+            incoming: BytesMut::new(),
+            telemetry: false,
+            api: None,
+            telemetry_pending: false,
+            recv_timeout: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_break_on_io_errors() {
+        let (client, server) = tokio::io::duplex(64);
+        let mut connection = connection(client);
+        drop(server);
+
+        assert!(connection.recv().await.is_err());
+        assert!(connection.is_broken());
+    }
+
+    #[tokio::test]
+    async fn should_time_out_after_the_hinted_recv_timeout() {
+        let (client, mut server) = tokio::io::duplex(64);
+        let mut connection = connection(client);
+        connection.recv_timeout = Some(Duration::from_millis(100));
+        // the empty chunks keep the connection alive until the response arrives
+        let noop = tokio::spawn(async move {
+            for _ in 0..3 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                server.write_all(&[0, 0]).await.unwrap();
+            }
+            server
+                .write_all(&[0, 3, 0xB1, 0x70, 0xA0, 0, 0])
+                .await
+                .unwrap();
+            server
+        });
+
+        assert!(matches!(
+            connection.recv().await.unwrap(),
+            BoltResponse::SuccessMessage(_)
+        ));
+        let _server = noop.await.unwrap();
+        assert!(matches!(
+            connection.recv().await,
+            Err(Error::IOError { .. })
+        ));
+        assert!(connection.is_broken());
+    }
+
+    #[tokio::test]
+    async fn should_say_goodbye() {
+        let (client, mut server) = tokio::io::duplex(64);
+
+        connection(client).goodbye().await;
+        let mut bytes = Vec::new();
+        server.read_to_end(&mut bytes).await.unwrap();
+
+        assert_eq!(bytes, vec![0x00, 0x02, 0xB0, 0x02, 0x00, 0x00]);
+    }
+
+    #[tokio::test]
+    async fn should_send_pipelined_messages_at_once() {
+        let (client, mut server) = tokio::io::duplex(128);
+        let mut connection = connection(client);
+
+        connection
+            .send_all(vec![BoltRequest::discard(), BoltRequest::reset()])
+            .await
+            .unwrap();
+        let mut sent = [0; 21];
+        server.read_exact(&mut sent).await.unwrap();
+        server
+            .write_all(&[0, 3, 0xB1, 0x70, 0xA0, 0, 0, 0, 2, 0xB0, 0x7E, 0, 0])
+            .await
+            .unwrap();
+
+        assert_eq!(&sent[..4], &[0x00, 0x0B, 0xB1, 0x2F]);
+        assert_eq!(&sent[15..], &[0x00, 0x02, 0xB0, 0x0F, 0x00, 0x00]);
+        assert!(matches!(
+            connection.recv().await.unwrap(),
+            BoltResponse::SuccessMessage(_)
+        ));
+        assert!(matches!(
+            connection.recv().await.unwrap(),
+            BoltResponse::IgnoredMessage(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_send_the_discard_along_with_the_run() {
+        let (client, mut server) = tokio::io::duplex(256);
+        let mut connection = connection(client);
+        let config = crate::config::config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .build()
+            .unwrap();
+        let query = crate::query("RETURN 1");
+        let run = BoltRequest::run("", "RETURN 1", Default::default(), &[], &Default::default());
+        let sent = run.into_bytes(Version::V4_4).unwrap().len() + 4 + 15;
+        // the server only responds once both messages arrived
+        let server = tokio::spawn(async move {
+            let mut requests = vec![0; sent];
+            server.read_exact(&mut requests).await.unwrap();
+            for _ in 0..2 {
+                server
+                    .write_all(&[0, 3, 0xB1, 0x70, 0xA0, 0, 0])
+                    .await
+                    .unwrap();
+            }
+            requests.split_off(sent - 15)
+        });
+
+        let run = query.run(&config, &mut connection);
+        let ran = tokio::time::timeout(Duration::from_secs(5), run).await;
+
+        assert!(ran.unwrap().is_ok());
+        assert_eq!(server.await.unwrap()[..4], [0x00, 0x0B, 0xB1, 0x2F]);
+        assert_eq!(connection.pending, 0);
+    }
+
+    #[tokio::test]
+    async fn should_report_the_api_to_servers_asking_for_it() {
+        let (client, mut server) = tokio::io::duplex(256);
+        let mut connection = connection(client);
+        connection.report(TelemetryApi::AutoCommit);
+        connection.telemetry = true;
+        connection.report(TelemetryApi::ManagedTransaction);
+        let reply = tokio::spawn(async move {
+            let mut sent = [0; 7];
+            server.read_exact(&mut sent).await.unwrap();
+            server
+                .write_all(&[0, 3, 0xB1, 0x70, 0xA0, 0, 0])
+                .await
+                .unwrap();
+            server.write_all(&[0, 2, 0xB0, 0x7E, 0, 0]).await.unwrap();
+            sent
+        });
+
+        connection.send(BoltRequest::reset()).await.unwrap();
+        let response = connection.recv().await.unwrap();
+
+        assert_eq!(reply.await.unwrap(), [0, 3, 0xB1, 0x54, 0x00, 0, 0]);
+        assert!(matches!(response, BoltResponse::IgnoredMessage(_)));
+        assert_eq!(connection.pending, 0);
+        assert_eq!(connection.api, None);
+    }
+
+    #[tokio::test]
+    async fn should_discard_open_results_before_committing() {
+        let (client, mut server) = tokio::io::duplex(256);
+        let mut connection = connection(client);
+        assert_eq!(connection.open_result(None, true), -1);
+        assert_eq!(connection.open_result(Some(2), true), 2);
+        assert!(connection.is_result_open(-1));
+        assert!(connection.is_result_open(2));
+        let discard = BoltRequest::discard_all(2)
+            .into_bytes(Version::V4_4)
+            .unwrap();
+        let reply = tokio::spawn(async move {
+            let mut sent = vec![0; discard.len() + 10];
+            server.read_exact(&mut sent).await.unwrap();
+            for _ in 0..2 {
+                server
+                    .write_all(&[0, 3, 0xB1, 0x70, 0xA0, 0, 0])
+                    .await
+                    .unwrap();
+            }
+            (sent, discard)
+        });
+
+        let response = connection
+            .close_results_and_send(BoltRequest::commit())
+            .await
+            .unwrap();
+        let (sent, discard) = reply.await.unwrap();
+
+        assert!(matches!(response, BoltResponse::SuccessMessage(_)));
+        assert_eq!(&sent[..4], &[0, discard.len() as u8, 0xB1, 0x2F]);
+        assert_eq!(&sent[discard.len() + 4..], &[0, 2, 0xB0, 0x12, 0, 0]);
+        assert!(!connection.is_result_open(2));
+    }
+
+    #[tokio::test]
+    async fn should_skip_the_rows_of_abandoned_streams() {
+        let (client, mut server) = tokio::io::duplex(256);
+        let mut connection = connection(client);
+        connection.open_result(Some(3), true);
+        connection.send(BoltRequest::pull(10, 3)).await.unwrap();
+        // two records, the end of the result, and the response to the next message
+        let record = [0, 4, 0xB1, 0x71, 0x91, 0x01, 0, 0];
+        server.write_all(&record).await.unwrap();
+        server.write_all(&record).await.unwrap();
+        server
+            .write_all(&[0, 3, 0xB1, 0x70, 0xA0, 0, 0])
+            .await
+            .unwrap();
+        server
+            .write_all(&[0, 3, 0xB1, 0x70, 0xA0, 0, 0])
+            .await
+            .unwrap();
+        assert!(matches!(
+            connection.recv().await.unwrap(),
+            BoltResponse::RecordMessage(_)
+        ));
+        connection.abandon(3);
+
+        let response = connection.send_recv(BoltRequest::reset()).await.unwrap();
+
+        assert!(matches!(response, BoltResponse::SuccessMessage(_)));
+        assert!(!connection.is_result_open(3));
+    }
+
+    #[tokio::test]
+    async fn should_reset_connections_with_pending_responses() {
+        let (client, mut server) = tokio::io::duplex(256);
+        let mut connection = connection(client);
+        connection.send(BoltRequest::pull(10, -1)).await.unwrap();
+        server
+            .write_all(&[0, 4, 0xB1, 0x71, 0x91, 0x01, 0, 0])
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            server
+                .write_all(&[0, 3, 0xB1, 0x70, 0xA0, 0, 0])
+                .await
+                .unwrap();
+        }
+
+        connection.reset().await.unwrap();
+
+        assert_eq!(connection.pending, 0);
+    }
+
+    #[tokio::test]
+    async fn should_reset_when_cancelled() {
+        let (client, mut server) = tokio::io::duplex(256);
+        let mut connection = connection(client);
+        let token = crate::CancellationToken::new();
+        let cancel = Cancel::new(Some(token.clone()), None);
+        let pull = BoltRequest::pull(10, -1);
+        let pulled = pull.clone().into_bytes(Version::V4_4).unwrap().len() + 4;
+        connection.send(pull).await.unwrap();
+        let server = tokio::spawn(async move {
+            let mut sent = vec![0; pulled + 6];
+            server.read_exact(&mut sent).await.unwrap();
+            server.write_all(&[0, 2, 0xB0, 0x7E, 0, 0]).await.unwrap();
+            server
+                .write_all(&[0, 3, 0xB1, 0x70, 0xA0, 0, 0])
+                .await
+                .unwrap();
+            sent.split_off(pulled)
+        });
+        tokio::spawn({
+            let token = token.clone();
+            async move {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                token.cancel();
+            }
+        });
+
+        let cancelled = connection.recv_cancellable(&cancel).await;
+
+        assert!(matches!(cancelled, Err(Error::Cancelled)));
+        assert_eq!(server.await.unwrap(), vec![0, 2, 0xB0, 0x0F, 0, 0]);
+        assert_eq!(connection.pending, 0);
+    }
+
+    #[tokio::test]
+    async fn should_notice_expired_tokens() {
+        let (client, mut server) = tokio::io::duplex(128);
+        let mut connection = connection(client);
+        let mut failure = vec![0xB1, 0x7F, 0xA1, 0x84, b'c', b'o', b'd', b'e', 0xD0, 0x25];
+        failure.extend_from_slice(TOKEN_EXPIRED.as_bytes());
+        server.write_u16(failure.len() as u16).await.unwrap();
+        server.write_all(&failure).await.unwrap();
+        server.write_u16(0).await.unwrap();
+
+        let response = connection.recv().await.unwrap();
+
+        assert!(matches!(response, BoltResponse::FailureMessage(_)));
+        assert!(connection.is_token_expired());
+        assert!(!connection.is_broken());
+    }
+
+    #[tokio::test]
+    async fn should_refuse_logons_failing_without_a_message() {
+        let (client, mut server) = tokio::io::duplex(128);
+        let mut connection = connection(client);
+        server
+            .write_all(&[0, 3, 0xB1, 0x7F, 0xA0, 0, 0])
+            .await
+            .unwrap();
+
+        let token = AuthToken::Bearer("other_token".to_owned());
+        let logon = connection.logon(token).await;
+
+        assert!(matches!(logon, Err(Error::AuthenticationError(message)) if message.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn should_report_failures_to_the_metrics() {
+        struct Codes(std::sync::Mutex<Vec<String>>);
+
+        impl crate::metrics::DriverMetrics for Codes {
+            fn error(&self, code: &str) {
+                self.0.lock().unwrap().push(code.to_owned());
+            }
+        }
+
+        let (client, mut server) = tokio::io::duplex(128);
+        let codes = std::sync::Arc::new(Codes(Default::default()));
+        let mut connection = connection(client);
+        connection.metrics = Some(MetricsHook(codes.clone()));
+        let mut failure = vec![0xB1, 0x7F, 0xA1, 0x84, b'c', b'o', b'd', b'e', 0xD0, 0x25];
+        failure.extend_from_slice(TOKEN_EXPIRED.as_bytes());
+        server.write_u16(failure.len() as u16).await.unwrap();
+        server.write_all(&failure).await.unwrap();
+        server.write_u16(0).await.unwrap();
+
+        connection.recv().await.unwrap();
+
+        assert_eq!(*codes.0.lock().unwrap(), vec![TOKEN_EXPIRED.to_owned()]);
+    }
+
+    #[tokio::test]
+    async fn should_connect_to_the_first_reachable_address() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let reachable = listener.local_addr().unwrap().to_string();
+        let unreachable = "127.0.0.1:1".to_owned();
+
+        let stream = connect_any(
+            "neo4j.local:7687",
+            vec![unreachable.clone(), reachable.clone()],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(stream.peer_addr().unwrap().to_string(), reachable);
+        assert!(connect_any("neo4j.local:7687", vec![unreachable], None)
+            .await
+            .is_err());
+        assert!(matches!(
+            connect_any("neo4j.local:7687", vec![], None).await,
+            Err(Error::IOError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_time_out_handshakes() {
+        // the listener accepts the connection but never answers the handshake
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = crate::config::config()
+            .uri(&listener.local_addr().unwrap().to_string())
+            .user("some_user")
+            .password("some_password")
+            .connection_timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        let result = Connection::new(&config.address(), &config).await;
+
+        assert!(matches!(result, Err(Error::IOError { detail }) if detail.contains("timed out")));
+    }
+
+    #[tokio::test]
+    async fn should_tune_the_sockets() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let stream = rt::connect_tcp(&listener.local_addr().unwrap().to_string())
+            .await
+            .unwrap();
+        let options = SocketOptions {
+            nodelay: Some(true),
+            keepalive: Some(Duration::from_secs(30)),
+            ..SocketOptions::default()
+        };
+
+        tune(&stream, &options).unwrap();
+
+        let socket = rt::sock_ref(&stream);
+        assert!(socket.tcp_nodelay().unwrap());
+        assert!(socket.keepalive().unwrap());
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn should_connect_to_unix_sockets() {
+        let path = std::env::temp_dir().join(format!("neo4rs-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let config = crate::config::config()
+            .uri(&format!("bolt+unix://{}", path.display()))
+            .user("some_user")
+            .password("some_password")
+            .build()
+            .unwrap();
+
+        let mut transport = connect(&config.address(), &config).await.unwrap();
+        let (mut accepted, _) = listener.accept().await.unwrap();
+        transport
+            .write_all(&[0x60, 0x60, 0xB0, 0x17])
+            .await
+            .unwrap();
+        let mut preamble = [0; 4];
+        accepted.read_exact(&mut preamble).await.unwrap();
+
+        assert_eq!(preamble, [0x60, 0x60, 0xB0, 0x17]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_negotiate_versions_with_the_manifest() {
+        let (mut client, mut server) = tokio::io::duplex(256);
+        server
+            .write_all(&[0, 0, 1, 0xFF, 2, 0, 4, 8, 5, 0, 2, 4, 4, 0x81, 0x01])
+            .await
+            .unwrap();
+
+        let version = handshake(&mut client, Version::supported_versions())
+            .await
+            .unwrap();
+        drop(client);
+        let mut sent = Vec::new();
+        server.read_to_end(&mut sent).await.unwrap();
+
+        assert_eq!(version, Some(Version::V5_7));
+        assert_eq!(&sent[20..], &[0, 0, 7, 5, 0]);
+    }
+
+    #[tokio::test]
+    async fn should_fall_back_to_fixed_versions() {
+        let (mut client, mut server) = tokio::io::duplex(256);
+        server.write_all(&[0, 0, 2, 4, 0, 0, 0, 0]).await.unwrap();
+
+        let legacy = handshake(&mut client, Version::supported_versions()).await;
+        let refused = handshake(&mut client, Version::supported_versions()).await;
+
+        assert_eq!(legacy.unwrap(), Some(Version::V4_2));
+        assert_eq!(refused.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn should_split_large_messages_into_chunks() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut connection = connection(client);
+        let mut params = crate::types::BoltMap::default();
+        params.put("payload".into(), "x".repeat(200_000).into());
+        let request = BoltRequest::run(
+            "",
+            "RETURN $payload",
+            params,
+            &[],
+            &crate::txn::TxOptions::default(),
+        );
+        let expected = request.clone().into_bytes(Version::V4_4).unwrap();
+        let received = tokio::spawn(async move {
+            let mut chunks = Vec::new();
+            loop {
+                let size = server.read_u16().await.unwrap() as usize;
+                if size == 0 {
+                    return chunks;
+                }
+                let mut chunk = vec![0; size];
+                server.read_exact(&mut chunk).await.unwrap();
+                chunks.push(chunk);
+            }
+        });
+
+        connection.send(request).await.unwrap();
+        let chunks = received.await.unwrap();
+
+        assert_eq!(chunks.len(), 4);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= MAX_CHUNK_SIZE));
+        assert_eq!(chunks.concat(), expected.to_vec());
+    }
+
+    #[tokio::test]
+    async fn should_reassemble_chunked_messages() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut connection = connection(client);
+        let value = "y".repeat(100_000);
+        let mut message = vec![0xB1, 0x70, 0xA1, 0x85, b'v', b'a', b'l', b'u', b'e', 0xD2];
+        message.extend_from_slice(&(value.len() as u32).to_be_bytes());
+        message.extend_from_slice(value.as_bytes());
+        tokio::spawn(async move {
+            server.write_all(&[0, 0]).await.unwrap();
+            for chunk in message.chunks(40_000) {
+                server.write_u16(chunk.len() as u16).await.unwrap();
+                server.write_all(chunk).await.unwrap();
+            }
+            server.write_all(&[0, 0]).await.unwrap();
+        });
+
+        let response = connection.recv().await.unwrap();
+
+        match response {
+            BoltResponse::SuccessMessage(success) => {
+                assert_eq!(success.get::<String>("value"), Some(value))
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_refuse_messages_beyond_the_limits() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut connection = connection(client);
+        connection.limits.message_size = Some(65_536);
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                server.write_all(&[0xFF, 0xFF]).await.unwrap();
+                server.write_all(&[0; 65_535]).await.unwrap();
+            }
+        });
+
+        let response = connection.recv().await;
+
+        assert!(matches!(response, Err(Error::LimitExceeded(_))));
+        assert!(connection.is_broken());
+    }
+
+    #[tokio::test]
+    async fn should_not_break_on_responses() {
+        let (client, mut server) = tokio::io::duplex(64);
+        let mut connection = connection(client);
+        server
+            .write_all(&[0x00, 0x03, 0xB1, 0x70, 0xA0, 0x00, 0x00])
+            .await
+            .unwrap();
+
+        let response = connection.recv().await.unwrap();
+
+        assert!(matches!(response, BoltResponse::SuccessMessage(_)));
+        assert!(!connection.is_broken());
+    }
+}
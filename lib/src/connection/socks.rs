@@ -0,0 +1,214 @@
+use crate::config::Proxy;
+use crate::errors::{Error, Result};
+use crate::rt::{self, TcpStream};
+use std::net::IpAddr;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const VERSION: u8 = 0x05;
+const NO_AUTHENTICATION: u8 = 0x00;
+const USER_PASSWORD: u8 = 0x02;
+const NO_ACCEPTABLE_METHOD: u8 = 0xFF;
+const USER_PASSWORD_VERSION: u8 = 0x01;
+const CONNECT: u8 = 0x01;
+const IPV4: u8 = 0x01;
+const DOMAIN_NAME: u8 = 0x03;
+const IPV6: u8 = 0x04;
+
+/// Opens a tcp stream to `address` through the SOCKS5 proxy of the config. Host names are
+/// resolved by the proxy, so servers only known inside the proxied network can be reached.
+pub(crate) async fn connect(proxy: &Proxy, address: &str) -> Result<TcpStream> {
+    let mut stream = rt::connect_tcp(&proxy.address).await?;
+    handshake(&mut stream, address, proxy.credentials.as_ref()).await?;
+    Ok(stream)
+}
+
+async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    address: &str,
+    credentials: Option<&(String, String)>,
+) -> Result<()> {
+    let method = if credentials.is_some() {
+        USER_PASSWORD
+    } else {
+        NO_AUTHENTICATION
+    };
+    stream.write_all(&[VERSION, 1, method]).await?;
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply).await?;
+    match (reply, credentials) {
+        ([VERSION, NO_AUTHENTICATION], None) => {}
+        ([VERSION, USER_PASSWORD], Some((user, password))) => {
+            authenticate(stream, user, password).await?
+        }
+        ([VERSION, NO_ACCEPTABLE_METHOD], _) => {
+            return Err(Error::ProxyError(
+                "the proxy accepts none of the authentication methods".into(),
+            ))
+        }
+        _ => return Err(Error::ProxyError(format!("unexpected reply {:?}", reply))),
+    }
+
+    let mut request = vec![VERSION, CONNECT, 0];
+    put_address(&mut request, address)?;
+    stream.write_all(&request).await?;
+    let mut reply = [0; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != VERSION {
+        return Err(Error::ProxyError(format!("unexpected reply {:?}", reply)));
+    }
+    if reply[1] != 0 {
+        return Err(refused(reply[1], address));
+    }
+    // the address the proxy bound for the connection is of no use
+    let bound = match reply[3] {
+        IPV4 => 4,
+        IPV6 => 16,
+        DOMAIN_NAME => stream.read_u8().await? as usize,
+        other => return Err(Error::ProxyError(format!("unknown address type {}", other))),
+    };
+    let mut skipped = vec![0; bound + 2];
+    stream.read_exact(&mut skipped).await?;
+    Ok(())
+}
+
+async fn authenticate<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    user: &str,
+    password: &str,
+) -> Result<()> {
+    if user.len() > 255 || password.len() > 255 {
+        return Err(Error::ProxyError(
+            "proxy credentials longer than 255 bytes".into(),
+        ));
+    }
+    let mut request = vec![USER_PASSWORD_VERSION, user.len() as u8];
+    request.extend_from_slice(user.as_bytes());
+    request.push(password.len() as u8);
+    request.extend_from_slice(password.as_bytes());
+    stream.write_all(&request).await?;
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0 {
+        return Err(Error::ProxyError(
+            "the proxy rejected the credentials".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn put_address(request: &mut Vec<u8>, address: &str) -> Result<()> {
+    let (host, port) = address
+        .rsplit_once(':')
+        .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)))
+        .ok_or_else(|| Error::ProxyError(format!("invalid address {}", address)))?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) if host.len() <= 255 => {
+            request.push(DOMAIN_NAME);
+            request.push(host.len() as u8);
+            request.extend_from_slice(host.as_bytes());
+        }
+        Err(_) => return Err(Error::ProxyError(format!("host name too long: {}", host))),
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    Ok(())
+}
+
+/// Servers the proxy can't reach fail like servers refusing a direct connection, so they are
+/// retried and removed from routing tables
+fn refused(reply: u8, address: &str) -> Error {
+    let reason = match reply {
+        0x01 => "general failure",
+        0x02 => "connection not allowed by the proxy",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "ttl expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown failure",
+    };
+    let detail = format!("proxy unable to connect to {}: {}", address, reason);
+    match reply {
+        0x03..=0x06 => Error::IOError { detail },
+        _ => Error::ProxyError(detail),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn should_connect_through_the_proxy() {
+        let (mut client, mut proxy) = tokio::io::duplex(256);
+        proxy
+            .write_all(&[0x05, 0x00, 0x05, 0x00, 0x00, 0x01, 10, 0, 0, 1, 0x1F, 0x90])
+            .await
+            .unwrap();
+
+        handshake(&mut client, "db.example.com:7687", None)
+            .await
+            .unwrap();
+        drop(client);
+        let mut sent = Vec::new();
+        proxy.read_to_end(&mut sent).await.unwrap();
+
+        let mut expected = vec![0x05, 0x01, 0x00, 0x05, 0x01, 0x00, 0x03, 14];
+        expected.extend_from_slice(b"db.example.com");
+        expected.extend_from_slice(&[0x1E, 0x07]);
+        assert_eq!(sent, expected);
+    }
+
+    #[tokio::test]
+    async fn should_authenticate_with_the_proxy() {
+        let (mut client, mut proxy) = tokio::io::duplex(256);
+        proxy
+            .write_all(&[
+                0x05, 0x02, 0x01, 0x00, 0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0,
+            ])
+            .await
+            .unwrap();
+        let credentials = ("user".to_owned(), "pw".to_owned());
+
+        handshake(&mut client, "127.0.0.1:7687", Some(&credentials))
+            .await
+            .unwrap();
+        drop(client);
+        let mut sent = Vec::new();
+        proxy.read_to_end(&mut sent).await.unwrap();
+
+        assert_eq!(
+            sent,
+            vec![
+                0x05, 0x01, 0x02, 0x01, 4, b'u', b's', b'e', b'r', 2, b'p', b'w', 0x05, 0x01, 0x00,
+                0x01, 127, 0, 0, 1, 0x1E, 0x07
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_fail_when_the_proxy_refuses() {
+        let (mut client, mut proxy) = tokio::io::duplex(256);
+        proxy
+            .write_all(&[0x05, 0x00, 0x05, 0x05, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+            .await
+            .unwrap();
+
+        let refused = handshake(&mut client, "[::1]:7687", None).await;
+        assert!(matches!(refused, Err(Error::IOError { .. })));
+
+        let (mut client, mut proxy) = tokio::io::duplex(256);
+        proxy.write_all(&[0x05, 0xFF]).await.unwrap();
+        let rejected = handshake(&mut client, "[::1]:7687", None).await;
+        assert!(matches!(rejected, Err(Error::ProxyError(_))));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::config::Config;
+use crate::errors::Result;
+use crate::rt::TcpStream;
+use async_trait::async_trait;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+#[cfg(feature = "native-tls")]
+mod native_tls;
+#[cfg(all(feature = "rustls", not(feature = "native-tls")))]
+mod rustls;
+
+/// A byte stream a connection can be established over, either a plain tcp stream or one of the
+/// encrypted streams of the TLS backends.
+pub(crate) trait Transport: AsyncRead + AsyncWrite + Unpin + Send + Sync + Debug {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + Sync + Debug> Transport for T {}
+
+/// Upgrades a tcp stream to an encrypted [`Transport`], verifying that the server certificate
+/// is trusted according to the [`TrustStrategy`](crate::TrustStrategy) and issued for `host`. A client certificate is
+/// presented when the config has one.
+#[async_trait]
+pub(crate) trait TlsConnector: Send + Sync {
+    async fn connect(&self, host: &str, stream: TcpStream) -> Result<Box<dyn Transport>>;
+}
+
+/// The connector shared by the encrypted connections of a graph. It is built once when the graph
+/// connects, so certificate files which are missing or invalid fail
+/// [`Graph::connect`](crate::Graph::connect) rather than a later connection of the pool.
+#[derive(Clone)]
+pub(crate) struct SharedConnector(pub(crate) Arc<dyn TlsConnector>);
+
+impl SharedConnector {
+    pub(crate) fn new(config: &Config) -> Result<Self> {
+        connector(config).map(SharedConnector)
+    }
+}
+
+impl Debug for SharedConnector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedConnector")
+    }
+}
+
+/// The connector of the enabled TLS backend, the platform TLS stack is preferred when the
+/// `native-tls` feature is enabled alongside the default `rustls` feature.
+#[cfg(feature = "native-tls")]
+fn connector(config: &Config) -> Result<Arc<dyn TlsConnector>> {
+    Ok(Arc::new(native_tls::NativeTlsConnector::new(config)?))
+}
+
+#[cfg(all(feature = "rustls", not(feature = "native-tls")))]
+fn connector(config: &Config) -> Result<Arc<dyn TlsConnector>> {
+    Ok(Arc::new(rustls::RustlsConnector::new(config)?))
+}
+
+#[cfg(not(any(feature = "rustls", feature = "native-tls")))]
+fn connector(_config: &Config) -> Result<Arc<dyn TlsConnector>> {
+    Err(crate::errors::Error::TlsError(
+        "encryption requires either the rustls or the native-tls feature".into(),
+    ))
+}
+
+#[cfg(all(test, any(feature = "rustls", feature = "native-tls")))]
+mod tests {
+    use std::path::PathBuf;
+
+    /// A self-signed P-256 certificate for `localhost`
+    pub(super) const CERTIFICATE: &str = "\
+-----BEGIN CERTIFICATE-----
+MIIBgDCCASWgAwIBAgIUEIStyzvml2sawGgOJdez3ccaDRAwCgYIKoZIzj0EAwIw
+FDESMBAGA1UEAwwJbG9jYWxob3N0MCAXDTI2MTAxNDEwMDQzNFoYDzIxMjYwOTIw
+MTAwNDM0WjAUMRIwEAYDVQQDDAlsb2NhbGhvc3QwWTATBgcqhkjOPQIBBggqhkjO
+PQMBBwNCAATbDOETg3kU6I2EXAMsAA5V8uPoi2LM047K1sXw2mL/h2PBFRK8PfW0
+Ky1Jm0QbC6V9TEVASsjmOcIelI7OP6gLo1MwUTAdBgNVHQ4EFgQUTRZqLNAhBinR
+gxVxZE7LLOaqbSowHwYDVR0jBBgwFoAUTRZqLNAhBinRgxVxZE7LLOaqbSowDwYD
+VR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNJADBGAiEA4qyiDracaCeeP8+0qdCx
+9yape4zu1kZeeZuvXFPLNxoCIQDeFQ/7Wnhl1NhJ1N3rIs0rEoXNZ22Rptg7gGMC
+nYQN9w==
+-----END CERTIFICATE-----
+";
+
+    /// Writes `contents` to a file of the temporary directory
+    pub(super) fn write(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("neo4rs-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+}
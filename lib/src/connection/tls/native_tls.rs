@@ -0,0 +1,125 @@
+use super::{TlsConnector, Transport};
+use crate::config::{CertificateVerifier, Config, TrustStrategy};
+use crate::errors::{Error, Result};
+use crate::rt::TcpStream;
+use async_trait::async_trait;
+use std::path::Path;
+use tokio_native_tls::native_tls;
+
+const PEM_END: &str = "-----END CERTIFICATE-----";
+
+/// Encrypts connections with the TLS stack of the platform, which also takes care of loading
+/// the system root store and verifying the host name.
+pub(crate) struct NativeTlsConnector {
+    connector: tokio_native_tls::TlsConnector,
+    verify: Option<CertificateVerifier>,
+}
+
+impl NativeTlsConnector {
+    pub(crate) fn new(config: &Config) -> Result<Self> {
+        let mut builder = native_tls::TlsConnector::builder();
+        let mut verify = None;
+        if let Some(client_certificate) = &config.client_certificate {
+            let cert = std::fs::read(&client_certificate.cert)?;
+            let key = std::fs::read(&client_certificate.key)?;
+            builder.identity(native_tls::Identity::from_pkcs8(&cert, &key).map_err(tls_error)?);
+        }
+        match &config.trust {
+            TrustStrategy::SystemRoots => {}
+            TrustStrategy::CustomCa(path) => {
+                builder.disable_built_in_roots(true);
+                for cert in custom_roots(path)? {
+                    builder.add_root_certificate(cert);
+                }
+            }
+            TrustStrategy::TrustAll => {
+                builder.danger_accept_invalid_certs(true);
+            }
+            TrustStrategy::Custom(callback) => {
+                // the platform stacks don't expose the verification, so the callback is applied
+                // to the peer certificate once the handshake completed
+                builder.danger_accept_invalid_certs(true);
+                verify = Some(callback.clone());
+            }
+        }
+        Ok(NativeTlsConnector {
+            connector: builder.build().map_err(tls_error)?.into(),
+            verify,
+        })
+    }
+}
+
+#[async_trait]
+impl TlsConnector for NativeTlsConnector {
+    async fn connect(&self, host: &str, stream: TcpStream) -> Result<Box<dyn Transport>> {
+        let stream = self
+            .connector
+            .connect(host, stream)
+            .await
+            .map_err(tls_error)?;
+        if let Some(verify) = &self.verify {
+            let cert = stream
+                .get_ref()
+                .peer_certificate()
+                .map_err(tls_error)?
+                .ok_or_else(|| Error::TlsError("server sent no certificate".into()))?;
+            if !verify(&cert.to_der().map_err(tls_error)?, host) {
+                return Err(Error::TlsError("certificate rejected".into()));
+            }
+        }
+        Ok(Box::new(stream))
+    }
+}
+
+/// Splits a PEM bundle into its certificates, as the platform stacks only parse one at a time
+fn custom_roots(path: &Path) -> Result<Vec<native_tls::Certificate>> {
+    let bundle = std::fs::read_to_string(path)
+        .map_err(|e| Error::TlsError(format!("unable to read {}: {}", path.display(), e)))?;
+    let certs = bundle
+        .split_inclusive(PEM_END)
+        .filter(|pem| pem.contains(PEM_END))
+        .map(|pem| native_tls::Certificate::from_pem(pem.trim().as_bytes()).map_err(tls_error))
+        .collect::<Result<Vec<_>>>()?;
+    if certs.is_empty() {
+        return Err(Error::TlsError(format!(
+            "no certificates found in {}",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+fn tls_error(e: native_tls::Error) -> Error {
+    Error::TlsError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{write, CERTIFICATE};
+    use super::*;
+
+    #[test]
+    fn should_name_missing_root_files() {
+        let path = Path::new("/nonexistent/roots.pem");
+
+        let roots = custom_roots(path).map(|_| ());
+
+        assert!(
+            matches!(roots, Err(Error::TlsError(message)) if message.contains("/nonexistent/roots.pem"))
+        );
+    }
+
+    #[test]
+    fn should_refuse_root_files_without_certificates() {
+        let empty = write("empty-native-roots.pem", "no certificates here\n");
+        let bundle = format!("{}{}", CERTIFICATE, CERTIFICATE);
+        let roots = write("native-roots.pem", &bundle);
+
+        let (empty_roots, custom) = (custom_roots(&empty), custom_roots(&roots));
+        std::fs::remove_file(empty).unwrap();
+        std::fs::remove_file(roots).unwrap();
+
+        assert!(matches!(empty_roots, Err(Error::TlsError(_))));
+        assert_eq!(custom.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,308 @@
+use super::{TlsConnector, Transport};
+use crate::config::{CertificateVerifier, ClientCertificate, Config, TrustStrategy};
+use crate::errors::{Error, Result};
+use crate::rt::TcpStream;
+use async_trait::async_trait;
+use log::warn;
+use std::convert::TryFrom;
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::client::WantsClientCert;
+use tokio_rustls::rustls::crypto::{self, CryptoProvider};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{
+    ClientConfig, ConfigBuilder, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+
+static SYSTEM_ROOTS_CONFIG: OnceLock<Arc<ClientConfig>> = OnceLock::new();
+
+/// Encrypts connections with rustls, verifying server certificates according to the
+/// [`TrustStrategy`] of the config.
+pub(crate) struct RustlsConnector {
+    connector: tokio_rustls::TlsConnector,
+}
+
+impl RustlsConnector {
+    pub(crate) fn new(config: &Config) -> Result<Self> {
+        let client_config = match (&config.trust, &config.client_certificate) {
+            (TrustStrategy::SystemRoots, None) => system_roots_config()?,
+            (trust, client_certificate) => {
+                Arc::new(with_client_auth(builder(trust)?, client_certificate)?)
+            }
+        };
+        Ok(RustlsConnector {
+            connector: tokio_rustls::TlsConnector::from(client_config),
+        })
+    }
+}
+
+#[async_trait]
+impl TlsConnector for RustlsConnector {
+    async fn connect(&self, host: &str, stream: TcpStream) -> Result<Box<dyn Transport>> {
+        let server_name = ServerName::try_from(host.to_owned())
+            .map_err(|e| Error::TlsError(format!("invalid server name {}: {}", host, e)))?;
+        let stream = self.connector.connect(server_name, stream).await?;
+        Ok(Box::new(stream))
+    }
+}
+
+fn builder(trust: &TrustStrategy) -> Result<ConfigBuilder<ClientConfig, WantsClientCert>> {
+    match trust {
+        TrustStrategy::SystemRoots => with_roots(system_roots()?),
+        TrustStrategy::CustomCa(path) => with_roots(custom_roots(path)?),
+        TrustStrategy::TrustAll => with_verifier(None),
+        TrustStrategy::Custom(verify) => with_verifier(Some(verify.clone())),
+    }
+}
+
+/// The system root store is only loaded once and shared by every connection without a client
+/// certificate
+fn system_roots_config() -> Result<Arc<ClientConfig>> {
+    if let Some(config) = SYSTEM_ROOTS_CONFIG.get() {
+        return Ok(config.clone());
+    }
+    let config = Arc::new(with_roots(system_roots()?)?.with_no_client_auth());
+    Ok(SYSTEM_ROOTS_CONFIG.get_or_init(|| config).clone())
+}
+
+fn with_client_auth(
+    builder: ConfigBuilder<ClientConfig, WantsClientCert>,
+    client_certificate: &Option<ClientCertificate>,
+) -> Result<ClientConfig> {
+    let client_certificate = match client_certificate {
+        Some(client_certificate) => client_certificate,
+        None => return Ok(builder.with_no_client_auth()),
+    };
+    let chain = CertificateDer::pem_file_iter(&client_certificate.cert)
+        .and_then(|certs| certs.collect::<std::result::Result<Vec<_>, _>>())
+        .map_err(|e| pem_error(&client_certificate.cert, e))?;
+    let key = PrivateKeyDer::from_pem_file(&client_certificate.key)
+        .map_err(|e| pem_error(&client_certificate.key, e))?;
+    builder
+        .with_client_auth_cert(chain, key)
+        .map_err(|e| Error::TlsError(e.to_string()))
+}
+
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(crypto::ring::default_provider())
+}
+
+fn with_roots(roots: RootCertStore) -> Result<ConfigBuilder<ClientConfig, WantsClientCert>> {
+    let builder = ClientConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| Error::TlsError(e.to_string()))?
+        .with_root_certificates(roots);
+    Ok(builder)
+}
+
+fn with_verifier(
+    verify: Option<CertificateVerifier>,
+) -> Result<ConfigBuilder<ClientConfig, WantsClientCert>> {
+    let provider = provider();
+    let verifier = CallbackVerifier {
+        provider: provider.clone(),
+        verify,
+    };
+    let builder = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| Error::TlsError(e.to_string()))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier));
+    Ok(builder)
+}
+
+fn system_roots() -> Result<RootCertStore> {
+    let native = rustls_native_certs::load_native_certs();
+    for error in native.errors {
+        warn!("unable to load a system root certificate: {}", error);
+    }
+    let mut roots = RootCertStore::empty();
+    let (added, ignored) = roots.add_parsable_certificates(native.certs);
+    if ignored > 0 {
+        warn!("ignored {} invalid system root certificates", ignored);
+    }
+    if added == 0 {
+        return Err(Error::TlsError(
+            "no root certificates found in the system store".into(),
+        ));
+    }
+    Ok(roots)
+}
+
+fn custom_roots(path: &Path) -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    let certs = CertificateDer::pem_file_iter(path).map_err(|e| pem_error(path, e))?;
+    for cert in certs {
+        let cert = cert.map_err(|e| Error::TlsError(e.to_string()))?;
+        roots
+            .add(cert)
+            .map_err(|e| Error::TlsError(e.to_string()))?;
+    }
+    if roots.is_empty() {
+        return Err(Error::TlsError(format!(
+            "no certificates found in {}",
+            path.display()
+        )));
+    }
+    Ok(roots)
+}
+
+fn pem_error(path: &Path, e: impl fmt::Display) -> Error {
+    Error::TlsError(format!("unable to read {}: {}", path.display(), e))
+}
+
+/// Accepts the server certificates approved by the callback, or any certificate without one.
+/// The handshake signatures are still verified so the server has to own the certificate.
+struct CallbackVerifier {
+    provider: Arc<CryptoProvider>,
+    verify: Option<CertificateVerifier>,
+}
+
+impl fmt::Debug for CallbackVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackVerifier").finish()
+    }
+}
+
+impl ServerCertVerifier for CallbackVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+        match &self.verify {
+            Some(verify) if !verify(end_entity.as_ref(), &server_name.to_str()) => Err(
+                tokio_rustls::rustls::Error::General("certificate rejected".into()),
+            ),
+            _ => Ok(ServerCertVerified::assertion()),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{write, CERTIFICATE};
+    use super::*;
+    use tokio_rustls::rustls::internal::msgs::codec::Codec;
+
+    /// The signature of `handshake` by the key of the certificate
+    const SIGNATURE: [u8; 72] = [
+        0x30, 0x46, 0x02, 0x21, 0x00, 0xA2, 0xA2, 0xC7, 0x1A, 0xCC, 0x47, 0x54, 0x23, 0xF3, 0xD7,
+        0xE9, 0x10, 0xAF, 0x95, 0x5C, 0x99, 0xD5, 0x2E, 0x17, 0x2D, 0xF9, 0x8D, 0xA6, 0xB7, 0xFF,
+        0x71, 0x4D, 0x4D, 0x8E, 0x55, 0x0E, 0x57, 0x02, 0x21, 0x00, 0x9D, 0xFD, 0xEE, 0x80, 0x73,
+        0x4E, 0xE9, 0x6B, 0x45, 0x19, 0x6C, 0x50, 0x7B, 0xE0, 0xD0, 0x31, 0xE8, 0xF2, 0x9B, 0x90,
+        0x73, 0x81, 0x4A, 0x47, 0x33, 0x97, 0x81, 0x8E, 0x28, 0x2A, 0x53, 0x55,
+    ];
+
+    fn certificate() -> CertificateDer<'static> {
+        CertificateDer::from_pem_slice(CERTIFICATE.as_bytes()).unwrap()
+    }
+
+    /// An ECDSA P-256 SHA-256 signature as sent in a handshake
+    fn signed(signature: &[u8]) -> DigitallySignedStruct {
+        let mut bytes = vec![0x04, 0x03, 0x00, signature.len() as u8];
+        bytes.extend_from_slice(signature);
+        DigitallySignedStruct::read_bytes(&bytes).unwrap()
+    }
+
+    #[test]
+    fn should_name_missing_root_files() {
+        let path = Path::new("/nonexistent/roots.pem");
+
+        let roots = custom_roots(path).map(|_| ());
+
+        assert!(
+            matches!(roots, Err(Error::TlsError(message)) if message.contains("/nonexistent/roots.pem"))
+        );
+    }
+
+    #[test]
+    fn should_refuse_root_files_without_certificates() {
+        let empty = write("empty-roots.pem", "no certificates here\n");
+        let roots = write("roots.pem", CERTIFICATE);
+
+        let (empty_roots, custom) = (custom_roots(&empty), custom_roots(&roots));
+        std::fs::remove_file(empty).unwrap();
+        std::fs::remove_file(roots).unwrap();
+
+        assert!(matches!(empty_roots, Err(Error::TlsError(_))));
+        assert_eq!(custom.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn should_reject_certificates_refused_by_the_callback() {
+        let verify: CertificateVerifier = Arc::new(|_, host| host == "example.com");
+        let verifier = CallbackVerifier {
+            provider: provider(),
+            verify: Some(verify),
+        };
+        let verified = |host: &str| {
+            let name = ServerName::try_from(host.to_owned()).unwrap();
+            verifier.verify_server_cert(&certificate(), &[], &name, &[], UnixTime::now())
+        };
+
+        assert!(verified("localhost").is_err());
+        assert!(verified("example.com").is_ok());
+    }
+
+    #[test]
+    fn should_verify_handshake_signatures_when_trusting_all_certificates() {
+        let verifier = CallbackVerifier {
+            provider: provider(),
+            verify: None,
+        };
+        let mut forged = SIGNATURE;
+        forged[10] ^= 0xFF;
+
+        for signature in [&SIGNATURE, &forged] {
+            let tls12 =
+                verifier.verify_tls12_signature(b"handshake", &certificate(), &signed(signature));
+            let tls13 =
+                verifier.verify_tls13_signature(b"handshake", &certificate(), &signed(signature));
+            assert_eq!(tls12.is_ok(), signature == &SIGNATURE);
+            assert_eq!(tls13.is_ok(), signature == &SIGNATURE);
+        }
+    }
+}
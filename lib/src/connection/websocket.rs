@@ -0,0 +1,532 @@
+use super::tls::Transport;
+use crate::errors::{Error, Result};
+use crate::rt;
+use std::collections::hash_map::RandomState;
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, WriteHalf};
+use tokio::sync::Mutex;
+
+/// Appended to the key of the client to compute the accept key of the server, see RFC 6455
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// The largest chunk of bolt messages sent in a single frame
+const MAX_FRAME_SIZE: usize = 64 * 1024;
+/// The largest message accepted from the server when no max message size is configured
+const MAX_RECEIVED_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+const MAX_RESPONSE_HEADER: usize = 8 * 1024;
+
+const CONTINUATION: u8 = 0x0;
+const TEXT: u8 = 0x1;
+const BINARY: u8 = 0x2;
+const CLOSE: u8 = 0x8;
+const PING: u8 = 0x9;
+const PONG: u8 = 0xA;
+
+/// The status codes of the CLOSE frames, see RFC 6455 section 7.4.1
+const PROTOCOL_ERROR: [u8; 2] = 1002u16.to_be_bytes();
+const UNSUPPORTED_DATA: [u8; 2] = 1003u16.to_be_bytes();
+const MESSAGE_TOO_BIG: [u8; 2] = 1009u16.to_be_bytes();
+
+type SharedWriter = Arc<Mutex<WriteHalf<Box<dyn Transport>>>>;
+
+/// Upgrades `transport` to a websocket and speaks bolt in binary frames over it, like the
+/// browser drivers do. The frames are unwrapped by background tasks, the returned transport
+/// carries the plain bolt messages. Messages from the server larger than `max_message_size`, or
+/// than [`MAX_RECEIVED_MESSAGE_SIZE`] without one, close the websocket.
+pub(crate) async fn upgrade(
+    mut transport: Box<dyn Transport>,
+    host: &str,
+    max_message_size: Option<usize>,
+) -> Result<Box<dyn Transport>> {
+    let key = base64(&random_bytes::<16>());
+    let request = format!(
+        "GET / HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+        host, key
+    );
+    transport.write_all(request.as_bytes()).await?;
+    transport.flush().await?;
+    let response = read_response_header(&mut transport).await?;
+    verify_response(&response, &key)?;
+
+    let (bolt, bridged) = tokio::io::duplex(MAX_FRAME_SIZE);
+    let (socket_reader, socket_writer) = tokio::io::split(transport);
+    let (bridged_reader, bridged_writer) = tokio::io::split(bridged);
+    let socket_writer: SharedWriter = Arc::new(Mutex::new(socket_writer));
+    rt::spawn(send_frames(bridged_reader, socket_writer.clone()));
+    let max_message_size = max_message_size
+        .unwrap_or(MAX_RECEIVED_MESSAGE_SIZE)
+        .max(MAX_FRAME_SIZE);
+    rt::spawn(receive_frames(
+        socket_reader,
+        bridged_writer,
+        socket_writer,
+        max_message_size,
+    ));
+    Ok(Box::new(bolt))
+}
+
+/// Reads the http response to the upgrade request, without reading past its header
+async fn read_response_header<S: AsyncRead + Unpin>(stream: &mut S) -> Result<String> {
+    let mut header = Vec::new();
+    while !header.ends_with(b"\r\n\r\n") {
+        if header.len() > MAX_RESPONSE_HEADER {
+            return Err(websocket_error("response header too long"));
+        }
+        header.push(stream.read_u8().await?);
+    }
+    String::from_utf8(header).map_err(|_| websocket_error("response header is not utf-8"))
+}
+
+fn verify_response(response: &str, key: &str) -> Result<()> {
+    let mut lines = response.split("\r\n");
+    let status = lines.next().unwrap_or_default();
+    if status.split(' ').nth(1) != Some("101") {
+        return Err(websocket_error(&format!("upgrade refused: {}", status)));
+    }
+    let expected = accept_key(key);
+    let accepted = lines
+        .filter_map(|line| line.split_once(':'))
+        .any(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("sec-websocket-accept") && value.trim() == expected
+        });
+    if !accepted {
+        return Err(websocket_error("invalid Sec-WebSocket-Accept header"));
+    }
+    Ok(())
+}
+
+fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key, ACCEPT_GUID).as_bytes()))
+}
+
+/// Wraps everything the connection writes in binary frames, and closes the websocket once the
+/// connection is dropped
+async fn send_frames<R: AsyncRead + Unpin>(mut bolt: R, socket: SharedWriter) {
+    let mut buffer = vec![0; MAX_FRAME_SIZE];
+    loop {
+        let (opcode, read) = match bolt.read(&mut buffer).await {
+            Ok(0) | Err(_) => (CLOSE, 0),
+            Ok(read) => (BINARY, read),
+        };
+        let frame = frame(opcode, &buffer[..read], random_bytes::<4>());
+        let mut socket = socket.lock().await;
+        if socket.write_all(&frame).await.is_err() || socket.flush().await.is_err() {
+            return;
+        }
+        if opcode == CLOSE {
+            let _ = socket.shutdown().await;
+            return;
+        }
+    }
+}
+
+/// Unwraps the payload of the binary messages sent by the server, reassembling the ones which
+/// were fragmented and answering pings on the way. The websocket is closed when the server
+/// closes it or sends anything but bolt.
+async fn receive_frames<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    mut socket: R,
+    mut bolt: W,
+    writer: SharedWriter,
+    max_message_size: usize,
+) {
+    // the opcode and the payload received so far of a fragmented message
+    let mut message: Option<(u8, Vec<u8>)> = None;
+    // the status of the CLOSE frame answering the server, none when the socket failed
+    let status = loop {
+        let frame = match read_frame(&mut socket, max_message_size).await {
+            Ok(frame) => frame,
+            Err(_) => break None,
+        };
+        match frame.opcode {
+            // control frames may arrive between the fragments of a message, but aren't fragmented
+            opcode if opcode & 0x08 != 0 && !frame.fin => break Some(PROTOCOL_ERROR.to_vec()),
+            PING => {
+                let pong = self::frame(PONG, &frame.payload, random_bytes::<4>());
+                let mut writer = writer.lock().await;
+                if writer.write_all(&pong).await.is_err() || writer.flush().await.is_err() {
+                    break None;
+                }
+                continue;
+            }
+            PONG => continue,
+            // the status code of the server is echoed
+            CLOSE => break Some(frame.payload.get(..2).unwrap_or_default().to_vec()),
+            CONTINUATION => match &mut message {
+                Some((_, payload)) if payload.len() + frame.payload.len() <= max_message_size => {
+                    payload.extend_from_slice(&frame.payload)
+                }
+                Some(_) => break Some(MESSAGE_TOO_BIG.to_vec()),
+                None => break Some(PROTOCOL_ERROR.to_vec()),
+            },
+            BINARY | TEXT if message.is_none() => message = Some((frame.opcode, frame.payload)),
+            _ => break Some(PROTOCOL_ERROR.to_vec()),
+        }
+        if !frame.fin {
+            continue;
+        }
+        match message.take() {
+            Some((BINARY, payload)) => {
+                if bolt.write_all(&payload).await.is_err() {
+                    break None;
+                }
+            }
+            _ => break Some(UNSUPPORTED_DATA.to_vec()),
+        }
+    };
+    if let Some(status) = status {
+        let close = frame(CLOSE, &status, random_bytes::<4>());
+        let mut writer = writer.lock().await;
+        // nothing is sent once the websocket is closed
+        if writer.write_all(&close).await.is_ok() && writer.flush().await.is_ok() {
+            let _ = writer.shutdown().await;
+        }
+    }
+    // the connection reads the end of the stream once the frames can't be unwrapped anymore
+    let _ = bolt.shutdown().await;
+}
+
+/// A frame received from the server
+struct Frame {
+    /// Whether the frame is the last one of its message
+    fin: bool,
+    opcode: u8,
+    payload: Vec<u8>,
+}
+
+/// Reads a frame of at most `max_size` bytes
+async fn read_frame<R: AsyncRead + Unpin>(socket: &mut R, max_size: usize) -> Result<Frame> {
+    let head = socket.read_u8().await?;
+    let length = socket.read_u8().await?;
+    let masked = length & 0x80 != 0;
+    let length = match length & 0x7F {
+        126 => socket.read_u16().await? as usize,
+        127 => usize::try_from(socket.read_u64().await?)
+            .map_err(|_| websocket_error("frame too large"))?,
+        length => length as usize,
+    };
+    if length > max_size {
+        return Err(websocket_error("frame too large"));
+    }
+    let mut mask = [0; 4];
+    if masked {
+        socket.read_exact(&mut mask).await?;
+    }
+    let mut payload = vec![0; length];
+    socket.read_exact(&mut payload).await?;
+    if masked {
+        apply_mask(&mut payload, mask);
+    }
+    Ok(Frame {
+        fin: head & 0x80 != 0,
+        opcode: head & 0x0F,
+        payload,
+    })
+}
+
+/// A final frame, masked as required for frames sent by clients
+fn frame(opcode: u8, payload: &[u8], mask: [u8; 4]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 14);
+    frame.push(0x80 | opcode);
+    match payload.len() {
+        length if length < 126 => frame.push(0x80 | length as u8),
+        length if length <= u16::MAX as usize => {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(length as u16).to_be_bytes());
+        }
+        length => {
+            frame.push(0x80 | 127);
+            frame.extend_from_slice(&(length as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(&mask);
+    let start = frame.len();
+    frame.extend_from_slice(payload);
+    apply_mask(&mut frame[start..], mask);
+    frame
+}
+
+fn apply_mask(payload: &mut [u8], mask: [u8; 4]) {
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+}
+
+/// Unpredictable bytes for the key and the masks, which only need to be hard to guess by
+/// intermediaries
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    for chunk in bytes.chunks_mut(8) {
+        let random = RandomState::new().build_hasher().finish().to_le_bytes();
+        chunk.copy_from_slice(&random[..chunk.len()]);
+    }
+    bytes
+}
+
+fn base64(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let triple = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[(triple >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// SHA-1 is only used to check the accept key of the upgrade response
+fn sha1(input: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = input.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((input.len() as u64) * 8).to_be_bytes());
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (value, added) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(added);
+        }
+    }
+    let mut digest = [0; 20];
+    for (bytes, value) in digest.chunks_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+fn websocket_error(detail: &str) -> Error {
+    Error::IOError {
+        detail: format!("websocket: {}", detail),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_compute_the_accept_key() {
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"a"), "YQ==");
+    }
+
+    #[tokio::test]
+    async fn should_frame_and_unframe_payloads() {
+        let small = frame(BINARY, &[0x60, 0x60, 0xB0, 0x17], [1, 2, 3, 4]);
+        assert_eq!(small, vec![0x82, 0x84, 1, 2, 3, 4, 0x61, 0x62, 0xB3, 0x13]);
+
+        let large = vec![7; 300];
+        let encoded = frame(BINARY, &large, [9, 8, 7, 6]);
+        assert_eq!(&encoded[..4], &[0x82, 0x80 | 126, 0x01, 0x2C]);
+        let frame = read_frame(&mut encoded.as_slice(), MAX_FRAME_SIZE)
+            .await
+            .unwrap();
+
+        assert!(frame.fin);
+        assert_eq!(frame.opcode, BINARY);
+        assert_eq!(frame.payload, large);
+    }
+
+    #[tokio::test]
+    async fn should_refuse_large_frames() {
+        let large = frame(BINARY, &[7; 300], [9, 8, 7, 6]);
+        let huge = [0x82, 127, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+
+        for frame in [&large[..], &huge[..]] {
+            let error = read_frame(&mut &frame[..], 256).await.err().unwrap();
+            assert!(
+                matches!(&error, Error::IOError { detail } if detail == "websocket: frame too large"),
+                "{:?}",
+                error
+            );
+        }
+    }
+
+    /// Answers the upgrade request of the client
+    async fn accept(server: &mut tokio::io::DuplexStream) {
+        let request = read_response_header(server).await.unwrap();
+        let key = request
+            .split("\r\n")
+            .find_map(|line| line.strip_prefix("Sec-WebSocket-Key: "))
+            .unwrap()
+            .to_owned();
+        let response = format!(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+             Connection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+            accept_key(&key)
+        );
+        server.write_all(response.as_bytes()).await.unwrap();
+    }
+
+    /// A frame sent by the server, which doesn't mask them
+    fn server_frame(fin: bool, opcode: u8, payload: &[u8]) -> Vec<u8> {
+        let mut frame = frame(opcode, payload, [0; 4]);
+        if !fin {
+            frame[0] &= 0x7F;
+        }
+        frame[1] &= 0x7F;
+        let mask = match payload.len() {
+            0..=125 => 2,
+            126..=0xFFFF => 4,
+            _ => 10,
+        };
+        frame.drain(mask..mask + 4);
+        frame
+    }
+
+    #[tokio::test]
+    async fn should_speak_bolt_over_websockets() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let server = tokio::spawn(async move {
+            accept(&mut server).await;
+            let preamble = read_frame(&mut server, MAX_FRAME_SIZE).await.unwrap();
+            server.write_all(&[0x89, 0x00]).await.unwrap();
+            server.write_all(&[0x82, 0x04, 0, 0, 4, 4]).await.unwrap();
+            let pong = read_frame(&mut server, MAX_FRAME_SIZE).await.unwrap();
+            (preamble.opcode, preamble.payload, pong.opcode)
+        });
+
+        let mut transport = upgrade(Box::new(client), "localhost:7687", None)
+            .await
+            .unwrap();
+        transport
+            .write_all(&[0x60, 0x60, 0xB0, 0x17])
+            .await
+            .unwrap();
+        transport.flush().await.unwrap();
+        let mut version = [0; 4];
+        transport.read_exact(&mut version).await.unwrap();
+
+        assert_eq!(version, [0, 0, 4, 4]);
+        assert_eq!(
+            server.await.unwrap(),
+            (BINARY, vec![0x60, 0x60, 0xB0, 0x17], PONG)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reassemble_fragmented_messages() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let server = tokio::spawn(async move {
+            accept(&mut server).await;
+            server
+                .write_all(&server_frame(false, BINARY, &[0, 0]))
+                .await
+                .unwrap();
+            server
+                .write_all(&server_frame(true, PING, b"ping"))
+                .await
+                .unwrap();
+            server
+                .write_all(&server_frame(false, CONTINUATION, &[4]))
+                .await
+                .unwrap();
+            server
+                .write_all(&server_frame(true, CONTINUATION, &[4]))
+                .await
+                .unwrap();
+            let pong = read_frame(&mut server, MAX_FRAME_SIZE).await.unwrap();
+            (pong.opcode, pong.payload)
+        });
+
+        let mut transport = upgrade(Box::new(client), "localhost:7687", None)
+            .await
+            .unwrap();
+        let mut version = [0; 4];
+        transport.read_exact(&mut version).await.unwrap();
+
+        assert_eq!(version, [0, 0, 4, 4]);
+        assert_eq!(server.await.unwrap(), (PONG, b"ping".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn should_answer_close_frames() {
+        let large = vec![7; MAX_FRAME_SIZE];
+        for (frames, status) in [
+            (
+                vec![server_frame(true, CLOSE, &[0x03, 0xE8])],
+                vec![0x03, 0xE8],
+            ),
+            (vec![server_frame(true, CLOSE, &[])], vec![]),
+            (
+                vec![server_frame(true, TEXT, b"bolt")],
+                UNSUPPORTED_DATA.to_vec(),
+            ),
+            (
+                vec![server_frame(true, CONTINUATION, &[4])],
+                PROTOCOL_ERROR.to_vec(),
+            ),
+            (
+                vec![server_frame(false, PING, &[])],
+                PROTOCOL_ERROR.to_vec(),
+            ),
+            (
+                vec![
+                    server_frame(false, BINARY, &large),
+                    server_frame(true, CONTINUATION, &[7]),
+                ],
+                MESSAGE_TOO_BIG.to_vec(),
+            ),
+        ] {
+            let (client, mut server) = tokio::io::duplex(4 * MAX_FRAME_SIZE);
+            let server = tokio::spawn(async move {
+                accept(&mut server).await;
+                for frame in frames {
+                    server.write_all(&frame).await.unwrap();
+                }
+                read_frame(&mut server, MAX_FRAME_SIZE).await.unwrap()
+            });
+
+            let mut transport = upgrade(Box::new(client), "localhost:7687", Some(MAX_FRAME_SIZE))
+                .await
+                .unwrap();
+            let close = server.await.unwrap();
+
+            assert_eq!(transport.read(&mut [0; 4]).await.unwrap(), 0);
+            assert_eq!((close.opcode, close.payload), (CLOSE, status));
+        }
+    }
+}
@@ -1,7 +1,74 @@
+use crate::duration::IsoDuration;
 use crate::errors::*;
 use crate::row::*;
 use crate::types::*;
-use std::convert::{TryFrom, TryInto};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+#[cfg(any(feature = "chrono", feature = "time"))]
+use std::convert::TryInto;
+
+/// A value that can be passed as a parameter with [`Query::param`](crate::Query::param)
+///
+/// It is implemented for every type converting into a [`BoltType`], and can be implemented for
+/// domain types like newtypes of identifiers so they are passed without unwrapping them:
+///
+/// ```
+/// use neo4rs::*;
+///
+/// struct UserId(i64);
+///
+/// impl IntoBoltParam for UserId {
+///     fn into_bolt_param(self) -> BoltType {
+///         self.0.into()
+///     }
+/// }
+///
+/// impl FromBoltValue for UserId {
+///     fn from_bolt_value(value: BoltType) -> Result<Self> {
+///         i64::from_bolt_value(value).map(UserId)
+///     }
+/// }
+///
+/// let q = query("MATCH (u:User {id: $id}) RETURN u.id AS id").param("id", UserId(42));
+/// assert_eq!(q.get_param::<UserId>("id").map(|id| id.0), Some(42));
+/// ```
+pub trait IntoBoltParam {
+    fn into_bolt_param(self) -> BoltType;
+}
+
+impl<T: Into<BoltType>> IntoBoltParam for T {
+    fn into_bolt_param(self) -> BoltType {
+        self.into()
+    }
+}
+
+/// A value that can be extracted from rows, nodes, relationships and maps with their `get`
+/// methods, the counterpart of [`IntoBoltParam`]
+///
+/// It is implemented for every type converting from a [`BoltType`] with [`TryFrom`], failing
+/// with an error converting into [`Error`].
+pub trait FromBoltValue: Sized {
+    fn from_bolt_value(value: BoltType) -> Result<Self>;
+}
+
+impl<T> FromBoltValue for T
+where
+    T: TryFrom<BoltType>,
+    Error: From<T::Error>,
+{
+    fn from_bolt_value(value: BoltType) -> Result<Self> {
+        T::try_from(value).map_err(Error::from)
+    }
+}
+
+/// The error of a value extracted as a type it doesn't convert to
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn mismatch(expected: &'static str, found: &BoltType) -> Error {
+    Error::TypeMismatch {
+        expected,
+        found: found.type_name(),
+    }
+}
 
 impl TryFrom<BoltType> for f64 {
     type Error = Error;
@@ -14,6 +81,23 @@ impl TryFrom<BoltType> for f64 {
     }
 }
 
+/// A number extracted as a float whether it is stored as a float or as an integer, since rows
+/// only convert integers into `f64` on request. Integers beyond 2<sup>53</sup> lose precision.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AsFloat(pub f64);
+
+impl TryFrom<BoltType> for AsFloat {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<AsFloat> {
+        match input {
+            BoltType::Float(t) => Ok(AsFloat(t.value)),
+            BoltType::Integer(t) => Ok(AsFloat(t.value as f64)),
+            _ => Err(Error::ConverstionError),
+        }
+    }
+}
+
 impl TryFrom<BoltType> for i64 {
     type Error = Error;
 
@@ -47,80 +131,248 @@ impl TryFrom<BoltType> for Point2D {
     }
 }
 
+impl TryFrom<BoltType> for IsoDuration {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<IsoDuration> {
+        match input {
+            BoltType::Duration(d) => Ok(d.into()),
+            _ => Err(Error::ConverstionError),
+        }
+    }
+}
+
 impl TryFrom<BoltType> for std::time::Duration {
     type Error = Error;
 
     fn try_from(input: BoltType) -> Result<std::time::Duration> {
         match input {
-            BoltType::Duration(d) => Ok(d.into()),
+            BoltType::Duration(d) => IsoDuration::from(d).to_std(),
             _ => Err(Error::ConverstionError),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryFrom<BoltType> for chrono::NaiveDate {
     type Error = Error;
 
     fn try_from(input: BoltType) -> Result<chrono::NaiveDate> {
         match input {
             BoltType::Date(d) => d.try_into(),
-            _ => Err(Error::ConverstionError),
+            other => Err(mismatch("date", &other)),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryFrom<BoltType> for chrono::DateTime<chrono::FixedOffset> {
     type Error = Error;
 
     fn try_from(input: BoltType) -> Result<chrono::DateTime<chrono::FixedOffset>> {
         match input {
             BoltType::DateTime(d) => d.try_into(),
-            _ => Err(Error::ConverstionError),
+            other => Err(mismatch("date time", &other)),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryFrom<BoltType> for chrono::NaiveDateTime {
     type Error = Error;
 
     fn try_from(input: BoltType) -> Result<chrono::NaiveDateTime> {
         match input {
             BoltType::LocalDateTime(d) => d.try_into(),
-            _ => Err(Error::ConverstionError),
+            other => Err(mismatch("local date time", &other)),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryFrom<BoltType> for (chrono::NaiveTime, Option<chrono::FixedOffset>) {
     type Error = Error;
 
     fn try_from(input: BoltType) -> Result<(chrono::NaiveTime, Option<chrono::FixedOffset>)> {
         match input {
             BoltType::Time(bolt_time) => {
-                let (time, offset) = bolt_time.into();
+                let (time, offset): (_, chrono::FixedOffset) = bolt_time.try_into()?;
                 if offset.local_minus_utc() == 0 {
                     Ok((time, None))
                 } else {
                     Ok((time, Some(offset)))
                 }
             }
-            BoltType::LocalTime(d) => Ok((d.into(), None)),
-            _ => Err(Error::ConverstionError),
+            BoltType::LocalTime(d) => Ok((d.try_into()?, None)),
+            other => Err(mismatch("time", &other)),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<BoltType> for chrono::NaiveTime {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<chrono::NaiveTime> {
+        match input {
+            BoltType::LocalTime(t) => t.try_into(),
+            other => Err(mismatch("local time", &other)),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
+impl TryFrom<BoltType> for (chrono::NaiveTime, chrono::FixedOffset) {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<(chrono::NaiveTime, chrono::FixedOffset)> {
+        match input {
+            BoltType::Time(t) => t.try_into(),
+            other => Err(mismatch("time", &other)),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
 impl TryFrom<BoltType> for (chrono::NaiveDateTime, String) {
     type Error = Error;
 
     fn try_from(input: BoltType) -> Result<(chrono::NaiveDateTime, String)> {
         match input {
             BoltType::DateTimeZoneId(date_time_zone_id) => date_time_zone_id.try_into(),
+            other => Err(mismatch("date time with a zone id", &other)),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<BoltType> for chrono::DateTime<chrono_tz::Tz> {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<chrono::DateTime<chrono_tz::Tz>> {
+        match input {
+            BoltType::DateTimeZoneId(date_time_zone_id) => date_time_zone_id.try_into(),
+            other => Err(mismatch("date time with a zone id", &other)),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltType> for ::time::Date {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<::time::Date> {
+        match input {
+            BoltType::Date(d) => d.try_into(),
+            other => Err(mismatch("date", &other)),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltType> for ::time::OffsetDateTime {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<::time::OffsetDateTime> {
+        match input {
+            BoltType::DateTime(d) => d.try_into(),
+            other => Err(mismatch("date time", &other)),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltType> for ::time::PrimitiveDateTime {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<::time::PrimitiveDateTime> {
+        match input {
+            BoltType::LocalDateTime(d) => d.try_into(),
+            other => Err(mismatch("local date time", &other)),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltType> for (::time::Time, Option<::time::UtcOffset>) {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<(::time::Time, Option<::time::UtcOffset>)> {
+        match input {
+            BoltType::Time(bolt_time) => {
+                let (time, offset): (::time::Time, ::time::UtcOffset) = bolt_time.try_into()?;
+                if offset.is_utc() {
+                    Ok((time, None))
+                } else {
+                    Ok((time, Some(offset)))
+                }
+            }
+            BoltType::LocalTime(d) => Ok((d.try_into()?, None)),
+            other => Err(mismatch("time", &other)),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltType> for ::time::Time {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<::time::Time> {
+        match input {
+            BoltType::LocalTime(t) => t.try_into(),
+            other => Err(mismatch("local time", &other)),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltType> for (::time::Time, ::time::UtcOffset) {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<(::time::Time, ::time::UtcOffset)> {
+        match input {
+            BoltType::Time(t) => t.try_into(),
+            other => Err(mismatch("time", &other)),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltType> for (::time::PrimitiveDateTime, String) {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<(::time::PrimitiveDateTime, String)> {
+        match input {
+            BoltType::DateTimeZoneId(date_time_zone_id) => date_time_zone_id.try_into(),
+            other => Err(mismatch("date time with a zone id", &other)),
+        }
+    }
+}
+
+/// Accepts 2D points of any coordinate reference system
+#[cfg(feature = "geo")]
+impl TryFrom<BoltType> for geo_types::Coord<f64> {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<geo_types::Coord<f64>> {
+        match input {
+            BoltType::Point2D(p) => Ok(geo_types::coord! { x: p.x.value, y: p.y.value }),
             _ => Err(Error::ConverstionError),
         }
     }
 }
 
+/// Accepts 2D points of any coordinate reference system, x is the longitude and y the latitude
+/// of WGS-84 points
+#[cfg(feature = "geo")]
+impl TryFrom<BoltType> for geo_types::Point<f64> {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<geo_types::Point<f64>> {
+        geo_types::Coord::try_from(input).map(geo_types::Point::from)
+    }
+}
+
 impl TryFrom<BoltType> for Vec<u8> {
     type Error = Error;
 
@@ -132,6 +384,17 @@ impl TryFrom<BoltType> for Vec<u8> {
     }
 }
 
+impl TryFrom<BoltType> for bytes::Bytes {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<bytes::Bytes> {
+        match input {
+            BoltType::Bytes(b) => Ok(b.value),
+            _ => Err(Error::ConverstionError),
+        }
+    }
+}
+
 impl TryFrom<BoltType> for Point3D {
     type Error = Error;
 
@@ -197,6 +460,16 @@ impl TryFrom<BoltType> for BoltList {
     }
 }
 
+impl TryFrom<BoltType> for BoltMap {
+    type Error = Error;
+    fn try_from(input: BoltType) -> Result<BoltMap> {
+        match input {
+            BoltType::Map(m) => Ok(m),
+            _ => Err(Error::ConverstionError),
+        }
+    }
+}
+
 impl TryFrom<BoltType> for BoltString {
     type Error = Error;
     fn try_from(input: BoltType) -> Result<BoltString> {
@@ -211,80 +484,646 @@ impl TryFrom<BoltType> for String {
     type Error = Error;
     fn try_from(input: BoltType) -> Result<String> {
         match input {
-            BoltType::String(t) => Ok(t.value),
+            BoltType::String(t) => Ok(t.into()),
+            _ => Err(Error::ConverstionError),
+        }
+    }
+}
+
+impl TryFrom<BoltType> for f32 {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<f32> {
+        f64::try_from(input).map(|value| value as f32)
+    }
+}
+
+macro_rules! try_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<BoltType> for $t {
+                type Error = Error;
+
+                fn try_from(input: BoltType) -> Result<$t> {
+                    i64::try_from(input).and_then(|value| {
+                        <$t>::try_from(value).map_err(|_| Error::IntegerOverflow {
+                            value,
+                            target: stringify!($t),
+                        })
+                    })
+                }
+            }
+        )*
+    };
+}
+
+// u8 is left out, a Vec<u8> is converted from bytes rather than from a list
+try_from_integer!(i8, i16, i32, u16, u32, u64, isize, usize);
+
+impl<T: TryFrom<BoltType>> TryFrom<BoltType> for Vec<T> {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<Vec<T>> {
+        match input {
+            BoltType::List(l) => l
+                .into_iter()
+                .map(|value| T::try_from(value).map_err(|_| Error::ConverstionError))
+                .collect(),
+            _ => Err(Error::ConverstionError),
+        }
+    }
+}
+
+impl<T: TryFrom<BoltType>> TryFrom<BoltType> for HashMap<String, T> {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<HashMap<String, T>> {
+        match input {
+            BoltType::Map(m) => m
+                .value
+                .into_iter()
+                .map(|(key, value)| {
+                    T::try_from(value)
+                        .map(|value| (key.into(), value))
+                        .map_err(|_| Error::ConverstionError)
+                })
+                .collect(),
+            _ => Err(Error::ConverstionError),
+        }
+    }
+}
+
+impl From<IsoDuration> for BoltType {
+    fn from(duration: IsoDuration) -> Self {
+        BoltType::Duration(duration.into())
+    }
+}
+
+impl From<std::time::Duration> for BoltType {
+    fn from(value: std::time::Duration) -> Self {
+        BoltType::Duration(value.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for BoltType {
+    fn from(value: chrono::NaiveDate) -> Self {
+        BoltType::Date(value.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveTime> for BoltType {
+    fn from(value: chrono::NaiveTime) -> Self {
+        BoltType::LocalTime(value.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDateTime> for BoltType {
+    fn from(value: chrono::NaiveDateTime) -> Self {
+        BoltType::LocalDateTime(value.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::FixedOffset>> for BoltType {
+    fn from(value: chrono::DateTime<chrono::FixedOffset>) -> Self {
+        BoltType::DateTime(value.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<(chrono::NaiveTime, chrono::FixedOffset)> for BoltType {
+    fn from(value: (chrono::NaiveTime, chrono::FixedOffset)) -> Self {
+        BoltType::Time(value.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<(chrono::NaiveDateTime, &str)> for BoltType {
+    fn from(value: (chrono::NaiveDateTime, &str)) -> Self {
+        BoltType::DateTimeZoneId(value.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono_tz::Tz>> for BoltType {
+    fn from(value: chrono::DateTime<chrono_tz::Tz>) -> Self {
+        BoltType::DateTimeZoneId(value.into())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<::time::Date> for BoltType {
+    fn from(value: ::time::Date) -> Self {
+        BoltType::Date(value.into())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<::time::Time> for BoltType {
+    fn from(value: ::time::Time) -> Self {
+        BoltType::LocalTime(value.into())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<::time::PrimitiveDateTime> for BoltType {
+    fn from(value: ::time::PrimitiveDateTime) -> Self {
+        BoltType::LocalDateTime(value.into())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<::time::OffsetDateTime> for BoltType {
+    fn from(value: ::time::OffsetDateTime) -> Self {
+        BoltType::DateTime(value.into())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<(::time::Time, ::time::UtcOffset)> for BoltType {
+    fn from(value: (::time::Time, ::time::UtcOffset)) -> Self {
+        BoltType::Time(value.into())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<(::time::PrimitiveDateTime, &str)> for BoltType {
+    fn from(value: (::time::PrimitiveDateTime, &str)) -> Self {
+        BoltType::DateTimeZoneId(value.into())
+    }
+}
+
+/// Points are geographic, a WGS-84 point with the longitude as x and the latitude as y
+#[cfg(feature = "geo")]
+impl From<geo_types::Point<f64>> for BoltType {
+    fn from(point: geo_types::Point<f64>) -> Self {
+        Point2D::wgs84(point.x(), point.y()).into()
+    }
+}
+
+/// Coordinates are cartesian, a point on a plane
+#[cfg(feature = "geo")]
+impl From<geo_types::Coord<f64>> for BoltType {
+    fn from(coord: geo_types::Coord<f64>) -> Self {
+        Point2D::cartesian(coord.x, coord.y).into()
+    }
+}
+
+/// Uuids are sent as their hyphenated string, like `67e55044-10b1-426f-9247-bb680e5fe0c8`, wrap
+/// them in a [`UuidBytes`] to send their 16 bytes instead
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for BoltType {
+    fn from(uuid: uuid::Uuid) -> Self {
+        BoltType::String(uuid.to_hyphenated().to_string().into())
+    }
+}
+
+/// A uuid passed as a parameter as its 16 bytes rather than its string, which takes less space
+/// in the database but can't be read in cypher
+#[cfg(feature = "uuid")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UuidBytes(pub uuid::Uuid);
+
+#[cfg(feature = "uuid")]
+impl From<UuidBytes> for BoltType {
+    fn from(uuid: UuidBytes) -> Self {
+        uuid.0.as_bytes().as_ref().into()
+    }
+}
+
+/// Accepts uuids stored as strings or as 16 bytes
+#[cfg(feature = "uuid")]
+impl TryFrom<BoltType> for uuid::Uuid {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<uuid::Uuid> {
+        match input {
+            BoltType::String(s) => s.parse().map_err(|_| Error::ConverstionError),
+            BoltType::Bytes(b) => {
+                uuid::Uuid::from_slice(&b.value).map_err(|_| Error::ConverstionError)
+            }
+            _ => Err(Error::ConverstionError),
+        }
+    }
+}
+
+/// Decimals are sent as their string, like `"12.30"`, since bolt has no decimal type and floats
+/// would lose precision. Cypher compares such strings as text, so amounts should be compared and
+/// summed in the application, or converted with `toFloat` when precision doesn't matter.
+#[cfg(feature = "rust_decimal")]
+impl From<rust_decimal::Decimal> for BoltType {
+    fn from(decimal: rust_decimal::Decimal) -> Self {
+        BoltType::String(decimal.to_string().into())
+    }
+}
+
+/// Accepts decimals stored as strings and integers, and floats as their shortest decimal
+/// representation
+#[cfg(feature = "rust_decimal")]
+impl TryFrom<BoltType> for rust_decimal::Decimal {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<rust_decimal::Decimal> {
+        match input {
+            BoltType::String(s) => s.parse().map_err(|_| Error::ConverstionError),
+            BoltType::Integer(i) => Ok(i.value.into()),
+            BoltType::Float(f) => f
+                .value
+                .to_string()
+                .parse()
+                .map_err(|_| Error::ConverstionError),
             _ => Err(Error::ConverstionError),
         }
     }
 }
 
-impl Into<BoltType> for std::time::Duration {
-    fn into(self) -> BoltType {
-        BoltType::Duration(self.into())
+/// Big decimals are sent as their string, see the conversion of `rust_decimal::Decimal`
+#[cfg(feature = "bigdecimal")]
+impl From<bigdecimal::BigDecimal> for BoltType {
+    fn from(decimal: bigdecimal::BigDecimal) -> Self {
+        BoltType::String(decimal.to_string().into())
+    }
+}
+
+/// Accepts big decimals stored as strings and integers, and floats as their shortest decimal
+/// representation
+#[cfg(feature = "bigdecimal")]
+impl TryFrom<BoltType> for bigdecimal::BigDecimal {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<bigdecimal::BigDecimal> {
+        match input {
+            BoltType::String(s) => s.parse().map_err(|_| Error::ConverstionError),
+            BoltType::Integer(i) => Ok(i.value.into()),
+            BoltType::Float(f) => f
+                .value
+                .to_string()
+                .parse()
+                .map_err(|_| Error::ConverstionError),
+            _ => Err(Error::ConverstionError),
+        }
+    }
+}
+
+impl From<Vec<u8>> for BoltType {
+    fn from(value: Vec<u8>) -> Self {
+        BoltType::Bytes(BoltBytes::new(value.into()))
+    }
+}
+
+impl From<&[u8]> for BoltType {
+    fn from(value: &[u8]) -> Self {
+        BoltType::Bytes(BoltBytes::new(bytes::Bytes::copy_from_slice(value)))
+    }
+}
+
+impl From<bytes::Bytes> for BoltType {
+    fn from(value: bytes::Bytes) -> Self {
+        BoltType::Bytes(BoltBytes::new(value))
+    }
+}
+
+impl From<f64> for BoltType {
+    fn from(value: f64) -> Self {
+        BoltType::Float(BoltFloat::new(value))
+    }
+}
+
+impl From<i64> for BoltType {
+    fn from(value: i64) -> Self {
+        BoltType::Integer(BoltInteger::new(value))
+    }
+}
+
+impl From<String> for BoltType {
+    fn from(value: String) -> Self {
+        BoltType::String(value.into())
+    }
+}
+
+impl From<&str> for BoltType {
+    fn from(value: &str) -> Self {
+        BoltType::String(value.into())
+    }
+}
+
+impl From<bool> for BoltType {
+    fn from(value: bool) -> Self {
+        BoltType::Boolean(BoltBoolean::new(value))
+    }
+}
+
+impl From<f32> for BoltType {
+    fn from(value: f32) -> Self {
+        BoltType::Float(BoltFloat::new(value.into()))
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BoltType {
+                fn from(value: $t) -> Self {
+                    BoltType::Integer(BoltInteger::new(value.into()))
+                }
+            }
+        )*
+    };
+}
+
+// u8 is left out, a Vec<u8> is sent as bytes rather than as a list
+from_integer!(i8, i16, i32, u16, u32);
+
+impl<T: Into<BoltType>> From<Vec<T>> for BoltType {
+    fn from(value: Vec<T>) -> Self {
+        BoltType::List(value.into_iter().map(Into::into).collect::<Vec<_>>().into())
     }
 }
 
-impl Into<BoltType> for chrono::NaiveDate {
-    fn into(self) -> BoltType {
-        BoltType::Date(self.into())
+impl<K: Into<BoltString>, V: Into<BoltType>> From<HashMap<K, V>> for BoltType {
+    fn from(value: HashMap<K, V>) -> Self {
+        BoltType::Map(
+            value
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
     }
 }
 
-impl Into<BoltType> for chrono::NaiveTime {
-    fn into(self) -> BoltType {
-        BoltType::LocalTime(self.into())
+/// `None` is sent as null
+impl<T: Into<BoltType>> From<Option<T>> for BoltType {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => BoltType::Null(BoltNull),
+        }
     }
 }
 
-impl Into<BoltType> for chrono::NaiveDateTime {
-    fn into(self) -> BoltType {
-        BoltType::LocalDateTime(self.into())
+impl From<BoltString> for BoltType {
+    fn from(value: BoltString) -> Self {
+        BoltType::String(value)
     }
 }
 
-impl Into<BoltType> for chrono::DateTime<chrono::FixedOffset> {
-    fn into(self) -> BoltType {
-        BoltType::DateTime(self.into())
+impl From<BoltList> for BoltType {
+    fn from(value: BoltList) -> Self {
+        BoltType::List(value)
     }
 }
 
-impl Into<BoltType> for (chrono::NaiveTime, chrono::FixedOffset) {
-    fn into(self) -> BoltType {
-        BoltType::Time(self.into())
+impl From<BoltMap> for BoltType {
+    fn from(value: BoltMap) -> Self {
+        BoltType::Map(value)
     }
 }
 
-impl Into<BoltType> for (chrono::NaiveDateTime, &str) {
-    fn into(self) -> BoltType {
-        BoltType::DateTimeZoneId(self.into())
+impl From<BoltNull> for BoltType {
+    fn from(value: BoltNull) -> Self {
+        BoltType::Null(value)
     }
 }
 
-impl Into<BoltType> for Vec<u8> {
-    fn into(self) -> BoltType {
-        BoltType::Bytes(BoltBytes::new(self.into()))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_primitives() {
+        assert_eq!(
+            BoltType::from(true),
+            BoltType::Boolean(BoltBoolean::new(true))
+        );
+        assert_eq!(
+            BoltType::from(42_i32),
+            BoltType::Integer(BoltInteger::new(42))
+        );
+        assert_eq!(
+            BoltType::from(1.5_f32),
+            BoltType::Float(BoltFloat::new(1.5))
+        );
+        assert_eq!(BoltType::from(None::<i64>), BoltType::Null(BoltNull));
+
+        assert_eq!(u16::try_from(BoltType::from(42_i64)).unwrap(), 42);
+        assert!(matches!(
+            i8::try_from(BoltType::from(300_i64)),
+            Err(Error::IntegerOverflow {
+                value: 300,
+                target: "i8"
+            })
+        ));
+        assert!(matches!(
+            u32::try_from(BoltType::from(-1_i64)),
+            Err(Error::IntegerOverflow {
+                value: -1,
+                target: "u32"
+            })
+        ));
+    }
+
+    #[test]
+    fn should_convert_integers_into_floats_only_on_request() {
+        assert!(f64::try_from(BoltType::from(42_i64)).is_err());
+        assert_eq!(
+            AsFloat::try_from(BoltType::from(42_i64)).unwrap(),
+            AsFloat(42.0)
+        );
+        assert_eq!(
+            AsFloat::try_from(BoltType::from(1.5_f64)).unwrap(),
+            AsFloat(1.5)
+        );
+        assert!(AsFloat::try_from(BoltType::from("42")).is_err());
+    }
+
+    #[test]
+    fn should_keep_non_finite_floats() {
+        let nan = f64::try_from(BoltType::from(f64::NAN)).unwrap();
+        assert!(nan.is_nan());
+        assert_eq!(
+            f64::try_from(BoltType::from(f64::INFINITY)).unwrap(),
+            f64::INFINITY
+        );
+        assert_eq!(
+            f32::try_from(BoltType::from(f64::NEG_INFINITY)).unwrap(),
+            f32::NEG_INFINITY
+        );
+    }
+
+    #[test]
+    fn should_convert_lists() {
+        let list = BoltType::from(vec!["a", "b"]);
+
+        assert_eq!(
+            list,
+            BoltType::List(vec![BoltType::from("a"), BoltType::from("b")].into())
+        );
+        assert_eq!(Vec::<String>::try_from(list).unwrap(), vec!["a", "b"]);
+        assert!(Vec::<i64>::try_from(BoltType::from(vec!["a"])).is_err());
+        assert_eq!(
+            BoltType::from(vec![1_u8, 2]),
+            BoltType::Bytes(BoltBytes::new(vec![1_u8, 2].into()))
+        );
+    }
+
+    #[test]
+    fn should_convert_bytes() {
+        let bytes = bytes::Bytes::from_static(&[1, 2, 3]);
+
+        assert_eq!(
+            BoltType::from(bytes.clone()),
+            BoltType::from(vec![1_u8, 2, 3])
+        );
+        assert_eq!(
+            BoltType::from(&[1_u8, 2, 3][..]),
+            BoltType::from(bytes.clone())
+        );
+        assert_eq!(
+            bytes::Bytes::try_from(BoltType::from(bytes.clone())).unwrap(),
+            bytes
+        );
+        assert_eq!(
+            Vec::<u8>::try_from(BoltType::from(bytes)).unwrap(),
+            vec![1, 2, 3]
+        );
+        assert!(Vec::<u8>::try_from(BoltType::from(vec![1_i64, 2])).is_err());
+    }
+
+    #[test]
+    fn should_convert_maps() {
+        let mut map = HashMap::new();
+        map.insert("a", 1_i64);
+        map.insert("b", 2_i64);
+
+        let bolt = BoltType::from(map);
+        let back = HashMap::<String, i64>::try_from(bolt.clone()).unwrap();
+
+        assert_eq!(BoltMap::try_from(bolt).unwrap().get::<i64>("a"), Some(1));
+        assert_eq!(back.len(), 2);
+        assert_eq!(back["b"], 2);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_extract_temporal_values_of_their_own_type_only() {
+        let time = chrono::NaiveTime::from_hms_opt(10, 15, 30).unwrap();
+        let date_time = chrono::NaiveDate::from_ymd_opt(2024, 1, 31)
+            .unwrap()
+            .and_time(time);
+        let mut map = BoltMap::default();
+        map.put("time".into(), time.into());
+        map.put("date_time".into(), date_time.into());
+
+        assert_eq!(map.get::<chrono::NaiveTime>("time"), Some(time));
+        assert_eq!(
+            map.get::<chrono::NaiveDateTime>("date_time"),
+            Some(date_time)
+        );
+        assert!(matches!(
+            map.get_opt::<chrono::NaiveDate>("date_time"),
+            Err(Error::TypeMismatch {
+                expected: "date",
+                found: "local date time"
+            })
+        ));
+        assert!(matches!(
+            map.get_opt::<(chrono::NaiveTime, chrono::FixedOffset)>("time"),
+            Err(Error::TypeMismatch {
+                expected: "time",
+                found: "local time"
+            })
+        ));
     }
 }
 
-impl Into<BoltType> for f64 {
-    fn into(self) -> BoltType {
-        BoltType::Float(BoltFloat::new(self))
+#[cfg(all(test, feature = "geo"))]
+mod geo_tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_geo_points() {
+        let point = geo_types::Point::new(12.5, 41.9);
+
+        let bolt: BoltType = point.into();
+        let p = Point2D::try_from(bolt.clone()).unwrap();
+        let back = geo_types::Point::try_from(bolt).unwrap();
+
+        assert_eq!(p.sr_id(), 4326);
+        assert_eq!((p.x(), p.y()), (12.5, 41.9));
+        assert_eq!(back, point);
+    }
+
+    #[test]
+    fn should_convert_geo_coordinates() {
+        let coord = geo_types::coord! { x: 2.3, y: 4.5 };
+
+        let bolt: BoltType = coord.into();
+        let p = Point2D::try_from(bolt.clone()).unwrap();
+
+        assert_eq!(p.sr_id(), 7203);
+        assert_eq!(geo_types::Coord::try_from(bolt).unwrap(), coord);
+        assert!(geo_types::Coord::<f64>::try_from(BoltType::Null(BoltNull)).is_err());
     }
 }
 
-impl Into<BoltType> for i64 {
-    fn into(self) -> BoltType {
-        BoltType::Integer(BoltInteger::new(self))
+#[cfg(all(test, feature = "uuid"))]
+mod uuid_tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_uuids() {
+        let uuid: uuid::Uuid = "67e55044-10b1-426f-9247-bb680e5fe0c8".parse().unwrap();
+
+        let string: BoltType = uuid.into();
+        let bytes: BoltType = UuidBytes(uuid).into();
+
+        assert_eq!(string, "67e55044-10b1-426f-9247-bb680e5fe0c8".into());
+        assert!(matches!(&bytes, BoltType::Bytes(b) if b.value.len() == 16));
+        assert_eq!(uuid::Uuid::try_from(string).unwrap(), uuid);
+        assert_eq!(uuid::Uuid::try_from(bytes).unwrap(), uuid);
+        assert!(uuid::Uuid::try_from(BoltType::from("not a uuid")).is_err());
     }
 }
 
-impl Into<BoltType> for String {
-    fn into(self) -> BoltType {
-        BoltType::String(self.into())
+#[cfg(all(test, feature = "rust_decimal"))]
+mod decimal_tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn should_convert_decimals_without_losing_precision() {
+        let amount: Decimal = "0.10000000000000000001".parse().unwrap();
+
+        let bolt: BoltType = amount.into();
+
+        assert_eq!(bolt, "0.10000000000000000001".into());
+        assert_eq!(Decimal::try_from(bolt).unwrap(), amount);
+        assert_eq!(
+            Decimal::try_from(BoltType::from(12)).unwrap(),
+            Decimal::new(12, 0)
+        );
+        assert_eq!(
+            Decimal::try_from(BoltType::from(0.1)).unwrap(),
+            Decimal::new(1, 1)
+        );
     }
 }
 
-impl Into<BoltType> for &str {
-    fn into(self) -> BoltType {
-        BoltType::String(self.into())
+#[cfg(all(test, feature = "bigdecimal"))]
+mod bigdecimal_tests {
+    use super::*;
+    use bigdecimal::BigDecimal;
+
+    #[test]
+    fn should_convert_big_decimals() {
+        let amount: BigDecimal = "123456789012345678901234567890.5".parse().unwrap();
+
+        let bolt: BoltType = amount.clone().into();
+
+        assert_eq!(BigDecimal::try_from(bolt).unwrap(), amount);
+        assert!(BigDecimal::try_from(BoltType::from(true)).is_err());
     }
 }
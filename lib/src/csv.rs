@@ -0,0 +1,116 @@
+use crate::errors::Result;
+use crate::row::Row;
+use crate::stream::RowStream;
+use crate::types::BoltType;
+use serde_json::Value;
+use std::convert::TryFrom;
+use std::io::Write;
+
+impl RowStream {
+    /// Fetches all the rows left in the stream and writes them to `writer` as CSV, returning how
+    /// many rows were written. Rows are written as they are fetched, so large results can be
+    /// exported without holding them in memory.
+    ///
+    /// The first line holds the names of the columns. Fields are separated with commas and
+    /// quoted when they hold a comma, a quote or a line break, following RFC 4180. Values are
+    /// formatted as:
+    ///
+    /// * null as an empty field
+    /// * booleans, integers and floats as in Rust, like `true`, `42` or `1.5`
+    /// * strings unchanged and bytes in lowercase hex
+    /// * dates, times and date times in ISO-8601, like `2024-01-31`, `10:15:00+01:00` or
+    ///   `2024-01-31T10:15:00+01:00`, date times with a named zone end with the zone in
+    ///   brackets, like `2024-01-31T10:15:00[Europe/Paris]` (requires the `chrono` feature)
+    /// * durations in ISO-8601, like `P1M2DT3S`
+    /// * points as `{"srid", "x", "y"}` or `{"srid", "x", "y", "z"}` JSON objects
+    /// * lists, maps, nodes, relationships and paths as JSON, see [`Row::to_json`]
+    pub async fn write_csv<W: Write>(&mut self, mut writer: W) -> Result<usize> {
+        let first = self.next().await?;
+        let mut header = self.keys().to_vec();
+        if header.is_empty() {
+            header = first.iter().flat_map(Row::keys).cloned().collect();
+        }
+        write_record(&mut writer, header.iter().map(|key| escape(key)))?;
+        let mut written = 0;
+        let mut row = first;
+        while let Some(current) = row {
+            let fields = current
+                .values()
+                .map(|value| value.and_then(format).map(|field| escape(&field)))
+                .collect::<Result<Vec<_>>>()?;
+            write_record(&mut writer, fields.into_iter())?;
+            written += 1;
+            row = self.next().await?;
+        }
+        writer.flush()?;
+        Ok(written)
+    }
+}
+
+fn write_record<W: Write>(writer: &mut W, fields: impl Iterator<Item = String>) -> Result<()> {
+    let line: Vec<String> = fields.collect();
+    writer.write_all(line.join(",").as_bytes())?;
+    writer.write_all(b"\r\n")?;
+    Ok(())
+}
+
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn format(value: &BoltType) -> Result<String> {
+    Ok(match value {
+        BoltType::Null(_) => String::new(),
+        BoltType::Boolean(b) => b.value.to_string(),
+        BoltType::Integer(i) => i.value.to_string(),
+        BoltType::Float(f) => f.value.to_string(),
+        BoltType::String(s) => s.as_str().to_owned(),
+        BoltType::Bytes(b) => b.value.iter().map(|byte| format!("{:02x}", byte)).collect(),
+        value => match Value::try_from(value.clone())? {
+            Value::String(s) => s,
+            json => json.to_string(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BoltBytes, BoltList, BoltNull};
+
+    #[tokio::test]
+    async fn should_write_rows_as_csv() {
+        let fields: BoltList = vec!["name".into(), "age".into(), "data".into()].into();
+        let rows = vec![
+            Row::new(
+                fields.clone(),
+                vec!["Mark".into(), 42.into(), BoltType::Null(BoltNull)].into(),
+            ),
+            Row::new(
+                fields,
+                vec![
+                    "Doe, \"Jane\"".into(),
+                    1.5.into(),
+                    BoltType::Bytes(BoltBytes::new(vec![0x0a, 0xff].into())),
+                ]
+                .into(),
+            ),
+        ];
+        let mut csv = Vec::new();
+
+        let written = RowStream::from_rows(rows)
+            .write_csv(&mut csv)
+            .await
+            .unwrap();
+
+        assert_eq!(written, 2);
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "name,age,data\r\nMark,42,\r\n\"Doe, \"\"Jane\"\"\",1.5,0aff\r\n"
+        );
+    }
+}
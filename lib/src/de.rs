@@ -0,0 +1,384 @@
+use crate::errors::Error;
+use crate::types::*;
+use serde::de::value::{MapAccessDeserializer, MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use std::fmt;
+
+/// Deserializes a value returned by the server into any type implementing
+/// [`serde::Deserialize`]
+pub(crate) fn from_bolt<T: DeserializeOwned>(value: BoltType) -> crate::errors::Result<T> {
+    T::deserialize(BoltTypeDeserializer(value)).map_err(|e| Error::DeserializationError(e.0))
+}
+
+/// Deserializes a node or a relationship, see [`EntityDeserializer`]
+pub(crate) fn from_entity<T: DeserializeOwned>(
+    entity: EntityDeserializer,
+) -> crate::errors::Result<T> {
+    T::deserialize(entity).map_err(|e| Error::DeserializationError(e.0))
+}
+
+/// The error raised by serde while mapping a value, turned into
+/// [`Error::DeserializationError`] once deserialization failed
+#[derive(Debug)]
+pub(crate) struct DeError(String);
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeError(msg.to_string())
+    }
+}
+
+pub(crate) struct BoltTypeDeserializer(BoltType);
+
+/// Deserializes the properties of a node or a relationship, structs can also receive the
+/// identity of the entity through fields with the reserved names like `__id`.
+pub(crate) struct EntityDeserializer {
+    properties: BoltMap,
+    identity: Vec<(&'static str, BoltType)>,
+}
+
+impl EntityDeserializer {
+    pub(crate) fn node(node: BoltNode) -> Self {
+        EntityDeserializer {
+            properties: node.properties,
+            identity: vec![
+                ("__id", BoltType::Integer(node.id)),
+                ("__element_id", element_id(node.element_id)),
+                ("__labels", BoltType::List(node.labels)),
+            ],
+        }
+    }
+
+    pub(crate) fn relation(relation: BoltRelation) -> Self {
+        EntityDeserializer {
+            properties: relation.properties,
+            identity: vec![
+                ("__id", BoltType::Integer(relation.id)),
+                ("__element_id", element_id(relation.element_id)),
+                ("__type", BoltType::String(relation.typ)),
+                ("__start_node_id", BoltType::Integer(relation.start_node_id)),
+                ("__end_node_id", BoltType::Integer(relation.end_node_id)),
+                (
+                    "__start_node_element_id",
+                    element_id(relation.start_node_element_id),
+                ),
+                (
+                    "__end_node_element_id",
+                    element_id(relation.end_node_element_id),
+                ),
+            ],
+        }
+    }
+
+    fn unbounded_relation(relation: BoltUnboundedRelation) -> Self {
+        EntityDeserializer {
+            properties: relation.properties,
+            identity: vec![
+                ("__id", BoltType::Integer(relation.id)),
+                ("__element_id", element_id(relation.element_id)),
+                ("__type", BoltType::String(relation.typ)),
+            ],
+        }
+    }
+}
+
+/// Element ids are only sent from bolt 5.0 onwards, the fields receive null before
+fn element_id(element_id: Option<BoltString>) -> BoltType {
+    element_id.map_or(BoltType::Null(BoltNull), BoltType::String)
+}
+
+impl<'de> IntoDeserializer<'de, DeError> for BoltType {
+    type Deserializer = BoltTypeDeserializer;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        BoltTypeDeserializer(self)
+    }
+}
+
+fn properties(map: BoltMap) -> impl Iterator<Item = (String, BoltType)> {
+    map.value
+        .into_iter()
+        .map(|(key, value)| (key.into(), value))
+}
+
+impl<'de> de::Deserializer<'de> for BoltTypeDeserializer {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            BoltType::Null(_) => visitor.visit_unit(),
+            BoltType::Boolean(b) => visitor.visit_bool(b.value),
+            BoltType::Integer(i) => visitor.visit_i64(i.value),
+            BoltType::Float(f) => visitor.visit_f64(f.value),
+            BoltType::String(s) => visitor.visit_string(s.into()),
+            BoltType::Bytes(b) => visitor.visit_byte_buf(b.value.to_vec()),
+            BoltType::List(l) => visitor.visit_seq(SeqDeserializer::new(l.value.into_iter())),
+            BoltType::Map(m) => visitor.visit_map(MapDeserializer::new(properties(m))),
+            BoltType::Node(n) => EntityDeserializer::node(n).deserialize_any(visitor),
+            BoltType::Relation(r) => EntityDeserializer::relation(r).deserialize_any(visitor),
+            BoltType::UnboundedRelation(r) => {
+                EntityDeserializer::unbounded_relation(r).deserialize_any(visitor)
+            }
+            BoltType::Point2D(p) => {
+                let coordinates = vec![
+                    ("srid", BoltType::Integer(p.sr_id)),
+                    ("x", BoltType::Float(p.x)),
+                    ("y", BoltType::Float(p.y)),
+                ];
+                visitor.visit_map(MapDeserializer::new(coordinates.into_iter()))
+            }
+            BoltType::Point3D(p) => {
+                let coordinates = vec![
+                    ("srid", BoltType::Integer(p.sr_id)),
+                    ("x", BoltType::Float(p.x)),
+                    ("y", BoltType::Float(p.y)),
+                    ("z", BoltType::Float(p.z)),
+                ];
+                visitor.visit_map(MapDeserializer::new(coordinates.into_iter()))
+            }
+            other => Err(de::Error::custom(format!(
+                "unable to deserialize {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0 {
+            BoltType::Null(_) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        match self.0 {
+            BoltType::Node(n) => {
+                EntityDeserializer::node(n).deserialize_struct(name, fields, visitor)
+            }
+            BoltType::Relation(r) => {
+                EntityDeserializer::relation(r).deserialize_struct(name, fields, visitor)
+            }
+            BoltType::UnboundedRelation(r) => {
+                EntityDeserializer::unbounded_relation(r).deserialize_struct(name, fields, visitor)
+            }
+            other => BoltTypeDeserializer(other).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        match self.0 {
+            BoltType::String(s) => visitor.visit_enum(String::from(s).into_deserializer()),
+            BoltType::Map(m) if m.len() == 1 => visitor.visit_enum(MapAccessDeserializer::new(
+                MapDeserializer::new(properties(m)),
+            )),
+            other => Err(de::Error::custom(format!(
+                "expected a string or a map with a single entry for an enum, found {:?}",
+                other
+            ))),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map
+        identifier ignored_any
+    }
+}
+
+impl<'de> de::Deserializer<'de> for EntityDeserializer {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_map(MapDeserializer::new(properties(self.properties)))
+    }
+
+    /// The identity is only passed to the fields asking for it, so it doesn't trip up structs
+    /// denying unknown fields
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let identity = self
+            .identity
+            .into_iter()
+            .filter(|(key, _)| fields.contains(key))
+            .map(|(key, value)| (key.to_owned(), value));
+        let entries = properties(self.properties).chain(identity);
+        visitor.visit_map(MapDeserializer::new(entries))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    fn map(entries: Vec<(&str, BoltType)>) -> BoltType {
+        BoltType::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Role {
+        Admin,
+        Reader,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Person {
+        name: String,
+        age: u8,
+        score: f64,
+        nickname: Option<String>,
+        tags: Vec<String>,
+        role: Role,
+    }
+
+    #[test]
+    fn should_deserialize_map_into_struct() {
+        let tags: BoltType = BoltType::List(vec!["a".into(), "b".into()].into());
+        let value = map(vec![
+            ("name", "Mark".into()),
+            ("age", 42.into()),
+            ("score", 7.into()),
+            ("nickname", BoltType::Null(BoltNull)),
+            ("tags", tags),
+            ("role", "Admin".into()),
+        ]);
+
+        let person: Person = from_bolt(value).unwrap();
+
+        assert_eq!(
+            person,
+            Person {
+                name: "Mark".into(),
+                age: 42,
+                score: 7.0,
+                nickname: None,
+                tags: vec!["a".into(), "b".into()],
+                role: Role::Admin,
+            }
+        );
+    }
+
+    #[test]
+    fn should_deserialize_row_columns_into_fields() {
+        #[derive(Deserialize)]
+        struct Count {
+            label: String,
+            total: i64,
+        }
+        let fields: BoltList = vec!["label".into(), "total".into()].into();
+        let data: BoltList = vec!["Person".into(), 3.into()].into();
+
+        let count: Count = crate::row::Row::new(fields, data).to().unwrap();
+
+        assert_eq!(count.label, "Person");
+        assert_eq!(count.total, 3);
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(deny_unknown_fields)]
+    struct Friend {
+        __id: i64,
+        __labels: Vec<String>,
+        name: String,
+    }
+
+    #[test]
+    fn should_deserialize_node_with_identity() {
+        let labels: BoltList = vec!["Person".into()].into();
+        let properties = vec![("name".into(), "Mark".into())].into_iter().collect();
+        let node = BoltNode::new(BoltInteger::new(7), labels, properties);
+
+        let friend: Friend = from_bolt(BoltType::Node(node.clone())).unwrap();
+        let row = map(vec![("friend", BoltType::Node(node))]);
+        let nested: HashMap<String, Friend> = from_bolt(row).unwrap();
+
+        let expected = Friend {
+            __id: 7,
+            __labels: vec!["Person".into()],
+            name: "Mark".into(),
+        };
+        assert_eq!(friend, expected);
+        assert_eq!(nested["friend"], expected);
+    }
+
+    #[test]
+    fn should_deserialize_relation_properties_only() {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Knows {
+            since: i64,
+        }
+        let properties = vec![("since".into(), 2010.into())].into_iter().collect();
+        let relation = BoltRelation::new(
+            BoltInteger::new(1),
+            BoltInteger::new(2),
+            BoltInteger::new(3),
+            BoltString::new("KNOWS"),
+            properties,
+        );
+
+        let knows: Knows = from_bolt(BoltType::Relation(relation)).unwrap();
+
+        assert_eq!(knows.since, 2010);
+    }
+
+    #[test]
+    fn should_fail_on_mismatched_types() {
+        let value = map(vec![("name", 42.into())]);
+
+        let result = from_bolt::<Person>(value);
+
+        assert!(matches!(result, Err(Error::DeserializationError(_))));
+    }
+
+    #[test]
+    fn should_fail_on_integer_overflow() {
+        let result = from_bolt::<u8>(300.into());
+
+        assert!(matches!(result, Err(Error::DeserializationError(_))));
+        assert_eq!(from_bolt::<Role>("Reader".into()).unwrap(), Role::Reader);
+    }
+}
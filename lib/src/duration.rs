@@ -0,0 +1,248 @@
+use crate::errors::{Error, Result};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+/// The average length of a month in seconds, used when a duration has to be expressed in
+/// seconds only
+const SECONDS_PER_MONTH: i64 = 2_629_800;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A temporal amount as stored by Neo4j, made of months, days and seconds which are kept apart
+/// because the length of months and days varies.
+///
+/// Formats to and parses from ISO-8601 strings like `P1Y2M3DT4H5M6.5S`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct IsoDuration {
+    pub months: i64,
+    pub days: i64,
+    pub seconds: i64,
+    /// Always within `0..1_000_000_000`, negative durations borrow from `seconds`
+    pub nanoseconds: i32,
+}
+
+impl IsoDuration {
+    pub fn new(months: i64, days: i64, seconds: i64, nanoseconds: i32) -> Self {
+        let total = seconds as i128 * NANOS_PER_SECOND + nanoseconds as i128;
+        IsoDuration {
+            months,
+            days,
+            seconds: total.div_euclid(NANOS_PER_SECOND) as i64,
+            nanoseconds: total.rem_euclid(NANOS_PER_SECOND) as i32,
+        }
+    }
+
+    /// Converts the duration to a [`std::time::Duration`], counting a month as 30.44 days and a
+    /// day as 24 hours. Fails for negative durations.
+    pub fn to_std(&self) -> Result<std::time::Duration> {
+        let seconds = self
+            .months
+            .checked_mul(SECONDS_PER_MONTH)
+            .and_then(|months| self.days.checked_mul(SECONDS_PER_DAY)?.checked_add(months))
+            .and_then(|days| days.checked_add(self.seconds))
+            .ok_or(Error::ConverstionError)?;
+        let seconds = u64::try_from(seconds).map_err(|_| Error::ConverstionError)?;
+        Ok(std::time::Duration::new(seconds, self.nanoseconds as u32))
+    }
+}
+
+impl From<std::time::Duration> for IsoDuration {
+    fn from(duration: std::time::Duration) -> Self {
+        IsoDuration::new(
+            0,
+            0,
+            duration.as_secs() as i64,
+            duration.subsec_nanos() as i32,
+        )
+    }
+}
+
+impl fmt::Display for IsoDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.seconds as i128 * NANOS_PER_SECOND + self.nanoseconds as i128;
+        if self.months == 0 && self.days == 0 && total == 0 {
+            return f.write_str("PT0S");
+        }
+        f.write_str("P")?;
+        let (years, months) = (self.months / 12, self.months % 12);
+        for (value, unit) in [(years, 'Y'), (months, 'M'), (self.days, 'D')].iter() {
+            if *value != 0 {
+                write!(f, "{}{}", value, unit)?;
+            }
+        }
+        if total == 0 {
+            return Ok(());
+        }
+        f.write_str("T")?;
+        let sign = if total < 0 { "-" } else { "" };
+        let total = total.abs();
+        let seconds = total / NANOS_PER_SECOND;
+        let nanoseconds = total % NANOS_PER_SECOND;
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        if hours != 0 {
+            write!(f, "{}{}H", sign, hours)?;
+        }
+        if minutes != 0 {
+            write!(f, "{}{}M", sign, minutes)?;
+        }
+        if seconds != 0 || nanoseconds != 0 {
+            write!(f, "{}{}", sign, seconds)?;
+            if nanoseconds != 0 {
+                let fraction = format!("{:09}", nanoseconds);
+                write!(f, ".{}", fraction.trim_end_matches('0'))?;
+            }
+            f.write_str("S")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for IsoDuration {
+    type Err = Error;
+
+    /// Parses `PnYnMnWnDTnHnMnS` durations, every component is optional and may be negative,
+    /// only the seconds may have a fraction.
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid =
+            || Error::DeserializationError(format!("invalid ISO-8601 duration {}", value));
+        let rest = value.strip_prefix('P').ok_or_else(invalid)?;
+        let (date, time) = match rest.find('T') {
+            Some(index) => (&rest[..index], Some(&rest[index + 1..])),
+            None => (rest, None),
+        };
+        if rest.is_empty() || time == Some("") {
+            return Err(invalid());
+        }
+
+        let (mut months, mut days) = (0i64, 0i64);
+        for (amount, unit) in components(date).ok_or_else(invalid)? {
+            let amount: i64 = amount.parse().map_err(|_| invalid())?;
+            match unit {
+                'Y' => months += amount * 12,
+                'M' => months += amount,
+                'W' => days += amount * 7,
+                'D' => days += amount,
+                _ => return Err(invalid()),
+            }
+        }
+
+        let mut nanoseconds = 0i128;
+        for (amount, unit) in components(time.unwrap_or("")).ok_or_else(invalid)? {
+            let factor = match unit {
+                'H' => 3600,
+                'M' => 60,
+                'S' => 1,
+                _ => return Err(invalid()),
+            };
+            let amount = match amount.split_once('.') {
+                Some((whole, fraction)) if unit == 'S' => {
+                    seconds_with_fraction(whole, fraction).ok_or_else(invalid)?
+                }
+                Some(_) => return Err(invalid()),
+                None => amount.parse::<i128>().map_err(|_| invalid())? * NANOS_PER_SECOND,
+            };
+            nanoseconds += amount * factor;
+        }
+
+        Ok(IsoDuration::new(
+            months,
+            days,
+            nanoseconds.div_euclid(NANOS_PER_SECOND) as i64,
+            nanoseconds.rem_euclid(NANOS_PER_SECOND) as i32,
+        ))
+    }
+}
+
+/// Splits `1Y-2M` into `[("1", 'Y'), ("-2", 'M')]`
+fn components(value: &str) -> Option<Vec<(&str, char)>> {
+    let mut components = Vec::new();
+    let mut start = 0;
+    for (index, c) in value.char_indices() {
+        if c.is_ascii_alphabetic() {
+            if index == start {
+                return None;
+            }
+            components.push((&value[start..index], c));
+            start = index + 1;
+        }
+    }
+    if start == value.len() {
+        Some(components)
+    } else {
+        None
+    }
+}
+
+/// Parses seconds like `-6.25` into nanoseconds
+fn seconds_with_fraction(whole: &str, fraction: &str) -> Option<i128> {
+    if fraction.is_empty() || fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let negative = whole.starts_with('-');
+    let seconds = whole.parse::<i128>().ok()?.abs();
+    let nanoseconds = format!("{:0<9}", fraction).parse::<i128>().ok()?;
+    let total = seconds * NANOS_PER_SECOND + nanoseconds;
+    Some(if negative { -total } else { total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_format_iso_8601() {
+        assert_eq!(
+            IsoDuration::new(14, 3, 3723, 500_000_000).to_string(),
+            "P1Y2M3DT1H2M3.5S"
+        );
+        assert_eq!(IsoDuration::new(0, 0, 0, 0).to_string(), "PT0S");
+        assert_eq!(IsoDuration::new(0, 7, 0, 0).to_string(), "P7D");
+        assert_eq!(
+            IsoDuration::new(0, 0, -1, 500_000_000).to_string(),
+            "PT-0.5S"
+        );
+    }
+
+    #[test]
+    fn should_parse_iso_8601() {
+        assert_eq!(
+            "P1Y2M3DT1H2M3.5S".parse::<IsoDuration>().unwrap(),
+            IsoDuration::new(14, 3, 3723, 500_000_000)
+        );
+        assert_eq!(
+            "P2W".parse::<IsoDuration>().unwrap(),
+            IsoDuration::new(0, 14, 0, 0)
+        );
+        assert_eq!(
+            "PT-0.5S".parse::<IsoDuration>().unwrap(),
+            IsoDuration::new(0, 0, -1, 500_000_000)
+        );
+        assert_eq!(
+            "P-1M".parse::<IsoDuration>().unwrap(),
+            IsoDuration::new(-1, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_iso_8601() {
+        for invalid in ["", "P", "PT", "1Y", "P1", "PT1.5H", "P1X", "PYM"].iter() {
+            assert!(invalid.parse::<IsoDuration>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn should_convert_to_std_duration() {
+        let duration = IsoDuration::new(1, 1, 1, 7);
+
+        let std = duration.to_std().unwrap();
+
+        assert_eq!(std.as_secs(), 2_629_800 + 86_400 + 1);
+        assert_eq!(std.subsec_nanos(), 7);
+        assert!(IsoDuration::new(0, 0, -1, 0).to_std().is_err());
+        assert_eq!(
+            IsoDuration::from(std::time::Duration::new(5, 3)),
+            IsoDuration::new(0, 0, 5, 3)
+        );
+    }
+}
@@ -1,9 +1,31 @@
+use crate::summary::InputPosition;
+use crate::types::BoltMap;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
-    IOError { detail: String },
+    IOError {
+        detail: String,
+    },
     ConnectionError,
+    PoolTimeout,
+    PoolClosed,
+    /// The rows of a stream can't be pulled anymore, since its transaction was committed or
+    /// rolled back
+    ResultConsumed,
+    /// The transaction can't run queries anymore, it was committed, rolled back or failed
+    TransactionClosed(crate::txn::TxState),
+    /// The query was cancelled with its [`CancellationToken`](crate::CancellationToken)
+    Cancelled,
+    /// The query didn't complete within its [`ConfigBuilder::query_timeout`]
+    ///
+    /// [`ConfigBuilder::query_timeout`]: crate::ConfigBuilder::query_timeout
+    Timeout,
+    /// A stream expected to hold a single row has none
+    NoRows,
+    /// A stream expected to hold a single row has more
+    TooManyRows,
     StringTooLong,
     MapTooBig,
     BytesTooBig,
@@ -14,9 +36,240 @@ pub enum Error {
     UnknownType(String),
     UnknownMessage(String),
     ConverstionError,
+    /// A value was extracted as a type it doesn't convert to, like a local date time as a date
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// An integer was extracted as a smaller integer type which can't hold it, like `300` as an
+    /// `i8` or `-1` as a `u32`
+    IntegerOverflow {
+        value: i64,
+        target: &'static str,
+    },
+    /// A column was read as a type it doesn't convert to, with the requested Rust type, the
+    /// bolt type found, and the value found when it is a scalar
+    ColumnTypeMismatch {
+        column: String,
+        expected: &'static str,
+        found: &'static str,
+        value: Option<String>,
+    },
+    MissingKey(String),
     AuthenticationError(String),
     InvalidTypeMarker(String),
     DeserializationError(String),
+    SerializationError(String),
+    RoutingError(String),
+    TlsError(String),
+    ProxyError(String),
+    /// A migration failed, or doesn't match the migration applied with its version
+    MigrationError(String),
+    /// An index or constraint required with [`Schema`](crate::Schema) is missing
+    SchemaError(String),
+    /// The database rejected queries verified with [`offline::prepare`](crate::offline::prepare)
+    InvalidQuery(String),
+    /// A point was built with the SRID of an unknown or mismatched reference system, or was
+    /// read from malformed WKT
+    InvalidPoint(String),
+    /// The parameters of a query don't match the `$placeholders` of its cypher, see
+    /// [`ConfigBuilder::strict_parameters`](crate::ConfigBuilder::strict_parameters)
+    ParameterMismatch {
+        missing: Vec<String>,
+        unused: Vec<String>,
+    },
+    /// Rows can't be converted to arrow record batches, like when a value doesn't fit the type
+    /// of its column
+    ArrowError(String),
+    LimitExceeded(String),
+    Neo4jError(Neo4jError),
+}
+
+/// A failure reported by the server, identified by a status code like
+/// `Neo.ClientError.Schema.ConstraintValidationFailed`
+///
+/// The code is made of the [`Classification`], the category and the title of the failure, so
+/// applications can match on specific failures, like constraint violations. From bolt 5.7 the
+/// server also describes the failure with a GQL status and the chain of failures that caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Neo4jError {
+    code: String,
+    message: String,
+    /// Boxed, so errors without it remain small
+    gql: Option<Box<GqlDetails>>,
+    query: Option<String>,
+    parameters: Vec<(String, Option<String>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GqlDetails {
+    status: String,
+    description: Option<String>,
+    diagnostic_record: Option<DiagnosticRecord>,
+    cause: Option<Neo4jError>,
+}
+
+/// Where a GQL status was raised, as described by the server from bolt 5.7
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DiagnosticRecord {
+    pub operation: String,
+    pub operation_code: String,
+    pub current_schema: String,
+    /// The classification of the failure, like `CLIENT_ERROR`
+    pub classification: Option<String>,
+    /// Where in the query the failure was raised
+    pub position: Option<InputPosition>,
+}
+
+impl DiagnosticRecord {
+    fn parse(record: BoltMap) -> DiagnosticRecord {
+        DiagnosticRecord {
+            operation: record.get("OPERATION").unwrap_or_default(),
+            operation_code: record.get("OPERATION_CODE").unwrap_or_default(),
+            current_schema: record.get("CURRENT_SCHEMA").unwrap_or_default(),
+            classification: record.get("_classification"),
+            position: record.get::<BoltMap>("_position").map(InputPosition::parse),
+        }
+    }
+}
+
+/// The kind of failure of a [`Neo4jError`], the second part of its code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification {
+    /// The request was invalid, like a syntax or a constraint violation, and fails again when
+    /// sent unchanged
+    ClientError,
+    /// A warning about the request, which didn't prevent it from completing
+    ClientNotification,
+    /// The request may succeed when it is sent again, like after a deadlock
+    TransientError,
+    /// The server failed to process the request
+    DatabaseError,
+    /// A classification this driver doesn't know about
+    Unknown,
+}
+
+impl Neo4jError {
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Neo4jError {
+            code: code.into(),
+            message: message.into(),
+            gql: None,
+            query: None,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Parses the metadata of a FAILURE, along with the failures which caused it
+    pub(crate) fn parse(metadata: BoltMap) -> Neo4jError {
+        let code = metadata
+            .get::<String>("neo4j_code")
+            .or_else(|| metadata.get("code"))
+            .unwrap_or_default();
+        let gql = metadata.get::<String>("gql_status").map(|status| {
+            Box::new(GqlDetails {
+                status,
+                description: metadata.get("description"),
+                diagnostic_record: metadata
+                    .get::<BoltMap>("diagnostic_record")
+                    .map(DiagnosticRecord::parse),
+                cause: metadata.get::<BoltMap>("cause").map(Neo4jError::parse),
+            })
+        });
+        Neo4jError {
+            gql,
+            ..Neo4jError::new(code, metadata.get::<String>("message").unwrap_or_default())
+        }
+    }
+
+    /// The full status code, like `Neo.ClientError.Schema.ConstraintValidationFailed`
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// A description of the failure meant for humans
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn classification(&self) -> Classification {
+        match self.part(1) {
+            "ClientError" => Classification::ClientError,
+            "ClientNotification" => Classification::ClientNotification,
+            "TransientError" => Classification::TransientError,
+            "DatabaseError" => Classification::DatabaseError,
+            _ => Classification::Unknown,
+        }
+    }
+
+    /// The area of the failure, like `Schema` or `Statement`
+    pub fn category(&self) -> &str {
+        self.part(2)
+    }
+
+    /// The specific failure, like `ConstraintValidationFailed` or `SyntaxError`
+    pub fn title(&self) -> &str {
+        self.part(3)
+    }
+
+    /// The GQL status code of the failure, like `22N69`, sent from bolt 5.7
+    pub fn gql_status(&self) -> Option<&str> {
+        self.gql.as_ref().map(|gql| gql.status.as_str())
+    }
+
+    /// The standard description of the GQL status, sent from bolt 5.7
+    pub fn status_description(&self) -> Option<&str> {
+        self.gql.as_ref()?.description.as_deref()
+    }
+
+    /// Where the failure was raised, sent from bolt 5.7
+    pub fn diagnostic_record(&self) -> Option<&DiagnosticRecord> {
+        self.gql.as_ref()?.diagnostic_record.as_ref()
+    }
+
+    /// The failure which caused this one, sent from bolt 5.7
+    pub fn cause(&self) -> Option<&Neo4jError> {
+        self.gql.as_ref()?.cause.as_ref()
+    }
+
+    /// The cypher text of the query that failed, if the failure is about a query
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    /// The names of the parameters of the query that failed
+    pub fn parameter_names(&self) -> impl Iterator<Item = &str> {
+        self.parameters.iter().map(|(name, _)| name.as_str())
+    }
+
+    fn part(&self, index: usize) -> &str {
+        self.code.split('.').nth(index).unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for Neo4jError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)?;
+        if let Some(query) = &self.query {
+            write!(f, " (query: {}", query)?;
+            for (index, (name, value)) in self.parameters.iter().enumerate() {
+                let separator = if index == 0 { ", parameters: " } else { ", " };
+                match value {
+                    Some(value) => write!(f, "{}${} = {}", separator, name, value)?,
+                    None => write!(f, "{}${}", separator, name)?,
+                }
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// Extracting a [`BoltType`](crate::BoltType) as itself can't fail
+impl std::convert::From<std::convert::Infallible> for Error {
+    fn from(never: std::convert::Infallible) -> Self {
+        match never {}
+    }
 }
 
 impl std::convert::From<std::io::Error> for Error {
@@ -31,14 +284,235 @@ impl std::convert::From<deadpool::managed::PoolError<Error>> for Error {
     fn from(e: deadpool::managed::PoolError<Error>) -> Self {
         match e {
             deadpool::managed::PoolError::Backend(e) => e,
+            deadpool::managed::PoolError::Timeout(_) => Error::PoolTimeout,
+            deadpool::managed::PoolError::Closed => Error::PoolClosed,
             _ => Error::ConnectionError,
         }
     }
 }
 
+const NOT_A_LEADER: &str = "Neo.ClientError.Cluster.NotALeader";
+const FORBIDDEN_ON_READ_ONLY: &str = "Neo.ClientError.General.ForbiddenOnReadOnlyDatabase";
+const DATABASE_UNAVAILABLE: &str = "Neo.TransientError.General.DatabaseUnavailable";
+/// Raised once the credentials of a connection expired, the work succeeds on a connection
+/// which authenticated again
+pub(crate) const AUTHORIZATION_EXPIRED: &str = "Neo.ClientError.Security.AuthorizationExpired";
+const TRANSIENT_ERROR: &str = "Neo.TransientError.";
+/// Transient codes which are raised on purpose and must not be retried
+const NON_RETRYABLE_TRANSIENT_ERRORS: [&str; 2] = [
+    "Neo.TransientError.Transaction.Terminated",
+    "Neo.TransientError.Transaction.LockClientStopped",
+];
+
+impl Error {
+    /// Attaches the query and the names, or the formatted values, of its parameters to a
+    /// failure reported by the server
+    pub(crate) fn with_query(
+        self,
+        query: &str,
+        parameters: Vec<(String, Option<String>)>,
+    ) -> Error {
+        match self {
+            Error::Neo4jError(mut error) => {
+                error.query = Some(query.to_owned());
+                error.parameters = parameters;
+                Error::Neo4jError(error)
+            }
+            error => error,
+        }
+    }
+
+    /// Whether the work that failed with this error may succeed when it is replayed, like after
+    /// a deadlock, a leader switch, expired credentials or a lost connection. This is what
+    /// [`Graph::write_tx`](crate::Graph::write_tx) and [`Graph::read_tx`](crate::Graph::read_tx)
+    /// retry on, for code retrying transactions on its own.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::IOError { .. } | Error::ConnectionError => true,
+            Error::Neo4jError(error) => {
+                (self.is_transient() && !NON_RETRYABLE_TRANSIENT_ERRORS.contains(&error.code()))
+                    || error.code() == AUTHORIZATION_EXPIRED
+                    || self.is_routing_failure()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the server classified the failure as transient, with a `Neo.TransientError.*`
+    /// code. Unlike [`Error::is_retryable`] this includes the transactions terminated on
+    /// purpose, which must not be replayed.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Error::Neo4jError(error) if error.code().starts_with(TRANSIENT_ERROR))
+    }
+
+    /// Whether the connection failed rather than the server rejecting the request, the request
+    /// may then not have reached the server
+    pub(crate) fn is_connection_failure(&self) -> bool {
+        matches!(self, Error::IOError { .. } | Error::ConnectionError)
+    }
+
+    /// Whether the server that raised this error no longer serves the role it was picked for,
+    /// in which case the routing table has to be refreshed.
+    pub(crate) fn is_routing_failure(&self) -> bool {
+        match self {
+            Error::Neo4jError(error) => matches!(
+                error.code(),
+                NOT_A_LEADER | FORBIDDEN_ON_READ_ONLY | DATABASE_UNAVAILABLE
+            ),
+            _ => false,
+        }
+    }
+}
+
 pub fn unexpected<T: std::fmt::Debug>(response: T, request: &str) -> Error {
     Error::UnexpectedMessage(format!(
         "unexpected response for {}: {:?}",
         request, response
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::FromIterator;
+
+    fn neo4j_error(code: &str) -> Error {
+        Error::Neo4jError(Neo4jError::new(code, "some message"))
+    }
+
+    #[test]
+    fn should_describe_the_failed_query() {
+        let parameters = vec![("id".to_owned(), None), ("name".to_owned(), None)];
+        let redacted = neo4j_error("Neo.ClientError.Statement.SyntaxError")
+            .with_query("MATCH (n {id: $id}) RETRUN n", parameters);
+        let revealed = neo4j_error("Neo.ClientError.Statement.SyntaxError")
+            .with_query("RETURN $id", vec![("id".to_owned(), Some("42".to_owned()))]);
+
+        match (redacted, revealed) {
+            (Error::Neo4jError(redacted), Error::Neo4jError(revealed)) => {
+                assert_eq!(redacted.query(), Some("MATCH (n {id: $id}) RETRUN n"));
+                assert_eq!(
+                    redacted.parameter_names().collect::<Vec<_>>(),
+                    ["id", "name"]
+                );
+                assert_eq!(
+                    redacted.to_string(),
+                    "Neo.ClientError.Statement.SyntaxError: some message \
+                     (query: MATCH (n {id: $id}) RETRUN n, parameters: $id, $name)"
+                );
+                assert_eq!(
+                    revealed.to_string(),
+                    "Neo.ClientError.Statement.SyntaxError: some message \
+                     (query: RETURN $id, parameters: $id = 42)"
+                );
+            }
+            other => panic!("unexpected errors {:?}", other),
+        }
+        assert!(matches!(
+            Error::ConnectionError.with_query("RETURN 1", vec![]),
+            Error::ConnectionError
+        ));
+    }
+
+    #[test]
+    fn should_parse_the_status_code() {
+        let error = Neo4jError::new(
+            "Neo.ClientError.Schema.ConstraintValidationFailed",
+            "Node(0) already exists",
+        );
+
+        assert_eq!(error.classification(), Classification::ClientError);
+        assert_eq!(error.category(), "Schema");
+        assert_eq!(error.title(), "ConstraintValidationFailed");
+        assert_eq!(error.message(), "Node(0) already exists");
+        assert_eq!(
+            Neo4jError::new("Neo.TransientError.Transaction.DeadlockDetected", "").classification(),
+            Classification::TransientError
+        );
+        assert_eq!(
+            Neo4jError::new("garbage", "").classification(),
+            Classification::Unknown
+        );
+        assert_eq!(Neo4jError::new("garbage", "").title(), "");
+    }
+
+    #[test]
+    fn should_parse_the_gql_status_and_its_causes() {
+        let cause = BoltMap::from_iter(vec![
+            ("gql_status".into(), "22N27".into()),
+            ("description".into(), "invalid entity type".into()),
+            ("message".into(), "expected a node".into()),
+        ]);
+        let record = BoltMap::from_iter(vec![
+            ("OPERATION".into(), "".into()),
+            ("CURRENT_SCHEMA".into(), "/".into()),
+            ("_classification".into(), "CLIENT_ERROR".into()),
+        ]);
+        let metadata = BoltMap::from_iter(vec![
+            (
+                "neo4j_code".into(),
+                "Neo.ClientError.Statement.TypeError".into(),
+            ),
+            ("message".into(), "invalid input".into()),
+            ("gql_status".into(), "22N01".into()),
+            ("description".into(), "data exception".into()),
+            ("diagnostic_record".into(), record.into()),
+            ("cause".into(), cause.into()),
+        ]);
+
+        let error = Neo4jError::parse(metadata);
+
+        assert_eq!(error.code(), "Neo.ClientError.Statement.TypeError");
+        assert_eq!(error.gql_status(), Some("22N01"));
+        assert_eq!(error.status_description(), Some("data exception"));
+        let record = error.diagnostic_record().unwrap();
+        assert_eq!(record.current_schema, "/");
+        assert_eq!(record.classification.as_deref(), Some("CLIENT_ERROR"));
+        let cause = error.cause().unwrap();
+        assert_eq!(cause.gql_status(), Some("22N27"));
+        assert_eq!(cause.message(), "expected a node");
+        assert!(cause.cause().is_none());
+        assert!(Neo4jError::new("garbage", "").gql_status().is_none());
+    }
+
+    #[test]
+    fn should_retry_transient_errors() {
+        assert!(neo4j_error("Neo.TransientError.Transaction.DeadlockDetected").is_retryable());
+        assert!(neo4j_error("Neo.ClientError.Cluster.NotALeader").is_retryable());
+        assert!(Error::ConnectionError.is_retryable());
+    }
+
+    #[test]
+    fn should_not_retry_other_errors() {
+        assert!(!neo4j_error("Neo.TransientError.Transaction.Terminated").is_retryable());
+        assert!(!neo4j_error("Neo.ClientError.Statement.SyntaxError").is_retryable());
+        assert!(!Error::AuthenticationError("unauthorized".into()).is_retryable());
+        assert!(!Error::PoolClosed.is_retryable());
+        assert!(!Error::LimitExceeded("message too large".into()).is_retryable());
+    }
+
+    #[test]
+    fn should_retry_expired_authorizations_and_lost_connections() {
+        assert!(neo4j_error("Neo.ClientError.Security.AuthorizationExpired").is_retryable());
+        assert!(Error::IOError {
+            detail: "connection reset by peer".into()
+        }
+        .is_retryable());
+    }
+
+    #[test]
+    fn should_classify_transient_errors() {
+        assert!(neo4j_error("Neo.TransientError.Transaction.Terminated").is_transient());
+        assert!(neo4j_error("Neo.TransientError.General.DatabaseUnavailable").is_transient());
+        assert!(!neo4j_error("Neo.ClientError.Cluster.NotALeader").is_transient());
+        assert!(!Error::ConnectionError.is_transient());
+    }
+
+    #[test]
+    fn should_detect_routing_failures() {
+        assert!(neo4j_error("Neo.ClientError.Cluster.NotALeader").is_routing_failure());
+        assert!(
+            !neo4j_error("Neo.TransientError.Transaction.DeadlockDetected").is_routing_failure()
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use crate::errors::Result;
+use crate::graph::Graph;
+use crate::query::Query;
+use crate::session::Session;
+use crate::stream::RowStream;
+use crate::txn::Txn;
+use async_trait::async_trait;
+
+/// Runs queries either on connections of the pool with a [`Graph`] or within a [`Txn`], so
+/// functions taking an `&impl Execute` work inside and outside of an explicit transaction
+#[async_trait]
+pub trait Execute: Send + Sync {
+    /// Runs a query and discards its rows, see [`Graph::run`] and [`Txn::run`]
+    async fn run(&self, q: Query) -> Result<()>;
+
+    /// Executes a query and returns its [`RowStream`], see [`Graph::execute`] and
+    /// [`Txn::execute`]
+    async fn execute(&self, q: Query) -> Result<RowStream>;
+}
+
+#[async_trait]
+impl Execute for Graph {
+    async fn run(&self, q: Query) -> Result<()> {
+        Graph::run(self, q).await
+    }
+
+    async fn execute(&self, q: Query) -> Result<RowStream> {
+        Graph::execute(self, q).await
+    }
+}
+
+#[async_trait]
+impl Execute for Session {
+    async fn run(&self, q: Query) -> Result<()> {
+        Session::run(self, q).await
+    }
+
+    async fn execute(&self, q: Query) -> Result<RowStream> {
+        Session::execute(self, q).await
+    }
+}
+
+#[async_trait]
+impl Execute for Txn {
+    async fn run(&self, q: Query) -> Result<()> {
+        Txn::run(self, q).await
+    }
+
+    async fn execute(&self, q: Query) -> Result<RowStream> {
+        Txn::execute(self, q).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_be_implemented_by_graphs_and_transactions() {
+        fn executor<E: Execute>() {}
+
+        executor::<Graph>();
+        executor::<Session>();
+        executor::<Txn>();
+    }
+}
@@ -0,0 +1,145 @@
+//! A Neo4j server in a docker container for integration tests, with the `test-harness` feature
+//!
+//! ```no_run
+//! use neo4rs::harness::Neo4jContainer;
+//! use neo4rs::query;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let neo4j = Neo4jContainer::start().await.unwrap();
+//!     neo4j.graph().run(query("CREATE (:Person)")).await.unwrap();
+//!     neo4j.clear().await.unwrap();
+//! }
+//! ```
+use crate::errors::{Error, Result};
+use crate::graph::{query, Graph};
+use crate::rt;
+use log::info;
+use std::time::Duration;
+use testcontainers::core::{IntoContainerPort, WaitFor};
+use testcontainers::runners::AsyncRunner;
+use testcontainers::{ContainerAsync, GenericImage, ImageExt};
+
+const IMAGE: &str = "neo4j";
+const DEFAULT_TAG: &str = "5";
+const BOLT_PORT: u16 = 7687;
+const USER: &str = "neo4j";
+/// Neo4j 5 refuses passwords shorter than 8 characters
+const PASSWORD: &str = "neo4rs-harness";
+/// How often and how long to wait between attempts to connect once the server logged it started,
+/// it may still be initializing the databases
+const READINESS_ATTEMPTS: usize = 30;
+const READINESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A Neo4j server running in a docker container, along with a [`Graph`] connected to it. The
+/// container is removed once this is dropped.
+pub struct Neo4jContainer {
+    graph: Graph,
+    uri: String,
+    _container: ContainerAsync<GenericImage>,
+}
+
+impl Neo4jContainer {
+    /// Starts a container of the latest Neo4j 5 image
+    pub async fn start() -> Result<Neo4jContainer> {
+        Self::with_tag(DEFAULT_TAG).await
+    }
+
+    /// Starts a container of the Neo4j image with `tag`, like `4.4` or `5-enterprise`. The
+    /// license of enterprise images is accepted.
+    pub async fn with_tag(tag: &str) -> Result<Neo4jContainer> {
+        let container = GenericImage::new(IMAGE, tag)
+            .with_exposed_port(BOLT_PORT.tcp())
+            .with_wait_for(WaitFor::message_on_stdout("Started."))
+            .with_env_var("NEO4J_AUTH", format!("{}/{}", USER, PASSWORD))
+            .with_env_var("NEO4J_ACCEPT_LICENSE_AGREEMENT", "yes")
+            .start()
+            .await
+            .map_err(container_error)?;
+        let host = container.get_host().await.map_err(container_error)?;
+        let port = container
+            .get_host_port_ipv4(BOLT_PORT)
+            .await
+            .map_err(container_error)?;
+        let uri = format!("bolt://{}:{}", host, port);
+        let graph = Graph::new(&uri, USER, PASSWORD).await?;
+        wait_until_ready(&graph).await?;
+        info!("neo4j {} container ready at {}", tag, uri);
+        Ok(Neo4jContainer {
+            graph,
+            uri,
+            _container: container,
+        })
+    }
+
+    /// The graph connected to the server of the container
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// The bolt uri of the server, for connecting with a config of its own
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// The user the server accepts
+    pub fn user(&self) -> &str {
+        USER
+    }
+
+    /// The password of [`Neo4jContainer::user`]
+    pub fn password(&self) -> &str {
+        PASSWORD
+    }
+
+    /// Empties the database between tests sharing the container, see [`clear`]
+    pub async fn clear(&self) -> Result<()> {
+        clear(&self.graph).await
+    }
+}
+
+/// Deletes every node and relationship of the database of `graph` and drops its constraints and
+/// indexes, so the next test starts from an empty database
+pub async fn clear(graph: &Graph) -> Result<()> {
+    graph.run(query("MATCH (n) DETACH DELETE n")).await?;
+    // dropping a constraint also drops the index backing it
+    for constraint in names(graph, "SHOW CONSTRAINTS YIELD name").await? {
+        graph
+            .run(query(&format!("DROP CONSTRAINT `{}`", constraint)))
+            .await?;
+    }
+    let indexes = "SHOW INDEXES YIELD name, type WHERE type <> 'LOOKUP' RETURN name";
+    for index in names(graph, indexes).await? {
+        graph.run(query(&format!("DROP INDEX `{}`", index))).await?;
+    }
+    Ok(())
+}
+
+async fn names(graph: &Graph, cypher: &str) -> Result<Vec<String>> {
+    let mut rows = graph.execute(query(cypher)).await?;
+    let mut names = Vec::new();
+    while let Some(row) = rows.next().await? {
+        names.extend(row.get::<String>("name"));
+    }
+    Ok(names)
+}
+
+async fn wait_until_ready(graph: &Graph) -> Result<()> {
+    let mut attempts = 1;
+    loop {
+        match graph.verify_connectivity().await {
+            Ok(_) => return Ok(()),
+            Err(_) if attempts < READINESS_ATTEMPTS => {
+                attempts += 1;
+                rt::sleep(READINESS_INTERVAL).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn container_error(error: testcontainers::TestcontainersError) -> Error {
+    Error::IOError {
+        detail: format!("unable to start the neo4j container: {}", error),
+    }
+}
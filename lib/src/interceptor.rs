@@ -0,0 +1,153 @@
+use crate::errors::{Error, Result};
+use crate::query::Query;
+use crate::summary::ResultSummary;
+use std::fmt;
+use std::sync::Arc;
+
+/// Observes and changes every query of the driver, for cross-cutting concerns like auditing,
+/// tagging queries with a tenant or custom metrics, see
+/// [`ConfigBuilder::interceptor`](crate::ConfigBuilder::interceptor)
+///
+/// Both hooks have a default implementation, so implementations only provide the ones they
+/// need. They are called on the hot path of queries and should return quickly.
+pub trait QueryInterceptor: Send + Sync {
+    /// Called before `query` is sent to the server, the query returned is sent instead, like
+    /// the query with a parameter or metadata added. Failing rejects the query, the error is
+    /// returned to the caller without the query being sent.
+    fn on_query_start(&self, query: Query) -> Result<Query> {
+        Ok(query)
+    }
+
+    /// Called once the `query` run on `db` failed or all of its rows were consumed, with the
+    /// summary or the error. A stream dropped before all of its rows were consumed doesn't end.
+    /// The queries of a transaction batch ignored after one of them failed end with
+    /// [`Error::TransactionClosed`].
+    fn on_query_end(
+        &self,
+        _query: &str,
+        _db: &str,
+        _outcome: std::result::Result<&ResultSummary, &Error>,
+    ) {
+    }
+}
+
+/// The interceptors of the config, in the order they were added
+#[derive(Clone, Default)]
+pub(crate) struct Interceptors(pub(crate) Arc<[Arc<dyn QueryInterceptor>]>);
+
+impl Interceptors {
+    /// Passes `query` through the interceptors
+    pub(crate) fn start(&self, query: Query) -> Result<Query> {
+        self.0.iter().try_fold(query, |query, interceptor| {
+            interceptor.on_query_start(query)
+        })
+    }
+
+    /// Follows a query which started until it ends, `None` without interceptors
+    pub(crate) fn follow(&self, query: &str, db: &str) -> Option<InterceptedQuery> {
+        if self.0.is_empty() {
+            return None;
+        }
+        Some(InterceptedQuery {
+            interceptors: self.clone(),
+            query: query.to_owned(),
+            db: db.to_owned(),
+        })
+    }
+}
+
+impl fmt::Debug for Interceptors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Interceptors({})", self.0.len())
+    }
+}
+
+/// A query which started and is reported to the interceptors once it ends
+#[derive(Debug)]
+pub(crate) struct InterceptedQuery {
+    interceptors: Interceptors,
+    query: String,
+    db: String,
+}
+
+impl InterceptedQuery {
+    pub(crate) fn end(self, outcome: std::result::Result<&ResultSummary, &Error>) {
+        for interceptor in self.interceptors.0.iter() {
+            interceptor.on_query_end(&self.query, &self.db, outcome);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Audit(Mutex<Vec<String>>);
+
+    impl QueryInterceptor for Audit {
+        fn on_query_start(&self, query: Query) -> Result<Query> {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("start {}", query.cypher()));
+            Ok(query.param("tenant", "acme"))
+        }
+
+        fn on_query_end(
+            &self,
+            query: &str,
+            db: &str,
+            outcome: std::result::Result<&ResultSummary, &Error>,
+        ) {
+            let outcome = if outcome.is_ok() { "ok" } else { "failed" };
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("end {} on {}: {}", query, db, outcome));
+        }
+    }
+
+    struct Reject;
+
+    impl QueryInterceptor for Reject {
+        fn on_query_start(&self, _: Query) -> Result<Query> {
+            Err(Error::InvalidConfig)
+        }
+    }
+
+    #[test]
+    fn should_pass_queries_through_the_interceptors() {
+        let audit = Arc::new(Audit::default());
+        let interceptors = Interceptors(Arc::new([audit.clone() as Arc<dyn QueryInterceptor>]));
+
+        let query = interceptors.start(crate::query("RETURN $tenant")).unwrap();
+        let followed = interceptors.follow(query.cypher(), "neo4j").unwrap();
+        followed.end(Ok(&ResultSummary::default()));
+        interceptors
+            .follow("RETURN 1", "neo4j")
+            .unwrap()
+            .end(Err(&Error::NoRows));
+
+        assert_eq!(query.get_param::<String>("tenant").unwrap(), "acme");
+        assert_eq!(
+            *audit.0.lock().unwrap(),
+            vec![
+                "start RETURN $tenant",
+                "end RETURN $tenant on neo4j: ok",
+                "end RETURN 1 on neo4j: failed"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reject_queries() {
+        let rejecting = Interceptors(Arc::new([Arc::new(Reject) as Arc<dyn QueryInterceptor>]));
+
+        assert!(rejecting.start(crate::query("RETURN 1")).is_err());
+        assert!(Interceptors::default()
+            .follow("RETURN 1", "neo4j")
+            .is_none());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::errors::*;
+use crate::types::*;
+use serde_json::{Map, Number, Value};
+use std::convert::TryFrom;
+
+/// Integers that fit in an i64 are sent as integers, every other number as a float
+impl From<Value> for BoltType {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => BoltType::Null(BoltNull),
+            Value::Bool(b) => b.into(),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => i.into(),
+                None => n.as_f64().unwrap_or(f64::NAN).into(),
+            },
+            Value::String(s) => s.into(),
+            Value::Array(values) => values.into(),
+            Value::Object(entries) => BoltType::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value.into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Nodes become `{"id", "labels", "properties"}` objects, relationships
+/// `{"id", "start_node_id", "end_node_id", "type", "properties"}` objects, paths
+/// `{"nodes", "rels"}` objects and points `{"srid", "x", "y"}` objects. Durations and, with the
+/// `chrono` feature, dates and times become ISO-8601 strings.
+impl TryFrom<BoltType> for Value {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<Value> {
+        match input {
+            BoltType::Null(_) => Ok(Value::Null),
+            BoltType::Boolean(b) => Ok(Value::Bool(b.value)),
+            BoltType::Integer(i) => Ok(i.value.into()),
+            BoltType::Float(f) => Number::from_f64(f.value)
+                .map(Value::Number)
+                .ok_or(Error::ConverstionError),
+            BoltType::String(s) => Ok(Value::String(s.into())),
+            BoltType::Bytes(b) => Ok(b.value.to_vec().into()),
+            BoltType::List(l) => l.into_iter().map(Value::try_from).collect(),
+            BoltType::Map(m) => object(m),
+            BoltType::Node(n) => node(n),
+            BoltType::Relation(r) => Ok(Value::Object(
+                vec![
+                    ("id".to_owned(), r.id.value.into()),
+                    ("start_node_id".to_owned(), r.start_node_id.value.into()),
+                    ("end_node_id".to_owned(), r.end_node_id.value.into()),
+                    ("type".to_owned(), r.typ.as_str().into()),
+                    ("properties".to_owned(), object(r.properties)?),
+                ]
+                .into_iter()
+                .collect(),
+            )),
+            BoltType::UnboundedRelation(r) => unbounded_relation(r),
+            BoltType::Path(p) => Ok(Value::Object(
+                vec![
+                    (
+                        "nodes".to_owned(),
+                        p.nodes().into_iter().map(node).collect::<Result<_>>()?,
+                    ),
+                    (
+                        "rels".to_owned(),
+                        p.rels()
+                            .into_iter()
+                            .map(unbounded_relation)
+                            .collect::<Result<_>>()?,
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )),
+            BoltType::Point2D(p) => Ok(serde_json::json!({
+                "srid": p.sr_id.value,
+                "x": p.x.value,
+                "y": p.y.value,
+            })),
+            BoltType::Point3D(p) => Ok(serde_json::json!({
+                "srid": p.sr_id.value,
+                "x": p.x.value,
+                "y": p.y.value,
+                "z": p.z.value,
+            })),
+            BoltType::Duration(d) => Ok(crate::IsoDuration::from(d).to_string().into()),
+            other => crate::ser::iso_8601(other).map(Value::String),
+        }
+    }
+}
+
+pub(crate) fn object(map: BoltMap) -> Result<Value> {
+    map.value
+        .into_iter()
+        .map(|(key, value)| Ok((key.into(), Value::try_from(value)?)))
+        .collect::<Result<Map<_, _>>>()
+        .map(Value::Object)
+}
+
+pub(crate) fn node(node: BoltNode) -> Result<Value> {
+    Ok(Value::Object(
+        vec![
+            ("id".to_owned(), node.id.value.into()),
+            (
+                "labels".to_owned(),
+                Value::try_from(BoltType::List(node.labels))?,
+            ),
+            ("properties".to_owned(), object(node.properties)?),
+        ]
+        .into_iter()
+        .collect(),
+    ))
+}
+
+fn unbounded_relation(relation: BoltUnboundedRelation) -> Result<Value> {
+    Ok(Value::Object(
+        vec![
+            ("id".to_owned(), relation.id.value.into()),
+            ("type".to_owned(), relation.typ.as_str().into()),
+            ("properties".to_owned(), object(relation.properties)?),
+        ]
+        .into_iter()
+        .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::row::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_convert_json_to_bolt() {
+        let value = json!({"name": "Mark", "age": 42, "score": 1.5, "tags": ["a"], "x": null});
+
+        let bolt = BoltMap::try_from(BoltType::from(value)).unwrap();
+
+        assert_eq!(bolt.get::<String>("name"), Some("Mark".to_owned()));
+        assert_eq!(bolt.get::<i64>("age"), Some(42));
+        assert_eq!(bolt.get::<f64>("score"), Some(1.5));
+        assert_eq!(bolt.get::<Vec<String>>("tags"), Some(vec!["a".to_owned()]));
+        assert_eq!(bolt.get_opt::<i64>("x").unwrap(), None);
+    }
+
+    #[test]
+    fn should_convert_rows_to_json() {
+        let properties = vec![("name".into(), "Mark".into())].into_iter().collect();
+        let node = BoltNode::new(
+            BoltInteger::new(7),
+            vec!["Person".into()].into(),
+            properties,
+        );
+        let fields: BoltList = vec!["p".into(), "n".into()].into();
+        let data: BoltList = vec![BoltType::Node(node), BoltType::Null(BoltNull)].into();
+
+        let row = Row::new(fields, data);
+
+        assert_eq!(
+            row.to_json().unwrap(),
+            json!({
+                "p": {"id": 7, "labels": ["Person"], "properties": {"name": "Mark"}},
+                "n": null
+            })
+        );
+    }
+
+    #[test]
+    fn should_convert_relations_to_json() {
+        let relation = Relation::new(BoltRelation::new(
+            BoltInteger::new(1),
+            BoltInteger::new(2),
+            BoltInteger::new(3),
+            "KNOWS".into(),
+            BoltMap::default(),
+        ));
+
+        assert_eq!(
+            relation.to_json().unwrap(),
+            json!({
+                "id": 1,
+                "start_node_id": 2,
+                "end_node_id": 3,
+                "type": "KNOWS",
+                "properties": {}
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn should_convert_temporals_to_json() {
+        let date = chrono::NaiveDate::from_ymd_opt(2020, 1, 31).unwrap();
+        let duration = crate::IsoDuration::new(0, 1, 90, 0);
+
+        assert_eq!(
+            Value::try_from(BoltType::from(date)).unwrap(),
+            json!("2020-01-31")
+        );
+        assert_eq!(
+            Value::try_from(BoltType::from(duration)).unwrap(),
+            json!("P1DT1M30S")
+        );
+    }
+}
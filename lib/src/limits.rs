@@ -0,0 +1,209 @@
+use crate::errors::{Error, Result};
+
+/// How large the messages received from the server may get, see
+/// [`ConfigBuilder::max_message_size`](crate::ConfigBuilder::max_message_size)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Limits {
+    pub(crate) message_size: Option<usize>,
+    pub(crate) container_length: Option<usize>,
+    pub(crate) nesting_depth: usize,
+}
+
+impl Limits {
+    /// Fails once a message grows beyond the max message size while its chunks are read
+    pub(crate) fn check_size(&self, size: usize) -> Result<()> {
+        match self.message_size {
+            Some(max) if size > max => Err(Error::LimitExceeded(format!(
+                "message larger than {} bytes",
+                max
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Walks the packstream encoded `message` without allocating, before it is parsed, so lists
+    /// and maps claiming more entries than allowed or than the message holds, as well as values
+    /// nested too deeply, are rejected instead of exhausting the memory or the stack.
+    pub(crate) fn check(&self, message: &[u8]) -> Result<()> {
+        // unknown markers are reported by the parser
+        self.walk(&mut Input(message)).map(|_| ())
+    }
+
+    /// Skips the packstream encoded value at the start of `input`, stopping at the first marker
+    /// unknown to the walker, which is returned
+    fn walk(&self, input: &mut Input) -> Result<Option<u8>> {
+        // the number of values left in each of the open structures, starting with the value
+        let mut open = vec![1usize];
+        while let Some(left) = open.last_mut() {
+            if *left == 0 {
+                open.pop();
+                continue;
+            }
+            *left -= 1;
+            let marker = input.u8()?;
+            let values = match marker {
+                0x00..=0x7F | 0xF0..=0xFF | 0xC0 | 0xC2 | 0xC3 => 0,
+                0xC8 => input.skip(1)?,
+                0xC9 => input.skip(2)?,
+                0xCA => input.skip(4)?,
+                0xC1 | 0xCB => input.skip(8)?,
+                0x80..=0x8F => input.skip(marker & 0x0F)?,
+                0xCC | 0xD0 => input.u8().and_then(|length| input.skip(length))?,
+                0xCD | 0xD1 => input.u16().and_then(|length| input.skip(length))?,
+                0xCE | 0xD2 => input.u32().and_then(|length| input.skip(length))?,
+                0x90..=0x9F => self.container(marker & 0x0F)?,
+                0xD4 => self.container(input.u8()?)?,
+                0xD5 => self.container(input.u16()?)?,
+                0xD6 => self.container(input.u32()?)?,
+                0xA0..=0xAF => self.container(marker & 0x0F)? * 2,
+                0xD8 => self.container(input.u8()?)? * 2,
+                0xD9 => self.container(input.u16()?)? * 2,
+                0xDA => self.container(input.u32()?)? * 2,
+                // the signature of the structure precedes its fields
+                0xB0..=0xBF => input.skip(1).map(|_| marker & 0x0F)?,
+                _ => return Ok(Some(marker as u8)),
+            };
+            if values > 0 {
+                if open.len() > self.nesting_depth {
+                    return Err(Error::LimitExceeded(format!(
+                        "values nested deeper than {} levels",
+                        self.nesting_depth
+                    )));
+                }
+                open.push(values);
+            }
+        }
+        Ok(None)
+    }
+
+    fn container(&self, length: usize) -> Result<usize> {
+        match self.container_length {
+            Some(max) if length > max => Err(Error::LimitExceeded(format!(
+                "list or map with more than {} entries",
+                max
+            ))),
+            _ => Ok(length),
+        }
+    }
+}
+
+/// The length in bytes of the packstream encoded value at the start of `input`, once it was
+/// checked against the limits as part of its message, so a record can be split into the values
+/// of its columns without decoding them
+pub(crate) fn value_length(input: &[u8]) -> Result<usize> {
+    let unlimited = Limits {
+        message_size: None,
+        container_length: None,
+        nesting_depth: usize::MAX,
+    };
+    let mut rest = Input(input);
+    match unlimited.walk(&mut rest)? {
+        Some(marker) => Err(Error::UnknownType(format!("{:#04X}", marker))),
+        None => Ok(input.len() - rest.0.len()),
+    }
+}
+
+struct Input<'a>(&'a [u8]);
+
+impl Input<'_> {
+    /// Skips `length` bytes, values following a skipped one hold no nested values
+    fn skip(&mut self, length: usize) -> Result<usize> {
+        if length > self.0.len() {
+            return Err(truncated());
+        }
+        self.0 = &self.0[length..];
+        Ok(0)
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(self.0.get(..N).ok_or_else(truncated)?);
+        self.0 = &self.0[N..];
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<usize> {
+        self.bytes::<1>().map(|[byte]| byte as usize)
+    }
+
+    fn u16(&mut self) -> Result<usize> {
+        self.bytes().map(|bytes| u16::from_be_bytes(bytes) as usize)
+    }
+
+    fn u32(&mut self) -> Result<usize> {
+        self.bytes().map(|bytes| u32::from_be_bytes(bytes) as usize)
+    }
+}
+
+fn truncated() -> Error {
+    Error::DeserializationError("truncated message".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(container_length: Option<usize>, nesting_depth: usize) -> Limits {
+        Limits {
+            message_size: Some(16),
+            container_length,
+            nesting_depth,
+        }
+    }
+
+    #[test]
+    fn should_accept_messages_within_the_limits() {
+        // SUCCESS {"fields": ["n", 1.5]}
+        let message = [
+            0xB1, 0x70, 0xA1, 0x86, b'f', b'i', b'e', b'l', b'd', b's', 0x92, 0x81, b'n', 0xC1,
+            0x3F, 0xF8, 0, 0, 0, 0, 0, 0,
+        ];
+
+        assert!(limits(Some(2), 3).check(&message).is_ok());
+        assert!(limits(Some(16), 2).check(&[0xB1, 0x70, 0xA0]).is_ok());
+        assert!(limits(None, 1).check_size(16).is_ok());
+    }
+
+    #[test]
+    fn should_reject_messages_beyond_the_limits() {
+        let message = [0xB1, 0x71, 0x93, 0x91, 0x91, 0x01, 0x02, 0x03];
+
+        assert!(matches!(
+            limits(Some(2), 8).check(&message),
+            Err(Error::LimitExceeded(_))
+        ));
+        assert!(matches!(
+            limits(None, 3).check(&message),
+            Err(Error::LimitExceeded(_))
+        ));
+        assert!(limits(None, 4).check(&message).is_ok());
+        assert!(matches!(
+            limits(None, 1).check_size(17),
+            Err(Error::LimitExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn should_reject_lists_longer_than_the_message() {
+        let message = [0xB1, 0x71, 0xD6, 0x7F, 0xFF, 0xFF, 0xFF, 0x01];
+
+        assert!(matches!(
+            limits(None, 8).check(&message),
+            Err(Error::DeserializationError(_))
+        ));
+    }
+
+    #[test]
+    fn should_measure_values() {
+        // [1, "ab"] followed by another value
+        let input = [0x92, 0x01, 0x82, b'a', b'b', 0x03];
+
+        assert_eq!(value_length(&input).unwrap(), 5);
+        assert_eq!(value_length(&input[5..]).unwrap(), 1);
+        assert!(matches!(value_length(&[0xE0]), Err(Error::UnknownType(_))));
+        assert!(matches!(
+            value_length(&[0x92, 0x01]),
+            Err(Error::DeserializationError(_))
+        ));
+    }
+}
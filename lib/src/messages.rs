@@ -4,40 +4,62 @@ mod commit;
 mod discard;
 mod failure;
 mod hello;
+mod ignored;
+mod logoff;
+mod logon;
 mod pull;
 mod record;
 mod reset;
 mod rollback;
+mod route;
 mod run;
 mod success;
+mod telemetry;
+use crate::auth::AuthToken;
+use crate::bookmark::{self, Bookmark};
+use crate::config::DRIVER_AGENT;
 use crate::errors::*;
+use crate::routing::AccessMode;
+use crate::summary::{adapt_filter, NotificationFilter};
+use crate::txn::TxOptions;
 use crate::types::*;
-use crate::version::Version;
+use crate::version::{Feature, Version};
 use begin::Begin;
+use bye::Bye;
 use bytes::*;
 use commit::Commit;
 use discard::Discard;
 use failure::Failure;
 use hello::Hello;
+use ignored::Ignored;
+use logoff::Logoff;
+use logon::Logon;
 use pull::Pull;
-use record::Record;
+pub(crate) use record::Record;
 use reset::Reset;
 use rollback::Rollback;
+use route::Route;
 use run::Run;
 use std::cell::RefCell;
 use std::rc::Rc;
-use success::Success;
+pub(crate) use success::Success;
+use telemetry::Telemetry;
+pub(crate) use telemetry::TelemetryApi;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum BoltResponse {
     SuccessMessage(Success),
     FailureMessage(Failure),
     RecordMessage(Record),
+    /// The response to every message following a failure, until the connection is reset
+    IgnoredMessage(Ignored),
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum BoltRequest {
     HelloMessage(Hello),
+    LogonMessage(Logon),
+    LogoffMessage(Logoff),
     RunMessage(Run),
     PullMessage(Pull),
     DiscardMessage(Discard),
@@ -45,20 +67,77 @@ pub enum BoltRequest {
     CommitMessage(Commit),
     RollbackMessage(Rollback),
     ResetMessage(Reset),
+    RouteMessage(Route),
+    TelemetryMessage(Telemetry),
+    GoodbyeMessage(Bye),
 }
 
 impl BoltRequest {
-    pub fn hello(agent: &str, principal: String, credentials: String) -> BoltRequest {
+    /// Builds the HELLO message for the negotiated version, credentials are only embedded for
+    /// versions prior to 5.1, later versions authenticate with a separate [`BoltRequest::logon`].
+    /// From 5.3 on the driver also describes itself and its platform in the `bolt_agent`. On 4.4
+    /// the driver asks for the `utc` patch, which encodes date times like 5.0 does. The
+    /// notification filter is left out for versions prior to 5.2, which can't filter them.
+    pub fn hello(
+        version: Version,
+        user_agent: &str,
+        routing: Option<BoltMap>,
+        notifications: &NotificationFilter,
+        auth: &AuthToken,
+    ) -> BoltRequest {
         let mut data = BoltMap::default();
-        data.put("user_agent".into(), agent.into());
-        data.put("scheme".into(), "basic".into());
-        data.put("principal".into(), principal.into());
-        data.put("credentials".into(), credentials.into());
+        data.put("user_agent".into(), user_agent.into());
+        if let Some(routing) = routing {
+            data.put("routing".into(), BoltType::Map(routing));
+        }
+        if version.supports(Feature::BoltAgent) {
+            let mut bolt_agent = BoltMap::default();
+            bolt_agent.put("product".into(), DRIVER_AGENT.into());
+            bolt_agent.put(
+                "platform".into(),
+                format!("{}; {}", std::env::consts::OS, std::env::consts::ARCH).into(),
+            );
+            bolt_agent.put("language".into(), "Rust".into());
+            data.put("bolt_agent".into(), BoltType::Map(bolt_agent));
+        }
+        if version == Version::V4_4 {
+            data.put("patch_bolt".into(), vec![BoltType::from("utc")].into());
+        }
+        if version.supports(Feature::NotificationFiltering) {
+            notifications.put(&mut data);
+        }
+        if !version.supports(Feature::ReAuthentication) {
+            put_auth(&mut data, auth);
+        }
         BoltRequest::HelloMessage(Hello::new(data))
     }
 
-    pub fn run(db: &str, query: &str, params: BoltMap) -> BoltRequest {
-        BoltRequest::RunMessage(Run::new(db.into(), query.into(), params))
+    pub fn logon(auth: &AuthToken) -> BoltRequest {
+        let mut data = BoltMap::default();
+        put_auth(&mut data, auth);
+        BoltRequest::LogonMessage(Logon::new(data))
+    }
+
+    /// Drops the authentication of a connection, which can then authenticate again with
+    /// [`BoltRequest::logon`]
+    pub fn logoff() -> BoltRequest {
+        BoltRequest::LogoffMessage(Logoff::new())
+    }
+
+    /// Builds a RUN message, `bookmarks` and `options` are only needed for queries outside of a
+    /// transaction
+    pub fn run(
+        db: &str,
+        query: &str,
+        params: BoltMap,
+        bookmarks: &[Bookmark],
+        options: &TxOptions,
+    ) -> BoltRequest {
+        let mut extra = BoltMap::default();
+        extra.put("db".into(), db.into());
+        put_bookmarks(&mut extra, bookmarks);
+        put_options(&mut extra, options);
+        BoltRequest::RunMessage(Run::new(query.into(), params, extra))
     }
 
     pub fn pull(n: usize, qid: i64) -> BoltRequest {
@@ -69,8 +148,20 @@ impl BoltRequest {
         BoltRequest::DiscardMessage(Discard::default())
     }
 
-    pub fn begin() -> BoltRequest {
-        BoltRequest::BeginMessage(Begin::new(BoltMap::default()))
+    /// Discards all the rows left in the result `qid` of a transaction
+    pub fn discard_all(qid: i64) -> BoltRequest {
+        BoltRequest::DiscardMessage(Discard::new(-1, qid))
+    }
+
+    /// Builds a BEGIN message, an empty `db` refers to the default database
+    pub fn begin(db: &str, bookmarks: &[Bookmark], options: &TxOptions) -> BoltRequest {
+        let mut extra = BoltMap::default();
+        if !db.is_empty() {
+            extra.put("db".into(), db.into());
+        }
+        put_bookmarks(&mut extra, bookmarks);
+        put_options(&mut extra, options);
+        BoltRequest::BeginMessage(Begin::new(extra))
     }
 
     pub fn commit() -> BoltRequest {
@@ -84,22 +175,185 @@ impl BoltRequest {
     pub fn reset() -> BoltRequest {
         BoltRequest::ResetMessage(Reset::new())
     }
+
+    /// Reports the API a query or transaction was started with, from bolt 5.4
+    pub(crate) fn telemetry(api: TelemetryApi) -> BoltRequest {
+        BoltRequest::TelemetryMessage(Telemetry::new(api))
+    }
+
+    /// Announces that the connection is about to be closed, the server doesn't respond to it
+    pub fn goodbye() -> BoltRequest {
+        BoltRequest::GoodbyeMessage(Bye::new())
+    }
+
+    /// Requests the routing table for `db`, an empty name refers to the default database of the
+    /// user, or of the impersonated user when there is one.
+    pub fn route(
+        version: Version,
+        routing: BoltMap,
+        db: &str,
+        impersonated_user: Option<&str>,
+    ) -> BoltRequest {
+        let db_name: BoltType = if db.is_empty() {
+            BoltType::Null(BoltNull)
+        } else {
+            db.into()
+        };
+        let target = if version.supports(Feature::Impersonation) {
+            let mut extra = BoltMap::default();
+            if !db.is_empty() {
+                extra.put("db".into(), db_name);
+            }
+            if let Some(user) = impersonated_user {
+                extra.put("imp_user".into(), user.into());
+            }
+            BoltType::Map(extra)
+        } else {
+            db_name
+        };
+        BoltRequest::RouteMessage(Route::new(routing, BoltList::new(), target))
+    }
+}
+
+fn put_auth(data: &mut BoltMap, auth: &AuthToken) {
+    match auth {
+        AuthToken::Basic { user, password } => {
+            data.put("scheme".into(), "basic".into());
+            data.put("principal".into(), user.as_str().into());
+            data.put("credentials".into(), password.as_str().into());
+        }
+        AuthToken::Bearer(token) => {
+            data.put("scheme".into(), "bearer".into());
+            data.put("credentials".into(), token.as_str().into());
+        }
+        AuthToken::Kerberos(ticket) => {
+            data.put("scheme".into(), "kerberos".into());
+            data.put("principal".into(), "".into());
+            data.put("credentials".into(), ticket.as_str().into());
+        }
+        AuthToken::Custom {
+            scheme,
+            principal,
+            credentials,
+            parameters,
+        } => {
+            data.put("scheme".into(), scheme.as_str().into());
+            if !principal.is_empty() {
+                data.put("principal".into(), principal.as_str().into());
+            }
+            if !credentials.is_empty() {
+                data.put("credentials".into(), credentials.as_str().into());
+            }
+            if !parameters.value.is_empty() {
+                data.put("parameters".into(), BoltType::Map(parameters.clone()));
+            }
+        }
+    }
+}
+
+fn put_bookmarks(extra: &mut BoltMap, bookmarks: &[Bookmark]) {
+    if !bookmarks.is_empty() {
+        extra.put(
+            "bookmarks".into(),
+            BoltType::List(bookmark::to_bolt_list(bookmarks)),
+        );
+    }
+}
+
+fn put_options(extra: &mut BoltMap, options: &TxOptions) {
+    if options.mode == AccessMode::Read {
+        extra.put("mode".into(), "r".into());
+    }
+    if let Some(timeout) = options.timeout {
+        extra.put("tx_timeout".into(), (timeout.as_millis() as i64).into());
+    }
+    if let Some(user) = &options.impersonated_user {
+        extra.put("imp_user".into(), user.as_str().into());
+    }
+    if !options.metadata.value.is_empty() {
+        extra.put(
+            "tx_metadata".into(),
+            BoltType::Map(options.metadata.clone()),
+        );
+    }
+    options.notifications.put(extra);
+}
+
+/// The bolt 3 PULL_ALL message, which has no fields since all the rows are pulled at once
+const PULL_ALL: [u8; 2] = [0xB0, 0x3F];
+/// The bolt 3 DISCARD_ALL message
+const DISCARD_ALL: [u8; 2] = [0xB0, 0x2F];
+
+/// Removes the database of the extra of a message for bolt 3 servers, which only know the
+/// default database and fail on the field
+fn adapt_database(extra: &mut BoltMap, version: Version) -> Result<()> {
+    if version.supports(Feature::MultiDatabase) {
+        return Ok(());
+    }
+    match extra.value.remove(&BoltString::from("db")) {
+        Some(BoltType::String(db)) if !db.is_empty() => Err(Error::UnsupportedVersion(
+            "databases other than the default one require bolt 4.0 or newer".into(),
+        )),
+        _ => Ok(()),
+    }
 }
 
 impl BoltRequest {
     pub fn into_bytes(self, version: Version) -> Result<Bytes> {
         let bytes: Bytes = match self {
-            BoltRequest::HelloMessage(hello) => hello.into_bytes(version)?,
-            BoltRequest::RunMessage(run) => run.into_bytes(version)?,
+            BoltRequest::HelloMessage(mut hello) => {
+                adapt_filter(&mut hello.extra, version);
+                hello.into_bytes(version)?
+            }
+            BoltRequest::LogonMessage(logon) => logon.into_bytes(version)?,
+            BoltRequest::LogoffMessage(logoff) => logoff.into_bytes(version)?,
+            BoltRequest::RunMessage(mut run) => {
+                adapt_filter(&mut run.extra, version);
+                adapt_database(&mut run.extra, version)?;
+                run.into_bytes(version)?
+            }
+            BoltRequest::PullMessage(_) if !version.supports(Feature::ResultBatches) => {
+                Bytes::from_static(&PULL_ALL)
+            }
             BoltRequest::PullMessage(pull) => pull.into_bytes(version)?,
+            BoltRequest::DiscardMessage(_) if !version.supports(Feature::ResultBatches) => {
+                Bytes::from_static(&DISCARD_ALL)
+            }
             BoltRequest::DiscardMessage(discard) => discard.into_bytes(version)?,
-            BoltRequest::BeginMessage(begin) => begin.into_bytes(version)?,
+            BoltRequest::BeginMessage(mut begin) => {
+                adapt_filter(&mut begin.extra, version);
+                adapt_database(&mut begin.extra, version)?;
+                begin.into_bytes(version)?
+            }
             BoltRequest::CommitMessage(commit) => commit.into_bytes(version)?,
             BoltRequest::RollbackMessage(rollback) => rollback.into_bytes(version)?,
             BoltRequest::ResetMessage(reset) => reset.into_bytes(version)?,
+            BoltRequest::RouteMessage(route) => route.into_bytes(version)?,
+            BoltRequest::TelemetryMessage(telemetry) => telemetry.into_bytes(version)?,
+            BoltRequest::GoodbyeMessage(bye) => bye.into_bytes(version)?,
         };
         Ok(bytes)
     }
+
+    /// The name of the message as in the bolt specification, without any of its content
+    #[cfg(feature = "tracing")]
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            BoltRequest::HelloMessage(_) => "HELLO",
+            BoltRequest::LogonMessage(_) => "LOGON",
+            BoltRequest::LogoffMessage(_) => "LOGOFF",
+            BoltRequest::RunMessage(_) => "RUN",
+            BoltRequest::PullMessage(_) => "PULL",
+            BoltRequest::DiscardMessage(_) => "DISCARD",
+            BoltRequest::BeginMessage(_) => "BEGIN",
+            BoltRequest::CommitMessage(_) => "COMMIT",
+            BoltRequest::RollbackMessage(_) => "ROLLBACK",
+            BoltRequest::ResetMessage(_) => "RESET",
+            BoltRequest::RouteMessage(_) => "ROUTE",
+            BoltRequest::TelemetryMessage(_) => "TELEMETRY",
+            BoltRequest::GoodbyeMessage(_) => "GOODBYE",
+        }
+    }
 }
 
 impl BoltResponse {
@@ -114,7 +368,32 @@ impl BoltResponse {
             input if Record::can_parse(version, input.clone()) => {
                 Ok(BoltResponse::RecordMessage(Record::parse(version, input)?))
             }
+            input if Ignored::can_parse(version, input.clone()) => Ok(
+                BoltResponse::IgnoredMessage(Ignored::parse(version, input)?),
+            ),
             msg => Err(Error::UnknownMessage(format!("unknown message {:?}", msg))),
         }
     }
+
+    /// Turns a response `request` didn't expect into an error, failures reported by the server
+    /// keep their status code.
+    pub fn into_error(self, request: &str) -> Error {
+        match self {
+            BoltResponse::FailureMessage(failure) => {
+                Error::Neo4jError(Neo4jError::parse(failure.metadata))
+            }
+            msg => unexpected(msg, request),
+        }
+    }
+
+    /// The name of the message as in the bolt specification, without any of its content
+    #[cfg(feature = "tracing")]
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            BoltResponse::SuccessMessage(_) => "SUCCESS",
+            BoltResponse::FailureMessage(_) => "FAILURE",
+            BoltResponse::RecordMessage(_) => "RECORD",
+            BoltResponse::IgnoredMessage(_) => "IGNORED",
+        }
+    }
 }
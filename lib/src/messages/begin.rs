@@ -4,7 +4,7 @@ use neo4rs_macros::BoltStruct;
 #[derive(Debug, PartialEq, Clone, BoltStruct)]
 #[signature(0xB1, 0x11)]
 pub struct Begin {
-    extra: BoltMap,
+    pub(crate) extra: BoltMap,
 }
 
 impl Begin {
@@ -52,4 +52,19 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn should_begin_read_transactions() {
+        let options = crate::txn::TxOptions::new().read();
+
+        let begin = crate::messages::BoltRequest::begin("neo4j", &[], &options);
+
+        let extra = vec![("db".into(), "neo4j".into()), ("mode".into(), "r".into())]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            begin,
+            crate::messages::BoltRequest::BeginMessage(Begin::new(extra))
+        );
+    }
 }
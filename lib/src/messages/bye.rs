@@ -4,6 +4,12 @@ use neo4rs_macros::BoltStruct;
 #[signature(0xB0, 0x02)]
 pub struct Bye;
 
+impl Bye {
+    pub fn new() -> Bye {
+        Bye
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -12,7 +18,7 @@ mod tests {
 
     #[test]
     fn should_serialize_bye() {
-        let bye = Bye {};
+        let bye = Bye::new();
 
         let bytes: Bytes = bye.into_bytes(Version::V4_1).unwrap();
 
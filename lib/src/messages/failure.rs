@@ -4,13 +4,18 @@ use neo4rs_macros::BoltStruct;
 #[derive(Debug, PartialEq, Clone, BoltStruct)]
 #[signature(0xB1, 0x7F)]
 pub struct Failure {
-    metadata: BoltMap,
+    pub(crate) metadata: BoltMap,
 }
 
 impl Failure {
-    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+    pub fn get<T: crate::convert::FromBoltValue>(&self, key: &str) -> Option<T> {
         self.metadata.get(key)
     }
+
+    /// The status code of the failure, servers send it as `neo4j_code` from bolt 5.7
+    pub(crate) fn code(&self) -> Option<String> {
+        self.get("neo4j_code").or_else(|| self.get("code"))
+    }
 }
 
 #[cfg(test)]
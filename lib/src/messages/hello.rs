@@ -4,7 +4,7 @@ use neo4rs_macros::BoltStruct;
 #[derive(Debug, PartialEq, Clone, BoltStruct)]
 #[signature(0xB1, 0x01)]
 pub struct Hello {
-    extra: BoltMap,
+    pub(crate) extra: BoltMap,
 }
 
 impl Hello {
@@ -19,6 +19,88 @@ mod tests {
     use crate::version::Version;
     use bytes::*;
 
+    #[test]
+    fn should_describe_the_driver_in_the_bolt_agent() {
+        let auth = crate::auth::AuthToken::Bearer("some_token".into());
+
+        let hello = crate::messages::BoltRequest::hello(
+            Version::V5_3,
+            "my-service/1.0",
+            None,
+            &Default::default(),
+            &auth,
+        );
+        let older = crate::messages::BoltRequest::hello(
+            Version::V5_2,
+            "my-service/1.0",
+            None,
+            &Default::default(),
+            &auth,
+        );
+
+        let extra = match hello {
+            crate::messages::BoltRequest::HelloMessage(hello) => hello.extra,
+            _ => panic!("expected a hello message"),
+        };
+        let bolt_agent: BoltMap = extra.get("bolt_agent").unwrap();
+        assert_eq!(extra.get::<String>("user_agent").unwrap(), "my-service/1.0");
+        assert_eq!(
+            bolt_agent.get::<String>("product").unwrap(),
+            crate::config::DRIVER_AGENT
+        );
+        assert_eq!(bolt_agent.get::<String>("language").unwrap(), "Rust");
+        assert!(bolt_agent.get::<String>("platform").is_some());
+        match older {
+            crate::messages::BoltRequest::HelloMessage(hello) => {
+                assert!(hello.extra.get::<BoltMap>("bolt_agent").is_none())
+            }
+            _ => panic!("expected a hello message"),
+        }
+    }
+
+    #[test]
+    fn should_ask_for_the_utc_patch_on_4_4() {
+        let auth = crate::auth::AuthToken::Bearer("some_token".into());
+        let patches = |version| match crate::messages::BoltRequest::hello(
+            version,
+            "my-service/1.0",
+            None,
+            &Default::default(),
+            &auth,
+        ) {
+            crate::messages::BoltRequest::HelloMessage(hello) => {
+                hello.extra.get::<Vec<String>>("patch_bolt")
+            }
+            _ => panic!("expected a hello message"),
+        };
+
+        assert_eq!(patches(Version::V4_4), Some(vec!["utc".to_owned()]));
+        assert_eq!(patches(Version::V4_3), None);
+        assert_eq!(patches(Version::V5_0), None);
+    }
+
+    #[test]
+    fn should_filter_notifications_from_5_2() {
+        use crate::summary::{MinimumSeverity, NotificationFilter};
+        let auth = crate::auth::AuthToken::Bearer("some_token".into());
+        let filter = NotificationFilter::new().minimum_severity(MinimumSeverity::Off);
+        let severity = |version| match crate::messages::BoltRequest::hello(
+            version,
+            "my-service/1.0",
+            None,
+            &filter,
+            &auth,
+        ) {
+            crate::messages::BoltRequest::HelloMessage(hello) => {
+                hello.extra.get::<String>("notifications_minimum_severity")
+            }
+            _ => panic!("expected a hello message"),
+        };
+
+        assert_eq!(severity(Version::V5_2), Some("OFF".to_owned()));
+        assert_eq!(severity(Version::V5_1), None);
+    }
+
     #[test]
     fn should_serialize_hello() {
         let hello = Hello::new(
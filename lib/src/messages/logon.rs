@@ -0,0 +1,97 @@
+use crate::types::*;
+use neo4rs_macros::BoltStruct;
+
+#[derive(Debug, PartialEq, Clone, BoltStruct)]
+#[signature(0xB1, 0x6A)]
+pub struct Logon {
+    auth: BoltMap,
+}
+
+impl Logon {
+    pub fn new(auth: BoltMap) -> Logon {
+        Logon { auth }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use bytes::*;
+
+    #[test]
+    fn should_serialize_logon() {
+        let logon = Logon::new(
+            vec![("scheme".into(), "basic".into())]
+                .into_iter()
+                .collect(),
+        );
+
+        let bytes: Bytes = logon.into_bytes(Version::V5_1).unwrap();
+
+        assert_eq!(
+            bytes,
+            Bytes::from_static(&[
+                0xB1,
+                0x6A,
+                map::TINY | 1,
+                string::TINY | 6,
+                b's',
+                b'c',
+                b'h',
+                b'e',
+                b'm',
+                b'e',
+                string::TINY | 5,
+                b'b',
+                b'a',
+                b's',
+                b'i',
+                b'c',
+            ])
+        );
+    }
+
+    #[test]
+    fn should_log_on_with_bearer_token() {
+        let auth = crate::auth::AuthToken::Bearer("some_token".into());
+
+        let logon = crate::messages::BoltRequest::logon(&auth);
+
+        let expected = vec![
+            ("scheme".into(), "bearer".into()),
+            ("credentials".into(), "some_token".into()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            logon,
+            crate::messages::BoltRequest::LogonMessage(Logon::new(expected))
+        );
+    }
+
+    #[test]
+    fn should_log_on_with_custom_auth() {
+        let parameters: BoltMap = vec![("realm".into(), "corp".into())].into_iter().collect();
+        let auth = crate::auth::AuthToken::Custom {
+            scheme: "plugin".into(),
+            principal: "some_user".into(),
+            credentials: "".into(),
+            parameters: parameters.clone(),
+        };
+
+        let logon = crate::messages::BoltRequest::logon(&auth);
+
+        let expected = vec![
+            ("scheme".into(), "plugin".into()),
+            ("principal".into(), "some_user".into()),
+            ("parameters".into(), BoltType::Map(parameters)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            logon,
+            crate::messages::BoltRequest::LogonMessage(Logon::new(expected))
+        );
+    }
+}
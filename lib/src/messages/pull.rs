@@ -42,8 +42,8 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(extra.get::<i64>("n").unwrap(), 42.into());
-        assert_eq!(extra.get::<i64>("qid").unwrap(), 1.into());
+        assert_eq!(extra.get::<i64>("n").unwrap(), 42);
+        assert_eq!(extra.get::<i64>("qid").unwrap(), 1);
     }
 
     #[test]
@@ -58,7 +58,16 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(extra.get::<i64>("n").unwrap(), 255.into());
-        assert_eq!(extra.get::<i64>("qid").unwrap(), 255.into());
+        assert_eq!(extra.get::<i64>("n").unwrap(), 255);
+        assert_eq!(extra.get::<i64>("qid").unwrap(), 255);
+    }
+
+    #[test]
+    fn should_pull_all_rows_on_bolt_3() {
+        let pull = crate::messages::BoltRequest::pull(1000, 2);
+        let discard = crate::messages::BoltRequest::discard_all(2);
+
+        assert_eq!(pull.into_bytes(Version::V3).unwrap(), &[0xB0, 0x3F][..]);
+        assert_eq!(discard.into_bytes(Version::V3).unwrap(), &[0xB0, 0x2F][..]);
     }
 }
@@ -1,19 +1,67 @@
+use crate::errors::*;
+use crate::limits::value_length;
 use crate::types::*;
-use neo4rs_macros::BoltStruct;
+use crate::version::Version;
+use bytes::*;
+use std::cell::RefCell;
+use std::rc::Rc;
 
-#[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB1, 0x71)]
+/// A RECORD message, the values of its columns are kept packstream encoded until they are read,
+/// see [`Row`](crate::Row)
+#[derive(Debug, PartialEq, Clone)]
 pub struct Record {
-    pub data: BoltList,
+    pub(crate) version: Version,
+    pub(crate) values: Vec<Bytes>,
+}
+
+impl Record {
+    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
+        let input = input.borrow();
+        input.len() >= 2 && input[0] == 0xB1 && input[1] == 0x71
+    }
+
+    /// Splits the record into the encoded values of its columns, which are slices of the
+    /// message rather than copies
+    pub fn parse(version: Version, input: Rc<RefCell<Bytes>>) -> Result<Record> {
+        let mut input = input.borrow_mut();
+        input.advance(2);
+        if input.is_empty() {
+            return Err(Error::DeserializationError("truncated message".into()));
+        }
+        let marker = input.get_u8();
+        let count = match marker {
+            0x90..=0x9F => (marker & 0x0F) as usize,
+            list::SMALL if !input.is_empty() => input.get_u8() as usize,
+            list::MEDIUM if input.len() >= 2 => input.get_u16() as usize,
+            list::LARGE if input.len() >= 4 => input.get_u32() as usize,
+            _ => {
+                return Err(Error::InvalidTypeMarker(format!(
+                    "invalid record marker {}",
+                    marker
+                )))
+            }
+        };
+        let mut values = Vec::with_capacity(count.min(input.len()));
+        for _ in 0..count {
+            let length = value_length(&input)?;
+            values.push(input.split_to(length));
+        }
+        Ok(Record { version, values })
+    }
+
+    /// Decodes the values of all the columns
+    pub fn data(&self) -> Result<BoltList> {
+        self.values
+            .iter()
+            .map(|value| BoltType::parse(self.version, Rc::new(RefCell::new(value.clone()))))
+            .collect::<Result<Vec<_>>>()
+            .map(BoltList::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::version::Version;
-    use bytes::*;
-    use std::cell::RefCell;
-    use std::rc::Rc;
 
     #[test]
     fn should_deserialize_record_message() {
@@ -23,6 +71,27 @@ mod tests {
 
         let record: Record = Record::parse(Version::V4_1, bytes).unwrap();
 
-        assert_eq!(record.data.len(), 2);
+        assert_eq!(record.values.len(), 2);
+        assert_eq!(
+            record.data().unwrap(),
+            BoltList::from(vec![BoltType::from("a"), BoltType::from("b")])
+        );
+    }
+
+    #[test]
+    fn should_keep_the_values_encoded() {
+        let bytes = Rc::new(RefCell::new(Bytes::from_static(&[
+            0xB1, 0x71, 0x92, 0x92, 0x01, 0x02, 0x81, 0x61,
+        ])));
+
+        let record: Record = Record::parse(Version::V4_1, bytes).unwrap();
+
+        assert_eq!(
+            record.values,
+            vec![
+                Bytes::from_static(&[0x92, 0x01, 0x02]),
+                Bytes::from_static(&[0x81, 0x61])
+            ]
+        );
     }
 }
@@ -0,0 +1,62 @@
+use crate::types::*;
+use neo4rs_macros::BoltStruct;
+
+// Requests the routing table of a database, the last field is the database name (or null for
+// the default database) in bolt 4.3 and a map of extras from bolt 4.4 onwards.
+#[derive(Debug, PartialEq, Clone, BoltStruct)]
+#[signature(0xB3, 0x66)]
+pub struct Route {
+    routing: BoltMap,
+    bookmarks: BoltList,
+    db: BoltType,
+}
+
+impl Route {
+    pub fn new(routing: BoltMap, bookmarks: BoltList, db: BoltType) -> Route {
+        Route {
+            routing,
+            bookmarks,
+            db,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+
+    #[test]
+    fn should_serialize_route() {
+        let route = Route::new(
+            vec![("address".into(), "a:1".into())].into_iter().collect(),
+            BoltList::new(),
+            BoltType::Null(BoltNull),
+        );
+
+        let bytes: Bytes = route.into_bytes(Version::V4_3).unwrap();
+
+        assert_eq!(
+            bytes,
+            Bytes::from_static(&[
+                0xB3,
+                0x66,
+                map::TINY | 1,
+                string::TINY | 7,
+                b'a',
+                b'd',
+                b'd',
+                b'r',
+                b'e',
+                b's',
+                b's',
+                string::TINY | 3,
+                b'a',
+                b':',
+                b'1',
+                list::TINY,
+                0xC0,
+            ])
+        );
+    }
+}
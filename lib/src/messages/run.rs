@@ -6,17 +6,15 @@ use neo4rs_macros::BoltStruct;
 pub struct Run {
     query: BoltString,
     parameters: BoltMap,
-    extra: BoltMap,
+    pub(crate) extra: BoltMap,
 }
 
 impl Run {
-    pub fn new(db: BoltString, query: BoltString, parameters: BoltMap) -> Run {
+    pub fn new(query: BoltString, parameters: BoltMap, extra: BoltMap) -> Run {
         Run {
             query,
             parameters,
-            extra: vec![("db".into(), BoltType::String(db))]
-                .into_iter()
-                .collect(),
+            extra,
         }
     }
 }
@@ -29,9 +27,9 @@ mod tests {
     #[test]
     fn should_serialize_run() {
         let run = Run::new(
-            "test".into(),
             "query".into(),
             vec![("k".into(), "v".into())].into_iter().collect(),
+            vec![("db".into(), "test".into())].into_iter().collect(),
         );
 
         let bytes: Bytes = run.into_bytes(Version::V4_1).unwrap();
@@ -67,7 +65,11 @@ mod tests {
 
     #[test]
     fn should_serialize_run_with_no_params() {
-        let run = Run::new("".into(), "query".into(), BoltMap::default());
+        let run = Run::new(
+            "query".into(),
+            BoltMap::default(),
+            vec![("db".into(), "".into())].into_iter().collect(),
+        );
 
         let bytes: Bytes = run.into_bytes(Version::V4_1).unwrap();
 
@@ -91,4 +93,52 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn should_send_tx_options_in_extra() {
+        let options = crate::txn::TxOptions::new()
+            .timeout(std::time::Duration::from_secs(2))
+            .metadata(vec![("app", "billing")]);
+
+        let run =
+            crate::messages::BoltRequest::run("neo4j", "query", BoltMap::default(), &[], &options);
+
+        let metadata: BoltMap = vec![("app".into(), "billing".into())].into_iter().collect();
+        let extra = vec![
+            ("db".into(), "neo4j".into()),
+            ("tx_timeout".into(), 2000.into()),
+            ("tx_metadata".into(), BoltType::Map(metadata)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            run,
+            crate::messages::BoltRequest::RunMessage(Run::new(
+                "query".into(),
+                BoltMap::default(),
+                extra
+            ))
+        );
+    }
+
+    #[test]
+    fn should_leave_the_database_out_on_bolt_3() {
+        let run = |db| {
+            crate::messages::BoltRequest::run(
+                db,
+                "query",
+                BoltMap::default(),
+                &[],
+                &Default::default(),
+            )
+        };
+        let default = run("").into_bytes(Version::V3).unwrap();
+
+        assert_eq!(&default[default.len() - 1..], &[map::TINY]);
+        assert!(run("").into_bytes(Version::V4).unwrap().len() > default.len());
+        assert!(matches!(
+            run("neo4j").into_bytes(Version::V3),
+            Err(crate::errors::Error::UnsupportedVersion(_))
+        ));
+    }
 }
@@ -8,7 +8,12 @@ pub struct Success {
 }
 
 impl Success {
-    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+    #[cfg(test)]
+    pub fn new(metadata: BoltMap) -> Success {
+        Success { metadata }
+    }
+
+    pub fn get<T: crate::convert::FromBoltValue>(&self, key: &str) -> Option<T> {
         self.metadata.get(key)
     }
 }
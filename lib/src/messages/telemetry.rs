@@ -0,0 +1,44 @@
+use crate::types::*;
+use neo4rs_macros::BoltStruct;
+
+/// The shape of the API a query or transaction was started with, reported to servers which ask
+/// for it with a TELEMETRY message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TelemetryApi {
+    /// A transaction retried by the driver, like [`Graph::write_tx`](crate::Graph::write_tx)
+    ManagedTransaction = 0,
+    /// A transaction committed by the application, see [`Graph::start_txn`](crate::Graph::start_txn)
+    ExplicitTransaction = 1,
+    /// An auto-commit query, like [`Graph::run`](crate::Graph::run)
+    AutoCommit = 2,
+}
+
+#[derive(Debug, PartialEq, Clone, BoltStruct)]
+#[signature(0xB1, 0x54)]
+pub struct Telemetry {
+    api: BoltInteger,
+}
+
+impl Telemetry {
+    pub(crate) fn new(api: TelemetryApi) -> Telemetry {
+        Telemetry {
+            api: BoltInteger::new(api as i64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use bytes::*;
+
+    #[test]
+    fn should_serialize_telemetry() {
+        let telemetry = Telemetry::new(TelemetryApi::AutoCommit);
+
+        let bytes: Bytes = telemetry.into_bytes(Version::V5_4).unwrap();
+
+        assert_eq!(bytes, Bytes::from_static(&[0xB1, 0x54, 0x02]));
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Receives measurements of the driver, for dashboards and alerts, see
+/// [`ConfigBuilder::metrics`](crate::ConfigBuilder::metrics)
+///
+/// Every measurement has an empty default implementation, so implementations only provide the
+/// ones they record. The methods are called on the hot path of queries and should return quickly.
+pub trait DriverMetrics: Send + Sync {
+    /// A query on `db` was accepted by the server after `latency`, for queries whose rows are
+    /// discarded the latency includes discarding the rows
+    fn query_latency(&self, _db: &str, _latency: Duration) {}
+
+    /// A batch of `rows` rows was pulled from the server
+    fn rows_streamed(&self, _rows: u64) {}
+
+    /// A connection was handed out by the pool after waiting for `wait`
+    fn pool_acquire_time(&self, _wait: Duration) {}
+
+    /// The server reported a failure with the status `code`, like
+    /// `Neo.ClientError.Statement.SyntaxError`
+    fn error(&self, _code: &str) {}
+}
+
+/// The metrics of the config, shared by the pools, connections and streams
+#[derive(Clone)]
+pub(crate) struct MetricsHook(pub(crate) Arc<dyn DriverMetrics>);
+
+impl fmt::Debug for MetricsHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MetricsHook")
+    }
+}
+
+/// Records the measurements of the driver with the [metrics](https://docs.rs/metrics) crate, so
+/// they are exported by whichever recorder is installed, like the Prometheus exporter of
+/// `metrics-exporter-prometheus`
+///
+/// * `neo4rs_query_duration_seconds`: histogram of the query latencies, labeled by `db`
+/// * `neo4rs_rows_streamed_total`: counter of the rows pulled from the server
+/// * `neo4rs_pool_acquire_seconds`: histogram of the time waited for a connection of the pool
+/// * `neo4rs_errors_total`: counter of the failures reported by the server, labeled by `code`
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsRecorder;
+
+#[cfg(feature = "metrics")]
+impl DriverMetrics for MetricsRecorder {
+    fn query_latency(&self, db: &str, latency: Duration) {
+        ::metrics::histogram!("neo4rs_query_duration_seconds", "db" => db.to_owned())
+            .record(latency.as_secs_f64());
+    }
+
+    fn rows_streamed(&self, rows: u64) {
+        ::metrics::counter!("neo4rs_rows_streamed_total").increment(rows);
+    }
+
+    fn pool_acquire_time(&self, wait: Duration) {
+        ::metrics::histogram!("neo4rs_pool_acquire_seconds").record(wait.as_secs_f64());
+    }
+
+    fn error(&self, code: &str) {
+        ::metrics::counter!("neo4rs_errors_total", "code" => code.to_owned()).increment(1);
+    }
+}
@@ -0,0 +1,349 @@
+//! Versioned cypher migrations, applied in order and recorded in the database
+//!
+//! Every migration applied by a [`Migrator`] is recorded as a node with the label
+//! `__Neo4rsMigration` (see [`Migrator::label`]), holding its version, name, checksum and the
+//! time it was applied. Running the migrator again only applies the migrations that weren't
+//! recorded yet, and fails when a recorded migration was changed since it was applied.
+//!
+//! A migration can hold several statements separated by `;`. Since neo4j doesn't allow schema
+//! changes and writes in the same transaction, every statement runs in a transaction of its own
+//! and the migration is only recorded once all of them succeeded, so a migration failing
+//! halfway should be written to be applied again, like with `IF NOT EXISTS`.
+//!
+//! ```no_run
+//! use neo4rs::migrations::{Migration, Migrator};
+//! use neo4rs::*;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!    let graph = Graph::new("127.0.0.1:7687", "neo4j", "neo").await.unwrap();
+//!    let migrator = Migrator::new(vec![
+//!        Migration::new(1, "people", "CREATE CONSTRAINT people IF NOT EXISTS FOR (p:Person) REQUIRE p.id IS UNIQUE"),
+//!        Migration::new(2, "names", "MATCH (p:Person) WHERE p.name IS NULL SET p.name = 'unknown'"),
+//!    ]);
+//!    for migration in migrator.run(&graph).await.unwrap() {
+//!        println!("applied {} {}", migration.version(), migration.name());
+//!    }
+//! }
+//! ```
+use crate::errors::{Error, Result};
+use crate::graph::{query, Graph};
+use std::collections::HashMap;
+use std::path::Path;
+
+const LABEL: &str = "__Neo4rsMigration";
+
+/// A cypher script applied once to a database, identified by its version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    version: i64,
+    name: String,
+    cypher: String,
+}
+
+impl Migration {
+    pub fn new(version: i64, name: &str, cypher: &str) -> Self {
+        Migration {
+            version,
+            name: name.to_owned(),
+            cypher: cypher.to_owned(),
+        }
+    }
+
+    pub fn version(&self) -> i64 {
+        self.version
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn cypher(&self) -> &str {
+        &self.cypher
+    }
+
+    /// A hash of the cypher, recorded with the migration to detect changes once it was applied.
+    /// It is stable across platforms and releases, and ignores leading and trailing whitespace.
+    pub fn checksum(&self) -> String {
+        // FNV-1a, 64 bits
+        let hash = self
+            .cypher
+            .trim()
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+        format!("{:016x}", hash)
+    }
+
+    /// The statements of the script, split on the `;` outside of strings, quoted names and
+    /// comments
+    pub(crate) fn statements(&self) -> Vec<&str> {
+        let cypher = self.cypher.as_str();
+        let bytes = cypher.as_bytes();
+        let mut statements = Vec::new();
+        let mut start = 0;
+        let mut quote = None;
+        let mut i = 0;
+        while i < bytes.len() {
+            match (quote, bytes[i]) {
+                (Some(_), b'\\') => i += 1,
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, c @ (b'\'' | b'"' | b'`')) => quote = Some(c),
+                (None, b'/') if bytes.get(i + 1) == Some(&b'/') => {
+                    while i < bytes.len() && bytes[i] != b'\n' {
+                        i += 1;
+                    }
+                }
+                (None, b'/') if bytes.get(i + 1) == Some(&b'*') => {
+                    i = cypher[i + 2..]
+                        .find("*/")
+                        .map_or(bytes.len(), |end| i + end + 3);
+                }
+                (None, b';') => {
+                    statements.push(&cypher[start..i]);
+                    start = i + 1;
+                }
+                (None, _) => {}
+            }
+            i += 1;
+        }
+        statements.push(&cypher[start.min(cypher.len())..]);
+        statements.retain(|statement| !is_blank(statement));
+        statements.into_iter().map(str::trim).collect()
+    }
+}
+
+/// Whether a statement holds nothing but whitespace and comments
+fn is_blank(statement: &str) -> bool {
+    statement
+        .lines()
+        .map(|line| line.split("//").next().unwrap_or_default().trim())
+        .all(|line| line.is_empty() || (line.starts_with("/*") && line.ends_with("*/")))
+}
+
+/// A migration recorded in the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+}
+
+/// Applies the [`Migration`]s that weren't applied to a database yet, in the order of their
+/// versions
+#[derive(Debug, Clone)]
+pub struct Migrator {
+    migrations: Vec<Migration>,
+    label: String,
+    dry_run: bool,
+}
+
+impl Migrator {
+    pub fn new(migrations: impl IntoIterator<Item = Migration>) -> Self {
+        let mut migrations: Vec<_> = migrations.into_iter().collect();
+        migrations.sort_by_key(Migration::version);
+        Migrator {
+            migrations,
+            label: LABEL.to_owned(),
+            dry_run: false,
+        }
+    }
+
+    /// Reads the migrations of a directory, one per file named `V<version>__<name>.cypher`, like
+    /// `V1__create_constraints.cypher`. Other files are ignored.
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let mut migrations = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let file_name = path.file_name().and_then(|name| name.to_str());
+            if let Some((version, name)) = file_name.and_then(parse_file_name) {
+                let cypher = std::fs::read_to_string(&path)?;
+                migrations.push(Migration::new(version, name, &cypher));
+            }
+        }
+        let migrator = Self::new(migrations);
+        migrator.check()?;
+        Ok(migrator)
+    }
+
+    /// The label of the nodes recording the applied migrations (default is `__Neo4rsMigration`)
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = label.to_owned();
+        self
+    }
+
+    /// Only returns the migrations that would be applied, without running them
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    /// Applies the pending migrations in order and returns them. Fails without applying any
+    /// when an applied migration was changed, or when two migrations share a version.
+    pub async fn run(&self, graph: &Graph) -> Result<Vec<Migration>> {
+        self.check()?;
+        let pending = self.pending(&self.applied(graph).await?)?;
+        if self.dry_run {
+            return Ok(pending);
+        }
+        for migration in &pending {
+            for statement in migration.statements() {
+                graph.run(query(statement)).await.map_err(|e| {
+                    Error::MigrationError(format!(
+                        "migration {} {} failed: {:?}",
+                        migration.version, migration.name, e
+                    ))
+                })?;
+            }
+            let record = format!(
+                "CREATE (m:{} {{version: $version, name: $name, checksum: $checksum, applied_at: datetime()}})",
+                self.escaped_label()
+            );
+            graph
+                .run(
+                    query(&record)
+                        .param("version", migration.version)
+                        .param("name", migration.name.as_str())
+                        .param("checksum", migration.checksum()),
+                )
+                .await?;
+        }
+        Ok(pending)
+    }
+
+    /// The migrations recorded in the database, in the order of their versions
+    pub async fn applied(&self, graph: &Graph) -> Result<Vec<AppliedMigration>> {
+        let cypher = format!(
+            "MATCH (m:{}) RETURN m.version AS version, m.name AS name, m.checksum AS checksum ORDER BY version",
+            self.escaped_label()
+        );
+        let mut rows = graph.execute(query(&cypher)).await?;
+        let mut applied = Vec::new();
+        for row in rows.fetch_all().await? {
+            applied.push(AppliedMigration {
+                version: row.get("version").ok_or(Error::ConverstionError)?,
+                name: row.get("name").unwrap_or_default(),
+                checksum: row.get("checksum").unwrap_or_default(),
+            });
+        }
+        Ok(applied)
+    }
+
+    /// The migrations not applied yet, after checking that the applied ones are unchanged
+    fn pending(&self, applied: &[AppliedMigration]) -> Result<Vec<Migration>> {
+        let applied: HashMap<_, _> = applied
+            .iter()
+            .map(|migration| (migration.version, migration))
+            .collect();
+        let mut pending = Vec::new();
+        for migration in &self.migrations {
+            match applied.get(&migration.version) {
+                Some(recorded) if recorded.checksum != migration.checksum() => {
+                    return Err(Error::MigrationError(format!(
+                        "migration {} {} was changed after it was applied",
+                        migration.version, migration.name
+                    )))
+                }
+                Some(_) => {}
+                None => pending.push(migration.clone()),
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Fails when two migrations share a version
+    fn check(&self) -> Result<()> {
+        match self
+            .migrations
+            .windows(2)
+            .find(|pair| pair[0].version == pair[1].version)
+        {
+            Some(pair) => Err(Error::MigrationError(format!(
+                "migrations {} and {} share the version {}",
+                pair[0].name, pair[1].name, pair[0].version
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn escaped_label(&self) -> String {
+        format!("`{}`", self.label.replace('`', "``"))
+    }
+}
+
+fn parse_file_name(file_name: &str) -> Option<(i64, &str)> {
+    let stem = file_name.strip_suffix(".cypher")?;
+    let (version, name) = stem.strip_prefix('V')?.split_once("__")?;
+    Some((version.parse().ok()?, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_split_statements() {
+        let migration = Migration::new(
+            1,
+            "split",
+            "CREATE (:A {name: 'a;b'});\n// comment; here\nCREATE (:`B;`);\n/* ; */ MATCH (n) RETURN n;\n",
+        );
+
+        assert_eq!(
+            migration.statements(),
+            vec![
+                "CREATE (:A {name: 'a;b'})",
+                "// comment; here\nCREATE (:`B;`)",
+                "/* ; */ MATCH (n) RETURN n"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_find_pending_and_changed_migrations() {
+        let migrator = Migrator::new(vec![
+            Migration::new(2, "second", "CREATE (:B)"),
+            Migration::new(1, "first", "CREATE (:A)"),
+        ]);
+        let first = &migrator.migrations()[0];
+        assert_eq!(first.version(), 1);
+        assert_eq!(
+            first.checksum(),
+            Migration::new(1, "", " CREATE (:A)\n").checksum()
+        );
+
+        let applied = vec![AppliedMigration {
+            version: 1,
+            name: "first".into(),
+            checksum: first.checksum(),
+        }];
+        let pending = migrator.pending(&applied).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].name(), "second");
+
+        let changed = vec![AppliedMigration {
+            checksum: "0".into(),
+            ..applied[0].clone()
+        }];
+        assert!(matches!(
+            migrator.pending(&changed),
+            Err(Error::MigrationError(_))
+        ));
+    }
+
+    #[test]
+    fn should_parse_migration_file_names() {
+        assert_eq!(
+            parse_file_name("V12__add_index.cypher"),
+            Some((12, "add_index"))
+        );
+        assert_eq!(parse_file_name("V1_add_index.cypher"), None);
+        assert_eq!(parse_file_name("README.md"), None);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::errors::Result;
+use crate::execute::Execute;
+use crate::query::Query;
+use crate::row::Row;
+use crate::stream::RowStream;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// An [`Execute`] implementation answering queries with canned rows instead of a server, for
+/// unit tests of code taking an `&impl Execute` or a `&dyn Execute`
+///
+/// The results are returned in the order they were queued, queries run once the queue is empty
+/// return no rows. Clones share the queue and the queries received.
+#[derive(Default, Clone)]
+pub struct MockGraph {
+    results: Arc<Mutex<VecDeque<Result<Vec<Row>>>>>,
+    queries: Arc<Mutex<Vec<Query>>>,
+}
+
+impl MockGraph {
+    pub fn new() -> MockGraph {
+        MockGraph::default()
+    }
+
+    /// Queues the rows of the next query
+    pub fn returning(self, rows: Vec<Row>) -> MockGraph {
+        self.results.lock().unwrap().push_back(Ok(rows));
+        self
+    }
+
+    /// Queues an error the next query fails with
+    pub fn failing(self, error: crate::errors::Error) -> MockGraph {
+        self.results.lock().unwrap().push_back(Err(error));
+        self
+    }
+
+    /// The queries received so far, in order
+    pub fn queries(&self) -> Vec<Query> {
+        self.queries.lock().unwrap().clone()
+    }
+
+    fn answer(&self, q: Query) -> Result<Vec<Row>> {
+        self.queries.lock().unwrap().push(q);
+        self.results
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| Ok(Vec::new()))
+    }
+}
+
+#[async_trait]
+impl Execute for MockGraph {
+    async fn run(&self, q: Query) -> Result<()> {
+        self.answer(q).map(|_| ())
+    }
+
+    async fn execute(&self, q: Query) -> Result<RowStream> {
+        self.answer(q).map(RowStream::from_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+    use crate::graph::query;
+    use crate::types::BoltList;
+
+    async fn names(executor: &dyn Execute) -> Result<Vec<String>> {
+        let mut rows = executor
+            .execute(query("MATCH (p:Person) RETURN p.name AS name"))
+            .await?;
+        let mut names = Vec::new();
+        while let Some(row) = rows.next().await? {
+            names.push(row.get("name").unwrap());
+        }
+        Ok(names)
+    }
+
+    #[tokio::test]
+    async fn should_answer_with_the_queued_results() {
+        let row = |name: &str| {
+            Row::new(
+                BoltList::from(vec!["name".into()]),
+                BoltList::from(vec![name.into()]),
+            )
+        };
+        let graph = MockGraph::new()
+            .returning(vec![row("Alice"), row("Bob")])
+            .failing(Error::ConnectionError);
+
+        assert_eq!(names(&graph).await.unwrap(), vec!["Alice", "Bob"]);
+        assert!(matches!(names(&graph).await, Err(Error::ConnectionError)));
+        assert!(names(&graph).await.unwrap().is_empty());
+        graph
+            .run(query("CREATE (p:Person {name: $name})").param("name", "Carol"))
+            .await
+            .unwrap();
+
+        let queries = graph.queries();
+        assert_eq!(queries.len(), 4);
+        assert_eq!(queries[3].get_param::<String>("name").unwrap(), "Carol");
+        assert_eq!(
+            queries[0].cypher(),
+            "MATCH (p:Person) RETURN p.name AS name"
+        );
+    }
+}
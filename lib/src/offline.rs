@@ -0,0 +1,122 @@
+//! Verification of the queries of [`query_checked!`](crate::query_checked) against a development
+//! database, so typos in cypher or parameter names are caught when building instead of once the
+//! query runs
+//!
+//! Like [`query!`](crate::query!), `query_checked!` always checks that values are only given for
+//! the `$parameters` of the query. When the crate has a `.neo4rs` directory next to its `Cargo.toml`
+//! (or a directory named by `NEO4RS_OFFLINE_DIR`), the query must further be verified, which is
+//! done in two steps:
+//!
+//! 1. Building the crate with `NEO4RS_PREPARE=1`, like `NEO4RS_PREPARE=1 cargo check`, records
+//!    the queries of every `query_checked!` in `.neo4rs/pending`. Cargo doesn't rebuild a crate
+//!    when only the variable changed, `cargo clean -p <crate>` beforehand makes sure every query
+//!    is recorded.
+//! 2. [`prepare`] sends every recorded query with `EXPLAIN` to a development database, which
+//!    plans it without running it, and moves the queries it accepts to `.neo4rs`
+//!
+//! The `.neo4rs` directory is meant to be committed, builds then check the queries offline,
+//! without a database. `prepare` can be run from a small binary of the project:
+//!
+//! ```no_run
+//! use neo4rs::*;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!    let graph = Graph::new("127.0.0.1:7687", "neo4j", "neo").await.unwrap();
+//!    let verified = offline::prepare(&graph, ".neo4rs").await.unwrap();
+//!    println!("verified {} queries", verified);
+//! }
+//! ```
+use crate::errors::{Error, Result};
+use crate::graph::{query, Graph};
+use crate::types::{BoltNull, BoltType};
+use std::path::Path;
+
+const PARAMS: &str = "// params:";
+
+/// Verifies the queries recorded in `dir/pending` with `EXPLAIN` against the database of
+/// `graph` and moves the valid ones to `dir`, returning how many were verified. Fails with
+/// [`Error::InvalidQuery`] listing the queries the database rejected, which are left pending.
+pub async fn prepare(graph: &Graph, dir: impl AsRef<Path>) -> Result<usize> {
+    let dir = dir.as_ref();
+    let pending = dir.join("pending");
+    if !pending.is_dir() {
+        return Ok(0);
+    }
+    let mut verified = 0;
+    let mut rejected = Vec::new();
+    for entry in std::fs::read_dir(&pending)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_none_or(|extension| extension != "cypher")
+        {
+            continue;
+        }
+        let recorded = std::fs::read_to_string(&path)?;
+        let (params, cypher) = parse(&recorded);
+        let mut explain = query(&format!("EXPLAIN {}", cypher));
+        for param in params {
+            explain = explain.param(param, BoltType::Null(BoltNull));
+        }
+        match graph.run(explain).await {
+            Ok(()) => {
+                std::fs::rename(&path, dir.join(path.file_name().unwrap_or_default()))?;
+                verified += 1;
+            }
+            Err(Error::Neo4jError(e)) => rejected.push(format!("{}\n  {}", cypher, e)),
+            Err(e) => return Err(e),
+        }
+    }
+    if rejected.is_empty() {
+        Ok(verified)
+    } else {
+        Err(Error::InvalidQuery(rejected.join("\n")))
+    }
+}
+
+/// Splits a recorded query into the names of its parameters and its cypher
+fn parse(recorded: &str) -> (Vec<&str>, &str) {
+    match recorded
+        .strip_prefix(PARAMS)
+        .and_then(|rest| rest.split_once('\n'))
+    {
+        Some((params, cypher)) => (
+            params
+                .split(',')
+                .map(str::trim)
+                .filter(|param| !param.is_empty())
+                .collect(),
+            cypher,
+        ),
+        None => (Vec::new(), recorded),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_check_the_parameters_of_checked_queries() {
+        let name = "Alice";
+        let q = crate::query_checked!(
+            "MATCH (p:Person {name: $name}) WHERE p.age > $age RETURN p, '$quoted'",
+            name,
+            age = 30
+        );
+
+        assert!(q.has_param_key("name"));
+        assert!(q.has_param_key("age"));
+        assert!(!q.has_param_key("quoted"));
+    }
+
+    #[test]
+    fn should_parse_recorded_queries() {
+        let (params, cypher) = parse("// params: age, name\nMATCH (p) RETURN p");
+        assert_eq!(params, vec!["age", "name"]);
+        assert_eq!(cypher, "MATCH (p) RETURN p");
+
+        assert_eq!(parse("// params: \nRETURN 1"), (vec![], "RETURN 1"));
+    }
+}
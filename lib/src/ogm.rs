@@ -0,0 +1,256 @@
+//! Structs saved and loaded as nodes, without writing the CREATE and MATCH queries mapping them
+//!
+//! `#[derive(Entity)]` maps a struct to the nodes of a label, the name of the struct unless
+//! `#[neo4rs(label = "...")]` is given, and its fields to their properties, like
+//! `#[derive(FromNode)]` which it also implements. The field marked with `#[neo4rs(key)]`
+//! identifies the node of an entity: [`Graph::save`] merges the node with the same key and
+//! replaces its properties, [`Graph::find`] loads an entity back by its key.
+//!
+//! This isn't an ORM, there is no tracking of changes nor loading of related entities.
+//! [`Graph::link`] and [`Graph::related`] only spare the queries relating two entities.
+//!
+//! ```no_run
+//! use neo4rs::ogm::Entity;
+//! use neo4rs::*;
+//!
+//! #[derive(Clone, Entity)]
+//! #[neo4rs(label = "Person")]
+//! struct Person {
+//!     #[neo4rs(key)]
+//!     email: String,
+//!     name: String,
+//!     #[neo4rs(default)]
+//!     age: i64,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!    let graph = Graph::new("127.0.0.1:7687", "neo4j", "neo").await.unwrap();
+//!    let mark = Person { email: "mark@example.com".into(), name: "Mark".into(), age: 0 };
+//!    let ann = Person { email: "ann@example.com".into(), name: "Ann".into(), age: 42 };
+//!    graph.save(&mark).await.unwrap();
+//!    graph.save(&ann).await.unwrap();
+//!    graph.link(&mark, "KNOWS", &ann).await.unwrap();
+//!
+//!    let found: Person = graph.find("mark@example.com").await.unwrap().unwrap();
+//!    let friends: Vec<Person> = graph.related(&found, "KNOWS").await.unwrap();
+//!    assert_eq!(friends[0].name, "Ann");
+//! }
+//! ```
+use crate::convert::IntoBoltParam;
+use crate::errors::Result;
+use crate::graph::{query, Graph};
+use crate::query::Query;
+use crate::row::{FromNode, Node};
+use crate::types::{BoltMap, BoltType};
+use futures::stream::TryStreamExt;
+
+pub use neo4rs_macros::Entity;
+
+/// A struct saved as a node, usually implemented with `#[derive(Entity)]`, see the
+/// [module](self) documentation
+pub trait Entity: FromNode {
+    /// The label of the nodes of the entities
+    const LABEL: &'static str;
+    /// The property identifying an entity among the nodes of its label
+    const KEY: &'static str;
+
+    /// The value of the key property of the entity
+    fn key(&self) -> BoltType;
+
+    /// The properties the node of the entity is saved with, its key among them
+    fn properties(&self) -> BoltMap;
+}
+
+impl Graph {
+    /// Saves `entity` as a node, creating it unless a node of its label has the same key, whose
+    /// properties are then replaced by the ones of the entity
+    pub async fn save<T: Entity>(&self, entity: &T) -> Result<()> {
+        self.run(save(entity)).await
+    }
+
+    /// The entity whose node has the key `key`, if there is one
+    pub async fn find<T: Entity>(&self, key: impl IntoBoltParam) -> Result<Option<T>> {
+        let mut rows = self.execute(find::<T>(key.into_bolt_param())).await?;
+        match rows.next().await? {
+            Some(row) => T::from_node(&row.try_get::<Node>("n")?).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Deletes the node of `entity` along with its relationships
+    pub async fn delete<T: Entity>(&self, entity: &T) -> Result<()> {
+        self.run(delete(entity)).await
+    }
+
+    /// Relates the nodes of `from` and `to` with a relationship of type `relation`, unless they
+    /// already are. Nothing is created when one of the entities wasn't saved.
+    pub async fn link<A: Entity, B: Entity>(&self, from: &A, relation: &str, to: &B) -> Result<()> {
+        self.run(link(from, relation, to)).await
+    }
+
+    /// The entities related to `from` with an outgoing relationship of type `relation`
+    pub async fn related<A: Entity, B: Entity>(&self, from: &A, relation: &str) -> Result<Vec<B>> {
+        self.execute(related::<A, B>(from, relation))
+            .await?
+            .into_stream()
+            .and_then(|row| async move { B::from_node(&row.try_get::<Node>("n")?) })
+            .try_collect()
+            .await
+    }
+}
+
+/// Quotes a label, relationship type or property key
+fn escape(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// The pattern of the node of an entity of `T` with the key `$parameter`
+fn pattern<T: Entity>(variable: &str, parameter: &str) -> String {
+    format!(
+        "({}:{} {{{}: ${}}})",
+        variable,
+        escape(T::LABEL),
+        escape(T::KEY),
+        parameter
+    )
+}
+
+fn save<T: Entity>(entity: &T) -> Query {
+    query(&format!(
+        "MERGE {} SET n = $properties",
+        pattern::<T>("n", "key")
+    ))
+    .param("key", entity.key())
+    .param("properties", entity.properties())
+}
+
+fn find<T: Entity>(key: BoltType) -> Query {
+    query(&format!(
+        "MATCH {} RETURN n LIMIT 1",
+        pattern::<T>("n", "key")
+    ))
+    .param("key", key)
+    .read()
+}
+
+fn delete<T: Entity>(entity: &T) -> Query {
+    query(&format!(
+        "MATCH {} DETACH DELETE n",
+        pattern::<T>("n", "key")
+    ))
+    .param("key", entity.key())
+}
+
+fn link<A: Entity, B: Entity>(from: &A, relation: &str, to: &B) -> Query {
+    query(&format!(
+        "MATCH {}, {} MERGE (a)-[:{}]->(b)",
+        pattern::<A>("a", "from"),
+        pattern::<B>("b", "to"),
+        escape(relation)
+    ))
+    .param("from", from.key())
+    .param("to", to.key())
+}
+
+fn related<A: Entity, B: Entity>(from: &A, relation: &str) -> Query {
+    query(&format!(
+        "MATCH {}-[:{}]->(n:{}) RETURN n",
+        pattern::<A>("a", "from"),
+        escape(relation),
+        escape(B::LABEL)
+    ))
+    .param("from", from.key())
+    .read()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BoltInteger, BoltList, BoltNode};
+
+    #[derive(Debug, Clone, PartialEq, Entity)]
+    #[neo4rs(label = "Person")]
+    struct Person {
+        #[neo4rs(id)]
+        id: i64,
+        #[neo4rs(key)]
+        email: String,
+        #[neo4rs(rename = "fullName")]
+        name: String,
+        #[neo4rs(default)]
+        age: i64,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Entity)]
+    struct Company {
+        #[neo4rs(key)]
+        name: String,
+    }
+
+    fn mark() -> Person {
+        Person {
+            id: 0,
+            email: "mark@example.com".into(),
+            name: "Mark".into(),
+            age: 42,
+        }
+    }
+
+    #[test]
+    fn should_map_structs_to_nodes() {
+        let properties = mark().properties();
+
+        assert_eq!(Person::LABEL, "Person");
+        assert_eq!(Company::LABEL, "Company");
+        assert_eq!(Person::KEY, "email");
+        assert_eq!(mark().key(), BoltType::from("mark@example.com"));
+        assert_eq!(properties.get::<String>("fullName").unwrap(), "Mark");
+        assert_eq!(properties.get::<i64>("age").unwrap(), 42);
+        // the id of the node isn't a property
+        assert!(properties.get::<i64>("id").is_none());
+
+        let node = BoltNode::new(
+            BoltInteger::new(7),
+            BoltList::from(vec![BoltType::from("Person")]),
+            properties,
+        );
+        assert_eq!(
+            Person::from_node(&Node::new(node)).unwrap(),
+            Person { id: 7, ..mark() }
+        );
+    }
+
+    #[test]
+    fn should_build_the_queries_of_entities() {
+        let neo = Company { name: "Neo".into() };
+
+        let saved = save(&mark());
+        assert_eq!(
+            saved.cypher(),
+            "MERGE (n:`Person` {`email`: $key}) SET n = $properties"
+        );
+        assert_eq!(
+            saved.get_param::<String>("key").unwrap(),
+            "mark@example.com"
+        );
+        assert!(saved.get_param::<BoltMap>("properties").is_some());
+        assert_eq!(
+            find::<Person>("mark@example.com".into()).cypher(),
+            "MATCH (n:`Person` {`email`: $key}) RETURN n LIMIT 1"
+        );
+        assert_eq!(
+            link(&mark(), "WORKS_AT", &neo).cypher(),
+            "MATCH (a:`Person` {`email`: $from}), (b:`Company` {`name`: $to}) \
+             MERGE (a)-[:`WORKS_AT`]->(b)"
+        );
+        assert_eq!(
+            related::<Person, Company>(&mark(), "WORKS`AT").cypher(),
+            "MATCH (a:`Person` {`email`: $from})-[:`WORKS``AT`]->(n:`Company`) RETURN n"
+        );
+        assert_eq!(
+            delete(&neo).cypher(),
+            "MATCH (n:`Company` {`name`: $key}) DETACH DELETE n"
+        );
+    }
+}
@@ -1,45 +1,468 @@
 use crate::config::Config;
 use crate::connection::Connection;
-use crate::errors::Error;
-use async_trait::async_trait;
-use log::info;
+use crate::errors::{Error, Result};
+use crate::routing::{AccessMode, RoutedPool};
+use crate::rt;
+use crate::trace::traced;
+use crate::version::Feature;
+use deadpool::managed::{Metrics, PoolError, RecycleError, RecycleResult, TimeoutType, Timeouts};
+use log::{info, warn};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
-pub type ConnectionPool = deadpool::managed::Pool<Connection, Error>;
-pub type ManagedConnection = deadpool::managed::Object<Connection, Error>;
+/// How often pools check for expired connections and open connections up to `min_idle`, unless
+/// the configured timeouts are shorter
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(30);
+/// How often a closing pool checks whether the connections in use were returned
+const CLOSE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+pub type ConnectionPool = deadpool::managed::Pool<ConnectionManager>;
+pub type ManagedConnection = deadpool::managed::Object<ConnectionManager>;
+
+/// Statistics of one or more connection pools, see [`Graph::pool_status`]
+///
+/// [`Graph::pool_status`]: crate::Graph::pool_status
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolStatus {
+    /// Maximum number of connections
+    pub max_size: usize,
+    /// Connections currently handed out
+    pub in_use: usize,
+    /// Open connections waiting to be handed out
+    pub idle: usize,
+    /// Tasks waiting for a connection
+    pub waiting: usize,
+    /// Connections opened since the pool was created
+    pub created: usize,
+    /// Connections closed since the pool was created
+    pub closed: usize,
+    /// Connections handed out since the pool was created
+    pub acquired: u64,
+    /// Time spent waiting for the connections handed out
+    pub total_wait: Duration,
+    /// Longest time spent waiting for a connection
+    pub max_wait: Duration,
+}
+
+impl PoolStatus {
+    /// Average time spent waiting for a connection
+    pub fn average_wait(&self) -> Duration {
+        match self.acquired {
+            0 => Duration::ZERO,
+            acquired => {
+                Duration::from_nanos((self.total_wait.as_nanos() / acquired as u128) as u64)
+            }
+        }
+    }
+
+    fn merge(self, other: PoolStatus) -> PoolStatus {
+        PoolStatus {
+            max_size: self.max_size + other.max_size,
+            in_use: self.in_use + other.in_use,
+            idle: self.idle + other.idle,
+            waiting: self.waiting + other.waiting,
+            created: self.created + other.created,
+            closed: self.closed + other.closed,
+            acquired: self.acquired + other.acquired,
+            total_wait: self.total_wait + other.total_wait,
+            max_wait: self.max_wait.max(other.max_wait),
+        }
+    }
+}
 
 pub struct ConnectionManager {
-    uri: String,
-    user: String,
-    password: String,
+    addresses: Vec<String>,
+    config: Config,
+    created: AtomicUsize,
+    acquired: AtomicU64,
+    total_wait: AtomicU64,
+    max_wait: AtomicU64,
+    closed: AtomicBool,
 }
 
 impl ConnectionManager {
-    pub fn new(uri: &str, user: &str, password: &str) -> ConnectionManager {
+    pub fn new(addresses: &[String], config: &Config) -> ConnectionManager {
         ConnectionManager {
-            uri: uri.to_owned(),
-            user: user.to_owned(),
-            password: password.to_owned(),
+            addresses: addresses.to_vec(),
+            config: config.clone(),
+            created: AtomicUsize::new(0),
+            acquired: AtomicU64::new(0),
+            total_wait: AtomicU64::new(0),
+            max_wait: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
         }
     }
+
+    fn acquired(&self, wait: Duration) {
+        if let Some(metrics) = &self.config.metrics {
+            metrics.0.pool_acquire_time(wait);
+        }
+        let nanos = wait.as_nanos() as u64;
+        self.acquired.fetch_add(1, Ordering::Relaxed);
+        self.total_wait.fetch_add(nanos, Ordering::Relaxed);
+        self.max_wait.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    /// Whether a connection outlived the max lifetime of the config
+    fn expired(&self, metrics: &Metrics) -> bool {
+        self.config
+            .max_lifetime
+            .is_some_and(|max_lifetime| metrics.age() >= max_lifetime)
+    }
+
+    /// Whether a connection stayed unused for longer than the idle timeout of the config
+    fn idle_expired(&self, metrics: &Metrics) -> bool {
+        self.config
+            .idle_timeout
+            .is_some_and(|idle_timeout| metrics.last_used() >= idle_timeout)
+    }
 }
 
-#[async_trait]
-impl deadpool::managed::Manager<Connection, Error> for ConnectionManager {
+impl deadpool::managed::Manager for ConnectionManager {
+    type Type = Connection;
+    type Error = Error;
+
+    /// Connects to the first of the addresses that can be connected to
     async fn create(&self) -> std::result::Result<Connection, Error> {
-        info!("creating new connection...");
-        Connection::new(&self.uri, &self.user, &self.password).await
+        let mut last_error = Error::InvalidConfig;
+        for address in &self.addresses {
+            info!("creating new connection to {}...", address);
+            match Connection::new(address, &self.config).await {
+                Ok(connection) => {
+                    self.created.fetch_add(1, Ordering::Relaxed);
+                    return Ok(connection);
+                }
+                Err(e) => {
+                    warn!("unable to connect to {}: {:?}", address, e);
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
     }
 
-    async fn recycle(&self, conn: &mut Connection) -> deadpool::managed::RecycleResult<Error> {
-        Ok(conn.reset().await?)
+    async fn recycle(&self, conn: &mut Connection, metrics: &Metrics) -> RecycleResult<Error> {
+        if conn.is_broken() {
+            return Err(RecycleError::message("connection is broken"));
+        }
+        if self.expired(metrics) || self.idle_expired(metrics) {
+            return Err(RecycleError::message("connection expired"));
+        }
+        conn.reset().await?;
+        if conn.is_token_expired() {
+            self.config.auth.expire(conn.token()).await;
+        }
+        let token = self.config.auth.token().await?;
+        if &token != conn.token() {
+            if !conn.version().supports(Feature::ReAuthentication) {
+                return Err(RecycleError::message("credentials changed"));
+            }
+            conn.reauthenticate(token).await?;
+        }
+        Ok(())
     }
 }
 
-pub async fn create_pool(config: &Config) -> ConnectionPool {
-    let mgr = ConnectionManager::new(&config.uri, &config.user, &config.password);
+/// Creates a pool of connections to the server at the first of `addresses` that can be
+/// connected to
+pub fn create_pool(addresses: &[String], config: &Config) -> ConnectionPool {
+    let mgr = ConnectionManager::new(addresses, config);
     info!(
-        "creating connection pool with max size {}",
+        "creating connection pool for {} with max size {}",
+        addresses.join(", "),
         config.max_connections
     );
-    ConnectionPool::new(mgr, config.max_connections)
+    let pool = ConnectionPool::builder(mgr)
+        .max_size(config.max_connections)
+        .build()
+        .expect("a pool without timeouts can always be built");
+    if config.min_idle > 0
+        || config.idle_timeout.is_some()
+        || config.max_lifetime.is_some()
+        || config.keep_alive.is_some()
+    {
+        rt::spawn(maintain(pool.weak(), config.clone()));
+    }
+    pool
+}
+
+/// Closes expired idle connections, pings the ones idle for longer than the keep alive interval
+/// and opens new ones up to `min_idle`, until the pool is dropped
+async fn maintain(pool: deadpool::managed::WeakPool<ConnectionManager>, config: Config) {
+    let period = [config.idle_timeout, config.max_lifetime, config.keep_alive]
+        .iter()
+        .flatten()
+        .fold(MAINTENANCE_INTERVAL, |period, timeout| period.min(*timeout));
+    // the first round runs right away, to open the idle connections
+    let mut delay = Duration::ZERO;
+    loop {
+        rt::sleep(delay).await;
+        delay = period;
+        let pool = match pool.upgrade() {
+            Some(pool) => pool,
+            None => return,
+        };
+        let manager = pool.manager();
+        if manager.closed.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut idle = 0;
+        let retained = pool.retain(|_, metrics| {
+            let keep = !manager.expired(&metrics)
+                && (!manager.idle_expired(&metrics) || idle < config.min_idle);
+            idle += usize::from(keep);
+            keep
+        });
+        if !retained.removed.is_empty() {
+            info!("closed {} expired connections", retained.removed.len());
+        }
+        if let Some(keep_alive) = config.keep_alive {
+            ping(&pool, keep_alive).await;
+        }
+        fill(&pool, config.min_idle).await;
+    }
+}
+
+/// Checks out the connections idle for at least `keep_alive`, which resets them, so the server
+/// and the network in between see some traffic. Connections in use are left alone.
+async fn ping(pool: &ConnectionPool, keep_alive: Duration) {
+    let mut idle = 0;
+    pool.retain(|_, metrics| {
+        idle += usize::from(metrics.last_used() >= keep_alive);
+        true
+    });
+    let timeouts = Timeouts {
+        wait: Some(Duration::ZERO),
+        ..pool.timeouts()
+    };
+    let mut connections = Vec::with_capacity(idle);
+    for _ in 0..idle.min(pool.status().available) {
+        match pool.timeout_get(&timeouts).await {
+            Ok(connection) => connections.push(connection),
+            Err(_) => break,
+        }
+    }
+}
+
+/// Opens connections until `min_idle` of them are idle
+async fn fill(pool: &ConnectionPool, min_idle: usize) {
+    let status = pool.status();
+    let missing = min_idle
+        .saturating_sub(status.available)
+        .min(status.max_size.saturating_sub(status.size));
+    if missing == 0 {
+        return;
+    }
+    // the idle connections are handed out first, they have to be held for new ones to be opened
+    let count = status.available + missing;
+    let mut connections = Vec::with_capacity(count);
+    for _ in 0..count {
+        match wait(pool).await {
+            Ok(connection) => connections.push(connection),
+            Err(e) => {
+                warn!("unable to open an idle connection: {:?}", e);
+                break;
+            }
+        }
+    }
+}
+
+/// Gets a connection from `pool`, keeping track of the time spent waiting for it
+pub(crate) async fn acquire(
+    pool: &ConnectionPool,
+) -> std::result::Result<ManagedConnection, PoolError<Error>> {
+    if pool.manager().closed.load(Ordering::Relaxed) {
+        return Err(PoolError::Closed);
+    }
+    traced!("neo4rs.acquire", async {
+        let start = Instant::now();
+        let connection = wait(pool).await?;
+        pool.manager().acquired(start.elapsed());
+        Ok(connection)
+    })
+}
+
+/// Waits at most the acquisition timeout of the config for a connection of `pool`, the timeout is
+/// applied here rather than by the pool, so it works with every runtime
+async fn wait(pool: &ConnectionPool) -> std::result::Result<ManagedConnection, PoolError<Error>> {
+    let timeout = pool.manager().config.acquisition_timeout;
+    rt::timeout(timeout, pool.get())
+        .await
+        .unwrap_or(Err(PoolError::Timeout(TimeoutType::Wait)))
+}
+
+/// Stops handing out connections and waits until the ones in use are returned or `deadline`
+/// passed, then says goodbye on every idle connection before closing the pool. Connections
+/// returned afterwards are closed without a goodbye.
+pub(crate) async fn close(pool: &ConnectionPool, deadline: Instant) {
+    pool.manager().closed.store(true, Ordering::Relaxed);
+    loop {
+        let status = pool.status();
+        if status.size == status.available || Instant::now() >= deadline {
+            break;
+        }
+        rt::sleep(CLOSE_POLL_INTERVAL).await;
+    }
+    for connection in pool.retain(|_, _| false).removed {
+        connection.goodbye().await;
+    }
+    pool.close();
+}
+
+pub(crate) fn status(pool: &ConnectionPool) -> PoolStatus {
+    let status = pool.status();
+    let manager = pool.manager();
+    let created = manager.created.load(Ordering::Relaxed);
+    PoolStatus {
+        max_size: status.max_size,
+        in_use: status.size - status.available,
+        idle: status.available,
+        waiting: status.waiting,
+        created,
+        closed: created.saturating_sub(status.size),
+        acquired: manager.acquired.load(Ordering::Relaxed),
+        total_wait: Duration::from_nanos(manager.total_wait.load(Ordering::Relaxed)),
+        max_wait: Duration::from_nanos(manager.max_wait.load(Ordering::Relaxed)),
+    }
+}
+
+/// Sums up the statistics of several pools
+pub(crate) fn total_status<'a>(pools: impl Iterator<Item = &'a ConnectionPool>) -> PoolStatus {
+    pools
+        .map(status)
+        .fold(PoolStatus::default(), PoolStatus::merge)
+}
+
+/// Hands out connections either from a single server or from the members of a cluster
+pub(crate) enum Connections {
+    Direct(ConnectionPool),
+    Routed(Box<RoutedPool>),
+}
+
+impl Connections {
+    /// Uses a routed pool for `neo4j://` uris and a single pool otherwise
+    pub fn new(config: &Config) -> Connections {
+        if config.routing() {
+            Connections::Routed(Box::new(RoutedPool::new(config)))
+        } else {
+            Connections::Direct(create_pool(&config.addresses(), config))
+        }
+    }
+
+    pub async fn get(&self, mode: AccessMode, db: &str) -> Result<ManagedConnection> {
+        match self {
+            Connections::Direct(pool) => Ok(acquire(pool).await?),
+            Connections::Routed(pool) => pool.get(mode, db).await,
+        }
+    }
+
+    pub fn status(&self) -> PoolStatus {
+        match self {
+            Connections::Direct(pool) => status(pool),
+            Connections::Routed(pool) => pool.status(),
+        }
+    }
+
+    /// Closes the pools, waiting at most `timeout` for the connections in use to be returned
+    pub async fn close(&self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        match self {
+            Connections::Direct(pool) => close(pool, deadline).await,
+            Connections::Routed(pool) => pool.close(deadline).await,
+        }
+    }
+
+    /// Forces the routing table of `db` to be refreshed before the next connection is handed out
+    pub async fn invalidate(&self, db: &str) {
+        if let Connections::Routed(pool) = self {
+            pool.invalidate(db).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config;
+    use std::time::Instant;
+
+    fn manager(idle_timeout: Duration, max_lifetime: Duration) -> ConnectionManager {
+        let config = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .idle_timeout(idle_timeout)
+            .max_lifetime(max_lifetime)
+            .build()
+            .unwrap();
+        ConnectionManager::new(&config.addresses(), &config)
+    }
+
+    #[test]
+    fn should_expire_connections() {
+        let manager = manager(Duration::from_secs(60), Duration::from_secs(600));
+        let now = Instant::now();
+        let metrics = |created: Duration, used: Duration| Metrics {
+            created: now - created,
+            recycled: Some(now - used),
+            recycle_count: 1,
+        };
+
+        let fresh = metrics(Duration::from_secs(100), Duration::from_secs(10));
+        let idle = metrics(Duration::from_secs(100), Duration::from_secs(90));
+        let old = metrics(Duration::from_secs(700), Duration::from_secs(10));
+
+        assert!(!manager.expired(&fresh) && !manager.idle_expired(&fresh));
+        assert!(!manager.expired(&idle) && manager.idle_expired(&idle));
+        assert!(manager.expired(&old) && !manager.idle_expired(&old));
+    }
+
+    #[tokio::test]
+    async fn should_time_out_when_pool_is_exhausted() {
+        let config = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .max_connections(0)
+            .acquisition_timeout(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        let connections = Connections::new(&config);
+
+        let result = connections.get(AccessMode::Write, "").await;
+
+        assert!(matches!(result, Err(Error::PoolTimeout)));
+        let status = connections.status();
+        assert_eq!(status.in_use, 0);
+        assert_eq!(status.created, 0);
+        assert_eq!(status.acquired, 0);
+    }
+
+    #[tokio::test]
+    async fn should_refuse_connections_once_closed() {
+        let config = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .build()
+            .unwrap();
+        let connections = Connections::new(&config);
+
+        connections.close(Duration::from_millis(10)).await;
+        let result = connections.get(AccessMode::Write, "").await;
+
+        assert!(matches!(result, Err(Error::PoolClosed)));
+    }
+
+    #[test]
+    fn should_average_wait_times() {
+        let status = PoolStatus {
+            acquired: 4,
+            total_wait: Duration::from_millis(10),
+            ..PoolStatus::default()
+        };
+
+        assert_eq!(status.average_wait(), Duration::from_micros(2500));
+        assert_eq!(PoolStatus::default().average_wait(), Duration::ZERO);
+    }
 }
@@ -1,17 +1,72 @@
+use crate::bookmark::{Bookmark, ManagedBookmarks};
+use crate::cancel::{Cancel, CancellationToken};
 use crate::config::Config;
+use crate::connection::Connection;
+use crate::convert::{FromBoltValue, IntoBoltParam};
 use crate::errors::*;
 use crate::messages::*;
-use crate::pool::*;
+use crate::routing::AccessMode;
+use crate::ser::to_bolt;
+use crate::session::Session;
 use crate::stream::*;
+use crate::summary::{NotificationFilter, ResultSummary};
+use crate::trace::traced;
+use crate::txn::TxOptions;
 use crate::types::*;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+/// A type whose fields can be passed as the parameters of a [`Query`], usually implemented with
+/// `#[derive(IntoParams)]`
+///
+/// Every field is passed as the parameter of the same name, `#[neo4rs(rename = "...")]` picks
+/// another name.
+pub trait IntoParams {
+    fn into_params(self, query: Query) -> Query;
+}
 
 /// Abstracts a cypher query that is sent to neo4j server.
+///
+/// Queries are cheap to clone, so a template can be kept and cloned for every request, binding
+/// its parameters again with [`Query::param`] or [`Query::params_from`]:
+///
+/// ```
+/// use neo4rs::*;
+///
+/// let by_name = query("MATCH (p:Person {name: $name}) RETURN p").param("name", "");
+///
+/// let mark = by_name.clone().param("name", "Mark");
+/// let ann = by_name.clone().param("name", "Ann");
+/// assert!(mark.has_param_key("name") && ann.has_param_key("name"));
+/// ```
 #[derive(Clone)]
 pub struct Query {
     query: String,
     params: BoltMap,
+    bookmarks: Vec<Bookmark>,
+    db: Option<String>,
+    fetch_size: Option<usize>,
+    options: TxOptions,
+    cancellation: Option<CancellationToken>,
+    query_timeout: Option<Duration>,
+}
+
+/// Shows the names of the parameters but not their values, which may be sensitive, see
+/// [`ConfigBuilder::parameters_in_errors`](crate::ConfigBuilder::parameters_in_errors)
+impl std::fmt::Debug for Query {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut params: Vec<_> = self.params.value.keys().map(|key| key.as_str()).collect();
+        params.sort();
+        f.debug_struct("Query")
+            .field("query", &self.query)
+            .field("params", &params)
+            .field("db", &self.db)
+            .field("fetch_size", &self.fetch_size)
+            .field("options", &self.options)
+            .field("query_timeout", &self.query_timeout)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Query {
@@ -19,50 +74,769 @@ impl Query {
         Query {
             query,
             params: BoltMap::default(),
+            bookmarks: Vec::new(),
+            db: None,
+            fetch_size: None,
+            options: TxOptions::default(),
+            cancellation: None,
+            query_timeout: None,
         }
     }
 
-    pub fn param<T: std::convert::Into<BoltType>>(mut self, key: &str, value: T) -> Self {
-        self.params.put(key.into(), value.into());
+    /// Adds a parameter, replacing the value of the parameter of the same name
+    pub fn param<T: IntoBoltParam>(mut self, key: &str, value: T) -> Self {
+        self.params.put(key.into(), value.into_bolt_param());
         self
     }
 
-    pub(crate) async fn run(
-        self,
-        config: &Config,
-        connection: Arc<Mutex<ManagedConnection>>,
-    ) -> Result<()> {
-        let run = BoltRequest::run(&config.db, &self.query, self.params.clone());
-        let mut connection = connection.lock().await;
-        match connection.send_recv(run).await? {
-            BoltResponse::SuccessMessage(_) => {
-                match connection.send_recv(BoltRequest::discard()).await? {
-                    BoltResponse::SuccessMessage(_) => Ok(()),
-                    msg => Err(unexpected(msg, "DISCARD")),
+    /// Adds every entry of a map as a parameter, replacing the values of parameters of the same
+    /// name
+    pub fn params_from<K, V>(mut self, params: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<BoltString>,
+        V: Into<BoltType>,
+    {
+        for (key, value) in params {
+            self.params.put(key.into(), value.into());
+        }
+        self
+    }
+
+    pub fn has_param_key(&self, key: &str) -> bool {
+        self.params.value.contains_key(&BoltString::from(key))
+    }
+
+    /// Adds every field of a struct or every entry of a map as a parameter, nested values are
+    /// sent as maps and lists
+    pub fn params<T: Serialize>(mut self, params: T) -> Result<Self> {
+        match to_bolt(&params)? {
+            BoltType::Map(map) => {
+                for (key, value) in map.value {
+                    self.params.put(key, value);
+                }
+                Ok(self)
+            }
+            other => Err(Error::SerializationError(format!(
+                "parameters must be a struct or a map, found {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Adds the fields of a type implementing [`IntoParams`] as parameters
+    pub fn with_params<T: IntoParams>(self, params: T) -> Self {
+        params.into_params(self)
+    }
+
+    /// Makes sure the query observes the changes of the transactions these bookmarks were
+    /// returned for, only applies to queries run outside of a transaction
+    pub fn bookmarks(mut self, bookmarks: &[Bookmark]) -> Self {
+        self.bookmarks = bookmarks.to_vec();
+        self
+    }
+
+    /// Runs the query on the given database instead of the one of the [`Config`], queries
+    /// within a transaction always run on the database of the transaction
+    pub fn db(mut self, db: &str) -> Self {
+        self.db = Some(db.to_owned());
+        self
+    }
+
+    /// Fetches the rows of this query in batches of `fetch_size` instead of the
+    /// [`ConfigBuilder::fetch_size`](crate::ConfigBuilder::fetch_size) of the config
+    pub fn fetch_size(mut self, fetch_size: usize) -> Self {
+        self.fetch_size = Some(fetch_size);
+        self
+    }
+
+    /// The server terminates the query once it ran for longer than `timeout`, only applies to
+    /// queries run outside of a transaction, see [`TxOptions::timeout`] otherwise
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.options = self.options.timeout(timeout);
+        self
+    }
+
+    /// Attaches metadata to the query, which shows up in the query log and the output of
+    /// `SHOW TRANSACTIONS`. Only applies to queries run outside of a transaction, see
+    /// [`TxOptions::metadata`] otherwise
+    pub fn metadata<K, V>(mut self, metadata: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<BoltString>,
+        V: Into<BoltType>,
+    {
+        self.options = self.options.metadata(metadata);
+        self
+    }
+
+    /// Marks the query as read only, when connected to a cluster it then runs on one of its
+    /// readers instead of the leader. Only applies to queries run outside of a transaction, see
+    /// [`TxOptions::read`] otherwise
+    pub fn read(mut self) -> Self {
+        self.options = self.options.read();
+        self
+    }
+
+    /// Runs the query as `user` instead of the authenticated user. Only applies to queries run
+    /// outside of a transaction, see [`TxOptions::impersonate`] otherwise
+    pub fn impersonate(mut self, user: &str) -> Self {
+        self.options = self.options.impersonate(user);
+        self
+    }
+
+    /// Filters the notifications the server sends for this query, instead of the filter of the
+    /// config. Only applies to queries run outside of a transaction, see
+    /// [`TxOptions::notification_filter`] otherwise. Requires bolt 5.2 or newer
+    pub fn notification_filter(mut self, filter: NotificationFilter) -> Self {
+        self.options = self.options.notification_filter(filter);
+        self
+    }
+
+    /// Cancels the query, as well as the stream of its rows, once `token` is cancelled, see
+    /// [`CancellationToken`]
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Fails the query with [`Error::Timeout`] once `timeout` elapsed and its rows aren't
+    /// consumed yet, instead of the [`ConfigBuilder::query_timeout`] of the config
+    ///
+    /// [`ConfigBuilder::query_timeout`]: crate::ConfigBuilder::query_timeout
+    pub fn query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = Some(timeout);
+        self
+    }
+
+    /// The cypher text of the query
+    pub fn cypher(&self) -> &str {
+        &self.query
+    }
+
+    /// The value of the parameter `key`, if the query has one that converts to `T`
+    pub fn get_param<T: FromBoltValue>(&self, key: &str) -> Option<T> {
+        self.params.get(key)
+    }
+
+    /// Checks the parameters against the `$placeholders` of the cypher, failing with
+    /// [`Error::ParameterMismatch`] listing the placeholders without a parameter and the
+    /// parameters the query doesn't use. Queries are checked before they are sent with
+    /// [`ConfigBuilder::strict_parameters`](crate::ConfigBuilder::strict_parameters).
+    ///
+    /// ```
+    /// use neo4rs::*;
+    ///
+    /// let typo = query("MATCH (p:Person {name: $name}) RETURN p").param("nmae", "Mark");
+    /// assert!(matches!(
+    ///     typo.check_params(),
+    ///     Err(Error::ParameterMismatch { missing, unused }) if missing == ["name"] && unused == ["nmae"]
+    /// ));
+    /// ```
+    pub fn check_params(&self) -> Result<()> {
+        let placeholders = placeholders(&self.query);
+        let params: BTreeSet<_> = self.params.value.keys().map(|key| key.as_str()).collect();
+        let missing: Vec<_> = placeholders
+            .iter()
+            .filter(|name| !params.contains(name.as_str()))
+            .cloned()
+            .collect();
+        let unused: Vec<_> = params
+            .into_iter()
+            .filter(|name| !placeholders.contains(*name))
+            .map(str::to_owned)
+            .collect();
+        if missing.is_empty() && unused.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::ParameterMismatch { missing, unused })
+        }
+    }
+
+    /// Runs the query outside of a transaction, with the settings of the config unless the query
+    /// overrides them
+    pub(crate) fn auto_commit(mut self, config: &Config) -> Self {
+        self.options = self.options.with_defaults(config);
+        self.bookmarks =
+            ManagedBookmarks::or_managed(config.bookmark_manager.as_ref(), &self.bookmarks);
+        self
+    }
+
+    /// Falls back to the defaults of `session` for the settings the query doesn't have
+    pub(crate) fn in_session(mut self, session: &Session) -> Self {
+        if self.db.is_none() {
+            self.db = session.db.clone();
+        }
+        if self.bookmarks.is_empty() {
+            self.bookmarks = session.bookmarks.clone();
+        }
+        self.options = session.txn_options(self.options);
+        self
+    }
+
+    /// Runs the query within a transaction on `db`, which carries the transaction settings
+    pub(crate) fn in_txn(mut self, db: &str) -> Self {
+        self.options = TxOptions::default();
+        self.db(db)
+    }
+
+    /// The database the query runs on
+    pub(crate) fn database<'a>(&'a self, config: &'a Config) -> &'a str {
+        self.db.as_deref().unwrap_or(&config.db)
+    }
+
+    /// The kind of cluster member the query has to run on
+    pub(crate) fn access_mode(&self) -> AccessMode {
+        self.options.mode
+    }
+
+    /// The number of rows requested with every PULL
+    pub(crate) fn batch_size(&self, config: &Config) -> usize {
+        self.fetch_size.unwrap_or(config.fetch_size)
+    }
+
+    /// The names of the parameters, along with their values when the config allows to show them
+    /// in errors
+    fn described_params(&self, config: &Config) -> Vec<(String, Option<String>)> {
+        let mut params: Vec<_> = self
+            .params
+            .value
+            .iter()
+            .map(|(key, value)| {
+                let value = config.parameters_in_errors.then(|| format!("{:?}", value));
+                (key.as_str().to_owned(), value)
+            })
+            .collect();
+        params.sort();
+        params
+    }
+
+    pub(crate) async fn run(self, config: &Config, connection: &mut Connection) -> Result<()> {
+        let this = config.interceptors.start(self)?;
+        if config.strict_parameters {
+            this.check_params()?;
+        }
+        let params = this.described_params(config);
+        let db = this.db.as_deref().unwrap_or(&config.db);
+        let run = BoltRequest::run(db, &this.query, this.params, &this.bookmarks, &this.options);
+        let (query, options, bookmarks) = (&this.query, &this.options, &this.bookmarks);
+        let timeout = this.query_timeout.or(config.query_timeout);
+        let cancel = &Cancel::new(this.cancellation, timeout);
+        let slow_query = config.slow_queries.as_ref().map(|log| log.watch(query, db));
+        let intercepted = config.interceptors.follow(query, db);
+        let start = Instant::now();
+        let result = traced!("neo4rs.run", { db = db }, async {
+            options.check(connection.version())?;
+            connection.close_legacy_result().await?;
+            // the DISCARD is sent along with the RUN, the server ignores it when the RUN fails
+            let requests = vec![run, BoltRequest::discard()];
+            match connection
+                .send_all_recv_cancellable(requests, cancel)
+                .await?
+            {
+                BoltResponse::SuccessMessage(success) => {
+                    match connection.recv_cancellable(cancel).await? {
+                        BoltResponse::SuccessMessage(discarded) => {
+                            let mut summary = ResultSummary::new(&success);
+                            summary.complete(&discarded);
+                            if let (Some(manager), Some(bookmark)) = (
+                                &config.bookmark_manager,
+                                discarded.get::<String>("bookmark"),
+                            ) {
+                                manager.0.update(bookmarks, bookmark.into());
+                            }
+                            Ok(summary)
+                        }
+                        msg => Err(msg.into_error("DISCARD").with_query(query, params)),
+                    }
+                }
+                msg => {
+                    connection.recv().await?;
+                    Err(msg.into_error("RUN").with_query(query, params))
                 }
             }
-            msg => Err(unexpected(msg, "RUN")),
+        });
+        if let (Ok(summary), Some(slow_query)) = (&result, slow_query) {
+            slow_query.finish(summary);
+        }
+        if let Some(intercepted) = intercepted {
+            intercepted.end(result.as_ref());
         }
+        if let (Ok(_), Some(metrics)) = (&result, &config.metrics) {
+            metrics.0.query_latency(db, start.elapsed());
+        }
+        result.map(|_| ())
     }
 
     pub(crate) async fn execute(
         self,
         config: &Config,
-        connection: Arc<Mutex<ManagedConnection>>,
+        mut connection: Source,
     ) -> Result<RowStream> {
-        let run = BoltRequest::run(&config.db, &self.query, self.params);
-        match connection.lock().await.send_recv(run).await {
-            Ok(BoltResponse::SuccessMessage(success)) => {
-                let fields: BoltList = success.get("fields").unwrap_or_else(BoltList::new);
-                let qid: i64 = success.get("qid").unwrap_or(-1);
-                Ok(RowStream::new(
-                    qid,
-                    fields,
-                    config.fetch_size,
-                    connection.clone(),
-                ))
+        let this = config.interceptors.start(self)?;
+        if config.strict_parameters {
+            this.check_params()?;
+        }
+        let fetch_size = this.batch_size(config);
+        let params = this.described_params(config);
+        let db = this.db.as_deref().unwrap_or(&config.db);
+        let run = BoltRequest::run(db, &this.query, this.params, &this.bookmarks, &this.options);
+        let (query, options, bookmarks) = (&this.query, &this.options, &this.bookmarks);
+        let timeout = this.query_timeout.or(config.query_timeout);
+        let cancel = Cancel::new(this.cancellation, timeout);
+        let slow_query = config.slow_queries.as_ref().map(|log| log.watch(query, db));
+        let mut intercepted = config.interceptors.follow(query, db);
+        let start = Instant::now();
+        let result = traced!("neo4rs.run", { db = db }, async {
+            let pulls_ahead = connection.pulls_ahead();
+            let response = {
+                let mut connection = connection.lock().await;
+                options.check(connection.version())?;
+                connection.close_legacy_result().await?;
+                let mut requests = vec![run];
+                if pulls_ahead {
+                    requests.push(BoltRequest::pull(fetch_size, -1));
+                }
+                let response = connection
+                    .send_all_recv_cancellable(requests, &cancel)
+                    .await?;
+                let qid = match &response {
+                    // the streams of a transaction are the ones not pulling ahead
+                    BoltResponse::SuccessMessage(success) => {
+                        connection.open_result(success.get("qid"), !pulls_ahead)
+                    }
+                    // the server ignores the PULL sent along with a RUN that failed
+                    _ if pulls_ahead => {
+                        connection.recv().await?;
+                        -1
+                    }
+                    _ => -1,
+                };
+                (response, qid)
+            };
+            match response {
+                (BoltResponse::SuccessMessage(success), qid) => {
+                    let fields: BoltList = success.get("fields").unwrap_or_else(BoltList::new);
+                    Ok(RowStream::new(
+                        qid,
+                        fields,
+                        fetch_size,
+                        ResultSummary::new(&success),
+                        connection,
+                        config.metrics.clone(),
+                        slow_query,
+                    )
+                    .cancellable(cancel)
+                    .prefetching(config.prefetch_watermark)
+                    .intercepted(intercepted.take())
+                    .bookmarking(config.bookmark_manager.clone(), bookmarks))
+                }
+                (msg, _) => Err(msg.into_error("RUN").with_query(query, params)),
+            }
+        });
+        if let (Err(error), Some(intercepted)) = (&result, intercepted) {
+            intercepted.end(Err(error));
+        }
+        if let (Ok(_), Some(metrics)) = (&result, &config.metrics) {
+            metrics.0.query_latency(db, start.elapsed());
+        }
+        result
+    }
+}
+
+/// Sends the RUN and DISCARD messages of all `queries` at once and reads their responses
+/// afterwards, returning the outcome of every query up to the first one that fails. The server
+/// ignores the queries following a failure until the connection is reset, their outcomes are
+/// missing and they don't end yet.
+///
+/// The queries already passed through the interceptors, a batch starts its queries once even
+/// when the ignored ones are sent again after a reset.
+pub(crate) async fn run_pipelined(
+    queries: &[Query],
+    config: &Config,
+    connection: &mut Connection,
+) -> Result<Vec<Result<()>>> {
+    let mut requests = Vec::with_capacity(queries.len() * 2);
+    for query in queries {
+        query.options.check(connection.version())?;
+        let db = query.database(config);
+        let params = query.params.clone();
+        let run = BoltRequest::run(db, &query.query, params, &query.bookmarks, &query.options);
+        requests.push(run);
+        requests.push(BoltRequest::discard());
+    }
+    connection.close_legacy_result().await?;
+    connection.send_all(requests).await?;
+    // every response is read, even after a failure, so none is left for the next request
+    let mut outcomes = Vec::with_capacity(queries.len());
+    for query in queries {
+        let mut outcome = Ok(ResultSummary::default());
+        let mut ignored = false;
+        for request in ["RUN", "DISCARD"] {
+            match connection.recv().await? {
+                BoltResponse::SuccessMessage(success) => match (&mut outcome, request) {
+                    (Ok(summary), "RUN") => *summary = ResultSummary::new(&success),
+                    (Ok(summary), _) => summary.complete(&success),
+                    _ => {}
+                },
+                BoltResponse::IgnoredMessage(_) => ignored = true,
+                msg if outcome.is_ok() => {
+                    let params = query.described_params(config);
+                    outcome = Err(msg.into_error(request).with_query(&query.query, params));
+                }
+                _ => {}
+            }
+        }
+        if let Some(intercepted) = config
+            .interceptors
+            .follow(&query.query, query.database(config))
+            .filter(|_| !ignored || outcome.is_err())
+        {
+            intercepted.end(outcome.as_ref());
+        }
+        if !matches!(outcomes.last(), Some(Err(_))) {
+            outcomes.push(outcome.map(|_| ()));
+        }
+    }
+    Ok(outcomes)
+}
+
+/// The names of the `$parameters` of a query, outside of strings and comments, like the
+/// `query!` macro finds them
+fn placeholders(cypher: &str) -> BTreeSet<String> {
+    let chars: Vec<char> = cypher.chars().collect();
+    let mut names = BTreeSet::new();
+    let mut quote = None;
+    let mut i = 0;
+    while i < chars.len() {
+        match (quote, chars[i]) {
+            (Some(_), '\\') => i += 1,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, c @ ('\'' | '"' | '`')) => quote = Some(c),
+            (None, '/') if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            (None, '/') if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                    i += 1;
+                }
+                i += 1;
             }
-            msg => Err(unexpected(msg, "RUN")),
+            (None, '$') => {
+                let name: String = chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_')
+                    .collect();
+                i += name.chars().count();
+                if !name.is_empty() {
+                    names.insert(name);
+                }
+            }
+            (None, _) => {}
+        }
+        i += 1;
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config;
+    use std::collections::HashMap;
+
+    #[test]
+    fn should_check_the_parameters_against_the_placeholders() {
+        let cypher = "MATCH (p {name: $name}) // $commented\nWHERE p.age > $age AND p.tag = '$quoted' RETURN p";
+        let base = Query::new(cypher.to_owned()).param("name", "Mark");
+        assert!(base.clone().param("age", 30).check_params().is_ok());
+
+        let result = base.param("agee", 30).check_params();
+        assert!(matches!(
+            result,
+            Err(Error::ParameterMismatch { missing, unused }) if missing == ["age"] && unused == ["agee"]
+        ));
+        assert!(Query::new("RETURN 1".into()).check_params().is_ok());
+    }
+
+    #[test]
+    fn should_run_on_query_db_over_config_db() {
+        let config = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .db("neo4j")
+            .build()
+            .unwrap();
+
+        let default = Query::new("RETURN 1".to_owned());
+        let analytics = Query::new("RETURN 1".to_owned()).db("analytics");
+
+        assert_eq!(default.database(&config), "neo4j");
+        assert_eq!(analytics.database(&config), "analytics");
+    }
+
+    #[test]
+    fn should_only_describe_parameter_values_when_allowed() {
+        let redacted = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .build()
+            .unwrap();
+        let revealed = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .parameters_in_errors(true)
+            .build()
+            .unwrap();
+        let query = Query::new("RETURN $b, $a".to_owned())
+            .param("b", "secret")
+            .param("a", 1);
+
+        assert_eq!(
+            query.described_params(&redacted),
+            vec![("a".to_owned(), None), ("b".to_owned(), None)]
+        );
+        let described = query.described_params(&revealed);
+        assert!(described[1].1.as_deref().unwrap().contains("secret"));
+    }
+
+    #[test]
+    fn should_add_list_and_map_params() {
+        let rows: Vec<HashMap<String, BoltType>> = vec![
+            vec![
+                ("name".to_owned(), "Mark".into()),
+                ("age".to_owned(), 42.into()),
+            ]
+            .into_iter()
+            .collect(),
+            vec![("name".to_owned(), "James".into())]
+                .into_iter()
+                .collect(),
+        ];
+
+        let q = Query::new("UNWIND $rows AS row CREATE (p:Person) SET p = row".to_owned())
+            .param("rows", rows)
+            .param("matrix", vec![vec![1, 2], vec![3]]);
+
+        let rows = q
+            .params
+            .get::<Vec<HashMap<String, BoltType>>>("rows")
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["name"], "Mark".into());
+        assert_eq!(rows[1].get("age"), None);
+        assert_eq!(
+            q.params.get::<Vec<Vec<i64>>>("matrix"),
+            Some(vec![vec![1, 2], vec![3]])
+        );
+    }
+
+    #[test]
+    fn should_add_struct_fields_as_params() {
+        #[derive(Serialize)]
+        struct Person {
+            name: &'static str,
+            age: i64,
+        }
+
+        let q = Query::new("CREATE (p:Person {name: $name, age: $age})".to_owned())
+            .param("id", 1)
+            .params(Person {
+                name: "Mark",
+                age: 42,
+            })
+            .unwrap();
+
+        assert_eq!(q.params.get::<i64>("id"), Some(1));
+        assert_eq!(q.params.get::<String>("name"), Some("Mark".to_owned()));
+        assert_eq!(q.params.get::<i64>("age"), Some(42));
+        assert!(Query::new("RETURN $x".to_owned()).params(42).is_err());
+    }
+
+    #[test]
+    fn should_add_derived_params() {
+        #[derive(crate::IntoParams)]
+        struct Person {
+            #[neo4rs(rename = "fullName")]
+            name: String,
+            age: i64,
         }
+
+        let q = Query::new("CREATE (p:Person {name: $fullName, age: $age})".to_owned())
+            .with_params(Person {
+                name: "Mark".to_owned(),
+                age: 42,
+            });
+
+        assert_eq!(q.params.get::<String>("fullName"), Some("Mark".to_owned()));
+        assert_eq!(q.params.get::<i64>("age"), Some(42));
+    }
+
+    #[test]
+    fn should_rebind_the_params_of_cloned_queries() {
+        let template = Query::new("MATCH (p {name: $name, age: $age}) RETURN p".to_owned())
+            .param("name", "secret");
+        let params: HashMap<_, _> = vec![("name", "Mark"), ("age", "42")].into_iter().collect();
+
+        let q = template.clone().params_from(params).param("age", 42);
+
+        assert_eq!(q.params.get::<String>("name"), Some("Mark".to_owned()));
+        assert_eq!(q.params.get::<i64>("age"), Some(42));
+        assert_eq!(
+            template.params.get::<String>("name"),
+            Some("secret".to_owned())
+        );
+        let debug = format!("{:?}", q);
+        assert!(debug.contains(r#"params: ["age", "name"]"#));
+        assert!(!debug.contains("Mark"));
+    }
+
+    #[test]
+    fn should_capture_params_in_the_query_macro() {
+        let name = "Mark";
+        let age = 42;
+        let q = crate::query!(
+            "MATCH (p:Person {name: $name}) SET p.age = $age, p.role = $type",
+            r#type = "admin"
+        );
+
+        assert_eq!(q.params.get::<String>("name"), Some("Mark".to_owned()));
+        assert_eq!(q.params.get::<i64>("age"), Some(age));
+        assert_eq!(q.params.get::<String>("type"), Some("admin".to_owned()));
+    }
+
+    #[test]
+    fn should_leave_tx_options_to_the_transaction() {
+        let q = Query::new("RETURN 1".to_owned())
+            .timeout(Duration::from_secs(5))
+            .metadata(vec![("request", "42")]);
+
+        assert_eq!(q.options.timeout, Some(Duration::from_secs(5)));
+        assert_eq!(
+            q.options.metadata.get::<String>("request"),
+            Some("42".to_owned())
+        );
+        assert_eq!(q.in_txn("neo4j").options, TxOptions::default());
+    }
+
+    #[test]
+    fn should_mark_read_queries() {
+        let write = Query::new("CREATE (p:Person)".to_owned());
+        let read = Query::new("MATCH (p:Person) RETURN p".to_owned()).read();
+
+        assert_eq!(write.access_mode(), AccessMode::Write);
+        assert_eq!(read.access_mode(), AccessMode::Read);
+    }
+
+    #[test]
+    fn should_impersonate_the_config_user_unless_overridden() {
+        let config = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .impersonated_user("jane")
+            .build()
+            .unwrap();
+
+        let default = Query::new("RETURN 1".to_owned()).auto_commit(&config);
+        let john = Query::new("RETURN 1".to_owned())
+            .impersonate("john")
+            .auto_commit(&config);
+
+        assert_eq!(default.options.impersonated_user.as_deref(), Some("jane"));
+        assert_eq!(john.options.impersonated_user.as_deref(), Some("john"));
+        assert!(john.options.check(crate::Version::V4_4).is_ok());
+        assert!(matches!(
+            john.options.check(crate::Version::V4_3),
+            Err(Error::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn should_add_the_metadata_of_the_context() {
+        let config = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .metadata_context(|| {
+                let mut metadata = BoltMap::default();
+                metadata.put("trace_id".into(), "4bf92f35".into());
+                metadata.put("app".into(), "shop".into());
+                metadata
+            })
+            .build()
+            .unwrap();
+
+        let traced = Query::new("RETURN 1".to_owned()).auto_commit(&config);
+        let tagged = Query::new("RETURN 1".to_owned())
+            .metadata([("app", "admin")])
+            .auto_commit(&config);
+
+        assert_eq!(
+            traced.options.metadata.get::<String>("trace_id").as_deref(),
+            Some("4bf92f35")
+        );
+        assert_eq!(
+            tagged.options.metadata.get::<String>("app").as_deref(),
+            Some("admin")
+        );
+        assert_eq!(tagged.options.metadata.len(), 2);
+    }
+
+    #[test]
+    fn should_wait_for_the_bookmarks_of_the_manager() {
+        use crate::bookmark::InMemoryBookmarkManager;
+        let manager = InMemoryBookmarkManager::new(&[Bookmark::new("FB:managed")]);
+        let config = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .bookmark_manager(manager)
+            .build()
+            .unwrap();
+
+        let managed = Query::new("RETURN 1".to_owned()).auto_commit(&config);
+        let explicit = Query::new("RETURN 1".to_owned())
+            .bookmarks(&[Bookmark::new("FB:explicit")])
+            .auto_commit(&config);
+
+        assert_eq!(managed.bookmarks, vec![Bookmark::new("FB:managed")]);
+        assert_eq!(explicit.bookmarks, vec![Bookmark::new("FB:explicit")]);
+    }
+
+    #[test]
+    fn should_filter_the_notifications_of_the_query() {
+        use crate::summary::{NotificationCategory, NotificationFilter};
+        let filter = NotificationFilter::new().disable(NotificationCategory::Hint);
+        let q = Query::new("RETURN 1".to_owned()).notification_filter(filter.clone());
+
+        assert_eq!(q.options.notifications, filter);
+        assert!(q.options.check(crate::Version::V5_2).is_ok());
+        assert!(matches!(
+            q.options.check(crate::Version::V5_1),
+            Err(Error::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn should_fetch_query_batches_over_config_batches() {
+        let config = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .fetch_size(200)
+            .build()
+            .unwrap();
+
+        let default = Query::new("RETURN 1".to_owned());
+        let small = Query::new("RETURN 1".to_owned()).fetch_size(10);
+
+        assert_eq!(default.batch_size(&config), 200);
+        assert_eq!(small.batch_size(&config), 10);
     }
 }
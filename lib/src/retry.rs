@@ -0,0 +1,72 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+
+const INITIAL_DELAY: Duration = Duration::from_secs(1);
+const MULTIPLIER: f64 = 2.0;
+const JITTER: f64 = 0.2;
+
+/// Exponential backoff between the attempts of a managed transaction, the delays double after
+/// every attempt and are spread by a random jitter so competing clients don't retry in lockstep.
+pub(crate) struct Backoff {
+    delay: Duration,
+    deadline: Instant,
+}
+
+impl Backoff {
+    pub(crate) fn new(max_retry_time: Duration) -> Self {
+        Backoff {
+            delay: INITIAL_DELAY,
+            deadline: Instant::now() + max_retry_time,
+        }
+    }
+
+    /// The delay to wait before the next attempt, or none once the retry time is exhausted
+    pub(crate) fn next_delay(&mut self) -> Option<Duration> {
+        let remaining = self.deadline.checked_duration_since(Instant::now())?;
+        if remaining.is_zero() {
+            return None;
+        }
+        let delay = jitter(self.delay).min(remaining);
+        self.delay = self.delay.mul_f64(MULTIPLIER);
+        Some(delay)
+    }
+}
+
+fn jitter(delay: Duration) -> Duration {
+    // every RandomState is seeded differently, which is random enough to spread retries
+    let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+    delay.mul_f64(1.0 - JITTER + 2.0 * JITTER * random)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_grow_delays_exponentially() {
+        let mut backoff = Backoff::new(Duration::from_secs(60));
+
+        let first = backoff.next_delay().unwrap();
+        let second = backoff.next_delay().unwrap();
+        let third = backoff.next_delay().unwrap();
+
+        assert!(first >= Duration::from_millis(800) && first <= Duration::from_millis(1200));
+        assert!(second >= Duration::from_millis(1600) && second <= Duration::from_millis(2400));
+        assert!(third >= Duration::from_millis(3200) && third <= Duration::from_millis(4800));
+    }
+
+    #[test]
+    fn should_stop_after_max_retry_time() {
+        let mut backoff = Backoff::new(Duration::from_secs(0));
+
+        assert_eq!(backoff.next_delay(), None);
+    }
+
+    #[test]
+    fn should_not_wait_past_max_retry_time() {
+        let mut backoff = Backoff::new(Duration::from_millis(100));
+
+        assert!(backoff.next_delay().unwrap() <= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,427 @@
+use crate::balancing::Member;
+use crate::breaker::CircuitBreaker;
+use crate::config::Config;
+use crate::connection::Connection;
+use crate::errors::*;
+use crate::messages::*;
+use crate::pool::{
+    acquire, close, create_pool, status, total_status, ConnectionPool, ManagedConnection,
+    PoolStatus,
+};
+use crate::srv;
+use crate::txn::TxOptions;
+use crate::types::*;
+use crate::version::Feature;
+use log::{info, warn};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryInto;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const ROUTING_TABLE_PROCEDURE: &str = "CALL dbms.routing.getRoutingTable($context, $database)";
+/// The procedure of Neo4j 3.5 clusters, which only have the default database
+const LEGACY_ROUTING_TABLE_PROCEDURE: &str = "CALL dbms.cluster.routing.getRoutingTable($context)";
+
+/// Whether a unit of work only reads from the database or may also write to it, used to pick
+/// a suitable cluster member.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum AccessMode {
+    Read,
+    #[default]
+    Write,
+}
+
+/// The cluster members serving each role for a database, as advertised by the cluster.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RoutingTable {
+    routers: Vec<String>,
+    readers: Vec<String>,
+    writers: Vec<String>,
+    ttl: Duration,
+    fetched_at: Instant,
+}
+
+impl RoutingTable {
+    /// Parses the `rt` map of a ROUTE response
+    fn parse(rt: BoltMap) -> Result<RoutingTable> {
+        let ttl = rt
+            .get::<i64>("ttl")
+            .ok_or_else(|| Error::RoutingError("routing table without ttl".into()))?;
+        let servers = rt
+            .get::<BoltList>("servers")
+            .ok_or_else(|| Error::RoutingError("routing table without servers".into()))?;
+        RoutingTable::from_servers(ttl, servers)
+    }
+
+    fn from_servers(ttl: i64, servers: BoltList) -> Result<RoutingTable> {
+        let mut table = RoutingTable {
+            routers: Vec::new(),
+            readers: Vec::new(),
+            writers: Vec::new(),
+            ttl: Duration::from_secs(ttl.max(0) as u64),
+            fetched_at: Instant::now(),
+        };
+        for server in servers {
+            let server: BoltMap = server.try_into()?;
+            let addresses: Vec<String> = server
+                .get::<BoltList>("addresses")
+                .ok_or_else(|| Error::RoutingError("server without addresses".into()))?
+                .into();
+            match server.get::<String>("role").as_deref() {
+                Some("ROUTE") => table.routers.extend(addresses),
+                Some("READ") => table.readers.extend(addresses),
+                Some("WRITE") => table.writers.extend(addresses),
+                role => warn!("ignoring servers with unknown role {:?}", role),
+            }
+        }
+        Ok(table)
+    }
+
+    fn servers(&self, mode: AccessMode) -> &[String] {
+        match mode {
+            AccessMode::Read => &self.readers,
+            AccessMode::Write => &self.writers,
+        }
+    }
+
+    /// A table must be refreshed once its ttl expired or when it can't serve the requested mode
+    fn is_stale(&self, mode: AccessMode) -> bool {
+        self.fetched_at.elapsed() >= self.ttl
+            || self.routers.is_empty()
+            || self.servers(mode).is_empty()
+    }
+
+    fn forget(&mut self, address: &str) {
+        self.routers.retain(|a| a != address);
+        self.readers.retain(|a| a != address);
+        self.writers.retain(|a| a != address);
+    }
+
+    fn addresses(&self) -> impl Iterator<Item = &String> {
+        self.routers
+            .iter()
+            .chain(self.readers.iter())
+            .chain(self.writers.iter())
+    }
+}
+
+/// Connection pools for every member of a cluster, picking a member based on the routing table
+/// of the database and the access mode of the work to be done.
+pub(crate) struct RoutedPool {
+    config: Config,
+    tables: tokio::sync::Mutex<HashMap<String, RoutingTable>>,
+    pools: Mutex<HashMap<String, ConnectionPool>>,
+    breaker: CircuitBreaker,
+    closed: AtomicBool,
+}
+
+impl RoutedPool {
+    pub(crate) fn new(config: &Config) -> Self {
+        RoutedPool {
+            config: config.clone(),
+            tables: tokio::sync::Mutex::new(HashMap::new()),
+            pools: Mutex::new(HashMap::new()),
+            breaker: CircuitBreaker::new(config.breaker_threshold, config.breaker_cooldown),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Gets a connection to a cluster member able to serve `mode` for `db`, members that can't
+    /// be connected to are removed from the routing table and the next one is tried. Members
+    /// failing again and again are skipped for a while, see [`CircuitBreaker`].
+    pub(crate) async fn get(&self, mode: AccessMode, db: &str) -> Result<ManagedConnection> {
+        if self.closed.load(Ordering::Relaxed) {
+            return Err(Error::PoolClosed);
+        }
+        let mut tried = HashSet::new();
+        loop {
+            let address = self.select(mode, db).await?;
+            if !tried.insert(address.clone()) {
+                return Err(Error::RoutingError(format!(
+                    "no reachable server for database '{}'",
+                    db
+                )));
+            }
+            self.breaker.attempt(&address);
+            match acquire(&self.pool(&address)).await {
+                Ok(connection) => {
+                    self.breaker.succeeded(&address);
+                    return Ok(connection);
+                }
+                // the member is reachable but all of its connections are in use
+                Err(deadpool::managed::PoolError::Timeout(_)) => {
+                    self.breaker.succeeded(&address);
+                    return Err(Error::PoolTimeout);
+                }
+                Err(e) => {
+                    warn!("unable to connect to {}: {:?}", address, e);
+                    self.breaker.failed(&address);
+                    self.forget(&address).await;
+                }
+            }
+        }
+    }
+
+    async fn select(&self, mode: AccessMode, db: &str) -> Result<String> {
+        let mut tables = self.tables.lock().await;
+        if tables.get(db).is_none_or(|table| table.is_stale(mode)) {
+            let routers = tables
+                .get(db)
+                .map(|table| table.routers.clone())
+                .unwrap_or_default();
+            let table = self.fetch(routers, db).await?;
+            info!("updated routing table for database '{}': {:?}", db, table);
+            tables.insert(db.to_owned(), table);
+            self.prune(&tables);
+        }
+        let servers = self.breaker.available(tables[db].servers(mode));
+        if servers.is_empty() {
+            return Err(Error::RoutingError(format!(
+                "no server available for {:?} on database '{}'",
+                mode, db
+            )));
+        }
+        let members: Vec<_> = servers
+            .iter()
+            .map(|address| Member {
+                address,
+                status: status(&self.pool(address)),
+            })
+            .collect();
+        let index = self.config.load_balancing.select(&members);
+        Ok(servers[index].clone())
+    }
+
+    /// Fetches a fresh routing table from the known routers, falling back to the members listed
+    /// by the SRV records and to the addresses the driver was configured with in order.
+    async fn fetch(&self, routers: Vec<String>, db: &str) -> Result<RoutingTable> {
+        let mut last_error = Error::RoutingError("no router available".into());
+        let mut seeds = self.config.addresses();
+        if let Some(name) = self.config.srv_name() {
+            match srv::lookup(&name, self.config.connection_timeout).await {
+                Ok(members) => {
+                    seeds.retain(|seed| !members.contains(seed));
+                    seeds.splice(0..0, members);
+                }
+                Err(e) => warn!("unable to look up the SRV records {}: {:?}", name, e),
+            }
+        }
+        let candidates = routers
+            .into_iter()
+            .filter(|router| !seeds.contains(router))
+            .chain(seeds.iter().cloned());
+        for router in candidates {
+            let result = match acquire(&self.pool(&router)).await {
+                Ok(mut connection) => fetch_routing_table(&mut connection, &self.config, db).await,
+                Err(e) => Err(e.into()),
+            };
+            match result {
+                Ok(table) => return Ok(table),
+                Err(e) => {
+                    warn!("unable to fetch routing table from {}: {:?}", router, e);
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Drops the routing table of `db`, for instance after its leader stepped down
+    pub(crate) async fn invalidate(&self, db: &str) {
+        self.tables.lock().await.remove(db);
+    }
+
+    async fn forget(&self, address: &str) {
+        let mut tables = self.tables.lock().await;
+        for table in tables.values_mut() {
+            table.forget(address);
+        }
+        self.pools.lock().unwrap().remove(address);
+    }
+
+    /// Drops the pools of members that are no longer part of any routing table
+    fn prune(&self, tables: &HashMap<String, RoutingTable>) {
+        let known: HashSet<&String> = tables.values().flat_map(|t| t.addresses()).collect();
+        let seeds = self.config.addresses();
+        self.pools
+            .lock()
+            .unwrap()
+            .retain(|address, _| seeds.contains(address) || known.contains(address));
+    }
+
+    /// Closes the pools of every member, see [`close`]
+    pub(crate) async fn close(&self, deadline: Instant) {
+        self.closed.store(true, Ordering::Relaxed);
+        let pools: Vec<ConnectionPool> = self.pools.lock().unwrap().values().cloned().collect();
+        futures::future::join_all(pools.iter().map(|pool| close(pool, deadline))).await;
+    }
+
+    /// The statistics of the pools of every known member
+    pub(crate) fn status(&self) -> PoolStatus {
+        total_status(self.pools.lock().unwrap().values())
+    }
+
+    fn pool(&self, address: &str) -> ConnectionPool {
+        self.pools
+            .lock()
+            .unwrap()
+            .entry(address.to_owned())
+            .or_insert_with(|| create_pool(&[address.to_owned()], &self.config))
+            .clone()
+    }
+}
+
+async fn fetch_routing_table(
+    connection: &mut Connection,
+    config: &Config,
+    db: &str,
+) -> Result<RoutingTable> {
+    let context = config.routing_context();
+    if connection.version().supports(Feature::RouteMessage) {
+        let route = BoltRequest::route(
+            connection.version(),
+            context,
+            db,
+            config.impersonated_user.as_deref(),
+        );
+        return match connection.send_recv(route).await? {
+            BoltResponse::SuccessMessage(success) => {
+                let rt = success
+                    .get::<BoltMap>("rt")
+                    .ok_or_else(|| Error::RoutingError("missing routing table".into()))?;
+                RoutingTable::parse(rt)
+            }
+            BoltResponse::FailureMessage(failure) => Err(Error::RoutingError(
+                failure.get("message").unwrap_or_default(),
+            )),
+            msg => Err(unexpected(msg, "ROUTE")),
+        };
+    }
+
+    // servers prior to 4.3 expose the routing table through a procedure on the system database
+    let mut params = BoltMap::default();
+    params.put("context".into(), BoltType::Map(context));
+    let run = if connection.version().supports(Feature::MultiDatabase) {
+        let database = if db.is_empty() {
+            BoltType::Null(BoltNull)
+        } else {
+            db.into()
+        };
+        params.put("database".into(), database);
+        BoltRequest::run(
+            "system",
+            ROUTING_TABLE_PROCEDURE,
+            params,
+            &[],
+            &TxOptions::default(),
+        )
+    } else {
+        BoltRequest::run(
+            db,
+            LEGACY_ROUTING_TABLE_PROCEDURE,
+            params,
+            &[],
+            &TxOptions::default(),
+        )
+    };
+    match connection.send_recv(run).await? {
+        BoltResponse::SuccessMessage(_) => {}
+        BoltResponse::FailureMessage(failure) => {
+            return Err(Error::RoutingError(
+                failure.get("message").unwrap_or_default(),
+            ))
+        }
+        msg => return Err(unexpected(msg, "RUN")),
+    }
+    connection.send(BoltRequest::pull(1000, -1)).await?;
+    let mut record = None;
+    loop {
+        match connection.recv().await? {
+            BoltResponse::RecordMessage(r) => record = Some(r),
+            BoltResponse::SuccessMessage(_) => break,
+            msg => return Err(unexpected(msg, "PULL")),
+        }
+    }
+    let data = record
+        .ok_or_else(|| Error::RoutingError("empty routing table".into()))?
+        .data()?;
+    match (data.get(0), data.get(1)) {
+        (Some(BoltType::Integer(ttl)), Some(BoltType::List(servers))) => {
+            RoutingTable::from_servers(ttl.value, servers.clone())
+        }
+        _ => Err(Error::RoutingError("malformed routing table".into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(role: &str, addresses: Vec<&str>) -> BoltType {
+        let addresses: Vec<BoltType> = addresses.into_iter().map(|a| a.into()).collect();
+        BoltType::Map(
+            vec![
+                ("role".into(), role.into()),
+                ("addresses".into(), BoltType::List(addresses.into())),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+
+    fn routing_table() -> RoutingTable {
+        let servers: BoltList = vec![
+            server("ROUTE", vec!["a:7687", "b:7687"]),
+            server("READ", vec!["b:7687", "c:7687"]),
+            server("WRITE", vec!["a:7687"]),
+        ]
+        .into();
+        let rt: BoltMap = vec![
+            ("ttl".into(), 300.into()),
+            ("servers".into(), BoltType::List(servers)),
+        ]
+        .into_iter()
+        .collect();
+        RoutingTable::parse(rt).unwrap()
+    }
+
+    #[test]
+    fn should_parse_routing_table() {
+        let table = routing_table();
+
+        assert_eq!(table.routers, vec!["a:7687", "b:7687"]);
+        assert_eq!(table.readers, vec!["b:7687", "c:7687"]);
+        assert_eq!(table.writers, vec!["a:7687"]);
+        assert_eq!(table.ttl, Duration::from_secs(300));
+        assert!(!table.is_stale(AccessMode::Read));
+        assert!(!table.is_stale(AccessMode::Write));
+    }
+
+    #[test]
+    fn should_become_stale_without_servers_for_mode() {
+        let mut table = routing_table();
+
+        table.forget("a:7687");
+
+        assert_eq!(table.routers, vec!["b:7687"]);
+        assert!(table.is_stale(AccessMode::Write));
+        assert!(!table.is_stale(AccessMode::Read));
+    }
+
+    #[test]
+    fn should_become_stale_after_ttl() {
+        let mut table = routing_table();
+
+        table.ttl = Duration::from_secs(0);
+
+        assert!(table.is_stale(AccessMode::Read));
+    }
+
+    #[test]
+    fn should_reject_malformed_routing_table() {
+        let rt: BoltMap = vec![("ttl".into(), 300.into())].into_iter().collect();
+
+        assert!(RoutingTable::parse(rt).is_err());
+    }
+}
@@ -1,12 +1,50 @@
+use crate::convert::FromBoltValue;
+use crate::de::{from_bolt, from_entity, EntityDeserializer};
+use crate::errors::{Error, Result};
+use crate::messages::Record;
 use crate::types::*;
+use crate::version::Version;
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::convert::TryInto;
+use std::rc::Rc;
+use std::sync::{Arc, OnceLock};
 
 /// Represents a row returned as a result of executing a query.
 ///
 /// A row is very similar to a `HashMap`, you can get the attributes using [`Row::get`] method.
+/// The columns of rows received from the server are only decoded once they are read, so reading
+/// a few columns of wide rows doesn't pay for decoding all of them.
 #[derive(Debug)]
 pub struct Row {
-    attributes: BoltMap,
+    keys: Arc<[String]>,
+    columns: Vec<Column>,
+}
+
+/// A column of a row, decoded from its packstream encoding the first time it is read
+#[derive(Debug)]
+struct Column {
+    encoded: Option<(Version, Bytes)>,
+    decoded: OnceLock<BoltType>,
+}
+
+impl Column {
+    fn value(&self) -> Result<&BoltType> {
+        if let Some(value) = self.decoded.get() {
+            return Ok(value);
+        }
+        let value = match &self.encoded {
+            Some((version, bytes)) => {
+                BoltType::parse(*version, Rc::new(RefCell::new(bytes.clone())))?
+            }
+            None => BoltType::Null(BoltNull),
+        };
+        Ok(self.decoded.get_or_init(|| value))
+    }
 }
 
 /// Snapshot of a node within a graph database
@@ -33,16 +71,97 @@ pub struct UnboundedRelation {
     inner: BoltUnboundedRelation,
 }
 
+/// A type that can be extracted from the columns of a [`Row`], usually implemented with
+/// `#[derive(FromRow)]`
+///
+/// Every field is read from the column of the same name. `#[neo4rs(rename = "...")]` reads
+/// another column and `#[neo4rs(default)]` falls back to [`Default`] for a missing column.
+pub trait FromRow: Sized {
+    fn from_row(row: &Row) -> Result<Self>;
+}
+
+/// A type that can be extracted from the properties of a [`Node`], usually implemented with
+/// `#[derive(FromNode)]`
+///
+/// Fields are read like with [`FromRow`], `#[neo4rs(id)]` and `#[neo4rs(labels)]` receive the
+/// id and the labels of the node, and an `Option<String>` with `#[neo4rs(element_id)]` its
+/// [`Node::element_id`].
+pub trait FromNode: Sized {
+    fn from_node(node: &Node) -> Result<Self>;
+}
+
 /// Represents a single location in 2-dimensional space
+#[derive(Debug, Clone, PartialEq)]
 pub struct Point2D {
     inner: BoltPoint2D,
 }
 
 /// Represents a single location in 3-dimensional space
+#[derive(Debug, Clone, PartialEq)]
 pub struct Point3D {
     inner: BoltPoint3D,
 }
 
+/// The coordinate reference systems of the points stored by Neo4j, identified by their SRID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Crs {
+    /// Geographic points with the longitude as x and the latitude as y, SRID 4326
+    Wgs84,
+    /// Geographic points with the longitude, the latitude and the height in meters, SRID 4979
+    Wgs84_3D,
+    /// Points on a plane, SRID 7203
+    Cartesian,
+    /// Points in space, SRID 9157
+    Cartesian3D,
+}
+
+impl Crs {
+    /// The reference system identified by `srid`, unless Neo4j doesn't know it
+    pub fn from_srid(srid: i64) -> Option<Crs> {
+        match srid {
+            4326 => Some(Crs::Wgs84),
+            4979 => Some(Crs::Wgs84_3D),
+            7203 => Some(Crs::Cartesian),
+            9157 => Some(Crs::Cartesian3D),
+            _ => None,
+        }
+    }
+
+    /// Spatial reference system identifier, see <https://en.wikipedia.org/wiki/Spatial_reference_system#Identifier>
+    pub fn srid(self) -> i64 {
+        match self {
+            Crs::Wgs84 => 4326,
+            Crs::Wgs84_3D => 4979,
+            Crs::Cartesian => 7203,
+            Crs::Cartesian3D => 9157,
+        }
+    }
+
+    /// The name of the reference system in cypher, like `point({x: 1, y: 2, crs: 'cartesian'})`
+    pub fn name(self) -> &'static str {
+        match self {
+            Crs::Wgs84 => "wgs-84",
+            Crs::Wgs84_3D => "wgs-84-3d",
+            Crs::Cartesian => "cartesian",
+            Crs::Cartesian3D => "cartesian-3d",
+        }
+    }
+
+    pub fn is_3d(self) -> bool {
+        matches!(self, Crs::Wgs84_3D | Crs::Cartesian3D)
+    }
+
+    pub fn is_geographic(self) -> bool {
+        matches!(self, Crs::Wgs84 | Crs::Wgs84_3D)
+    }
+}
+
+impl std::fmt::Display for Crs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 impl Path {
     pub fn new(inner: BoltPath) -> Self {
         Path { inner }
@@ -62,6 +181,30 @@ impl Path {
         let rels = self.inner.rels();
         rels.into_iter().map(UnboundedRelation::new).collect()
     }
+
+    /// The relationships of the path in order, with their start and end nodes
+    pub fn relationships(&self) -> Vec<Relation> {
+        self.segments().map(|(_, rel, _)| rel).collect()
+    }
+
+    /// The number of relationships of the path
+    pub fn len(&self) -> usize {
+        self.inner.ids.len() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Walks the path, yielding every relationship with the node before and the node after it.
+    /// The order of the nodes follows the path while the relationship keeps its own direction,
+    /// so `rel.start_node_id()` is the id of the node after it when it was traversed backwards.
+    pub fn segments(&self) -> impl Iterator<Item = (Node, Relation, Node)> {
+        self.inner
+            .segments()
+            .into_iter()
+            .map(|(start, rel, end)| (Node::new(start), Relation::new(rel), Node::new(end)))
+    }
 }
 
 impl Point2D {
@@ -69,6 +212,62 @@ impl Point2D {
         Point2D { inner }
     }
 
+    /// A point on a plane
+    pub fn cartesian(x: f64, y: f64) -> Self {
+        Point2D::in_crs(Crs::Cartesian, x, y)
+    }
+
+    /// A geographic point, in degrees
+    pub fn wgs84(longitude: f64, latitude: f64) -> Self {
+        Point2D::in_crs(Crs::Wgs84, longitude, latitude)
+    }
+
+    /// A point of the 2d reference system identified by `srid`, to be passed as a parameter
+    pub fn with_srid(srid: i64, x: f64, y: f64) -> Result<Self> {
+        match Crs::from_srid(srid) {
+            Some(crs) if !crs.is_3d() => Ok(Point2D::in_crs(crs, x, y)),
+            _ => Err(Error::InvalidPoint(format!(
+                "{} is not the srid of a 2d reference system",
+                srid
+            ))),
+        }
+    }
+
+    fn in_crs(crs: Crs, x: f64, y: f64) -> Self {
+        Point2D::new(BoltPoint2D {
+            sr_id: BoltInteger::new(crs.srid()),
+            x: BoltFloat::new(x),
+            y: BoltFloat::new(y),
+        })
+    }
+
+    /// Reads a point written in WKT, like `POINT(2.3 4.5)`, or in EWKT, like
+    /// `SRID=4326;POINT(12.5 41.9)`. Points without an SRID are cartesian.
+    pub fn from_wkt(wkt: &str) -> Result<Self> {
+        let (srid, coordinates, z) = parse_wkt(wkt)?;
+        let srid = srid.unwrap_or_else(|| Crs::Cartesian.srid());
+        match (&coordinates[..], z) {
+            (&[x, y], false) => Point2D::with_srid(srid, x, y),
+            _ => Err(Error::InvalidPoint(format!("{} is not a 2d point", wkt))),
+        }
+    }
+
+    /// The point in WKT, like `POINT(2.3 4.5)`
+    pub fn to_wkt(&self) -> String {
+        format!("POINT({} {})", self.x(), self.y())
+    }
+
+    /// The point in EWKT, the WKT of PostGIS prefixed with the SRID, like
+    /// `SRID=4326;POINT(12.5 41.9)`
+    pub fn to_ewkt(&self) -> String {
+        format!("SRID={};{}", self.sr_id(), self.to_wkt())
+    }
+
+    /// The reference system of the point, unless its SRID is unknown to Neo4j
+    pub fn crs(&self) -> Option<Crs> {
+        Crs::from_srid(self.sr_id())
+    }
+
     /// Spatial refrerence system identifier, see <https://en.wikipedia.org/wiki/Spatial_reference_system#Identifier>
     pub fn sr_id(&self) -> i64 {
         self.inner.sr_id.value
@@ -88,6 +287,69 @@ impl Point3D {
         Point3D { inner }
     }
 
+    /// A point in space
+    pub fn cartesian(x: f64, y: f64, z: f64) -> Self {
+        Point3D::in_crs(Crs::Cartesian3D, x, y, z)
+    }
+
+    /// A geographic point, in degrees with the height in meters
+    pub fn wgs84(longitude: f64, latitude: f64, height: f64) -> Self {
+        Point3D::in_crs(Crs::Wgs84_3D, longitude, latitude, height)
+    }
+
+    /// A point of the 3d reference system identified by `srid`, to be passed as a parameter
+    pub fn with_srid(srid: i64, x: f64, y: f64, z: f64) -> Result<Self> {
+        match Crs::from_srid(srid) {
+            Some(crs) if crs.is_3d() => Ok(Point3D::in_crs(crs, x, y, z)),
+            _ => Err(Error::InvalidPoint(format!(
+                "{} is not the srid of a 3d reference system",
+                srid
+            ))),
+        }
+    }
+
+    fn in_crs(crs: Crs, x: f64, y: f64, z: f64) -> Self {
+        Point3D::new(BoltPoint3D {
+            sr_id: BoltInteger::new(crs.srid()),
+            x: BoltFloat::new(x),
+            y: BoltFloat::new(y),
+            z: BoltFloat::new(z),
+        })
+    }
+
+    /// Reads a point written in WKT, like `POINT Z (1 2 3)`, or in EWKT, like
+    /// `SRID=4979;POINT(12.5 41.9 21)`. Points without an SRID are cartesian.
+    pub fn from_wkt(wkt: &str) -> Result<Self> {
+        let (srid, coordinates, _) = parse_wkt(wkt)?;
+        let srid = srid.unwrap_or_else(|| Crs::Cartesian3D.srid());
+        match coordinates[..] {
+            [x, y, z] => Point3D::with_srid(srid, x, y, z),
+            _ => Err(Error::InvalidPoint(format!("{} is not a 3d point", wkt))),
+        }
+    }
+
+    /// The point in WKT, like `POINT Z (1 2 3)`
+    pub fn to_wkt(&self) -> String {
+        format!("POINT Z ({} {} {})", self.x(), self.y(), self.z())
+    }
+
+    /// The point in EWKT, the WKT of PostGIS prefixed with the SRID which leaves out the `Z`, like
+    /// `SRID=4979;POINT(12.5 41.9 21)`
+    pub fn to_ewkt(&self) -> String {
+        format!(
+            "SRID={};POINT({} {} {})",
+            self.sr_id(),
+            self.x(),
+            self.y(),
+            self.z()
+        )
+    }
+
+    /// The reference system of the point, unless its SRID is unknown to Neo4j
+    pub fn crs(&self) -> Option<Crs> {
+        Crs::from_srid(self.sr_id())
+    }
+
     /// Spatial refrerence system identifier, see <https://en.wikipedia.org/wiki/Spatial_reference_system#Identifier>
     pub fn sr_id(&self) -> i64 {
         self.inner.sr_id.value
@@ -106,20 +368,205 @@ impl Point3D {
     }
 }
 
+impl From<Point2D> for BoltType {
+    fn from(point: Point2D) -> Self {
+        BoltType::Point2D(point.inner)
+    }
+}
+
+impl From<Point3D> for BoltType {
+    fn from(point: Point3D) -> Self {
+        BoltType::Point3D(point.inner)
+    }
+}
+
+/// The SRID, the coordinates and whether the `Z` tag was given of a point in WKT or EWKT
+fn parse_wkt(wkt: &str) -> Result<(Option<i64>, Vec<f64>, bool)> {
+    let invalid = || Error::InvalidPoint(format!("{} is not a point in wkt", wkt));
+    let text = wkt.trim();
+    let (srid, text) = match text.split_once(';') {
+        Some((srid, text)) => {
+            let (tag, srid) = srid.trim().split_once('=').ok_or_else(invalid)?;
+            if !tag.trim().eq_ignore_ascii_case("SRID") {
+                return Err(invalid());
+            }
+            (Some(srid.trim().parse().map_err(|_| invalid())?), text)
+        }
+        None => (None, text),
+    };
+    let text = text.trim_start();
+    if text.len() < 5 || !text[..5].eq_ignore_ascii_case("POINT") {
+        return Err(invalid());
+    }
+    let text = text[5..].trim_start();
+    let (z, text) = match text.strip_prefix(|c| c == 'Z' || c == 'z') {
+        Some(text) => (true, text.trim_start()),
+        None => (false, text),
+    };
+    let coordinates = text
+        .strip_prefix('(')
+        .and_then(|text| text.trim_end().strip_suffix(')'))
+        .ok_or_else(invalid)?
+        .split_whitespace()
+        .map(|coordinate| coordinate.parse().map_err(|_| invalid()))
+        .collect::<Result<Vec<f64>>>()?;
+    if z && coordinates.len() != 3 {
+        return Err(invalid());
+    }
+    Ok((srid, coordinates, z))
+}
+
 impl Row {
     pub fn new(fields: BoltList, data: BoltList) -> Self {
-        let mut attributes = BoltMap::with_capacity(fields.len());
+        let mut keys = Vec::with_capacity(fields.len());
+        let mut columns = Vec::with_capacity(fields.len());
         for (field, value) in fields.into_iter().zip(data.into_iter()) {
-            if let Ok(key) = field.try_into() {
-                attributes.put(key, value);
+            if let Ok(key) = TryInto::<BoltString>::try_into(field) {
+                keys.push(key.into());
+                columns.push(Column {
+                    encoded: None,
+                    decoded: OnceLock::from(value),
+                });
             }
         }
-        Row { attributes }
+        Row {
+            keys: keys.into(),
+            columns,
+        }
+    }
+
+    /// A row of the columns `keys` whose values are decoded from `record` once they are read
+    pub(crate) fn from_record(keys: Arc<[String]>, record: Record) -> Self {
+        let version = record.version;
+        let columns = record
+            .values
+            .into_iter()
+            .map(|bytes| Column {
+                encoded: Some((version, bytes)),
+                decoded: OnceLock::new(),
+            })
+            .collect();
+        Row { keys, columns }
+    }
+
+    /// The names of the columns, in the order of the query
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// The number of columns
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Gets the column at `index`, in the order of [`Row::keys`]
+    pub fn get_by_index<T: FromBoltValue>(&self, index: usize) -> Option<T> {
+        let value = self.columns.get(index)?.value().ok()?;
+        T::from_bolt_value(value.clone()).ok()
+    }
+
+    pub fn get<T: FromBoltValue>(&self, key: &str) -> Option<T> {
+        self.get_by_index(self.index(key)?)
+    }
+
+    /// Gets a column like [`Row::get`], failing with [`Error::MissingKey`] when the column is
+    /// missing and with [`Error::ColumnTypeMismatch`] when it doesn't convert to `T`, which
+    /// names the column, `T` and the value found
+    pub fn try_get<T: FromBoltValue>(&self, key: &str) -> Result<T> {
+        let index = self
+            .index(key)
+            .ok_or_else(|| Error::MissingKey(key.to_owned()))?;
+        convert(key, self.columns[index].value()?)
+    }
+
+    /// Gets a column which may be null, `Ok(None)` for null and an error when the column is
+    /// missing or has another type, see [`Row::try_get`]
+    pub fn get_opt<T: FromBoltValue>(&self, key: &str) -> Result<Option<T>> {
+        let index = self
+            .index(key)
+            .ok_or_else(|| Error::MissingKey(key.to_owned()))?;
+        match self.columns[index].value()? {
+            BoltType::Null(_) => Ok(None),
+            value => convert(key, value).map(Some),
+        }
+    }
+
+    /// Gets the value at `path` within a column, like `order.items[0].name` for the `name` of
+    /// the first of the `items` of the `order` column, see [`BoltType::at`]. Fails with
+    /// [`Error::MissingKey`] when a part of the path is missing, and with
+    /// [`Error::ColumnTypeMismatch`] when the value is null or of another type
+    pub fn get_path<T: FromBoltValue>(&self, path: &str) -> Result<T> {
+        let missing = || Error::MissingKey(path.to_owned());
+        let (key, rest) = path.split_at(path.find(['.', '[']).unwrap_or(path.len()));
+        let value = self.columns[self.index(key).ok_or_else(missing)?].value()?;
+        let value = match rest.strip_prefix('.').unwrap_or(rest) {
+            "" => value,
+            rest => value.at(rest).ok_or_else(missing)?,
+        };
+        convert(path, value)
     }
 
-    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
-        self.attributes.get(key)
+    /// Deserializes the whole row into `T`, mapping every column to the field of the same name
+    pub fn to<T: DeserializeOwned>(&self) -> Result<T> {
+        from_bolt(BoltType::Map(self.attributes()?))
     }
+
+    /// The columns keyed by their names, for code which doesn't know the shape of the rows, like
+    /// admin tools. Fails when a column can't be decoded
+    pub fn to_map(&self) -> Result<HashMap<String, BoltType>> {
+        self.keys
+            .iter()
+            .zip(self.values())
+            .map(|(key, value)| Ok((key.clone(), value?.clone())))
+            .collect()
+    }
+
+    /// Converts the row into a JSON object with an entry per column
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<serde_json::Value> {
+        crate::json::object(self.attributes()?)
+    }
+
+    /// The values of the columns, in the order of [`Row::keys`], decoding the ones not read yet
+    pub(crate) fn values(&self) -> impl Iterator<Item = Result<&BoltType>> {
+        self.columns.iter().map(Column::value)
+    }
+
+    fn index(&self, key: &str) -> Option<usize> {
+        self.keys.iter().position(|k| k == key)
+    }
+
+    fn attributes(&self) -> Result<BoltMap> {
+        let mut attributes = BoltMap::with_capacity(self.len());
+        for (key, value) in self.keys.iter().zip(self.values()) {
+            attributes.put(key.as_str().into(), value?.clone());
+        }
+        Ok(attributes)
+    }
+}
+
+/// Converts the value found at `column` into `T`, describing the mismatch when it doesn't
+/// convert
+fn convert<T: FromBoltValue>(column: &str, value: &BoltType) -> Result<T> {
+    T::from_bolt_value(value.clone()).map_err(|error| match error {
+        Error::ConverstionError | Error::TypeMismatch { .. } => Error::ColumnTypeMismatch {
+            column: column.to_owned(),
+            expected: std::any::type_name::<T>(),
+            found: value.type_name(),
+            value: match value {
+                BoltType::String(s) => Some(format!("{:?}", s.as_str())),
+                BoltType::Integer(i) => Some(i.value.to_string()),
+                BoltType::Float(f) => Some(f.value.to_string()),
+                BoltType::Boolean(b) => Some(b.value.to_string()),
+                _ => None,
+            },
+        },
+        error => error,
+    })
 }
 
 impl Node {
@@ -132,15 +579,61 @@ impl Node {
         self.inner.id.value
     }
 
+    /// The element id of the node, sent from bolt 5.0 (neo4j 5) onwards. Unlike the numeric
+    /// [`Node::id`], deprecated in neo4j 5, it isn't reused once the node is deleted.
+    pub fn element_id(&self) -> Option<&str> {
+        self.inner.element_id.as_ref().map(|id| id.as_str())
+    }
+
     /// various labels attached to this node
     pub fn labels(&self) -> Vec<String> {
         self.inner.labels.iter().map(|l| l.to_string()).collect()
     }
 
+    /// Whether the label is attached to this node
+    pub fn has_label(&self, label: &str) -> bool {
+        self.inner
+            .labels
+            .iter()
+            .any(|l| matches!(l, BoltType::String(s) if s.as_str() == label))
+    }
+
     /// Get the attributes of the node
-    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+    pub fn get<T: FromBoltValue>(&self, key: &str) -> Option<T> {
         self.inner.get(key)
     }
+
+    /// Get an attribute which may be null, a missing attribute is an error
+    pub fn get_opt<T: FromBoltValue>(&self, key: &str) -> Result<Option<T>> {
+        self.inner.get_opt(key)
+    }
+
+    /// All the attributes of the node
+    pub fn properties(&self) -> &BoltMap {
+        &self.inner.properties
+    }
+
+    /// The names of the attributes of the node, in no particular order
+    pub fn keys(&self) -> Vec<&str> {
+        self.inner.properties.keys().collect()
+    }
+
+    /// The attributes of the node keyed by their names, see [`Row::to_map`]
+    pub fn to_map(&self) -> HashMap<String, BoltType> {
+        to_map(&self.inner.properties)
+    }
+
+    /// Deserializes the properties of the node into `T`, the fields `__id`, `__element_id` and
+    /// `__labels` receive the ids and the labels of the node
+    pub fn to<T: DeserializeOwned>(&self) -> Result<T> {
+        from_entity(EntityDeserializer::node(self.inner.clone()))
+    }
+
+    /// Converts the node into a `{"id", "labels", "properties"}` JSON object
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<serde_json::Value> {
+        crate::json::node(self.inner.clone())
+    }
 }
 
 impl Relation {
@@ -160,13 +653,70 @@ impl Relation {
         self.inner.end_node_id.value
     }
 
+    /// The element id of the relationship, sent from bolt 5.0 onwards, see [`Node::element_id`]
+    pub fn element_id(&self) -> Option<&str> {
+        self.inner.element_id.as_ref().map(|id| id.as_str())
+    }
+
+    pub fn start_node_element_id(&self) -> Option<&str> {
+        self.inner
+            .start_node_element_id
+            .as_ref()
+            .map(|id| id.as_str())
+    }
+
+    pub fn end_node_element_id(&self) -> Option<&str> {
+        self.inner
+            .end_node_element_id
+            .as_ref()
+            .map(|id| id.as_str())
+    }
+
     pub fn typ(&self) -> String {
-        self.inner.typ.value.clone()
+        self.inner.typ.as_str().to_owned()
+    }
+
+    /// Whether the relationship has this type
+    pub fn has_type(&self, typ: &str) -> bool {
+        self.inner.typ.as_str() == typ
     }
 
-    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+    pub fn get<T: FromBoltValue>(&self, key: &str) -> Option<T> {
         self.inner.get(key)
     }
+
+    pub fn get_opt<T: FromBoltValue>(&self, key: &str) -> Result<Option<T>> {
+        self.inner.get_opt(key)
+    }
+
+    /// All the attributes of the relationship
+    pub fn properties(&self) -> &BoltMap {
+        &self.inner.properties
+    }
+
+    /// The names of the attributes of the relationship, in no particular order
+    pub fn keys(&self) -> Vec<&str> {
+        self.inner.properties.keys().collect()
+    }
+
+    /// The attributes of the relationship keyed by their names, see [`Row::to_map`]
+    pub fn to_map(&self) -> HashMap<String, BoltType> {
+        to_map(&self.inner.properties)
+    }
+
+    /// Deserializes the properties of the relationship into `T`, the fields `__id`, `__type`,
+    /// `__start_node_id` and `__end_node_id`, and `__element_id`, `__start_node_element_id` and
+    /// `__end_node_element_id`, receive the identity of the relationship
+    pub fn to<T: DeserializeOwned>(&self) -> Result<T> {
+        from_entity(EntityDeserializer::relation(self.inner.clone()))
+    }
+
+    /// Converts the relationship into a
+    /// `{"id", "start_node_id", "end_node_id", "type", "properties"}` JSON object
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<serde_json::Value> {
+        std::convert::TryFrom::try_from(BoltType::Relation(self.inner.clone()))
+    }
 }
 
 impl UnboundedRelation {
@@ -178,11 +728,455 @@ impl UnboundedRelation {
         self.inner.id.value
     }
 
+    /// The element id of the relationship, sent from bolt 5.0 onwards, see [`Node::element_id`]
+    pub fn element_id(&self) -> Option<&str> {
+        self.inner.element_id.as_ref().map(|id| id.as_str())
+    }
+
     pub fn typ(&self) -> String {
-        self.inner.typ.value.clone()
+        self.inner.typ.as_str().to_owned()
     }
 
-    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+    /// Whether the relationship has this type
+    pub fn has_type(&self, typ: &str) -> bool {
+        self.inner.typ.as_str() == typ
+    }
+
+    pub fn get<T: FromBoltValue>(&self, key: &str) -> Option<T> {
         self.inner.get(key)
     }
+
+    pub fn get_opt<T: FromBoltValue>(&self, key: &str) -> Result<Option<T>> {
+        self.inner.get_opt(key)
+    }
+
+    /// All the attributes of the relationship
+    pub fn properties(&self) -> &BoltMap {
+        &self.inner.properties
+    }
+
+    /// The names of the attributes of the relationship, in no particular order
+    pub fn keys(&self) -> Vec<&str> {
+        self.inner.properties.keys().collect()
+    }
+
+    /// The attributes of the relationship keyed by their names, see [`Row::to_map`]
+    pub fn to_map(&self) -> HashMap<String, BoltType> {
+        to_map(&self.inner.properties)
+    }
+}
+
+/// The entries of `properties` keyed by plain strings
+fn to_map(properties: &BoltMap) -> HashMap<String, BoltType> {
+    properties
+        .iter()
+        .map(|(key, value)| (key.to_owned(), value.clone()))
+        .collect()
+}
+
+/// Rows serialize to a map with an entry per column, in the order of the query
+#[cfg(feature = "serde")]
+impl Serialize for Row {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self.keys.iter().zip(self.values()) {
+            map.serialize_entry(
+                key,
+                value.map_err(|e| serde::ser::Error::custom(format!("{:?}", e)))?,
+            )?;
+        }
+        map.end()
+    }
+}
+
+/// Nodes serialize to `{"id", "labels", "properties"}`
+#[cfg(feature = "serde")]
+impl Serialize for Node {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize(serializer)
+    }
+}
+
+/// Relationships serialize to `{"id", "start_node_id", "end_node_id", "type", "properties"}`
+#[cfg(feature = "serde")]
+impl Serialize for Relation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize(serializer)
+    }
+}
+
+/// Relationships serialize to `{"id", "type", "properties"}`
+#[cfg(feature = "serde")]
+impl Serialize for UnboundedRelation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize(serializer)
+    }
+}
+
+/// Paths serialize to `{"nodes", "rels"}`, see [`Node`] and [`UnboundedRelation`]
+#[cfg(feature = "serde")]
+impl Serialize for Path {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.inner.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+    use crate::{FromNode, FromRow};
+
+    #[derive(Debug, PartialEq, FromRow)]
+    struct Count {
+        label: String,
+        #[neo4rs(rename = "count(*)")]
+        total: i64,
+        #[neo4rs(default)]
+        ratio: f64,
+    }
+
+    #[derive(Debug, PartialEq, FromNode)]
+    struct Person {
+        #[neo4rs(id)]
+        id: i64,
+        #[neo4rs(labels)]
+        labels: Vec<String>,
+        name: String,
+    }
+
+    #[test]
+    fn should_derive_from_row() {
+        let fields: BoltList = vec!["label".into(), "count(*)".into()].into();
+        let data: BoltList = vec!["Person".into(), 3.into()].into();
+
+        let count = Count::from_row(&Row::new(fields, data)).unwrap();
+
+        assert_eq!(
+            count,
+            Count {
+                label: "Person".into(),
+                total: 3,
+                ratio: 0.0
+            }
+        );
+    }
+
+    #[test]
+    fn should_fail_on_missing_column() {
+        let fields: BoltList = vec!["label".into()].into();
+        let data: BoltList = vec!["Person".into()].into();
+
+        let result = Count::from_row(&Row::new(fields, data));
+
+        assert!(matches!(result, Err(Error::DeserializationError(_))));
+    }
+
+    #[test]
+    fn should_get_columns_by_index() {
+        let fields: BoltList = vec!["name".into(), "age".into()].into();
+        let data: BoltList = vec!["Mark".into(), 42.into()].into();
+        let row = Row::new(fields, data);
+
+        assert_eq!(row.keys(), ["name", "age"]);
+        assert_eq!(row.len(), 2);
+        assert_eq!(row.get_by_index::<String>(0), Some("Mark".into()));
+        assert_eq!(row.get_by_index::<i64>(1), Some(42));
+        assert_eq!(row.get_by_index::<i64>(2), None);
+    }
+
+    #[test]
+    fn should_get_nullable_columns() {
+        let fields: BoltList = vec!["name".into(), "age".into()].into();
+        let data: BoltList = vec!["Mark".into(), BoltType::Null(BoltNull)].into();
+        let row = Row::new(fields, data);
+
+        assert_eq!(row.get_opt::<String>("name").unwrap(), Some("Mark".into()));
+        assert_eq!(row.get_opt::<i64>("age").unwrap(), None);
+        assert!(matches!(
+            row.get_opt::<i64>("name"),
+            Err(Error::ColumnTypeMismatch { .. })
+        ));
+        assert!(matches!(row.get_opt::<i64>("email"), Err(Error::MissingKey(k)) if k == "email"));
+    }
+
+    #[test]
+    fn should_describe_columns_of_another_type() {
+        let fields: BoltList = vec!["name".into(), "friends".into()].into();
+        let data: BoltList = vec!["Mark".into(), BoltType::List(BoltList::default())].into();
+        let row = Row::new(fields, data);
+
+        assert_eq!(row.try_get::<String>("name").unwrap(), "Mark");
+        assert!(matches!(
+            row.try_get::<i64>("name"),
+            Err(Error::ColumnTypeMismatch { column, expected: "i64", found: "string", value })
+                if column == "name" && value.as_deref() == Some("\"Mark\"")
+        ));
+        assert!(matches!(
+            row.try_get::<bool>("friends"),
+            Err(Error::ColumnTypeMismatch {
+                found: "list",
+                value: None,
+                ..
+            })
+        ));
+        assert!(matches!(
+            row.try_get::<i64>("email"),
+            Err(Error::MissingKey(_))
+        ));
+    }
+
+    #[test]
+    fn should_get_nested_values_by_path() {
+        let mut item = BoltMap::default();
+        item.put("name".into(), "pen".into());
+        let mut order = BoltMap::default();
+        order.put("items".into(), BoltType::List(vec![item.into()].into()));
+        let matrix = BoltType::List(vec![BoltType::List(vec![1.into(), 2.into()].into())].into());
+        let fields: BoltList = vec!["order".into(), "matrix".into()].into();
+        let row = Row::new(fields, vec![order.into(), matrix].into());
+
+        assert_eq!(
+            row.get_path::<String>("order.items[0].name").unwrap(),
+            "pen"
+        );
+        assert_eq!(row.get_path::<i64>("matrix[0][1]").unwrap(), 2);
+        assert_eq!(row.get_path::<BoltList>("order.items").unwrap().len(), 1);
+        assert!(matches!(
+            row.get_path::<String>("order.items[1].name"),
+            Err(Error::MissingKey(path)) if path == "order.items[1].name"
+        ));
+        assert!(matches!(
+            row.get_path::<String>("order.items[x]"),
+            Err(Error::MissingKey(_))
+        ));
+        assert!(row.get_path::<i64>("order.items[0].name").is_err());
+        assert!(row.get_path::<i64>("other.items").is_err());
+    }
+
+    #[test]
+    fn should_decode_columns_once_they_are_read() {
+        // RECORD ["Mark", <invalid utf-8>, 42]
+        let message = Bytes::from_static(&[
+            0xB1, 0x71, 0x93, 0x84, b'M', b'a', b'r', b'k', 0x81, 0xFF, 42,
+        ]);
+        let record = Record::parse(Version::V4_1, Rc::new(RefCell::new(message))).unwrap();
+        let keys: Arc<[String]> =
+            vec!["name".to_owned(), "broken".to_owned(), "age".to_owned()].into();
+        let row = Row::from_record(keys, record);
+
+        assert!(row
+            .columns
+            .iter()
+            .all(|column| column.decoded.get().is_none()));
+        assert_eq!(row.get::<i64>("age"), Some(42));
+        assert_eq!(row.columns[2].decoded.get(), Some(&BoltType::from(42)));
+        assert!(row.columns[0].decoded.get().is_none());
+        assert_eq!(row.get_opt::<String>("name").unwrap(), Some("Mark".into()));
+        assert_eq!(row.get::<String>("broken"), None);
+        assert!(matches!(
+            row.get_opt::<String>("broken"),
+            Err(Error::DeserializationError(_))
+        ));
+        assert!(row
+            .to::<std::collections::HashMap<String, String>>()
+            .is_err());
+    }
+
+    #[test]
+    fn should_derive_from_node() {
+        let labels: BoltList = vec!["Person".into()].into();
+        let properties = vec![("name".into(), "Mark".into())].into_iter().collect();
+        let node = Node::new(BoltNode::new(BoltInteger::new(7), labels, properties));
+
+        let person = Person::from_node(&node).unwrap();
+
+        assert_eq!(
+            person,
+            Person {
+                id: 7,
+                labels: vec!["Person".into()],
+                name: "Mark".into()
+            }
+        );
+    }
+
+    #[test]
+    fn should_convert_rows_and_nodes_into_maps() {
+        let labels: BoltList = vec!["Person".into()].into();
+        let properties = vec![("name".into(), "Mark".into())].into_iter().collect();
+        let node = BoltNode::new(BoltInteger::new(7), labels, properties);
+        let fields: BoltList = vec!["p".into(), "score".into()].into();
+        let row = Row::new(fields, vec![node.clone().into(), 1.5.into()].into());
+
+        let map = row.to_map().unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["score"], BoltType::from(1.5));
+        assert_eq!(map["p"], BoltType::Node(node.clone()));
+        assert_eq!(
+            Node::new(node).to_map(),
+            HashMap::from([("name".to_owned(), BoltType::from("Mark"))])
+        );
+    }
+
+    #[test]
+    fn should_list_the_properties_of_nodes() {
+        let labels: BoltList = vec!["Person".into()].into();
+        let properties = vec![("name".into(), "Mark".into()), ("age".into(), 42.into())]
+            .into_iter()
+            .collect();
+        let node = Node::new(BoltNode::new(BoltInteger::new(7), labels, properties));
+
+        let mut keys = node.keys();
+        keys.sort_unstable();
+        assert_eq!(keys, vec!["age", "name"]);
+
+        let mut properties: Vec<_> = node.properties().iter().collect();
+        properties.sort_by_key(|(key, _)| *key);
+        assert_eq!(
+            properties,
+            vec![
+                ("age", &BoltType::from(42)),
+                ("name", &BoltType::from("Mark"))
+            ]
+        );
+    }
+
+    #[test]
+    fn should_match_labels_and_types() {
+        let labels: BoltList = vec!["Person".into(), "Admin".into()].into();
+        let node = Node::new(BoltNode::new(
+            BoltInteger::new(1),
+            labels,
+            BoltMap::default(),
+        ));
+        assert!(node.has_label("Admin"));
+        assert!(!node.has_label("admin"));
+
+        let rel = UnboundedRelation::new(BoltUnboundedRelation::new(
+            BoltInteger::new(2),
+            "KNOWS".into(),
+            BoltMap::default(),
+        ));
+        assert_eq!(rel.typ(), "KNOWS");
+        assert!(rel.has_type("KNOWS"));
+        assert!(!rel.has_type("LIKES"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn should_serialize_rows_and_nodes() {
+        let labels: BoltList = vec!["Person".into()].into();
+        let properties = vec![("name".into(), "Mark".into())].into_iter().collect();
+        let node = BoltNode::new(BoltInteger::new(7), labels, properties);
+        let fields: BoltList = vec!["p".into(), "score".into()].into();
+        let row = Row::new(
+            fields,
+            vec![BoltType::Node(node.clone()), 1.5.into()].into(),
+        );
+
+        assert_eq!(
+            serde_json::to_value(&row).unwrap(),
+            serde_json::json!({
+                "p": {"id": 7, "labels": ["Person"], "properties": {"name": "Mark"}},
+                "score": 1.5
+            })
+        );
+        assert_eq!(
+            serde_json::to_string(&Node::new(node)).unwrap(),
+            r#"{"id":7,"labels":["Person"],"properties":{"name":"Mark"}}"#
+        );
+    }
+
+    #[test]
+    fn should_expose_element_ids() {
+        #[derive(Debug, PartialEq, FromNode)]
+        struct Entity {
+            #[neo4rs(element_id)]
+            element_id: Option<String>,
+        }
+
+        let mut inner = BoltNode::new(BoltInteger::new(7), BoltList::default(), BoltMap::default());
+        let legacy = Node::new(inner.clone());
+        assert_eq!(legacy.element_id(), None);
+        assert_eq!(Entity::from_node(&legacy).unwrap().element_id, None);
+
+        inner.element_id = Some("4:abc:7".into());
+        let node = Node::new(inner);
+        assert_eq!(node.element_id(), Some("4:abc:7"));
+        assert_eq!(
+            Entity::from_node(&node).unwrap().element_id.as_deref(),
+            Some("4:abc:7")
+        );
+    }
+
+    #[test]
+    fn should_build_points_of_a_reference_system() {
+        let point = Point2D::wgs84(12.5, 41.9);
+        assert_eq!(point.crs(), Some(Crs::Wgs84));
+        assert_eq!(point.sr_id(), 4326);
+        assert_eq!(
+            Point3D::cartesian(1.0, 2.0, 3.0).crs(),
+            Some(Crs::Cartesian3D)
+        );
+        assert_eq!(
+            Point2D::with_srid(7203, 1.0, 2.0).unwrap(),
+            Point2D::cartesian(1.0, 2.0)
+        );
+        assert!(matches!(
+            Point2D::with_srid(4979, 1.0, 2.0),
+            Err(Error::InvalidPoint(_))
+        ));
+        assert!(Point3D::with_srid(42, 1.0, 2.0, 3.0).is_err());
+        assert_eq!(
+            Point2D::new(BoltPoint2D {
+                sr_id: BoltInteger::new(42),
+                x: BoltFloat::new(1.0),
+                y: BoltFloat::new(2.0),
+            })
+            .crs(),
+            None
+        );
+        assert_eq!(Crs::Wgs84_3D.to_string(), "wgs-84-3d");
+        assert!(Crs::Wgs84_3D.is_3d() && Crs::Wgs84_3D.is_geographic());
+
+        let param: BoltType = Point3D::wgs84(12.5, 41.9, 21.0).into();
+        assert!(matches!(param, BoltType::Point3D(p) if p.sr_id.value == 4979));
+    }
+
+    #[test]
+    fn should_read_and_write_wkt() {
+        let point = Point2D::from_wkt("SRID=4326;POINT(12.5 41.9)").unwrap();
+        assert_eq!(point, Point2D::wgs84(12.5, 41.9));
+        assert_eq!(point.to_wkt(), "POINT(12.5 41.9)");
+        assert_eq!(point.to_ewkt(), "SRID=4326;POINT(12.5 41.9)");
+        assert_eq!(
+            Point2D::from_wkt(" point ( -1 2.5 ) ").unwrap(),
+            Point2D::cartesian(-1.0, 2.5)
+        );
+
+        let point = Point3D::from_wkt("POINT Z (1 2 3)").unwrap();
+        assert_eq!(point, Point3D::cartesian(1.0, 2.0, 3.0));
+        assert_eq!(point.to_wkt(), "POINT Z (1 2 3)");
+        let point = Point3D::from_wkt("SRID=4979;POINT(12.5 41.9 21)").unwrap();
+        assert_eq!(point.crs(), Some(Crs::Wgs84_3D));
+        assert_eq!(point.to_ewkt(), "SRID=4979;POINT(12.5 41.9 21)");
+
+        for invalid in [
+            "POINT(1 2 3)",
+            "POINT Z (1 2)",
+            "POINT EMPTY",
+            "LINESTRING(1 2, 3 4)",
+            "SRID=4979;POINT(1 2)",
+            "SRID=x;POINT(1 2)",
+            "POINT(1 two)",
+        ] {
+            assert!(
+                matches!(Point2D::from_wkt(invalid), Err(Error::InvalidPoint(_))),
+                "{}",
+                invalid
+            );
+        }
+        assert!(Point3D::from_wkt("SRID=4326;POINT(1 2 3)").is_err());
+    }
 }
@@ -0,0 +1,44 @@
+// The primitives of the async runtime, tokio or smol depending on the features
+//
+// Only sockets, timers and tasks need a runtime, the locks and buffered streams of tokio used
+// throughout the driver work on any executor (and smol's reactor also drives async-std).
+
+#[cfg(not(any(feature = "runtime-tokio", feature = "runtime-smol")))]
+compile_error!("either the `runtime-tokio` or the `runtime-smol` feature has to be enabled");
+
+/// The largest datagram read by [`exchange_udp`]
+const MAX_DATAGRAM_SIZE: usize = 4096;
+
+#[cfg(all(feature = "runtime-smol", not(feature = "runtime-tokio")))]
+mod smol_runtime;
+#[cfg(feature = "runtime-tokio")]
+mod tokio_runtime;
+
+#[cfg(all(feature = "runtime-smol", not(feature = "runtime-tokio")))]
+pub(crate) use smol_runtime::*;
+#[cfg(feature = "runtime-tokio")]
+pub(crate) use tokio_runtime::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_time_out_futures_that_take_too_long() {
+        let slow = sleep(Duration::from_secs(60));
+        let fast = async { 1 };
+
+        assert_eq!(timeout(Duration::from_millis(10), slow).await, None);
+        assert_eq!(timeout(Duration::from_secs(60), fast).await, Some(1));
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    #[test]
+    fn should_only_spawn_within_a_runtime() {
+        assert!(!try_spawn(async {}));
+
+        let runtime = Runtime::new().unwrap();
+        assert!(runtime.block_on(async { try_spawn(async {}) }));
+    }
+}
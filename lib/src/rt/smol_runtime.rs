@@ -0,0 +1,132 @@
+use super::MAX_DATAGRAM_SIZE;
+use futures::io::{AsyncRead as FuturesRead, AsyncWrite as FuturesWrite};
+use socket2::SockRef;
+use std::future::Future;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::ops::Deref;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+pub(crate) type TcpStream = Compat<smol::net::TcpStream>;
+#[cfg(unix)]
+pub(crate) type UnixStream = Compat<smol::net::unix::UnixStream>;
+
+pub(crate) async fn connect_tcp(address: &str) -> io::Result<TcpStream> {
+    smol::net::TcpStream::connect(address).await.map(Compat)
+}
+
+#[cfg(unix)]
+pub(crate) async fn connect_unix(path: &str) -> io::Result<UnixStream> {
+    smol::net::unix::UnixStream::connect(path).await.map(Compat)
+}
+
+/// The socket of `stream`, to set its options
+pub(crate) fn sock_ref(stream: &TcpStream) -> SockRef<'_> {
+    SockRef::from(&stream.0)
+}
+
+/// Runs `future` in the background, the task is detached
+pub(crate) fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+    smol::spawn(future).detach();
+}
+
+/// Runs `future` in the background like [`spawn`], smol's executor is always available
+pub(crate) fn try_spawn(future: impl Future<Output = ()> + Send + 'static) -> bool {
+    spawn(future);
+    true
+}
+
+/// Sends `packet` in a datagram to `server` and returns the datagram it answers with
+pub(crate) async fn exchange_udp(server: SocketAddr, packet: &[u8]) -> io::Result<Vec<u8>> {
+    let local: SocketAddr = if server.is_ipv6() {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = smol::net::UdpSocket::bind(local).await?;
+    socket.connect(server).await?;
+    socket.send(packet).await?;
+    let mut response = vec![0; MAX_DATAGRAM_SIZE];
+    let size = socket.recv(&mut response).await?;
+    response.truncate(size);
+    Ok(response)
+}
+
+pub(crate) async fn sleep(duration: Duration) {
+    smol::Timer::after(duration).await;
+}
+
+/// The output of `future`, or `None` when it didn't complete within `duration`
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    let completed = async { Some(future.await) };
+    let elapsed = async {
+        sleep(duration).await;
+        None
+    };
+    smol::future::or(completed, elapsed).await
+}
+
+/// The blocking api runs on the global executor of smol, there is nothing to own
+pub(crate) struct Runtime;
+
+impl Runtime {
+    pub(crate) fn new() -> io::Result<Runtime> {
+        Ok(Runtime)
+    }
+
+    pub(crate) fn block_on<F: Future>(&self, future: F) -> F::Output {
+        smol::block_on(future)
+    }
+}
+
+/// Implements the io traits of tokio the connections are written against for the sockets of
+/// smol, which implement the ones of the futures crate
+#[derive(Debug)]
+pub(crate) struct Compat<T>(T);
+
+impl<T> Deref for Compat<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: FuturesRead + Unpin> AsyncRead for Compat<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let unfilled = buf.initialize_unfilled();
+        match Pin::new(&mut self.0).poll_read(cx, unfilled) {
+            Poll::Ready(Ok(read)) => {
+                buf.advance(read);
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T: FuturesWrite + Unpin> AsyncWrite for Compat<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_close(cx)
+    }
+}
@@ -0,0 +1,82 @@
+use super::MAX_DATAGRAM_SIZE;
+use socket2::SockRef;
+use std::future::Future;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+pub(crate) type TcpStream = tokio::net::TcpStream;
+#[cfg(unix)]
+pub(crate) type UnixStream = tokio::net::UnixStream;
+
+pub(crate) async fn connect_tcp(address: &str) -> io::Result<TcpStream> {
+    TcpStream::connect(address).await
+}
+
+#[cfg(unix)]
+pub(crate) async fn connect_unix(path: &str) -> io::Result<UnixStream> {
+    UnixStream::connect(path).await
+}
+
+/// The socket of `stream`, to set its options
+pub(crate) fn sock_ref(stream: &TcpStream) -> SockRef<'_> {
+    SockRef::from(stream)
+}
+
+/// Runs `future` in the background, the task is detached
+pub(crate) fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+    tokio::spawn(future);
+}
+
+/// Runs `future` in the background like [`spawn`] when called within a runtime, false
+/// otherwise
+pub(crate) fn try_spawn(future: impl Future<Output = ()> + Send + 'static) -> bool {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => {
+            handle.spawn(future);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Sends `packet` in a datagram to `server` and returns the datagram it answers with
+pub(crate) async fn exchange_udp(server: SocketAddr, packet: &[u8]) -> io::Result<Vec<u8>> {
+    let local: SocketAddr = if server.is_ipv6() {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = tokio::net::UdpSocket::bind(local).await?;
+    socket.connect(server).await?;
+    socket.send(packet).await?;
+    let mut response = vec![0; MAX_DATAGRAM_SIZE];
+    let size = socket.recv(&mut response).await?;
+    response.truncate(size);
+    Ok(response)
+}
+
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+/// The output of `future`, or `None` when it didn't complete within `duration`
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    tokio::time::timeout(duration, future).await.ok()
+}
+
+/// A runtime owned by the blocking api
+pub(crate) struct Runtime(tokio::runtime::Runtime);
+
+impl Runtime {
+    pub(crate) fn new() -> io::Result<Runtime> {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map(Runtime)
+    }
+
+    pub(crate) fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.0.block_on(future)
+    }
+}
@@ -0,0 +1,185 @@
+use crate::errors::{Error, Result};
+use crate::graph::{query, Graph};
+use serde::{Deserialize, Deserializer};
+
+/// The indexes and constraints of a database, see [`Graph::schema`]
+///
+/// Applications can check the indexes they rely on at startup:
+///
+/// ```no_run
+/// use neo4rs::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///    let graph = Graph::new("127.0.0.1:7687", "neo4j", "neo").await.unwrap();
+///    graph.schema().require_index("Person", &["name"]).await.unwrap();
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Schema<'a> {
+    graph: &'a Graph,
+}
+
+/// An index, as listed by `SHOW INDEXES`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Index {
+    pub name: String,
+    /// Like `RANGE`, `TEXT`, `POINT`, `FULLTEXT`, `VECTOR` or `LOOKUP`
+    #[serde(rename = "type")]
+    pub index_type: String,
+    /// `NODE` or `RELATIONSHIP`
+    #[serde(rename = "entityType")]
+    pub entity_type: String,
+    /// The labels or relationship types indexed, empty for lookup indexes
+    #[serde(rename = "labelsOrTypes", default, deserialize_with = "nullable")]
+    pub labels: Vec<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub properties: Vec<String>,
+    /// `ONLINE` once the index can be used, `POPULATING` while it is being built or `FAILED`
+    pub state: String,
+    /// The constraint backed by the index
+    #[serde(rename = "owningConstraint", default)]
+    pub owning_constraint: Option<String>,
+}
+
+impl Index {
+    pub fn is_online(&self) -> bool {
+        self.state == "ONLINE"
+    }
+}
+
+/// A constraint, as listed by `SHOW CONSTRAINTS`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Constraint {
+    pub name: String,
+    /// Like `UNIQUENESS`, `NODE_KEY` or `NODE_PROPERTY_EXISTENCE`
+    #[serde(rename = "type")]
+    pub constraint_type: String,
+    /// `NODE` or `RELATIONSHIP`
+    #[serde(rename = "entityType")]
+    pub entity_type: String,
+    #[serde(rename = "labelsOrTypes", default, deserialize_with = "nullable")]
+    pub labels: Vec<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub properties: Vec<String>,
+    /// The index backing the constraint
+    #[serde(rename = "ownedIndex", default)]
+    pub owned_index: Option<String>,
+}
+
+impl<'a> Schema<'a> {
+    pub(crate) fn new(graph: &'a Graph) -> Self {
+        Schema { graph }
+    }
+
+    /// Lists every index of the database
+    pub async fn indexes(&self) -> Result<Vec<Index>> {
+        let rows = self
+            .graph
+            .execute_read(query("SHOW INDEXES"))
+            .await?
+            .fetch_all()
+            .await?;
+        rows.iter().map(|row| row.to()).collect()
+    }
+
+    /// Lists every constraint of the database
+    pub async fn constraints(&self) -> Result<Vec<Constraint>> {
+        let rows = self
+            .graph
+            .execute_read(query("SHOW CONSTRAINTS"))
+            .await?
+            .fetch_all()
+            .await?;
+        rows.iter().map(|row| row.to()).collect()
+    }
+
+    /// Finds the index on exactly these properties of a label or relationship type, in order
+    pub async fn index_on(&self, label: &str, properties: &[&str]) -> Result<Option<Index>> {
+        Ok(self
+            .indexes()
+            .await?
+            .into_iter()
+            .find(|index| covers(index, label, properties)))
+    }
+
+    /// Fails with a [`Error::SchemaError`] unless an online index on exactly these properties of
+    /// a label or relationship type exists
+    pub async fn require_index(&self, label: &str, properties: &[&str]) -> Result<Index> {
+        match self.index_on(label, properties).await? {
+            Some(index) if index.is_online() => Ok(index),
+            Some(index) => Err(Error::SchemaError(format!(
+                "index {} on {}({}) is {}",
+                index.name,
+                label,
+                properties.join(", "),
+                index.state
+            ))),
+            None => Err(Error::SchemaError(format!(
+                "no index on {}({})",
+                label,
+                properties.join(", ")
+            ))),
+        }
+    }
+}
+
+/// Lookup indexes list null labels and properties
+fn nullable<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<String>, D::Error> {
+    Option::<Vec<String>>::deserialize(deserializer).map(Option::unwrap_or_default)
+}
+
+fn covers(index: &Index, label: &str, properties: &[&str]) -> bool {
+    index.labels == [label] && index.properties == properties
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::row::Row;
+    use crate::types::{BoltList, BoltNull, BoltType};
+
+    #[test]
+    fn should_read_indexes_from_rows() {
+        let fields = BoltList::from(vec![
+            BoltType::from("name"),
+            BoltType::from("type"),
+            BoltType::from("entityType"),
+            BoltType::from("labelsOrTypes"),
+            BoltType::from("properties"),
+            BoltType::from("state"),
+            BoltType::from("owningConstraint"),
+        ]);
+        let data = BoltList::from(vec![
+            BoltType::from("person_name"),
+            BoltType::from("RANGE"),
+            BoltType::from("NODE"),
+            BoltType::List(BoltList::from(vec![BoltType::from("Person")])),
+            BoltType::List(BoltList::from(vec![BoltType::from("name")])),
+            BoltType::from("ONLINE"),
+            BoltType::Null(BoltNull),
+        ]);
+
+        let index: Index = Row::new(fields.clone(), data).to().unwrap();
+
+        assert_eq!(index.name, "person_name");
+        assert_eq!(index.owning_constraint, None);
+        assert!(index.is_online());
+        assert!(covers(&index, "Person", &["name"]));
+        assert!(!covers(&index, "Person", &["name", "age"]));
+
+        let lookup = BoltList::from(vec![
+            BoltType::from("node_lookup"),
+            BoltType::from("LOOKUP"),
+            BoltType::from("NODE"),
+            BoltType::Null(BoltNull),
+            BoltType::Null(BoltNull),
+            BoltType::from("ONLINE"),
+            BoltType::Null(BoltNull),
+        ]);
+        let index: Index = Row::new(fields, lookup).to().unwrap();
+        assert!(index.labels.is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+// A bolt server playing a script, for the tests of the exchanges of queries and transactions
+
+use crate::{ConfigBuilder, Error, Graph, Query, QueryInterceptor, ResultSummary};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+const SUCCESS: &[u8] = &[0xB1, 0x70, 0xA0];
+const FAILURE: &[u8] = &[0xB1, 0x7F, 0xA0];
+const IGNORED: &[u8] = &[0xB0, 0x7E];
+
+/// A 4.4 server answering every message with a SUCCESS, except the RUN of a query containing
+/// `FAIL`, after which it ignores the messages until it is reset. The graph connects to it with
+/// `config`, the signatures of the messages the server receives are sent to the returned
+/// channel.
+pub(crate) async fn serve(config: ConfigBuilder) -> (Graph, UnboundedReceiver<u8>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let (received, receiver) = unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let received = received.clone();
+            tokio::spawn(async move {
+                let mut handshake = [0; 20];
+                stream.read_exact(&mut handshake).await?;
+                stream.write_all(&[0, 0, 4, 4]).await?;
+                let mut failed = false;
+                loop {
+                    let mut message = Vec::new();
+                    loop {
+                        let size = stream.read_u16().await? as usize;
+                        if size == 0 {
+                            break;
+                        }
+                        let start = message.len();
+                        message.resize(start + size, 0);
+                        stream.read_exact(&mut message[start..]).await?;
+                    }
+                    let _ = received.send(message[1]);
+                    let response = match message[1] {
+                        // GOODBYE
+                        0x02 => return Ok::<_, std::io::Error>(()),
+                        // RESET
+                        0x0F => {
+                            failed = false;
+                            SUCCESS
+                        }
+                        _ if failed => IGNORED,
+                        0x10 if message.windows(4).any(|w| w == b"FAIL") => {
+                            failed = true;
+                            FAILURE
+                        }
+                        _ => SUCCESS,
+                    };
+                    stream.write_u16(response.len() as u16).await?;
+                    stream.write_all(response).await?;
+                    stream.write_all(&[0, 0]).await?;
+                }
+            });
+        }
+    });
+    let config = config
+        .uri(&address)
+        .user("some_user")
+        .password("some_password")
+        .build()
+        .unwrap();
+    (Graph::connect(config).await.unwrap(), receiver)
+}
+
+/// The signatures of the next `count` messages the server receives
+pub(crate) async fn received(receiver: &mut UnboundedReceiver<u8>, count: usize) -> Vec<u8> {
+    let mut signatures = Vec::new();
+    for _ in 0..count {
+        let next = tokio::time::timeout(Duration::from_secs(5), receiver.recv());
+        signatures.push(next.await.unwrap().unwrap());
+    }
+    signatures
+}
+
+/// Records the queries starting and ending, with whether they succeeded
+#[derive(Clone, Default)]
+pub(crate) struct Recorder(pub(crate) Arc<Mutex<Vec<String>>>);
+
+impl QueryInterceptor for Recorder {
+    fn on_query_start(&self, query: Query) -> crate::Result<Query> {
+        let started = format!("start {}", query.cypher());
+        self.0.lock().unwrap().push(started);
+        Ok(query)
+    }
+
+    fn on_query_end(&self, query: &str, _: &str, outcome: Result<&ResultSummary, &Error>) {
+        let outcome = if outcome.is_ok() { "ok" } else { "failed" };
+        let ended = format!("end {}: {}", query, outcome);
+        self.0.lock().unwrap().push(ended);
+    }
+}
@@ -0,0 +1,588 @@
+use crate::errors::Error;
+use crate::types::*;
+#[cfg(feature = "serde")]
+use serde::ser::SerializeStruct;
+use serde::ser::{self, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Serializes any type implementing [`serde::Serialize`] into a value that can be sent to the
+/// server
+pub(crate) fn to_bolt<T: Serialize + ?Sized>(value: &T) -> crate::errors::Result<BoltType> {
+    value
+        .serialize(BoltTypeSerializer)
+        .map_err(|e| Error::SerializationError(e.0))
+}
+
+/// The error raised by serde while mapping a value, turned into [`Error::SerializationError`]
+/// once serialization failed
+#[derive(Debug)]
+pub(crate) struct SerError(String);
+
+impl fmt::Display for SerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SerError {}
+
+impl ser::Error for SerError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerError(msg.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, SerError>;
+
+struct BoltTypeSerializer;
+
+fn variant(name: &'static str, value: BoltType) -> BoltType {
+    let mut map = BoltMap::with_capacity(1);
+    map.put(name.into(), value);
+    BoltType::Map(map)
+}
+
+impl ser::Serializer for BoltTypeSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = ListSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<BoltType> {
+        Ok(BoltType::Boolean(BoltBoolean::new(v)))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<BoltType> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<BoltType> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<BoltType> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<BoltType> {
+        Ok(BoltType::Integer(BoltInteger::new(v)))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<BoltType> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<BoltType> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<BoltType> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<BoltType> {
+        let v =
+            i64::try_from(v).map_err(|_| SerError(format!("{} is too large for an integer", v)))?;
+        self.serialize_i64(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<BoltType> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<BoltType> {
+        Ok(BoltType::Float(BoltFloat::new(v)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<BoltType> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<BoltType> {
+        Ok(BoltType::String(v.into()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<BoltType> {
+        Ok(BoltType::Bytes(BoltBytes::new(v.to_vec().into())))
+    }
+
+    fn serialize_none(self) -> Result<BoltType> {
+        Ok(BoltType::Null(BoltNull))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<BoltType> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<BoltType> {
+        Ok(BoltType::Null(BoltNull))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<BoltType> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<BoltType> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<BoltType> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        name: &'static str,
+        value: &T,
+    ) -> Result<BoltType> {
+        Ok(variant(name, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ListSerializer> {
+        Ok(ListSerializer {
+            list: BoltList::with_capacity(len.unwrap_or(0)),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ListSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ListSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        name: &'static str,
+        len: usize,
+    ) -> Result<ListSerializer> {
+        Ok(ListSerializer {
+            list: BoltList::with_capacity(len),
+            variant: Some(name),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer> {
+        Ok(MapSerializer {
+            map: BoltMap::with_capacity(len.unwrap_or(0)),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapSerializer> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        name: &'static str,
+        len: usize,
+    ) -> Result<MapSerializer> {
+        Ok(MapSerializer {
+            map: BoltMap::with_capacity(len),
+            key: None,
+            variant: Some(name),
+        })
+    }
+}
+
+/// Collects sequences and tuples, enum variants are wrapped in a map keyed by the variant name
+struct ListSerializer {
+    list: BoltList,
+    variant: Option<&'static str>,
+}
+
+impl ListSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.list.push(value.serialize(BoltTypeSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<BoltType> {
+        let list = BoltType::List(self.list);
+        Ok(match self.variant {
+            Some(name) => variant(name, list),
+            None => list,
+        })
+    }
+}
+
+impl ser::SerializeSeq for ListSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<BoltType> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for ListSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<BoltType> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for ListSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<BoltType> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for ListSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<BoltType> {
+        self.finish()
+    }
+}
+
+/// Collects maps and structs, map keys have to serialize into strings
+struct MapSerializer {
+    map: BoltMap,
+    key: Option<BoltString>,
+    variant: Option<&'static str>,
+}
+
+impl MapSerializer {
+    fn put<T: Serialize + ?Sized>(&mut self, key: BoltString, value: &T) -> Result<()> {
+        self.map.put(key, value.serialize(BoltTypeSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<BoltType> {
+        let map = BoltType::Map(self.map);
+        Ok(match self.variant {
+            Some(name) => variant(name, map),
+            None => map,
+        })
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        match key.serialize(BoltTypeSerializer)? {
+            BoltType::String(key) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            key => Err(SerError(format!(
+                "map keys must be strings, found {:?}",
+                key
+            ))),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| SerError("map value without a key".into()))?;
+        self.put(key, value)
+    }
+
+    fn end(self) -> Result<BoltType> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.put(key.into(), value)
+    }
+
+    fn end(self) -> Result<BoltType> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for MapSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.put(key.into(), value)
+    }
+
+    fn end(self) -> Result<BoltType> {
+        self.finish()
+    }
+}
+
+/// Values serialize to JSON friendly structures, the same the `json` feature converts them to:
+/// nodes to `{"id", "labels", "properties"}`, relationships to
+/// `{"id", "start_node_id", "end_node_id", "type", "properties"}`, paths to `{"nodes", "rels"}`
+/// and points to `{"srid", "x", "y"}`. Durations and, with the `chrono` feature, dates and times
+/// serialize to ISO-8601 strings.
+#[cfg(feature = "serde")]
+impl Serialize for BoltType {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            BoltType::Null(_) => serializer.serialize_none(),
+            BoltType::Boolean(b) => serializer.serialize_bool(b.value),
+            BoltType::Integer(i) => serializer.serialize_i64(i.value),
+            BoltType::Float(f) => serializer.serialize_f64(f.value),
+            BoltType::String(s) => serializer.serialize_str(s.as_str()),
+            BoltType::Bytes(b) => serializer.serialize_bytes(&b.value),
+            BoltType::List(l) => l.serialize(serializer),
+            BoltType::Map(m) => m.serialize(serializer),
+            BoltType::Node(n) => n.serialize(serializer),
+            BoltType::Relation(r) => r.serialize(serializer),
+            BoltType::UnboundedRelation(r) => r.serialize(serializer),
+            BoltType::Path(p) => p.serialize(serializer),
+            BoltType::Point2D(p) => {
+                let mut point = serializer.serialize_struct("Point2D", 3)?;
+                point.serialize_field("srid", &p.sr_id.value)?;
+                point.serialize_field("x", &p.x.value)?;
+                point.serialize_field("y", &p.y.value)?;
+                point.end()
+            }
+            BoltType::Point3D(p) => {
+                let mut point = serializer.serialize_struct("Point3D", 4)?;
+                point.serialize_field("srid", &p.sr_id.value)?;
+                point.serialize_field("x", &p.x.value)?;
+                point.serialize_field("y", &p.y.value)?;
+                point.serialize_field("z", &p.z.value)?;
+                point.end()
+            }
+            BoltType::Duration(d) => serializer.collect_str(&crate::IsoDuration::from(d.clone())),
+            other => match iso_8601(other.clone()) {
+                Ok(formatted) => serializer.serialize_str(&formatted),
+                Err(_) => Err(ser::Error::custom(
+                    "temporal values can only be serialized with the chrono feature",
+                )),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for BoltList {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for BoltMap {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for BoltNode {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut node = serializer.serialize_struct("Node", 3)?;
+        node.serialize_field("id", &self.id.value)?;
+        node.serialize_field("labels", &self.labels)?;
+        node.serialize_field("properties", &self.properties)?;
+        node.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for BoltRelation {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut relation = serializer.serialize_struct("Relation", 5)?;
+        relation.serialize_field("id", &self.id.value)?;
+        relation.serialize_field("start_node_id", &self.start_node_id.value)?;
+        relation.serialize_field("end_node_id", &self.end_node_id.value)?;
+        relation.serialize_field("type", self.typ.as_str())?;
+        relation.serialize_field("properties", &self.properties)?;
+        relation.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for BoltUnboundedRelation {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut relation = serializer.serialize_struct("UnboundedRelation", 3)?;
+        relation.serialize_field("id", &self.id.value)?;
+        relation.serialize_field("type", self.typ.as_str())?;
+        relation.serialize_field("properties", &self.properties)?;
+        relation.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for BoltPath {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut path = serializer.serialize_struct("Path", 2)?;
+        path.serialize_field("nodes", &self.nodes())?;
+        path.serialize_field("rels", &self.rels())?;
+        path.end()
+    }
+}
+
+/// Formats dates, times and date times in ISO-8601
+#[cfg(all(feature = "chrono", any(feature = "serde", feature = "json")))]
+pub(crate) fn iso_8601(input: BoltType) -> crate::errors::Result<String> {
+    const LOCAL_DATE_TIME: &str = "%Y-%m-%dT%H:%M:%S%.f";
+    let formatted = match input {
+        BoltType::Date(_) => chrono::NaiveDate::try_from(input)?.to_string(),
+        BoltType::LocalTime(_) => {
+            <(chrono::NaiveTime, Option<chrono::FixedOffset>)>::try_from(input)?
+                .0
+                .to_string()
+        }
+        BoltType::Time(t) => {
+            let (time, offset) = <(chrono::NaiveTime, chrono::FixedOffset)>::try_from(t)?;
+            format!("{}{}", time, offset)
+        }
+        BoltType::LocalDateTime(_) => chrono::NaiveDateTime::try_from(input)?
+            .format(LOCAL_DATE_TIME)
+            .to_string(),
+        BoltType::DateTime(_) => {
+            chrono::DateTime::<chrono::FixedOffset>::try_from(input)?.to_rfc3339()
+        }
+        BoltType::DateTimeZoneId(_) => {
+            let (date_time, zone) = <(chrono::NaiveDateTime, String)>::try_from(input)?;
+            format!("{}[{}]", date_time.format(LOCAL_DATE_TIME), zone)
+        }
+        _ => return Err(Error::ConverstionError),
+    };
+    Ok(formatted)
+}
+
+#[cfg(all(not(feature = "chrono"), any(feature = "serde", feature = "json")))]
+pub(crate) fn iso_8601(_: BoltType) -> crate::errors::Result<String> {
+    Err(Error::ConverstionError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use std::collections::HashMap;
+    use std::convert::TryInto;
+
+    #[derive(Serialize)]
+    enum Shape {
+        Circle { radius: f64 },
+        Square(i64),
+        Empty,
+    }
+
+    #[derive(Serialize)]
+    struct Person {
+        name: String,
+        age: u8,
+        nickname: Option<String>,
+        tags: Vec<&'static str>,
+        shapes: Vec<Shape>,
+    }
+
+    #[test]
+    fn should_serialize_struct_into_map() {
+        let person = Person {
+            name: "Mark".into(),
+            age: 42,
+            nickname: None,
+            tags: vec!["a", "b"],
+            shapes: vec![Shape::Empty],
+        };
+
+        let map: BoltMap = to_bolt(&person).unwrap().try_into().unwrap();
+
+        assert_eq!(map.get::<String>("name").unwrap(), "Mark");
+        assert_eq!(map.get::<i64>("age").unwrap(), 42);
+        assert_eq!(
+            map.value.get(&"nickname".into()),
+            Some(&BoltType::Null(BoltNull))
+        );
+        assert_eq!(
+            map.get::<BoltList>("tags").unwrap(),
+            vec!["a".into(), "b".into()].into()
+        );
+        assert_eq!(
+            map.get::<BoltList>("shapes").unwrap(),
+            vec!["Empty".into()].into()
+        );
+    }
+
+    #[test]
+    fn should_serialize_enum_variants_as_maps() {
+        let circle: BoltMap = to_bolt(&Shape::Circle { radius: 1.5 })
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let square: BoltMap = to_bolt(&Shape::Square(2)).unwrap().try_into().unwrap();
+
+        let radius: BoltMap = circle.get("Circle").unwrap();
+        assert_eq!(radius.get::<f64>("radius").unwrap(), 1.5);
+        assert_eq!(square.get::<i64>("Square").unwrap(), 2);
+    }
+
+    #[test]
+    fn should_reject_non_string_keys_and_large_integers() {
+        let mut map = HashMap::new();
+        map.insert(1, "one");
+
+        assert!(matches!(to_bolt(&map), Err(Error::SerializationError(_))));
+        assert!(matches!(
+            to_bolt(&u64::MAX),
+            Err(Error::SerializationError(_))
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::bookmark::{Bookmark, BookmarkManager, ManagedBookmarks};
+use crate::errors::Result;
+use crate::graph::Graph;
+use crate::messages::TelemetryApi;
+use crate::query::Query;
+use crate::routing::AccessMode;
+use crate::stream::RowStream;
+use crate::txn::{TxOptions, Txn};
+use std::future::Future;
+use std::sync::Arc;
+
+/// Queries and transactions sharing the same database, access mode, bookmarks and impersonated
+/// user, created with [`Graph::session`]
+///
+/// The settings of a query or of the [`TxOptions`] of a transaction take precedence over the
+/// defaults of the session. Sessions are cheap to create and to clone, they share the connection
+/// pool of their graph, so a multi-tenant application can keep a session for each tenant:
+///
+/// ```no_run
+/// use neo4rs::*;
+///
+/// # async fn tenant(graph: Graph) -> Result<()> {
+/// let acme = graph.session().db("acme").impersonate("acme_app");
+/// acme.run(query("CREATE (:Order {id: 1})")).await?;
+/// let reports = acme.clone().read();
+/// let mut orders = reports.execute(query("MATCH (o:Order) RETURN o.id")).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Session {
+    graph: Graph,
+    pub(crate) db: Option<String>,
+    pub(crate) mode: AccessMode,
+    pub(crate) bookmarks: Vec<Bookmark>,
+    pub(crate) impersonated_user: Option<String>,
+}
+
+impl Session {
+    pub(crate) fn new(graph: Graph) -> Self {
+        Session {
+            graph,
+            db: None,
+            mode: AccessMode::Write,
+            bookmarks: Vec::new(),
+            impersonated_user: None,
+        }
+    }
+
+    /// Runs the queries and transactions on `db` instead of the database of the config
+    pub fn db(mut self, db: &str) -> Self {
+        self.db = Some(db.to_owned());
+        self
+    }
+
+    /// Marks the queries and explicit transactions as read only, when connected to a cluster
+    /// they then run on one of its readers
+    pub fn read(mut self) -> Self {
+        self.mode = AccessMode::Read;
+        self
+    }
+
+    /// The queries and transactions observe the changes of the transactions these bookmarks
+    /// were returned for, see [`Txn::commit`]
+    pub fn bookmarks(mut self, bookmarks: &[Bookmark]) -> Self {
+        self.bookmarks = bookmarks.to_vec();
+        self
+    }
+
+    /// Chains the transactions and auto-commit queries of the session with `manager` instead
+    /// of the [`ConfigBuilder::bookmark_manager`] of the config. Sessions sharing a manager, like
+    /// an `Arc<InMemoryBookmarkManager>`, observe the changes of each other
+    ///
+    /// [`ConfigBuilder::bookmark_manager`]: crate::ConfigBuilder::bookmark_manager
+    pub fn bookmark_manager(mut self, manager: impl BookmarkManager + 'static) -> Self {
+        self.graph = self
+            .graph
+            .with_bookmark_manager(ManagedBookmarks(Arc::new(manager)));
+        self
+    }
+
+    /// Runs the queries and transactions as `user` instead of the authenticated user, see
+    /// [`TxOptions::impersonate`]
+    pub fn impersonate(mut self, user: &str) -> Self {
+        self.impersonated_user = Some(user.to_owned());
+        self
+    }
+
+    /// Runs an auto-commit query with the defaults of the session, see [`Graph::run`]
+    pub async fn run(&self, q: Query) -> Result<()> {
+        self.graph.run(q.in_session(self)).await
+    }
+
+    /// Executes an auto-commit query with the defaults of the session, see [`Graph::execute`]
+    pub async fn execute(&self, q: Query) -> Result<RowStream> {
+        self.graph.execute(q.in_session(self)).await
+    }
+
+    /// Starts a transaction with the defaults of the session
+    pub async fn start_txn(&self) -> Result<Txn> {
+        self.start_txn_with(TxOptions::default()).await
+    }
+
+    /// Starts a transaction with `options`, falling back to the defaults of the session for the
+    /// settings they don't have
+    pub async fn start_txn_with(&self, options: TxOptions) -> Result<Txn> {
+        let options = self.txn_options(options);
+        let api = TelemetryApi::ExplicitTransaction;
+        self.graph
+            .begin(self.db.as_deref(), &self.bookmarks, &options, api)
+            .await
+    }
+
+    /// Runs `work` in a read transaction with the defaults of the session, retrying it on
+    /// transient errors, see [`Graph::read_tx`]
+    pub async fn read_tx<F, Fut, T>(&self, work: F) -> Result<T>
+    where
+        F: FnMut(Txn) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let options = self.txn_options(TxOptions::new().read());
+        self.graph
+            .managed_tx(self.db.as_deref(), &self.bookmarks, &options, work)
+            .await
+    }
+
+    /// Runs `work` in a write transaction with the defaults of the session, even in a read
+    /// session, retrying it on transient errors, see [`Graph::write_tx`]
+    pub async fn write_tx<F, Fut, T>(&self, work: F) -> Result<T>
+    where
+        F: FnMut(Txn) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut options = self.txn_options(TxOptions::new());
+        options.mode = AccessMode::Write;
+        self.graph
+            .managed_tx(self.db.as_deref(), &self.bookmarks, &options, work)
+            .await
+    }
+
+    /// Falls back to the access mode and the impersonated user of the session
+    pub(crate) fn txn_options(&self, mut options: TxOptions) -> TxOptions {
+        if self.mode == AccessMode::Read {
+            options.mode = AccessMode::Read;
+        }
+        if options.impersonated_user.is_none() {
+            options.impersonated_user = self.impersonated_user.clone();
+        }
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config;
+
+    async fn session() -> Session {
+        let config = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .db("neo4j")
+            .build()
+            .unwrap();
+        Graph::connect(config).await.unwrap().session()
+    }
+
+    #[tokio::test]
+    async fn should_apply_the_defaults_of_the_session() {
+        let bookmarks = [Bookmark::new("FB:kcwQ")];
+        let session = session()
+            .await
+            .db("acme")
+            .read()
+            .bookmarks(&bookmarks)
+            .impersonate("acme_app");
+
+        let q = crate::query("RETURN 1").in_session(&session);
+
+        assert_eq!(q.access_mode(), AccessMode::Read);
+        assert_eq!(
+            format!("{:?}", q),
+            format!(
+                "{:?}",
+                crate::query("RETURN 1")
+                    .db("acme")
+                    .read()
+                    .impersonate("acme_app")
+            )
+        );
+        let options = session.txn_options(TxOptions::new());
+        assert_eq!(options, TxOptions::new().read().impersonate("acme_app"));
+    }
+
+    #[tokio::test]
+    async fn should_prefer_the_settings_of_queries_and_transactions() {
+        let session = session().await.db("acme").impersonate("acme_app");
+
+        let q = crate::query("RETURN 1")
+            .db("other")
+            .impersonate("admin")
+            .in_session(&session);
+        let options = session.txn_options(TxOptions::new().impersonate("admin"));
+
+        assert_eq!(q.access_mode(), AccessMode::Write);
+        assert_eq!(
+            format!("{:?}", q),
+            format!(
+                "{:?}",
+                crate::query("RETURN 1").db("other").impersonate("admin")
+            )
+        );
+        assert_eq!(options.impersonated_user.as_deref(), Some("admin"));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::summary::ResultSummary;
+use log::warn;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A query that took longer than the threshold of the config to run and consume, see
+/// [`ConfigBuilder::slow_query_threshold`](crate::ConfigBuilder::slow_query_threshold)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlowQuery {
+    query: String,
+    database: String,
+    duration: Duration,
+    summary: ResultSummary,
+}
+
+impl SlowQuery {
+    /// The cypher of the query
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// The database the query ran on
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    /// The time from running the query until all of its rows were consumed
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The summary of the query, with the changes it made in its counters
+    pub fn summary(&self) -> &ResultSummary {
+        &self.summary
+    }
+}
+
+pub(crate) type SlowQueryCallback = Arc<dyn Fn(&SlowQuery) + Send + Sync>;
+
+/// Reports the queries slower than the threshold to the callback of the config, or logs them
+/// as warnings without a callback
+#[derive(Clone)]
+pub(crate) struct SlowQueryLog {
+    pub(crate) threshold: Duration,
+    pub(crate) callback: Option<SlowQueryCallback>,
+}
+
+impl SlowQueryLog {
+    /// Starts timing a query, which is reported once [`SlowQueryWatch::finish`]ed too late
+    pub(crate) fn watch(&self, query: &str, database: &str) -> SlowQueryWatch {
+        SlowQueryWatch {
+            log: self.clone(),
+            query: query.to_owned(),
+            database: database.to_owned(),
+            start: Instant::now(),
+        }
+    }
+}
+
+impl fmt::Debug for SlowQueryLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlowQueryLog")
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
+
+/// A query that is being timed, from running it until its rows were consumed
+#[derive(Debug)]
+pub(crate) struct SlowQueryWatch {
+    log: SlowQueryLog,
+    query: String,
+    database: String,
+    start: Instant,
+}
+
+impl SlowQueryWatch {
+    pub(crate) fn finish(self, summary: &ResultSummary) {
+        let duration = self.start.elapsed();
+        if duration < self.log.threshold {
+            return;
+        }
+        let slow = SlowQuery {
+            query: self.query,
+            database: self.database,
+            duration,
+            summary: summary.clone(),
+        };
+        match &self.log.callback {
+            Some(callback) => callback(&slow),
+            None => warn!(
+                "slow query on {} took {:?}: {} {:?}",
+                slow.database,
+                slow.duration,
+                slow.query,
+                slow.summary.counters()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::Success;
+    use crate::types::BoltMap;
+    use std::sync::Mutex;
+
+    #[test]
+    fn should_report_queries_slower_than_the_threshold() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let slow_queries = reported.clone();
+        let callback: SlowQueryCallback =
+            Arc::new(move |query: &SlowQuery| slow_queries.lock().unwrap().push(query.clone()));
+        let summary = ResultSummary::new(&Success::new(BoltMap::default()));
+        let log = |threshold| SlowQueryLog {
+            threshold,
+            callback: Some(callback.clone()),
+        };
+
+        log(Duration::from_secs(60))
+            .watch("MATCH (n) RETURN n", "neo4j")
+            .finish(&summary);
+        log(Duration::ZERO)
+            .watch("MATCH (n) RETURN n", "neo4j")
+            .finish(&summary);
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].query(), "MATCH (n) RETURN n");
+        assert_eq!(reported[0].database(), "neo4j");
+        assert_eq!(reported[0].summary(), &summary);
+    }
+}
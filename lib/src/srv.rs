@@ -0,0 +1,268 @@
+// Discovers the members of a cluster from the DNS SRV records its environment publishes, see
+// `ConfigBuilder::srv_discovery`. Only the records are needed, so rather than depending on a
+// resolver the query is sent to the first name server of the system over UDP. The name servers
+// are read from `/etc/resolv.conf`, so the discovery is only available on Unix systems.
+
+use crate::errors::{Error, Result};
+use crate::rt;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+const DEFAULT_NAME_SERVER: &str = "127.0.0.1:53";
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+const RECURSION_DESIRED: u16 = 0x0100;
+const TRUNCATED: u16 = 0x0200;
+const NAME_ERROR: u16 = 3;
+/// The highest number of compression pointers followed in a name, beyond which the name is
+/// considered to loop
+const MAX_POINTERS: usize = 16;
+
+/// A target of an SRV record
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SrvRecord {
+    priority: u16,
+    weight: u16,
+    port: u16,
+    target: String,
+}
+
+/// The `host:port` addresses of the targets of the SRV records of `name`, in the order they
+/// should be tried: by priority, then the heaviest first
+pub(crate) async fn lookup(name: &str, timeout: Duration) -> Result<Vec<String>> {
+    let server = name_server()?;
+    let id = RandomState::new().build_hasher().finish() as u16;
+    let query = query(id, name)?;
+    let response = rt::timeout(timeout, rt::exchange_udp(server, &query))
+        .await
+        .ok_or_else(|| Error::RoutingError(format!("timed out looking up {}", name)))??;
+    let mut records = parse(id, &response)?;
+    records.sort_by(|a, b| a.priority.cmp(&b.priority).then(b.weight.cmp(&a.weight)));
+    Ok(records
+        .into_iter()
+        .map(|record| format!("{}:{}", record.target, record.port))
+        .collect())
+}
+
+/// The first name server of `/etc/resolv.conf`, or the local one when it lists none
+fn name_server() -> Result<SocketAddr> {
+    let conf = std::fs::read_to_string(RESOLV_CONF).map_err(|e| {
+        Error::RoutingError(format!(
+            "unable to read the name servers of {}: {}",
+            RESOLV_CONF, e
+        ))
+    })?;
+    Ok(name_server_of(&conf))
+}
+
+fn name_server_of(conf: &str) -> SocketAddr {
+    conf.lines()
+        .filter_map(|line| line.trim().strip_prefix("nameserver"))
+        .find_map(|server| server.trim().parse().ok())
+        .map(|ip| SocketAddr::new(ip, 53))
+        .unwrap_or_else(|| DEFAULT_NAME_SERVER.parse().unwrap())
+}
+
+/// A query for the SRV records of `name`
+fn query(id: u16, name: &str) -> Result<Vec<u8>> {
+    let mut query = Vec::with_capacity(name.len() + 18);
+    for field in [id, RECURSION_DESIRED, 1, 0, 0, 0] {
+        query.extend_from_slice(&field.to_be_bytes());
+    }
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(Error::RoutingError(format!("invalid dns name {}", name)));
+        }
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&TYPE_SRV.to_be_bytes());
+    query.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(query)
+}
+
+/// The SRV records among the answers of the `response` to the query `id`
+fn parse(id: u16, response: &[u8]) -> Result<Vec<SrvRecord>> {
+    let malformed = || Error::RoutingError("malformed dns response".into());
+    let read_u16 = |at: usize| -> Result<u16> {
+        response
+            .get(at..at + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+            .ok_or_else(malformed)
+    };
+    if read_u16(0)? != id {
+        return Err(malformed());
+    }
+    // the records which didn't fit in the datagram are missing, rather than using only some
+    // of the members the lookup fails
+    if read_u16(2)? & TRUNCATED != 0 {
+        return Err(Error::RoutingError(
+            "dns response truncated, too many SRV records for udp".into(),
+        ));
+    }
+    match read_u16(2)? & 0x000F {
+        0 => {}
+        NAME_ERROR => return Err(Error::RoutingError("no such dns name".into())),
+        code => {
+            return Err(Error::RoutingError(format!(
+                "dns lookup failed with code {}",
+                code
+            )))
+        }
+    }
+    let questions = read_u16(4)?;
+    let answers = read_u16(6)?;
+    let mut at = 12;
+    for _ in 0..questions {
+        at = read_name(response, at)?.1 + 4;
+    }
+    let mut records = Vec::new();
+    for _ in 0..answers {
+        at = read_name(response, at)?.1;
+        let kind = read_u16(at)?;
+        let length = read_u16(at + 8)? as usize;
+        let data = at + 10;
+        if kind == TYPE_SRV {
+            let (target, _) = read_name(response, data + 6)?;
+            // a target of "." means the service isn't available at this name
+            if !target.is_empty() {
+                records.push(SrvRecord {
+                    priority: read_u16(data)?,
+                    weight: read_u16(data + 2)?,
+                    port: read_u16(data + 4)?,
+                    target,
+                });
+            }
+        }
+        at = data + length;
+    }
+    Ok(records)
+}
+
+/// Reads the possibly compressed name at `at`, returning it along with the position after it
+fn read_name(message: &[u8], mut at: usize) -> Result<(String, usize)> {
+    let malformed = || Error::RoutingError("malformed dns name".into());
+    let mut labels = Vec::new();
+    let mut end = None;
+    let mut pointers = 0;
+    loop {
+        let length = *message.get(at).ok_or_else(malformed)? as usize;
+        match length {
+            0 => break,
+            length if length & 0xC0 == 0xC0 => {
+                let low = *message.get(at + 1).ok_or_else(malformed)? as usize;
+                end.get_or_insert(at + 2);
+                pointers += 1;
+                if pointers > MAX_POINTERS {
+                    return Err(malformed());
+                }
+                at = ((length & 0x3F) << 8) | low;
+            }
+            length => {
+                let label = message.get(at + 1..at + 1 + length).ok_or_else(malformed)?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                at += 1 + length;
+            }
+        }
+    }
+    Ok((labels.join("."), end.unwrap_or(at + 1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(priority: u16, weight: u16, port: u16, target: &[u8]) -> Vec<u8> {
+        // the name points to the question
+        let mut answer = vec![0xC0, 12];
+        answer.extend_from_slice(&TYPE_SRV.to_be_bytes());
+        answer.extend_from_slice(&CLASS_IN.to_be_bytes());
+        answer.extend_from_slice(&300u32.to_be_bytes());
+        answer.extend_from_slice(&(6 + target.len() as u16).to_be_bytes());
+        for field in [priority, weight, port] {
+            answer.extend_from_slice(&field.to_be_bytes());
+        }
+        answer.extend_from_slice(target);
+        answer
+    }
+
+    #[test]
+    fn should_query_srv_records() {
+        let query = query(0x1234, "_neo4j._tcp.example.com").unwrap();
+
+        assert_eq!(&query[..4], &[0x12, 0x34, 0x01, 0x00]);
+        assert_eq!(&query[12..19], b"\x06_neo4j");
+        assert_eq!(&query[query.len() - 5..], &[0, 0, 33, 0, 1]);
+        assert!(super::query(1, "a..b").is_err());
+    }
+
+    #[test]
+    fn should_parse_srv_records() {
+        let mut response = query(7, "_neo4j._tcp.example.com").unwrap();
+        response[2] |= 0x80;
+        response[7] = 3;
+        response.extend(answer(10, 5, 7687, b"\x05core1\x07example\x03com\x00"));
+        // the second target shares the domain of the first
+        response.extend(answer(10, 50, 7688, b"\x05core2\xC0\x41"));
+        response.extend(answer(10, 0, 0, b"\x00"));
+
+        let records = parse(7, &response).unwrap();
+
+        assert_eq!(
+            records,
+            vec![
+                SrvRecord {
+                    priority: 10,
+                    weight: 5,
+                    port: 7687,
+                    target: "core1.example.com".into()
+                },
+                SrvRecord {
+                    priority: 10,
+                    weight: 50,
+                    port: 7688,
+                    target: "core2.example.com".into()
+                }
+            ]
+        );
+        assert!(parse(8, &response).is_err());
+    }
+
+    #[test]
+    fn should_reject_unknown_names_and_loops() {
+        let mut response = query(7, "_neo4j._tcp.example.com").unwrap();
+        response[3] |= NAME_ERROR as u8;
+        assert!(matches!(parse(7, &response), Err(Error::RoutingError(_))));
+
+        let looping = [0xC0, 0x00];
+        assert!(read_name(&looping, 0).is_err());
+    }
+
+    #[test]
+    fn should_reject_truncated_responses() {
+        let mut response = query(7, "_neo4j._tcp.example.com").unwrap();
+        response[2] |= 0x82;
+        response[7] = 1;
+        response.extend(answer(10, 5, 7687, b"\x05core1\x07example\x03com\x00"));
+
+        assert!(matches!(
+            parse(7, &response),
+            Err(Error::RoutingError(message)) if message.contains("truncated")
+        ));
+    }
+
+    #[test]
+    fn should_read_the_first_name_server() {
+        let conf = "# generated\nsearch example.com\nnameserver 10.0.0.2\nnameserver 10.0.0.3\n";
+
+        assert_eq!(name_server_of(conf), "10.0.0.2:53".parse().unwrap());
+        assert_eq!(
+            name_server_of("search example.com\n"),
+            "127.0.0.1:53".parse().unwrap()
+        );
+    }
+}
@@ -1,24 +1,102 @@
+use crate::bookmark::{Bookmark, ManagedBookmarks};
+use crate::cancel::Cancel;
+use crate::connection::Connection;
+use crate::convert::FromBoltValue;
 use crate::errors::*;
+use crate::interceptor::InterceptedQuery;
 use crate::messages::*;
+use crate::metrics::MetricsHook;
 use crate::pool::*;
 use crate::row::*;
+use crate::slow_query::SlowQueryWatch;
+use crate::summary::ResultSummary;
+use crate::trace::traced;
 use crate::types::*;
+use futures::stream::{self, Stream};
 use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{Mutex, MutexGuard};
 
 /// An abstraction over a stream of rows, this is returned as a result of [`Graph::execute`] or
 /// [`Txn::execute`] operations
 ///
-/// A stream will contain a connection from the connection pool which will be released to the pool
-/// when the stream is dropped.
+/// The stream of an auto-commit query owns its connection from the connection pool, which is
+/// released to the pool once all rows were received or when the stream is dropped. Streams of a
+/// transaction pull their rows through the connection of the [`Txn`].
 pub struct RowStream {
     qid: i64,
-    fields: BoltList,
+    /// The names of the columns, shared by the rows of the stream
+    keys: Arc<[String]>,
     state: State,
     fetch_size: usize,
+    /// The next batch is pulled once fewer rows are buffered, see
+    /// [`ConfigBuilder::prefetch_watermark`](crate::ConfigBuilder::prefetch_watermark)
+    watermark: usize,
     buffer: VecDeque<Row>,
-    connection: Arc<Mutex<ManagedConnection>>,
+    bookmark: Option<Bookmark>,
+    /// The manager updated with the bookmark, along with the bookmarks the query waited for
+    bookmark_manager: Option<(ManagedBookmarks, Vec<Bookmark>)>,
+    summary: ResultSummary,
+    /// `None` once all rows were received, and for rows held in memory
+    connection: Option<Source>,
+    metrics: Option<MetricsHook>,
+    slow_query: Option<SlowQueryWatch>,
+    intercepted: Option<InterceptedQuery>,
+    cancel: Cancel,
+}
+
+/// The connection the rows of a stream are pulled through
+pub(crate) enum Source {
+    /// The connection of an auto-commit query, owned by its stream
+    Owned(Box<ManagedConnection>),
+    /// The connection of a transaction, which it shares with its streams only
+    Txn(Arc<Mutex<ManagedConnection>>),
+}
+
+impl Source {
+    /// Whether rows are pulled before they are read, the first batch along with the query and
+    /// the next ones while the buffered rows are read. The connection of a transaction may be
+    /// used by another query before the stream receives its rows, so the rows of its streams
+    /// are only pulled once they are read.
+    pub(crate) fn pulls_ahead(&self) -> bool {
+        matches!(self, Source::Owned(_))
+    }
+
+    pub(crate) async fn lock(&mut self) -> Guard<'_> {
+        match self {
+            Source::Owned(connection) => Guard::Owned(connection),
+            Source::Txn(connection) => Guard::Txn(connection.lock().await),
+        }
+    }
+}
+
+/// Exclusive access to the connection of a [`Source`]
+pub(crate) enum Guard<'a> {
+    Owned(&'a mut ManagedConnection),
+    Txn(MutexGuard<'a, ManagedConnection>),
+}
+
+impl Deref for Guard<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            Guard::Owned(connection) => connection,
+            Guard::Txn(connection) => connection,
+        }
+    }
+}
+
+impl DerefMut for Guard<'_> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        match self {
+            Guard::Owned(connection) => connection,
+            Guard::Txn(connection) => connection,
+        }
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -34,15 +112,101 @@ impl RowStream {
         qid: i64,
         fields: BoltList,
         fetch_size: usize,
-        connection: Arc<Mutex<ManagedConnection>>,
+        summary: ResultSummary,
+        connection: Source,
+        metrics: Option<MetricsHook>,
+        slow_query: Option<SlowQueryWatch>,
     ) -> RowStream {
+        let keys = fields
+            .iter()
+            .filter_map(|field| BoltString::try_from(field.clone()).ok())
+            .map(String::from)
+            .collect();
         RowStream {
             qid,
-            fields,
-            connection,
+            keys,
             fetch_size,
-            state: State::Ready,
+            state: if connection.pulls_ahead() {
+                State::Streaming
+            } else {
+                State::Ready
+            },
+            connection: Some(connection),
+            watermark: 0,
             buffer: VecDeque::with_capacity(fetch_size),
+            bookmark: None,
+            bookmark_manager: None,
+            summary,
+            metrics,
+            slow_query,
+            intercepted: None,
+            cancel: Cancel::default(),
+        }
+    }
+
+    /// The names of the columns, in the order of the query, available as soon as the query was
+    /// sent, before any row is read
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// The time the server took to make the first row available, like
+    /// [`ResultSummary::result_available_after`], but known before any row is read
+    pub fn result_available_after(&self) -> Option<Duration> {
+        self.summary.result_available_after()
+    }
+
+    /// Stops pulling rows once the query is cancelled or timed out
+    pub(crate) fn cancellable(mut self, cancel: Cancel) -> RowStream {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Pulls the next batch once fewer than `watermark` rows are buffered
+    pub(crate) fn prefetching(mut self, watermark: usize) -> RowStream {
+        self.watermark = watermark;
+        self
+    }
+
+    /// Updates the bookmark manager of the config with the bookmark of the query, which
+    /// waited for the `previous` bookmarks
+    pub(crate) fn bookmarking(
+        mut self,
+        manager: Option<ManagedBookmarks>,
+        previous: &[Bookmark],
+    ) -> RowStream {
+        self.bookmark_manager = manager.map(|manager| (manager, previous.to_vec()));
+        self
+    }
+
+    /// Reports the end of the query to the interceptors of the config
+    pub(crate) fn intercepted(mut self, intercepted: Option<InterceptedQuery>) -> RowStream {
+        self.intercepted = intercepted;
+        self
+    }
+
+    /// A stream of rows held in memory instead of fetched from the server, for tests of code
+    /// consuming rows without a running server
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn from_rows(rows: Vec<Row>) -> RowStream {
+        let keys = rows
+            .first()
+            .map_or_else(|| Arc::from([]), |row| row.keys().into());
+        RowStream {
+            qid: -1,
+            keys,
+            connection: None,
+            fetch_size: rows.len(),
+            watermark: 0,
+            state: State::Complete,
+            buffer: rows.into(),
+            bookmark: None,
+            bookmark_manager: None,
+            summary: ResultSummary::default(),
+            metrics: None,
+            slow_query: None,
+            intercepted: None,
+            cancel: Cancel::default(),
         }
     }
 
@@ -50,31 +214,47 @@ impl RowStream {
     /// if the buffer is empty and the server has more rows left to consume, then a new batch of rows are fetched from the server (using the
     /// fetch_size value configured see [`ConfigBuilder::fetch_size`])
     pub async fn next(&mut self) -> Result<Option<Row>> {
-        let mut connection = self.connection.lock().await;
+        let next = self.advance().await;
+        if let (Err(error), Some(intercepted)) = (&next, self.intercepted.take()) {
+            intercepted.end(Err(error));
+        }
+        next
+    }
+
+    async fn advance(&mut self) -> Result<Option<Row>> {
+        match self.cancel.reason() {
+            Some(reason) if self.state != State::Complete => {
+                if let Some(connection) = &mut self.connection {
+                    connection.lock().await.reset().await?;
+                }
+                self.cancelled();
+                return Err(reason);
+            }
+            _ => {}
+        }
         loop {
             match self.state {
-                State::Ready => {
-                    let pull = BoltRequest::pull(self.fetch_size, self.qid);
-                    connection.send(pull).await?;
-                    self.state = State::Streaming;
+                // the rows of the batch pulled ahead arrive once the buffered ones were read
+                State::Streaming if !self.buffer.is_empty() => {
+                    return Ok(self.buffer.pop_front());
                 }
-                State::Streaming => match connection.recv().await {
-                    Ok(BoltResponse::SuccessMessage(s)) => {
-                        if s.get("has_more").unwrap_or(false) {
-                            self.state = State::Buffered;
-                        } else {
-                            self.state = State::Complete;
-                        }
-                    }
-                    Ok(BoltResponse::RecordMessage(record)) => {
-                        let row = Row::new(self.fields.clone(), record.data);
-                        self.buffer.push_back(row);
+                State::Ready | State::Streaming => {
+                    let pulled = traced!(
+                        "neo4rs.pull",
+                        { qid = self.qid, fetch_size = self.fetch_size },
+                        self.pull()
+                    );
+                    if let Err(Error::Cancelled | Error::Timeout) = pulled {
+                        self.cancelled();
                     }
-                    msg => return Err(unexpected(msg, "PULL")),
-                },
+                    pulled?;
+                }
                 State::Buffered => {
-                    if !self.buffer.is_empty() {
-                        return Ok(self.buffer.pop_front());
+                    if let Some(row) = self.buffer.pop_front() {
+                        if self.buffer.len() < self.watermark {
+                            self.pull_ahead().await?;
+                        }
+                        return Ok(Some(row));
                     }
                     self.state = State::Ready;
                 }
@@ -84,4 +264,410 @@ impl RowStream {
             }
         }
     }
+
+    /// Drops the rows of a cancelled or timed out stream and hands its connection back, the server was reset
+    /// already
+    fn cancelled(&mut self) {
+        self.state = State::Complete;
+        self.buffer.clear();
+        self.connection = None;
+    }
+
+    /// Requests the next batch of rows without receiving it, the rows buffered are read first
+    async fn pull_ahead(&mut self) -> Result<()> {
+        if let Some(connection) = self.connection.as_mut().filter(|c| c.pulls_ahead()) {
+            let pull = BoltRequest::pull(self.fetch_size, self.qid);
+            connection.lock().await.send(pull).await?;
+            self.state = State::Streaming;
+        }
+        Ok(())
+    }
+
+    /// Fetches the next batch of rows into the buffer
+    async fn pull(&mut self) -> Result<()> {
+        let mut connection = match &mut self.connection {
+            Some(connection) => connection.lock().await,
+            None => {
+                self.state = State::Complete;
+                return Ok(());
+            }
+        };
+        if !connection.is_result_open(self.qid) {
+            return Err(Error::ResultConsumed);
+        }
+        if let State::Ready = self.state {
+            let pull = BoltRequest::pull(self.fetch_size, self.qid);
+            connection.send(pull).await?;
+            self.state = State::Streaming;
+        }
+        let buffered = self.buffer.len();
+        let mut finished = None;
+        while let State::Streaming = self.state {
+            match connection.recv_cancellable(&self.cancel).await? {
+                BoltResponse::SuccessMessage(s) => {
+                    if s.get("has_more").unwrap_or(false) {
+                        self.state = State::Buffered;
+                    } else {
+                        self.state = State::Complete;
+                        connection.close_result(self.qid);
+                        finished = Some(s);
+                    }
+                }
+                BoltResponse::RecordMessage(record) => {
+                    let row = Row::from_record(self.keys.clone(), record);
+                    self.buffer.push_back(row);
+                }
+                msg => return Err(msg.into_error("PULL")),
+            }
+        }
+        drop(connection);
+        if let Some(success) = finished {
+            self.complete(&success);
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .0
+                .rows_streamed((self.buffer.len() - buffered) as u64);
+        }
+        // the connection of an auto-commit query goes back to the pool right away, rather than
+        // once the buffered rows are consumed
+        if let State::Complete = self.state {
+            self.connection = None;
+        }
+        Ok(())
+    }
+
+    /// Completes the summary with the SUCCESS response ending the result
+    fn complete(&mut self, success: &Success) {
+        self.bookmark = success.get::<String>("bookmark").map(Bookmark::from);
+        if let (Some(bookmark), Some((manager, previous))) =
+            (&self.bookmark, self.bookmark_manager.take())
+        {
+            manager.0.update(&previous, bookmark.clone());
+        }
+        self.summary.complete(success);
+        self.state = State::Complete;
+        if let Some(slow_query) = self.slow_query.take() {
+            slow_query.finish(&self.summary);
+        }
+        if let Some(intercepted) = self.intercepted.take() {
+            intercepted.end(Ok(&self.summary));
+        }
+    }
+
+    /// Discards the rows left in the stream without receiving them, with a DISCARD message,
+    /// and returns the [`ResultSummary`] of the query, for queries whose counters matter but
+    /// not their rows. The rows already received are dropped too.
+    ///
+    /// ```no_run
+    /// # async fn created(graph: &neo4rs::Graph) -> neo4rs::Result<i64> {
+    /// let summary = graph
+    ///     .execute(neo4rs::query("UNWIND range(1, 100) AS i CREATE (n) RETURN n"))
+    ///     .await?
+    ///     .consume()
+    ///     .await?;
+    /// Ok(summary.counters().nodes_created)
+    /// # }
+    /// ```
+    pub async fn consume(mut self) -> Result<ResultSummary> {
+        self.buffer.clear();
+        let discarded = self.discard().await;
+        if let Err(Error::Cancelled | Error::Timeout) = discarded {
+            self.cancelled();
+        }
+        if let (Err(error), Some(intercepted)) = (&discarded, self.intercepted.take()) {
+            intercepted.end(Err(error));
+        }
+        discarded?;
+        Ok(std::mem::take(&mut self.summary))
+    }
+
+    /// Ends the result on the server, skipping the rows of the batch still arriving
+    async fn discard(&mut self) -> Result<()> {
+        let mut connection = match &mut self.connection {
+            Some(connection) if self.state != State::Complete => connection.lock().await,
+            _ => {
+                self.state = State::Complete;
+                return Ok(());
+            }
+        };
+        if !connection.is_result_open(self.qid) {
+            return Err(Error::ResultConsumed);
+        }
+        let finished = loop {
+            match self.state {
+                State::Streaming => match connection.recv_cancellable(&self.cancel).await? {
+                    BoltResponse::SuccessMessage(s) if s.get("has_more").unwrap_or(false) => {
+                        self.state = State::Buffered;
+                    }
+                    BoltResponse::SuccessMessage(s) => break s,
+                    BoltResponse::RecordMessage(_) => {}
+                    msg => return Err(msg.into_error("PULL")),
+                },
+                _ => {
+                    let discard = BoltRequest::discard_all(self.qid);
+                    connection.send(discard).await?;
+                    match connection.recv_cancellable(&self.cancel).await? {
+                        BoltResponse::SuccessMessage(s) => break s,
+                        msg => return Err(msg.into_error("DISCARD")),
+                    }
+                }
+            }
+        };
+        connection.close_result(self.qid);
+        drop(connection);
+        self.connection = None;
+        self.complete(&finished);
+        Ok(())
+    }
+
+    /// Fetches all the rows left in the stream
+    pub async fn fetch_all(&mut self) -> Result<Vec<Row>> {
+        let mut rows = Vec::new();
+        while let Some(row) = self.next().await? {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Fetches the only row of the stream, fails with [`Error::NoRows`] for an empty stream and
+    /// with [`Error::TooManyRows`] when a second row follows, the rows after it are not fetched
+    pub async fn single(&mut self) -> Result<Row> {
+        let row = self.next().await?.ok_or(Error::NoRows)?;
+        match self.next().await? {
+            Some(_) => Err(Error::TooManyRows),
+            None => Ok(row),
+        }
+    }
+
+    /// Fetches all the rows left in the stream and collects the column `name` of each of them,
+    /// failing when a row is missing the column or when its value is null or of another type
+    pub async fn column<T: FromBoltValue>(&mut self, name: &str) -> Result<Vec<T>> {
+        let mut values = Vec::new();
+        while let Some(row) = self.next().await? {
+            values.push(value_of(&row, name)?);
+        }
+        Ok(values)
+    }
+
+    /// Turns the rows into a [`Stream`] of the values of their column `name`, for queries
+    /// returning one value per row. Like [`RowStream::column`] an item fails when its row is
+    /// missing the column or when its value is null or of another type, the rows are still
+    /// fetched in batches as the stream is polled.
+    pub fn into_values<T: FromBoltValue>(self, name: &str) -> impl Stream<Item = Result<T>> {
+        let name = name.to_owned();
+        stream::try_unfold(self, move |mut rows| {
+            let name = name.clone();
+            async move {
+                match rows.next().await? {
+                    Some(row) => Ok(Some((value_of(&row, &name)?, rows))),
+                    None => Ok(None),
+                }
+            }
+        })
+    }
+
+    /// Turns the rows into a [`Stream`], so they can be consumed with the combinators of
+    /// [`StreamExt`](futures::StreamExt) and [`TryStreamExt`](futures::TryStreamExt). Rows are
+    /// still fetched in batches as the stream is polled.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Row>> {
+        stream::try_unfold(self, |mut rows| async move {
+            Ok(rows.next().await?.map(|row| (row, rows)))
+        })
+    }
+
+    /// The bookmark of an auto-commit query once all rows were consumed, queries run within a
+    /// transaction have no bookmark of their own, see [`Txn::commit`]
+    pub fn bookmark(&self) -> Option<&Bookmark> {
+        self.bookmark.as_ref()
+    }
+
+    /// The [`ResultSummary`] of the query, available once all rows were consumed
+    pub fn summary(&self) -> Option<&ResultSummary> {
+        match self.state {
+            State::Complete => Some(&self.summary),
+            _ => None,
+        }
+    }
+
+    /// Fetches all the rows left in the stream along with the summary, releasing the
+    /// connection of an auto-commit query once they were received
+    pub(crate) async fn buffer(mut self) -> Result<EagerResult> {
+        let rows = self.fetch_all().await?;
+        Ok(EagerResult {
+            rows,
+            summary: std::mem::take(&mut self.summary),
+        })
+    }
+}
+
+/// All the rows of a query along with its summary, returned by [`Graph::execute_eager`]
+///
+/// [`Graph::execute_eager`]: crate::Graph::execute_eager
+#[derive(Debug)]
+pub struct EagerResult {
+    /// The rows of the query, in the order the server returned them
+    pub rows: Vec<Row>,
+    /// The summary the server sent once the last row was received
+    pub summary: ResultSummary,
+}
+
+/// The rows of a query split into pages of the same size, returned by [`Graph::execute_paged`]
+///
+/// Every page is fetched from the server with a PULL of its size when it is requested, so pages
+/// can be served to a user interface or exported in chunks without SKIP and LIMIT clauses.
+///
+/// [`Graph::execute_paged`]: crate::Graph::execute_paged
+pub struct RowPages {
+    rows: RowStream,
+    page_size: usize,
+}
+
+impl RowPages {
+    pub(crate) fn new(rows: RowStream, page_size: usize) -> RowPages {
+        RowPages { rows, page_size }
+    }
+
+    /// Fetches the next page, the last one may hold fewer rows and `None` follows it
+    pub async fn next(&mut self) -> Result<Option<Vec<Row>>> {
+        let mut page = Vec::with_capacity(self.page_size);
+        while page.len() < self.page_size {
+            match self.rows.next().await? {
+                Some(row) => page.push(row),
+                None => break,
+            }
+        }
+        Ok(if page.is_empty() { None } else { Some(page) })
+    }
+
+    /// Turns the pages into a [`Stream`], see [`RowStream::into_stream`]
+    pub fn into_stream(self) -> impl Stream<Item = Result<Vec<Row>>> {
+        stream::try_unfold(self, |mut pages| async move {
+            Ok(pages.next().await?.map(|page| (page, pages)))
+        })
+    }
+
+    /// The [`ResultSummary`] of the query, available once all pages were fetched
+    pub fn summary(&self) -> Option<&ResultSummary> {
+        self.rows.summary()
+    }
+}
+
+/// A stream dropped while it receives its rows, when the future pulling them is cancelled, leaves
+/// the rest of them on the connection, which skips them before it is used again
+impl Drop for RowStream {
+    fn drop(&mut self) {
+        match (self.state, &mut self.connection) {
+            (State::Streaming, Some(Source::Owned(connection))) => connection.abandon(self.qid),
+            (State::Streaming, Some(Source::Txn(connection))) => {
+                if let Ok(mut connection) = connection.try_lock() {
+                    connection.abandon(self.qid);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The value of the column `name` of `row`, which must not be null
+fn value_of<T: FromBoltValue>(row: &Row, name: &str) -> Result<T> {
+    row.get_opt(name)?.ok_or(Error::ConverstionError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(values: &[i64]) -> RowStream {
+        let fields = BoltList::from(vec![BoltType::from("n")]);
+        let rows = values
+            .iter()
+            .map(|&n| Row::new(fields.clone(), BoltList::from(vec![BoltType::from(n)])))
+            .collect();
+        RowStream::from_rows(rows)
+    }
+
+    #[tokio::test]
+    async fn should_buffer_the_rows_and_the_summary() {
+        let result = rows(&[1, 2, 3]).buffer().await.unwrap();
+
+        let numbers: Vec<i64> = result
+            .rows
+            .iter()
+            .map(|row| row.get("n").unwrap())
+            .collect();
+        assert_eq!(numbers, vec![1, 2, 3]);
+        assert_eq!(result.summary, ResultSummary::default());
+    }
+
+    #[tokio::test]
+    async fn should_consume_the_rows_left() {
+        let mut rows = rows(&[1, 2, 3]);
+        rows.next().await.unwrap();
+
+        let summary = rows.consume().await.unwrap();
+
+        assert_eq!(summary, ResultSummary::default());
+    }
+
+    #[tokio::test]
+    async fn should_know_the_keys_before_reading_rows() {
+        let mut rows = rows(&[1, 2]);
+
+        assert_eq!(rows.keys(), ["n"]);
+        rows.fetch_all().await.unwrap();
+        assert_eq!(rows.keys(), ["n"]);
+        assert!(RowStream::from_rows(Vec::new()).keys().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_split_the_rows_into_pages() {
+        let mut pages = RowPages::new(rows(&[1, 2, 3, 4, 5]), 2);
+
+        let mut sizes = Vec::new();
+        while let Some(page) = pages.next().await.unwrap() {
+            sizes.push(page.len());
+        }
+
+        assert_eq!(sizes, vec![2, 2, 1]);
+        assert!(pages.summary().is_some());
+    }
+
+    #[tokio::test]
+    async fn should_consume_the_rows() {
+        assert_eq!(rows(&[1, 2, 3]).fetch_all().await.unwrap().len(), 3);
+        assert_eq!(
+            rows(&[1, 2, 3]).column::<i64>("n").await.unwrap(),
+            vec![1, 2, 3]
+        );
+        assert!(matches!(
+            rows(&[1]).column::<i64>("m").await,
+            Err(Error::MissingKey(_))
+        ));
+        let row = rows(&[4]).single().await.unwrap();
+        assert_eq!(row.get::<i64>("n"), Some(4));
+        assert!(matches!(rows(&[]).single().await, Err(Error::NoRows)));
+        assert!(matches!(
+            rows(&[1, 2]).single().await,
+            Err(Error::TooManyRows)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_stream_the_values_of_a_column() {
+        use futures::TryStreamExt;
+
+        let values: Vec<i64> = rows(&[1, 2, 3])
+            .into_values::<i64>("n")
+            .try_collect()
+            .await
+            .unwrap();
+        let mut missing = Box::pin(rows(&[1]).into_values::<i64>("m"));
+
+        assert_eq!(values, vec![1, 2, 3]);
+        assert!(matches!(
+            missing.try_next().await,
+            Err(Error::MissingKey(_))
+        ));
+    }
 }
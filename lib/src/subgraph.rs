@@ -0,0 +1,197 @@
+use crate::errors::Result;
+use crate::row::{Node, Relation, Row};
+use crate::stream::RowStream;
+use crate::types::{BoltNode, BoltRelation, BoltType};
+use petgraph::graph::{DiGraph, NodeIndex};
+use std::collections::{HashMap, HashSet};
+
+/// The nodes and relationships of query results collected into a [`petgraph`] graph, to run
+/// graph algorithms on a subgraph client-side, see [`RowStream::collect_graph`]
+///
+/// Every node and relationship is added once, keyed by its id, wherever it appears in the rows:
+/// as a column, within a path or nested in lists and maps. Relationships whose start or end node
+/// is not part of the results are left out.
+///
+/// ```no_run
+/// use neo4rs::*;
+/// use petgraph::algo::dijkstra;
+///
+/// #[tokio::main]
+/// async fn main() {
+///    let graph = Graph::new("127.0.0.1:7687", "neo4j", "neo").await.unwrap();
+///    let mut rows = graph
+///        .execute(query("MATCH p = (:Person)-[:KNOWS*..3]->(:Person) RETURN p"))
+///        .await
+///        .unwrap();
+///    let subgraph = rows.collect_graph().await.unwrap();
+///    let start = subgraph.node_index(0).unwrap();
+///    let distances = dijkstra(subgraph.graph(), start, None, |_| 1);
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Subgraph {
+    graph: DiGraph<Node, Relation>,
+    nodes: HashMap<i64, NodeIndex>,
+    rels: HashSet<i64>,
+    pending: Vec<BoltRelation>,
+}
+
+impl Subgraph {
+    pub fn new() -> Self {
+        Subgraph::default()
+    }
+
+    /// Adds the nodes and relationships of a row, failing when one of its columns can't be
+    /// decoded
+    pub fn insert_row(&mut self, row: &Row) -> Result<()> {
+        for value in row.values() {
+            self.insert(value?);
+        }
+        self.connect();
+        Ok(())
+    }
+
+    /// The graph, with a petgraph node per node and an edge per relationship
+    pub fn graph(&self) -> &DiGraph<Node, Relation> {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> DiGraph<Node, Relation> {
+        self.graph
+    }
+
+    /// The index in [`Subgraph::graph`] of the node with this id
+    pub fn node_index(&self, id: i64) -> Option<NodeIndex> {
+        self.nodes.get(&id).copied()
+    }
+
+    fn insert(&mut self, value: &BoltType) {
+        match value {
+            BoltType::Node(node) => {
+                self.insert_node(node);
+            }
+            BoltType::Relation(rel) => self.insert_rel(rel.clone()),
+            BoltType::Path(path) => {
+                for node in path.nodes() {
+                    self.insert_node(&node);
+                }
+                for (_, rel, _) in path.segments() {
+                    self.insert_rel(rel);
+                }
+            }
+            BoltType::List(list) => list.iter().for_each(|value| self.insert(value)),
+            BoltType::Map(map) => map.value.values().for_each(|value| self.insert(value)),
+            _ => {}
+        }
+    }
+
+    fn insert_node(&mut self, node: &BoltNode) -> NodeIndex {
+        let graph = &mut self.graph;
+        *self
+            .nodes
+            .entry(node.id.value)
+            .or_insert_with(|| graph.add_node(Node::new(node.clone())))
+    }
+
+    /// Relationships are only added once both of their nodes are known
+    fn insert_rel(&mut self, rel: BoltRelation) {
+        if !self.rels.contains(&rel.id.value) {
+            self.pending.push(rel);
+        }
+    }
+
+    fn connect(&mut self) {
+        for rel in std::mem::take(&mut self.pending) {
+            let start = self.node_index(rel.start_node_id.value);
+            let end = self.node_index(rel.end_node_id.value);
+            match (start, end) {
+                (Some(start), Some(end)) if self.rels.insert(rel.id.value) => {
+                    self.graph.add_edge(start, end, Relation::new(rel));
+                }
+                (Some(_), Some(_)) => {}
+                _ => self.pending.push(rel),
+            }
+        }
+    }
+}
+
+impl RowStream {
+    /// Fetches all the rows left in the stream and collects their nodes and relationships into
+    /// a [`Subgraph`]
+    pub async fn collect_graph(&mut self) -> Result<Subgraph> {
+        let mut subgraph = Subgraph::new();
+        while let Some(row) = self.next().await? {
+            subgraph.insert_row(&row)?;
+        }
+        Ok(subgraph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BoltInteger, BoltList, BoltMap, BoltPath, BoltUnboundedRelation};
+
+    fn node(id: i64) -> BoltType {
+        BoltNode::new(
+            BoltInteger::new(id),
+            BoltList::default(),
+            BoltMap::default(),
+        )
+        .into()
+    }
+
+    #[test]
+    fn should_collect_nodes_and_relationships_into_a_graph() {
+        let fields: BoltList = vec!["a".into(), "r".into(), "b".into()].into();
+        let knows = |id: i64, start: i64, end: i64| {
+            BoltType::Relation(BoltRelation::new(
+                id.into(),
+                start.into(),
+                end.into(),
+                "KNOWS".into(),
+                BoltMap::default(),
+            ))
+        };
+        let rows = vec![
+            Row::new(
+                fields.clone(),
+                vec![node(1), knows(10, 1, 2), node(2)].into(),
+            ),
+            Row::new(
+                fields.clone(),
+                vec![node(2), knows(11, 2, 3), node(3)].into(),
+            ),
+            Row::new(fields, vec![node(1), knows(10, 1, 2), node(2)].into()),
+        ];
+        // (3)<-[12]-(1), traversed from 3
+        let path = BoltPath {
+            nodes: vec![node(3), node(1)].into(),
+            rels: vec![
+                BoltUnboundedRelation::new(12.into(), "KNOWS".into(), BoltMap::default()).into(),
+            ]
+            .into(),
+            ids: vec![(-1).into(), 1.into()].into(),
+        };
+
+        let mut subgraph = Subgraph::new();
+        for row in &rows {
+            subgraph.insert_row(row).unwrap();
+        }
+        subgraph
+            .insert_row(&Row::new(
+                vec!["p".into()].into(),
+                vec![BoltType::Path(path)].into(),
+            ))
+            .unwrap();
+
+        let graph = subgraph.graph();
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        let one = subgraph.node_index(1).unwrap();
+        let three = subgraph.node_index(3).unwrap();
+        let edge = graph.find_edge(one, three).unwrap();
+        assert_eq!(graph[edge].id(), 12);
+        assert!(graph.find_edge(three, one).is_none());
+    }
+}
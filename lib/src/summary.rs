@@ -0,0 +1,304 @@
+use crate::messages::Success;
+use crate::types::*;
+use std::time::Duration;
+
+mod notification;
+mod plan;
+
+pub(crate) use notification::adapt_filter;
+pub use notification::{
+    InputPosition, MinimumSeverity, Notification, NotificationCategory, NotificationFilter,
+    Severity,
+};
+pub use plan::{Plan, ProfiledPlan};
+/// The kind of work a query did, as reported by the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryType {
+    Read,
+    Write,
+    ReadWrite,
+    Schema,
+}
+
+impl QueryType {
+    fn parse(value: &str) -> Option<QueryType> {
+        match value {
+            "r" => Some(QueryType::Read),
+            "w" => Some(QueryType::Write),
+            "rw" => Some(QueryType::ReadWrite),
+            "s" => Some(QueryType::Schema),
+            _ => None,
+        }
+    }
+}
+
+/// The changes a query made to the database
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Counters {
+    pub nodes_created: i64,
+    pub nodes_deleted: i64,
+    pub relationships_created: i64,
+    pub relationships_deleted: i64,
+    pub properties_set: i64,
+    pub labels_added: i64,
+    pub labels_removed: i64,
+    pub indexes_added: i64,
+    pub indexes_removed: i64,
+    pub constraints_added: i64,
+    pub constraints_removed: i64,
+    pub system_updates: i64,
+}
+
+impl Counters {
+    fn parse(stats: &BoltMap) -> Counters {
+        let count = |key: &str| stats.get::<i64>(key).unwrap_or(0);
+        Counters {
+            nodes_created: count("nodes-created"),
+            nodes_deleted: count("nodes-deleted"),
+            relationships_created: count("relationships-created"),
+            relationships_deleted: count("relationships-deleted"),
+            properties_set: count("properties-set"),
+            labels_added: count("labels-added"),
+            labels_removed: count("labels-removed"),
+            indexes_added: count("indexes-added"),
+            indexes_removed: count("indexes-removed"),
+            constraints_added: count("constraints-added"),
+            constraints_removed: count("constraints-removed"),
+            system_updates: count("system-updates"),
+        }
+    }
+
+    /// Whether the query changed any data or schema of the database
+    pub fn contains_updates(&self) -> bool {
+        self.nodes_created > 0
+            || self.nodes_deleted > 0
+            || self.relationships_created > 0
+            || self.relationships_deleted > 0
+            || self.properties_set > 0
+            || self.labels_added > 0
+            || self.labels_removed > 0
+            || self.indexes_added > 0
+            || self.indexes_removed > 0
+            || self.constraints_added > 0
+            || self.constraints_removed > 0
+    }
+
+    /// Whether the query changed the system database, like creating a user or a database
+    pub fn contains_system_updates(&self) -> bool {
+        self.system_updates > 0
+    }
+}
+
+/// Adds up the changes of several queries
+impl std::ops::AddAssign<&Counters> for Counters {
+    fn add_assign(&mut self, other: &Counters) {
+        self.nodes_created += other.nodes_created;
+        self.nodes_deleted += other.nodes_deleted;
+        self.relationships_created += other.relationships_created;
+        self.relationships_deleted += other.relationships_deleted;
+        self.properties_set += other.properties_set;
+        self.labels_added += other.labels_added;
+        self.labels_removed += other.labels_removed;
+        self.indexes_added += other.indexes_added;
+        self.indexes_removed += other.indexes_removed;
+        self.constraints_added += other.constraints_added;
+        self.constraints_removed += other.constraints_removed;
+        self.system_updates += other.system_updates;
+    }
+}
+
+/// The outcome of [`Graph::ingest`]
+///
+/// [`Graph::ingest`]: crate::Graph::ingest
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IngestSummary {
+    /// The number of items ingested
+    pub items: usize,
+    /// The number of transactions the items were ingested in
+    pub batches: usize,
+    /// The changes of all the batches added up
+    pub counters: Counters,
+}
+
+/// Details about a query once its [`RowStream`] was consumed, see [`RowStream::summary`]
+///
+/// [`RowStream`]: crate::RowStream
+/// [`RowStream::summary`]: crate::RowStream::summary
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResultSummary {
+    query_type: Option<QueryType>,
+    counters: Counters,
+    database: Option<String>,
+    result_available_after: Option<Duration>,
+    result_consumed_after: Option<Duration>,
+    plan: Option<Plan>,
+    profile: Option<ProfiledPlan>,
+    notifications: Vec<Notification>,
+}
+
+impl ResultSummary {
+    /// Starts a summary from the SUCCESS response to RUN
+    pub(crate) fn new(run: &Success) -> ResultSummary {
+        ResultSummary {
+            query_type: None,
+            counters: Counters::default(),
+            database: None,
+            result_available_after: millis(run, "t_first"),
+            result_consumed_after: None,
+            plan: None,
+            profile: None,
+            notifications: Vec::new(),
+        }
+    }
+
+    /// Completes the summary with the SUCCESS response to the last PULL or DISCARD
+    pub(crate) fn complete(&mut self, pull: &Success) {
+        self.query_type = pull
+            .get::<String>("type")
+            .and_then(|value| QueryType::parse(&value));
+        self.counters = pull
+            .get::<BoltMap>("stats")
+            .map(|stats| Counters::parse(&stats))
+            .unwrap_or_default();
+        self.database = pull.get::<String>("db");
+        self.result_consumed_after = millis(pull, "t_last");
+        self.plan = pull.get::<BoltMap>("plan").map(Plan::parse);
+        self.profile = pull.get::<BoltMap>("profile").map(ProfiledPlan::parse);
+        self.notifications = match pull.get::<BoltList>("statuses") {
+            Some(statuses) => Notification::parse_statuses(statuses),
+            None => pull
+                .get::<BoltList>("notifications")
+                .map(Notification::parse_all)
+                .unwrap_or_default(),
+        };
+    }
+
+    pub fn query_type(&self) -> Option<QueryType> {
+        self.query_type
+    }
+
+    pub fn counters(&self) -> &Counters {
+        &self.counters
+    }
+
+    /// The database the query ran on
+    pub fn database(&self) -> Option<&str> {
+        self.database.as_deref()
+    }
+
+    /// The time it took the server to have the first record available
+    pub fn result_available_after(&self) -> Option<Duration> {
+        self.result_available_after
+    }
+
+    /// The time it took the server to consume the result, after it became available
+    pub fn result_consumed_after(&self) -> Option<Duration> {
+        self.result_consumed_after
+    }
+
+    /// The execution plan of an `EXPLAIN` query
+    pub fn plan(&self) -> Option<&Plan> {
+        self.plan.as_ref()
+    }
+
+    /// The execution plan of a `PROFILE` query, along with the work every operator did
+    pub fn profile(&self) -> Option<&ProfiledPlan> {
+        self.profile.as_ref()
+    }
+
+    /// The warnings and hints the server raised for the query
+    pub fn notifications(&self) -> &[Notification] {
+        &self.notifications
+    }
+}
+
+fn millis(success: &Success, key: &str) -> Option<Duration> {
+    success
+        .get::<i64>(key)
+        .map(|millis| Duration::from_millis(millis.max(0) as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn success(metadata: Vec<(&str, BoltType)>) -> Success {
+        Success::new(
+            metadata
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn should_add_up_counters() {
+        let mut counters = Counters {
+            nodes_created: 2,
+            properties_set: 4,
+            ..Counters::default()
+        };
+
+        counters += &Counters {
+            nodes_created: 3,
+            labels_added: 1,
+            ..Counters::default()
+        };
+
+        assert_eq!(counters.nodes_created, 5);
+        assert_eq!(counters.properties_set, 4);
+        assert_eq!(counters.labels_added, 1);
+    }
+
+    #[test]
+    fn should_parse_summary() {
+        let run = success(vec![("t_first", 12.into())]);
+        let stats: BoltMap = vec![
+            ("nodes-created".into(), 2.into()),
+            ("properties-set".into(), 3.into()),
+            ("labels-added".into(), 2.into()),
+        ]
+        .into_iter()
+        .collect();
+        let pull = success(vec![
+            ("type", "w".into()),
+            ("t_last", 5.into()),
+            ("db", "neo4j".into()),
+            ("stats", BoltType::Map(stats)),
+        ]);
+
+        let mut summary = ResultSummary::new(&run);
+        summary.complete(&pull);
+
+        assert_eq!(summary.query_type(), Some(QueryType::Write));
+        assert_eq!(summary.database(), Some("neo4j"));
+        assert_eq!(
+            summary.result_available_after(),
+            Some(Duration::from_millis(12))
+        );
+        assert_eq!(
+            summary.result_consumed_after(),
+            Some(Duration::from_millis(5))
+        );
+        assert_eq!(summary.counters().nodes_created, 2);
+        assert_eq!(summary.counters().properties_set, 3);
+        assert_eq!(summary.counters().labels_added, 2);
+        assert_eq!(summary.counters().relationships_created, 0);
+        assert!(summary.counters().contains_updates());
+        assert!(!summary.counters().contains_system_updates());
+    }
+
+    #[test]
+    fn should_parse_summary_without_stats() {
+        let mut summary = ResultSummary::new(&success(vec![]));
+        summary.complete(&success(vec![("type", "r".into())]));
+
+        assert_eq!(summary.query_type(), Some(QueryType::Read));
+        assert_eq!(summary.counters(), &Counters::default());
+        assert!(!summary.counters().contains_updates());
+        assert_eq!(summary.result_available_after(), None);
+        assert!(summary.plan().is_none());
+        assert!(summary.profile().is_none());
+        assert!(summary.notifications().is_empty());
+    }
+}
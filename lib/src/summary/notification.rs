@@ -0,0 +1,357 @@
+use crate::types::*;
+use crate::version::{Feature, Version};
+use std::convert::TryFrom;
+
+/// How serious the server considers a [`Notification`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Information,
+    Unknown(String),
+}
+
+impl Severity {
+    fn parse(value: String) -> Severity {
+        match value.as_str() {
+            "WARNING" => Severity::Warning,
+            "INFORMATION" => Severity::Information,
+            _ => Severity::Unknown(value),
+        }
+    }
+}
+
+/// The place in the query text a [`Notification`] refers to, lines and columns start at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputPosition {
+    pub offset: i64,
+    pub line: i64,
+    pub column: i64,
+}
+
+impl InputPosition {
+    pub(crate) fn parse(position: BoltMap) -> InputPosition {
+        InputPosition {
+            offset: position.get("offset").unwrap_or(0),
+            line: position.get("line").unwrap_or(0),
+            column: position.get("column").unwrap_or(0),
+        }
+    }
+}
+
+/// A warning or hint the server raised while planning or running a query, like a missing index
+/// or the use of a deprecated feature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub code: String,
+    pub title: String,
+    pub description: String,
+    pub severity: Severity,
+    pub position: Option<InputPosition>,
+}
+
+impl Notification {
+    fn parse(notification: BoltMap) -> Notification {
+        Notification {
+            code: notification.get("code").unwrap_or_default(),
+            title: notification.get("title").unwrap_or_default(),
+            description: notification.get("description").unwrap_or_default(),
+            severity: Severity::parse(notification.get("severity").unwrap_or_default()),
+            position: notification
+                .get::<BoltMap>("position")
+                .map(InputPosition::parse),
+        }
+    }
+
+    pub(crate) fn parse_all(notifications: BoltList) -> Vec<Notification> {
+        notifications
+            .into_iter()
+            .filter_map(|notification| BoltMap::try_from(notification).ok())
+            .map(Notification::parse)
+            .collect()
+    }
+
+    /// Parses the notifications among the GQL statuses servers send from bolt 5.6, the other
+    /// statuses, like the one of a successful completion, have no neo4j code
+    pub(crate) fn parse_statuses(statuses: BoltList) -> Vec<Notification> {
+        statuses
+            .into_iter()
+            .filter_map(|status| BoltMap::try_from(status).ok())
+            .filter_map(|status| {
+                let code = status.get::<String>("neo4j_code")?;
+                let record = status
+                    .get::<BoltMap>("diagnostic_record")
+                    .unwrap_or_default();
+                Some(Notification {
+                    code,
+                    title: status.get("title").unwrap_or_default(),
+                    description: status.get("description").unwrap_or_default(),
+                    severity: Severity::parse(record.get("_severity").unwrap_or_default()),
+                    position: record.get::<BoltMap>("_position").map(InputPosition::parse),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The least serious notifications the server sends, see [`NotificationFilter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimumSeverity {
+    /// No notifications at all
+    Off,
+    /// Only warnings
+    Warning,
+    /// Warnings and informations
+    Information,
+}
+
+impl MinimumSeverity {
+    fn as_str(self) -> &'static str {
+        match self {
+            MinimumSeverity::Off => "OFF",
+            MinimumSeverity::Warning => "WARNING",
+            MinimumSeverity::Information => "INFORMATION",
+        }
+    }
+}
+
+/// A kind of notification which can be disabled, see [`NotificationFilter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationCategory {
+    /// The query can't be planned like an index or a join hint asked for
+    Hint,
+    /// The query refers to labels, types or properties which don't exist
+    Unrecognized,
+    /// The query uses features which aren't supported in production
+    Unsupported,
+    /// The query is likely to be slow, like with a cartesian product
+    Performance,
+    /// The query uses deprecated features
+    Deprecation,
+    /// Notifications not falling into the other categories
+    Generic,
+}
+
+impl NotificationCategory {
+    fn as_str(self) -> &'static str {
+        match self {
+            NotificationCategory::Hint => "HINT",
+            NotificationCategory::Unrecognized => "UNRECOGNIZED",
+            NotificationCategory::Unsupported => "UNSUPPORTED",
+            NotificationCategory::Performance => "PERFORMANCE",
+            NotificationCategory::Deprecation => "DEPRECATION",
+            NotificationCategory::Generic => "GENERIC",
+        }
+    }
+}
+
+/// Which notifications the server sends along with the summaries of queries, from bolt 5.2
+///
+/// The filter of the config applies to every query of its connections, see
+/// [`ConfigBuilder::notification_filter`](crate::ConfigBuilder::notification_filter). Queries
+/// and transactions can replace it with their own, see [`Query::notification_filter`] and
+/// [`TxOptions::notification_filter`].
+///
+/// [`Query::notification_filter`]: crate::Query::notification_filter
+/// [`TxOptions::notification_filter`]: crate::TxOptions::notification_filter
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotificationFilter {
+    minimum_severity: Option<MinimumSeverity>,
+    disabled_categories: Vec<NotificationCategory>,
+}
+
+impl NotificationFilter {
+    /// A filter keeping the defaults of the server
+    pub fn new() -> Self {
+        NotificationFilter::default()
+    }
+
+    /// Only sends notifications at least as serious as `severity`
+    pub fn minimum_severity(mut self, severity: MinimumSeverity) -> Self {
+        self.minimum_severity = Some(severity);
+        self
+    }
+
+    /// Doesn't send the notifications of `category`
+    pub fn disable(mut self, category: NotificationCategory) -> Self {
+        if !self.disabled_categories.contains(&category) {
+            self.disabled_categories.push(category);
+        }
+        self
+    }
+
+    /// Whether the filter keeps the defaults of the server
+    pub fn is_empty(&self) -> bool {
+        self.minimum_severity.is_none() && self.disabled_categories.is_empty()
+    }
+
+    /// Adds the filter to the extra of a HELLO, BEGIN or RUN message
+    pub(crate) fn put(&self, extra: &mut BoltMap) {
+        if let Some(severity) = self.minimum_severity {
+            extra.put(
+                "notifications_minimum_severity".into(),
+                severity.as_str().into(),
+            );
+        }
+        if !self.disabled_categories.is_empty() {
+            let categories: Vec<BoltType> = self
+                .disabled_categories
+                .iter()
+                .map(|category| category.as_str().into())
+                .collect();
+            extra.put(
+                DISABLED_CATEGORIES.into(),
+                BoltType::List(categories.into()),
+            );
+        }
+    }
+}
+
+const DISABLED_CATEGORIES: &str = "notifications_disabled_categories";
+
+/// Renames the disabled categories of the extra of a message to the disabled classifications
+/// servers expect from bolt 5.6
+pub(crate) fn adapt_filter(extra: &mut BoltMap, version: Version) {
+    if version.supports(Feature::GqlStatuses) {
+        if let Some(categories) = extra.value.remove(&BoltString::from(DISABLED_CATEGORIES)) {
+            extra.put("notifications_disabled_classifications".into(), categories);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: Vec<(&str, BoltType)>) -> BoltMap {
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value))
+            .collect()
+    }
+
+    #[test]
+    fn should_parse_notifications() {
+        let position = map(vec![
+            ("offset", 9.into()),
+            ("line", 1.into()),
+            ("column", 10.into()),
+        ]);
+        let warning = map(vec![
+            (
+                "code",
+                "Neo.ClientNotification.Statement.CartesianProduct".into(),
+            ),
+            ("title", "This query builds a cartesian product".into()),
+            ("description", "If a part of a query contains...".into()),
+            ("severity", "WARNING".into()),
+            ("position", BoltType::Map(position)),
+        ]);
+        let hint = map(vec![("severity", "INFORMATION".into())]);
+        let notifications: BoltList = vec![BoltType::Map(warning), BoltType::Map(hint)].into();
+
+        let notifications = Notification::parse_all(notifications);
+
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(
+            notifications[0].code,
+            "Neo.ClientNotification.Statement.CartesianProduct"
+        );
+        assert_eq!(notifications[0].severity, Severity::Warning);
+        assert_eq!(
+            notifications[0].position,
+            Some(InputPosition {
+                offset: 9,
+                line: 1,
+                column: 10
+            })
+        );
+        assert_eq!(notifications[1].severity, Severity::Information);
+        assert_eq!(notifications[1].position, None);
+    }
+
+    #[test]
+    fn should_keep_unknown_severity() {
+        let notifications: BoltList =
+            vec![BoltType::Map(map(vec![("severity", "OFF".into())]))].into();
+
+        let notifications = Notification::parse_all(notifications);
+
+        assert_eq!(notifications[0].severity, Severity::Unknown("OFF".into()));
+    }
+
+    #[test]
+    fn should_put_the_notification_filter() {
+        let filter = NotificationFilter::new()
+            .minimum_severity(MinimumSeverity::Warning)
+            .disable(NotificationCategory::Hint)
+            .disable(NotificationCategory::Deprecation)
+            .disable(NotificationCategory::Hint);
+        let mut extra = BoltMap::default();
+
+        filter.put(&mut extra);
+        NotificationFilter::new().put(&mut BoltMap::default());
+
+        assert_eq!(
+            extra.get::<String>("notifications_minimum_severity"),
+            Some("WARNING".to_owned())
+        );
+        assert_eq!(
+            extra.get::<Vec<String>>("notifications_disabled_categories"),
+            Some(vec!["HINT".to_owned(), "DEPRECATION".to_owned()])
+        );
+        assert!(NotificationFilter::new().is_empty());
+        assert!(!filter.is_empty());
+    }
+
+    #[test]
+    fn should_parse_the_notifications_among_gql_statuses() {
+        let record = map(vec![
+            ("_severity", "WARNING".into()),
+            ("_classification", "PERFORMANCE".into()),
+            (
+                "_position",
+                BoltType::Map(map(vec![("line", 2.into()), ("column", 3.into())])),
+            ),
+        ]);
+        let warning = map(vec![
+            ("gql_status", "03N90".into()),
+            (
+                "neo4j_code",
+                "Neo.ClientNotification.Statement.CartesianProduct".into(),
+            ),
+            ("title", "This query builds a cartesian product".into()),
+            ("diagnostic_record", BoltType::Map(record)),
+        ]);
+        let success = map(vec![("gql_status", "00000".into())]);
+        let statuses: BoltList = vec![BoltType::Map(success), BoltType::Map(warning)].into();
+
+        let notifications = Notification::parse_statuses(statuses);
+
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(
+            notifications[0].title,
+            "This query builds a cartesian product"
+        );
+        assert_eq!(notifications[0].severity, Severity::Warning);
+        assert_eq!(notifications[0].position.as_ref().unwrap().column, 3);
+    }
+
+    #[test]
+    fn should_disable_classifications_from_bolt_5_6() {
+        let filter = NotificationFilter::new().disable(NotificationCategory::Hint);
+        let mut old = BoltMap::default();
+        let mut new = BoltMap::default();
+        filter.put(&mut old);
+        filter.put(&mut new);
+
+        adapt_filter(&mut old, Version::V5_4);
+        adapt_filter(&mut new, Version::V5_6);
+
+        assert!(old.get::<Vec<String>>(DISABLED_CATEGORIES).is_some());
+        assert!(new.get::<Vec<String>>(DISABLED_CATEGORIES).is_none());
+        assert_eq!(
+            new.get::<Vec<String>>("notifications_disabled_classifications"),
+            Some(vec!["HINT".to_owned()])
+        );
+    }
+}
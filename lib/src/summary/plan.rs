@@ -0,0 +1,201 @@
+use crate::convert::FromBoltValue;
+use crate::types::*;
+use std::convert::TryFrom;
+
+/// The execution plan of an `EXPLAIN` query, a tree of operators starting at the one producing
+/// the result
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    operator_type: String,
+    identifiers: Vec<String>,
+    arguments: BoltMap,
+    children: Vec<Plan>,
+}
+
+impl Plan {
+    pub(crate) fn parse(plan: BoltMap) -> Plan {
+        Plan {
+            operator_type: plan.get("operatorType").unwrap_or_default(),
+            identifiers: identifiers(&plan),
+            children: children(&plan, Plan::parse),
+            arguments: plan.get("args").unwrap_or_default(),
+        }
+    }
+
+    /// The name of the operator, like `NodeByLabelScan` or `ProduceResults`
+    pub fn operator_type(&self) -> &str {
+        &self.operator_type
+    }
+
+    /// The variables introduced by this operator
+    pub fn identifiers(&self) -> &[String] {
+        &self.identifiers
+    }
+
+    /// An argument of the operator, like `EstimatedRows` or `Details`
+    pub fn argument<T: FromBoltValue>(&self, key: &str) -> Option<T> {
+        self.arguments.get(key)
+    }
+
+    /// The operators feeding into this one
+    pub fn children(&self) -> &[Plan] {
+        &self.children
+    }
+}
+
+/// The execution plan of a `PROFILE` query, where every operator also records the work it did
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfiledPlan {
+    operator_type: String,
+    identifiers: Vec<String>,
+    arguments: BoltMap,
+    children: Vec<ProfiledPlan>,
+    db_hits: i64,
+    rows: i64,
+    page_cache_hits: i64,
+    page_cache_misses: i64,
+    page_cache_hit_ratio: f64,
+    time: i64,
+}
+
+impl ProfiledPlan {
+    pub(crate) fn parse(profile: BoltMap) -> ProfiledPlan {
+        ProfiledPlan {
+            operator_type: profile.get("operatorType").unwrap_or_default(),
+            identifiers: identifiers(&profile),
+            children: children(&profile, ProfiledPlan::parse),
+            db_hits: profile.get("dbHits").unwrap_or(0),
+            rows: profile.get("rows").unwrap_or(0),
+            page_cache_hits: profile.get("pageCacheHits").unwrap_or(0),
+            page_cache_misses: profile.get("pageCacheMisses").unwrap_or(0),
+            page_cache_hit_ratio: profile.get("pageCacheHitRatio").unwrap_or(0.0),
+            time: profile.get("time").unwrap_or(0),
+            arguments: profile.get("args").unwrap_or_default(),
+        }
+    }
+
+    /// The name of the operator, like `NodeByLabelScan` or `ProduceResults`
+    pub fn operator_type(&self) -> &str {
+        &self.operator_type
+    }
+
+    /// The variables introduced by this operator
+    pub fn identifiers(&self) -> &[String] {
+        &self.identifiers
+    }
+
+    /// An argument of the operator, like `EstimatedRows` or `Details`
+    pub fn argument<T: FromBoltValue>(&self, key: &str) -> Option<T> {
+        self.arguments.get(key)
+    }
+
+    /// The operators feeding into this one
+    pub fn children(&self) -> &[ProfiledPlan] {
+        &self.children
+    }
+
+    /// The number of times the operator hit the storage
+    pub fn db_hits(&self) -> i64 {
+        self.db_hits
+    }
+
+    /// The number of rows the operator produced
+    pub fn rows(&self) -> i64 {
+        self.rows
+    }
+
+    pub fn page_cache_hits(&self) -> i64 {
+        self.page_cache_hits
+    }
+
+    pub fn page_cache_misses(&self) -> i64 {
+        self.page_cache_misses
+    }
+
+    pub fn page_cache_hit_ratio(&self) -> f64 {
+        self.page_cache_hit_ratio
+    }
+
+    /// The time spent in the operator, in nanoseconds, if the server reports it
+    pub fn time(&self) -> i64 {
+        self.time
+    }
+}
+
+fn identifiers(plan: &BoltMap) -> Vec<String> {
+    plan.get::<BoltList>("identifiers")
+        .map(Into::into)
+        .unwrap_or_default()
+}
+
+fn children<T>(plan: &BoltMap, parse: fn(BoltMap) -> T) -> Vec<T> {
+    plan.get::<BoltList>("children")
+        .map(|children| {
+            children
+                .into_iter()
+                .filter_map(|child| BoltMap::try_from(child).ok())
+                .map(parse)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: Vec<(&str, BoltType)>) -> BoltMap {
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value))
+            .collect()
+    }
+
+    fn list(values: Vec<BoltType>) -> BoltType {
+        BoltType::List(values.into())
+    }
+
+    #[test]
+    fn should_parse_plan() {
+        let scan = map(vec![
+            ("operatorType", "NodeByLabelScan".into()),
+            ("identifiers", list(vec!["p".into()])),
+            (
+                "args",
+                BoltType::Map(map(vec![("EstimatedRows", 10.0.into())])),
+            ),
+        ]);
+        let plan = Plan::parse(map(vec![
+            ("operatorType", "ProduceResults".into()),
+            ("identifiers", list(vec!["p".into()])),
+            ("children", list(vec![BoltType::Map(scan)])),
+        ]));
+
+        assert_eq!(plan.operator_type(), "ProduceResults");
+        assert_eq!(plan.identifiers(), ["p"]);
+        assert_eq!(plan.children().len(), 1);
+        let child = &plan.children()[0];
+        assert_eq!(child.operator_type(), "NodeByLabelScan");
+        assert_eq!(child.argument::<f64>("EstimatedRows"), Some(10.0));
+        assert!(child.children().is_empty());
+    }
+
+    #[test]
+    fn should_parse_profiled_plan() {
+        let profile = ProfiledPlan::parse(map(vec![
+            ("operatorType", "ProduceResults".into()),
+            ("dbHits", 4.into()),
+            ("rows", 2.into()),
+            ("pageCacheHits", 3.into()),
+            ("pageCacheHitRatio", 0.75.into()),
+        ]));
+
+        assert_eq!(profile.operator_type(), "ProduceResults");
+        assert_eq!(profile.db_hits(), 4);
+        assert_eq!(profile.rows(), 2);
+        assert_eq!(profile.page_cache_hits(), 3);
+        assert_eq!(profile.page_cache_misses(), 0);
+        assert_eq!(profile.page_cache_hit_ratio(), 0.75);
+        assert!(profile.identifiers().is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+/// Awaits `$operation` within a debug span called `$name`, recording how long it took and whether
+/// it succeeded in the `duration_ms`, `status` and `error` fields of the span. Without the
+/// `tracing` feature the operation is just awaited.
+macro_rules! traced {
+    ($name:literal, { $($fields:tt)+ }, $operation:expr) => {{
+        #[cfg(feature = "tracing")]
+        let result = $crate::trace::record(
+            tracing::debug_span!(
+                $name,
+                $($fields)+,
+                duration_ms = tracing::field::Empty,
+                status = tracing::field::Empty,
+                error = tracing::field::Empty,
+            ),
+            $operation,
+        )
+        .await;
+        #[cfg(not(feature = "tracing"))]
+        let result = $operation.await;
+        result
+    }};
+    ($name:literal, $operation:expr) => {{
+        #[cfg(feature = "tracing")]
+        let result = $crate::trace::record(
+            tracing::debug_span!(
+                $name,
+                duration_ms = tracing::field::Empty,
+                status = tracing::field::Empty,
+                error = tracing::field::Empty,
+            ),
+            $operation,
+        )
+        .await;
+        #[cfg(not(feature = "tracing"))]
+        let result = $operation.await;
+        result
+    }};
+}
+
+pub(crate) use traced;
+
+#[cfg(feature = "tracing")]
+pub(crate) async fn record<T, E: std::fmt::Debug>(
+    span: tracing::Span,
+    operation: impl std::future::Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    use tracing::Instrument;
+
+    let start = std::time::Instant::now();
+    let result = operation.instrument(span.clone()).await;
+    span.record("duration_ms", start.elapsed().as_millis() as u64);
+    match &result {
+        Ok(_) => span.record("status", "ok"),
+        Err(error) => span
+            .record("status", "error")
+            .record("error", tracing::field::debug(error)),
+    };
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::{Error, Result};
+
+    #[tokio::test]
+    async fn should_return_the_result_of_the_operation() {
+        let ok: Result<u8> = traced!("neo4rs.test", { address = "localhost:7687" }, async {
+            Ok(1)
+        });
+        let failed: Result<u8> = traced!("neo4rs.test", async { Err(Error::ConnectionError) });
+
+        assert_eq!(ok.unwrap(), 1);
+        assert!(matches!(failed, Err(Error::ConnectionError)));
+    }
+}
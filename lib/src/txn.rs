@@ -1,66 +1,473 @@
+use crate::bookmark::{Bookmark, ManagedBookmarks};
 use crate::config::Config;
 use crate::errors::*;
 use crate::messages::*;
 use crate::pool::*;
 use crate::query::*;
+use crate::routing::AccessMode;
+use crate::rt;
 use crate::stream::*;
+use crate::summary::NotificationFilter;
+use crate::trace::traced;
+use crate::types::*;
+use crate::version::{Feature, Version};
+use log::warn;
+use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
+/// Settings of a transaction, passed to [`Graph::start_txn_with`]
+///
+/// [`Graph::start_txn_with`]: crate::Graph::start_txn_with
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TxOptions {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) metadata: BoltMap,
+    pub(crate) mode: AccessMode,
+    pub(crate) impersonated_user: Option<String>,
+    pub(crate) notifications: NotificationFilter,
+}
+
+impl TxOptions {
+    pub fn new() -> Self {
+        TxOptions::default()
+    }
+
+    /// The server terminates the transaction once it ran for longer than `timeout`, instead of
+    /// the timeout configured on the server
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Marks the transaction as read only, when connected to a cluster it then runs on one of
+    /// its readers instead of the leader
+    pub fn read(mut self) -> Self {
+        self.mode = AccessMode::Read;
+        self
+    }
+
+    /// Runs the transaction as `user` instead of the authenticated user, which needs the
+    /// permission to impersonate it. Requires bolt 4.4 or newer
+    pub fn impersonate(mut self, user: &str) -> Self {
+        self.impersonated_user = Some(user.to_owned());
+        self
+    }
+
+    /// Attaches metadata to the transaction, which shows up in the query log and the output of
+    /// `SHOW TRANSACTIONS`
+    pub fn metadata<K, V>(mut self, metadata: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<BoltString>,
+        V: Into<BoltType>,
+    {
+        for (key, value) in metadata {
+            self.metadata.put(key.into(), value.into());
+        }
+        self
+    }
+
+    /// Filters the notifications the server sends for the queries of the transaction, instead of
+    /// the filter of the config. Requires bolt 5.2 or newer
+    pub fn notification_filter(mut self, filter: NotificationFilter) -> Self {
+        self.notifications = filter;
+        self
+    }
+
+    /// Falls back to the user impersonated by the config, and adds the metadata of the
+    /// [`ConfigBuilder::metadata_context`](crate::ConfigBuilder::metadata_context) the options
+    /// don't set
+    pub(crate) fn with_defaults(mut self, config: &Config) -> Self {
+        if self.impersonated_user.is_none() {
+            self.impersonated_user = config.impersonated_user.clone();
+        }
+        if let Some(context) = &config.metadata_context {
+            for (key, value) in (context.0)().value {
+                if !self.metadata.value.contains_key(&key) {
+                    self.metadata.put(key, value);
+                }
+            }
+        }
+        self
+    }
+
+    /// Fails when the settings can't be sent with the negotiated version
+    pub(crate) fn check(&self, version: Version) -> Result<()> {
+        if self.impersonated_user.is_some() && !version.supports(Feature::Impersonation) {
+            return Err(Error::UnsupportedVersion(
+                "impersonation requires bolt 4.4 or newer".into(),
+            ));
+        }
+        if !self.notifications.is_empty() && !version.supports(Feature::NotificationFiltering) {
+            return Err(Error::UnsupportedVersion(
+                "notification filters require bolt 5.2 or newer".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Computes the metadata added to every transaction and auto-commit query, on the task
+/// beginning the transaction or running the query
+#[derive(Clone)]
+pub(crate) struct MetadataContext(pub(crate) Arc<dyn Fn() -> BoltMap + Send + Sync>);
+
+impl fmt::Debug for MetadataContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MetadataContext")
+    }
+}
+
+/// Where a [`Txn`] stands, see [`Txn::state`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxState {
+    /// Queries can run in the transaction
+    Open,
+    Committed,
+    RolledBack,
+    /// A query or the commit failed, the server rolled the transaction back
+    Failed,
+}
+
 /// A handle which is used to control a transaction, created as a result of [`Graph::start_txn`]
 ///
-/// When a transation is started, a dedicated connection is resered and moved into the handle which
-/// will be released to the connection pool when the [`Txn`] handle is dropped.
+/// When a transaction is started, a dedicated connection is reserved and moved into the handle
+/// which will be released to the connection pool when the [`Txn`] handle is dropped. The
+/// connection is only shared with the streams of the transaction, which pull their rows through
+/// it, so several streams of a transaction can be open at once. It is locked for a single
+/// exchange with the server at a time.
+///
+/// A transaction dropped while still open is rolled back: its connection is reset in the
+/// background before it returns to the pool, or closed when dropped outside of a runtime, so its
+/// locks aren't held by an idle connection and the next borrower doesn't inherit it. The
+/// connection of a failed transaction is reset the same way.
 pub struct Txn {
     config: Config,
     connection: Arc<Mutex<ManagedConnection>>,
+    /// The bookmarks the transaction waited for, superseded by its own once committed
+    bookmarks: Vec<Bookmark>,
+    /// Shared with the other handles of a managed transaction
+    state: Arc<std::sync::Mutex<TxState>>,
 }
 
 impl Txn {
-    pub(crate) async fn new(config: Config, mut connection: ManagedConnection) -> Result<Self> {
-        let begin = BoltRequest::begin();
+    /// Begins a transaction on `config.db`, which is also the database of its queries
+    pub(crate) async fn new(
+        config: Config,
+        mut connection: ManagedConnection,
+        bookmarks: &[Bookmark],
+        options: &TxOptions,
+    ) -> Result<Self> {
+        options.check(connection.version())?;
+        let bookmarks = ManagedBookmarks::or_managed(config.bookmark_manager.as_ref(), bookmarks);
+        let begin = BoltRequest::begin(&config.db, &bookmarks, options);
         match connection.send_recv(begin).await? {
             BoltResponse::SuccessMessage(_) => Ok(Txn {
                 config,
                 connection: Arc::new(Mutex::new(connection)),
+                bookmarks,
+                state: Arc::new(std::sync::Mutex::new(TxState::Open)),
             }),
-            msg => Err(unexpected(msg, "BEGIN")),
+            msg => Err(msg.into_error("BEGIN")),
         }
     }
 
-    /// Runs multiple queries one after the other in the same connection
-    pub async fn run_queries(&self, queries: Vec<Query>) -> Result<()> {
-        for query in queries.into_iter() {
-            self.run(query).await?;
+    /// Another handle of the same transaction, handed to the work of a managed transaction while
+    /// the driver keeps this one to commit or roll back.
+    pub(crate) fn handle(&self) -> Txn {
+        Txn {
+            config: self.config.clone(),
+            connection: self.connection.clone(),
+            bookmarks: self.bookmarks.clone(),
+            state: self.state.clone(),
         }
-        Ok(())
+    }
+
+    /// Whether the transaction was committed, rolled back or failed, as far as its handles
+    /// tell: a stream whose rows fail to be pulled doesn't change the state
+    pub fn state(&self) -> TxState {
+        *self.state.lock().unwrap()
+    }
+
+    /// Whether queries can still run in the transaction
+    pub fn is_open(&self) -> bool {
+        self.state() == TxState::Open
+    }
+
+    fn set_state(&self, state: TxState) {
+        *self.state.lock().unwrap() = state;
+    }
+
+    /// Fails with [`Error::TransactionClosed`] unless the transaction is open
+    fn check_open(&self) -> Result<()> {
+        match self.state() {
+            TxState::Open => Ok(()),
+            state => Err(Error::TransactionClosed(state)),
+        }
+    }
+
+    /// Marks the transaction as failed when `result` is an error the server or the connection
+    /// raised, rather than one raised before the request was sent
+    fn track<T>(&self, result: Result<T>) -> Result<T> {
+        if let Err(error) = &result {
+            if matches!(
+                error,
+                Error::Neo4jError(_) | Error::Cancelled | Error::Timeout
+            ) || error.is_connection_failure()
+            {
+                self.set_state(TxState::Failed);
+            }
+        }
+        result
+    }
+
+    /// Runs multiple queries in the same connection and discards their streams. The queries are
+    /// pipelined: all of them are sent at once and their responses are read afterwards, which
+    /// takes a single network round trip instead of two for each query.
+    ///
+    /// The server ignores the queries following one that fails, the error of the failed query is
+    /// returned.
+    pub async fn run_queries(&self, queries: Vec<Query>) -> Result<()> {
+        self.check_open()?;
+        let queries = queries
+            .into_iter()
+            .map(|query| {
+                self.config
+                    .interceptors
+                    .start(query.in_txn(&self.config.db))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let ran = traced!("neo4rs.run", { db = %self.config.db }, async {
+            let mut connection = self.connection.lock().await;
+            let outcomes = run_pipelined(&queries, &self.config, &mut connection).await?;
+            // the queries ignored after a failure end along with the transaction
+            for query in &queries[outcomes.len()..] {
+                let db = query.database(&self.config);
+                if let Some(intercepted) = self.config.interceptors.follow(query.cypher(), db) {
+                    intercepted.end(Err(&Error::TransactionClosed(TxState::Failed)));
+                }
+            }
+            outcomes.into_iter().collect()
+        });
+        self.track(ran)
     }
 
     /// Runs a single query and discards the stream.
     pub async fn run(&self, q: Query) -> Result<()> {
-        q.run(&self.config, self.connection.clone()).await
+        self.check_open()?;
+        let mut connection = self.connection.lock().await;
+        let ran = q
+            .in_txn(&self.config.db)
+            .run(&self.config, &mut connection)
+            .await;
+        self.track(ran)
     }
 
     /// Executes a query and returns a [`RowStream`]
     pub async fn execute(&self, q: Query) -> Result<RowStream> {
-        q.execute(&self.config, self.connection.clone()).await
+        self.check_open()?;
+        let executed = q
+            .in_txn(&self.config.db)
+            .execute(&self.config, Source::Txn(self.connection.clone()))
+            .await;
+        self.track(executed)
     }
 
-    /// Commits the transaction in progress
-    pub async fn commit(self) -> Result<()> {
+    /// Commits the transaction in progress, returning the bookmark of the transaction when the
+    /// server provides one
+    pub async fn commit(self) -> Result<Option<Bookmark>> {
+        self.check_open()?;
         let commit = BoltRequest::commit();
-        match self.connection.lock().await.send_recv(commit).await? {
-            BoltResponse::SuccessMessage(_) => Ok(()),
-            msg => Err(unexpected(msg, "COMMIT")),
-        }
+        let committed = traced!("neo4rs.commit", { db = %self.config.db }, async {
+            let mut connection = self.connection.lock().await;
+            match connection.close_results_and_send(commit).await? {
+                BoltResponse::SuccessMessage(success) => {
+                    let bookmark = success.get::<String>("bookmark").map(Bookmark::from);
+                    if let (Some(manager), Some(bookmark)) =
+                        (&self.config.bookmark_manager, &bookmark)
+                    {
+                        manager.0.update(&self.bookmarks, bookmark.clone());
+                    }
+                    Ok(bookmark)
+                }
+                msg => Err(msg.into_error("COMMIT")),
+            }
+        });
+        self.set_state(match committed {
+            Ok(_) => TxState::Committed,
+            Err(_) => TxState::Failed,
+        });
+        committed
     }
 
-    /// rollback/abort the current transaction
+    /// rollback/abort the current transaction. A failed transaction was already rolled back by
+    /// the server, its connection is only reset.
     pub async fn rollback(self) -> Result<()> {
+        match self.state() {
+            TxState::Open => {}
+            TxState::Failed => {
+                let reset = self.connection.lock().await.reset().await;
+                self.set_state(TxState::RolledBack);
+                return reset;
+            }
+            state => return Err(Error::TransactionClosed(state)),
+        }
         let rollback = BoltRequest::rollback();
-        match self.connection.lock().await.send_recv(rollback).await? {
-            BoltResponse::SuccessMessage(_) => Ok(()),
-            msg => Err(unexpected(msg, "ROLLBACK")),
+        let rolled_back = traced!("neo4rs.rollback", { db = %self.config.db }, async {
+            let mut connection = self.connection.lock().await;
+            match connection.close_results_and_send(rollback).await? {
+                BoltResponse::SuccessMessage(_) => Ok(()),
+                msg => Err(msg.into_error("ROLLBACK")),
+            }
+        });
+        self.set_state(match rolled_back {
+            Ok(_) => TxState::RolledBack,
+            Err(_) => TxState::Failed,
+        });
+        rolled_back
+    }
+}
+
+impl Drop for Txn {
+    fn drop(&mut self) {
+        // the other handles of a managed transaction share its state
+        if Arc::strong_count(&self.state) > 1 {
+            return;
+        }
+        match self.state() {
+            TxState::Open => {
+                warn!("rolling back a transaction dropped without being committed or rolled back")
+            }
+            // the server rolled the transaction back, but ignores the messages until a RESET
+            TxState::Failed => {}
+            TxState::Committed | TxState::RolledBack => return,
+        }
+        let connection = self.connection.clone();
+        let reset = async move {
+            // a failed reset breaks the connection, which the pool then closes
+            let _ = connection.lock().await.reset().await;
+        };
+        if !rt::try_spawn(reset) {
+            if let Ok(mut connection) = self.connection.try_lock() {
+                connection.mark_broken();
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripted::{received, serve, Recorder};
+    use crate::{config, query};
+
+    #[tokio::test]
+    async fn should_commit_transactions() {
+        let (graph, mut receiver) = serve(config()).await;
+        let txn = graph.start_txn().await.unwrap();
+        let handle = txn.handle();
+
+        txn.commit().await.unwrap();
+
+        assert_eq!(handle.state(), TxState::Committed);
+        assert_eq!(received(&mut receiver, 3).await, [0x01, 0x11, 0x12]);
+    }
+
+    #[tokio::test]
+    async fn should_close_transactions_failing_on_a_query() {
+        let (graph, mut receiver) = serve(config()).await;
+        let txn = graph.start_txn().await.unwrap();
+
+        assert!(txn.run(query("FAIL")).await.is_err());
+        assert_eq!(txn.state(), TxState::Failed);
+        assert!(matches!(
+            txn.run(query("RETURN 1")).await,
+            Err(Error::TransactionClosed(TxState::Failed))
+        ));
+        // HELLO, BEGIN, RUN and DISCARD
+        assert_eq!(received(&mut receiver, 4).await, [0x01, 0x11, 0x10, 0x2F]);
+    }
+
+    #[tokio::test]
+    async fn should_reset_failed_transactions_instead_of_rolling_back() {
+        let (graph, mut receiver) = serve(config()).await;
+        let txn = graph.start_txn().await.unwrap();
+        assert!(txn.run(query("FAIL")).await.is_err());
+
+        txn.rollback().await.unwrap();
+
+        let received = received(&mut receiver, 5).await;
+        assert_eq!(received, [0x01, 0x11, 0x10, 0x2F, 0x0F]);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn should_reset_transactions_dropped_while_open() {
+        let (graph, mut receiver) = serve(config()).await;
+        let txn = graph.start_txn().await.unwrap();
+
+        drop(txn);
+
+        assert_eq!(received(&mut receiver, 3).await, [0x01, 0x11, 0x0F]);
+    }
+
+    #[tokio::test]
+    async fn should_reset_failed_transactions_when_dropped() {
+        let (graph, mut receiver) = serve(config()).await;
+        let txn = graph.start_txn().await.unwrap();
+        assert!(txn.run(query("FAIL")).await.is_err());
+
+        drop(txn);
+
+        let received = received(&mut receiver, 5).await;
+        assert_eq!(received, [0x01, 0x11, 0x10, 0x2F, 0x0F]);
+    }
+
+    #[test]
+    fn should_break_the_connection_of_transactions_dropped_outside_of_a_runtime() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let (txn, _receiver) = runtime.block_on(async {
+            let (graph, receiver) = serve(config()).await;
+            (graph.start_txn().await.unwrap(), receiver)
+        });
+        let connection = txn.connection.clone();
+
+        drop(txn);
+
+        assert!(connection.try_lock().unwrap().is_broken());
+    }
+
+    #[tokio::test]
+    async fn should_leave_managed_transactions_to_their_owner() {
+        let (graph, mut receiver) = serve(config()).await;
+        let txn = graph.start_txn().await.unwrap();
+
+        drop(txn.handle());
+        txn.commit().await.unwrap();
+
+        assert_eq!(received(&mut receiver, 3).await, [0x01, 0x11, 0x12]);
+    }
+
+    #[tokio::test]
+    async fn should_end_the_queries_ignored_after_a_failure() {
+        let recorder = Recorder::default();
+        let (graph, _receiver) = serve(config().interceptor(recorder.clone())).await;
+        let txn = graph.start_txn().await.unwrap();
+        let queries = vec![query("CREATE (a)"), query("FAIL"), query("CREATE (c)")];
+
+        assert!(txn.run_queries(queries).await.is_err());
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                "start CREATE (a)",
+                "start FAIL",
+                "start CREATE (c)",
+                "end CREATE (a): ok",
+                "end FAIL: failed",
+                "end CREATE (c): failed",
+            ]
+        );
+    }
+}
@@ -63,6 +63,31 @@ pub enum BoltType {
     DateTimeZoneId(BoltDateTimeZoneId),
 }
 
+/// A part of a path passed to [`BoltType::at`]
+enum Step<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Splits a path like `items[0].name` into its keys and indexes
+fn steps(path: &str) -> Option<Vec<Step<'_>>> {
+    let mut steps = Vec::new();
+    for segment in path.split('.') {
+        let (key, mut indexes) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        if !key.is_empty() {
+            steps.push(Step::Key(key));
+        } else if indexes.is_empty() {
+            return None;
+        }
+        while !indexes.is_empty() {
+            let (index, rest) = indexes.strip_prefix('[')?.split_once(']')?;
+            steps.push(Step::Index(index.parse().ok()?));
+            indexes = rest;
+        }
+    }
+    Some(steps)
+}
+
 impl Display for BoltType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let value = match self {
@@ -74,6 +99,57 @@ impl Display for BoltType {
 }
 
 impl BoltType {
+    /// The name of the type of the value, for errors
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            BoltType::String(_) => "string",
+            BoltType::Boolean(_) => "boolean",
+            BoltType::Map(_) => "map",
+            BoltType::Null(_) => "null",
+            BoltType::Integer(_) => "integer",
+            BoltType::Float(_) => "float",
+            BoltType::List(_) => "list",
+            BoltType::Node(_) => "node",
+            BoltType::Relation(_) | BoltType::UnboundedRelation(_) => "relationship",
+            BoltType::Point2D(_) => "2d point",
+            BoltType::Point3D(_) => "3d point",
+            BoltType::Bytes(_) => "bytes",
+            BoltType::Path(_) => "path",
+            BoltType::Duration(_) => "duration",
+            BoltType::Date(_) => "date",
+            BoltType::Time(_) => "time",
+            BoltType::LocalTime(_) => "local time",
+            BoltType::DateTime(_) => "date time",
+            BoltType::LocalDateTime(_) => "local date time",
+            BoltType::DateTimeZoneId(_) => "date time with a zone id",
+        }
+    }
+
+    /// The value at `path` within this value, like `items[0].name` for the `name` of the first
+    /// of the `items` of a map. The keys of maps and the properties of nodes and relationships
+    /// are separated by dots, the indexes of lists are in brackets. `None` when a part of the
+    /// path is missing or the path is malformed.
+    pub fn at(&self, path: &str) -> Option<&BoltType> {
+        steps(path)?
+            .iter()
+            .try_fold(self, |value, step| value.step(step))
+    }
+
+    fn step(&self, step: &Step<'_>) -> Option<&BoltType> {
+        let properties = match (self, step) {
+            (BoltType::List(list), Step::Index(index)) => return list.get(*index),
+            (BoltType::Map(map), Step::Key(_)) => map,
+            (BoltType::Node(node), Step::Key(_)) => &node.properties,
+            (BoltType::Relation(relation), Step::Key(_)) => &relation.properties,
+            (BoltType::UnboundedRelation(relation), Step::Key(_)) => &relation.properties,
+            _ => return None,
+        };
+        match step {
+            Step::Key(key) => properties.value.get(&BoltString::from(*key)),
+            Step::Index(_) => None,
+        }
+    }
+
     pub fn into_bytes(self, version: Version) -> Result<Bytes> {
         match self {
             BoltType::Null(t) => t.into_bytes(version),
@@ -100,7 +176,7 @@ impl BoltType {
         }
     }
 
-    fn parse(version: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltType> {
+    pub(crate) fn parse(version: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltType> {
         let bolt_type = match input {
             input if BoltInteger::can_parse(version, input.clone()) => {
                 BoltType::Integer(BoltInteger::parse(version, input)?)
@@ -1,8 +1,11 @@
+#[cfg(any(feature = "chrono", feature = "time"))]
 use crate::errors::Error;
 use crate::types::*;
+#[cfg(feature = "chrono")]
 use chrono::{Duration, NaiveDate};
 use neo4rs_macros::BoltStruct;
-use std::convert::TryInto;
+#[cfg(any(feature = "chrono", feature = "time"))]
+use std::convert::TryFrom;
 
 #[derive(Debug, PartialEq, Clone, BoltStruct)]
 #[signature(0xB1, 0x44)]
@@ -10,27 +13,91 @@ pub struct BoltDate {
     days: BoltInteger,
 }
 
-impl Into<BoltDate> for NaiveDate {
-    fn into(self) -> BoltDate {
-        let epoch = NaiveDate::from_ymd(1970, 1, 1);
-        let days = (self - epoch).num_days().into();
+impl BoltDate {
+    /// The days since the unix epoch
+    #[cfg(feature = "arrow")]
+    pub(crate) fn days(&self) -> i64 {
+        self.days.value
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<NaiveDate> for BoltDate {
+    fn from(value: NaiveDate) -> Self {
+        let epoch = NaiveDate::default();
+        let days = (value - epoch).num_days().into();
         BoltDate { days }
     }
 }
 
-impl TryInto<NaiveDate> for BoltDate {
+#[cfg(feature = "chrono")]
+impl TryFrom<BoltDate> for NaiveDate {
     type Error = Error;
 
-    fn try_into(self) -> Result<NaiveDate> {
-        let epoch = NaiveDate::from_ymd(1970, 1, 1);
-        let days = Duration::days(self.days.value);
+    fn try_from(value: BoltDate) -> Result<Self> {
+        let epoch = NaiveDate::default();
+        let days = Duration::days(value.days.value);
         epoch
             .checked_add_signed(days)
             .ok_or(Error::ConverstionError)
     }
 }
 
-#[cfg(test)]
+/// The julian day of 1970-01-01
+#[cfg(feature = "time")]
+const UNIX_EPOCH_JULIAN_DAY: i64 = 2_440_588;
+
+#[cfg(feature = "time")]
+impl From<::time::Date> for BoltDate {
+    fn from(value: ::time::Date) -> Self {
+        let days = (value.to_julian_day() as i64 - UNIX_EPOCH_JULIAN_DAY).into();
+        BoltDate { days }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltDate> for ::time::Date {
+    type Error = Error;
+
+    fn try_from(value: BoltDate) -> Result<Self> {
+        let julian_day = (value.days.value + UNIX_EPOCH_JULIAN_DAY)
+            .try_into()
+            .map_err(|_| Error::ConverstionError)?;
+        ::time::Date::from_julian_day(julian_day).map_err(|_| Error::ConverstionError)
+    }
+}
+
+#[cfg(all(test, feature = "time"))]
+mod time_tests {
+    use super::*;
+    use crate::version::Version;
+    use ::time::{Date, Month};
+    use bytes::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn should_convert_time_dates() {
+        let date = Date::from_calendar_date(2010, Month::January, 1).unwrap();
+
+        let bolt: BoltDate = date.into();
+        assert_eq!(
+            bolt.into_bytes(Version::V4_1).unwrap(),
+            Bytes::from_static(&[0xB1, 0x44, 0xC9, 0x39, 0x12])
+        );
+
+        let bytes = Rc::new(RefCell::new(Bytes::from_static(&[
+            0xB1, 0x44, 0xC9, 0x39, 0x12,
+        ])));
+        let parsed: Date = BoltDate::parse(Version::V4_1, bytes)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(parsed, date);
+    }
+}
+
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use super::*;
     use crate::version::Version;
@@ -40,7 +107,7 @@ mod tests {
 
     #[test]
     fn should_serialize_a_date() {
-        let date: BoltDate = NaiveDate::from_ymd(2010, 1, 1).into();
+        let date: BoltDate = NaiveDate::from_ymd_opt(2010, 1, 1).unwrap().into();
         assert_eq!(
             date.into_bytes(Version::V4_1).unwrap(),
             Bytes::from_static(&[0xB1, 0x44, 0xC9, 0x39, 0x12])
@@ -1,11 +1,26 @@
 use crate::errors::Error;
 use crate::types::*;
-use chrono::{DateTime, FixedOffset, NaiveDateTime, Offset, Timelike};
+use crate::version::Version;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Offset, TimeZone, Timelike, Utc};
+#[cfg(feature = "chrono")]
+use chrono_tz::Tz;
 use neo4rs_macros::BoltStruct;
-use std::convert::TryInto;
+use std::cell::RefCell;
+#[cfg(any(feature = "chrono", feature = "time"))]
+use std::convert::TryFrom;
+use std::rc::Rc;
 
-#[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB3, 0x46)]
+pub const DATE_TIME: u8 = 0x46;
+pub const DATE_TIME_UTC: u8 = 0x49;
+pub const DATE_TIME_ZONE_ID: u8 = 0x66;
+pub const DATE_TIME_ZONE_ID_UTC: u8 = 0x69;
+
+/// A date time with an offset.
+///
+/// `seconds` always holds the local seconds since epoch (the legacy bolt 4 encoding), values
+/// received in the UTC based encoding used from bolt 5.0 onwards are normalized while parsing.
+#[derive(Debug, PartialEq, Clone)]
 pub struct BoltDateTime {
     seconds: BoltInteger,
     nanoseconds: BoltInteger,
@@ -19,40 +34,240 @@ pub struct BoltLocalDateTime {
     nanoseconds: BoltInteger,
 }
 
-#[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB3, 0x66)]
+/// A date time with a named time zone.
+///
+/// Like [`BoltDateTime`], `seconds` always holds the local seconds since epoch, converting from
+/// and to the UTC based encoding of bolt 5.0 resolves the zone using the tz database.
+#[derive(Debug, PartialEq, Clone)]
 pub struct BoltDateTimeZoneId {
     seconds: BoltInteger,
     nanoseconds: BoltInteger,
     tz_id: BoltString,
 }
 
-impl Into<BoltDateTimeZoneId> for (NaiveDateTime, &str) {
-    fn into(self) -> BoltDateTimeZoneId {
-        let seconds = self.0.timestamp().into();
-        let nanoseconds = (self.0.timestamp_subsec_nanos() as i64).into();
+/// The nanoseconds since the unix epoch, of the UTC instant for date times with an offset
+#[cfg(feature = "arrow")]
+impl BoltDateTime {
+    pub(crate) fn utc_nanos(&self) -> Option<i64> {
+        (self.seconds.value - self.tz_offset_seconds.value)
+            .checked_mul(1_000_000_000)?
+            .checked_add(self.nanoseconds.value)
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl BoltLocalDateTime {
+    pub(crate) fn local_nanos(&self) -> Option<i64> {
+        self.seconds
+            .value
+            .checked_mul(1_000_000_000)?
+            .checked_add(self.nanoseconds.value)
+    }
+}
+
+fn uses_utc_encoding(version: Version) -> bool {
+    version >= Version::V5_0
+}
+
+fn can_parse_struct(input: Rc<RefCell<Bytes>>, signatures: [u8; 2]) -> bool {
+    let input = input.borrow();
+    input.len() >= 2 && input[0] == 0xB3 && signatures.contains(&input[1])
+}
+
+#[cfg(feature = "chrono")]
+fn time_zone(tz_id: &BoltString) -> Result<Tz> {
+    tz_id
+        .parse::<Tz>()
+        .map_err(|_| Error::DeserializationError(format!("unknown time zone {}", tz_id)))
+}
+
+/// The offset of the zone in seconds at the given seconds since epoch in UTC
+#[cfg(feature = "chrono")]
+fn offset_at_utc(tz_id: &BoltString, seconds: i64) -> Result<i64> {
+    let utc = DateTime::from_timestamp(seconds, 0)
+        .ok_or(Error::ConverstionError)?
+        .naive_utc();
+    let offset = time_zone(tz_id)?.offset_from_utc_datetime(&utc).fix();
+    Ok(offset.local_minus_utc() as i64)
+}
+
+/// The offset of the zone in seconds at the given local seconds since epoch
+#[cfg(feature = "chrono")]
+fn offset_at_local(tz_id: &BoltString, seconds: i64) -> Result<i64> {
+    let local = DateTime::from_timestamp(seconds, 0)
+        .ok_or(Error::ConverstionError)?
+        .naive_utc();
+    let offset = time_zone(tz_id)?
+        .offset_from_local_datetime(&local)
+        .earliest()
+        .ok_or(Error::ConverstionError)?
+        .fix();
+    Ok(offset.local_minus_utc() as i64)
+}
+
+/// Named zones are resolved with the tz database of chrono-tz
+#[cfg(not(feature = "chrono"))]
+fn offset_at_utc(tz_id: &BoltString, _seconds: i64) -> Result<i64> {
+    Err(Error::DeserializationError(format!(
+        "resolving the time zone {} requires the chrono feature",
+        tz_id
+    )))
+}
+
+#[cfg(not(feature = "chrono"))]
+fn offset_at_local(tz_id: &BoltString, _seconds: i64) -> Result<i64> {
+    Err(Error::SerializationError(format!(
+        "resolving the time zone {} requires the chrono feature",
+        tz_id
+    )))
+}
+
+impl BoltDateTime {
+    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
+        can_parse_struct(input, [DATE_TIME, DATE_TIME_UTC])
+    }
+
+    pub fn parse(version: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltDateTime> {
+        let _marker = input.borrow_mut().get_u8();
+        let signature = input.borrow_mut().get_u8();
+        let seconds = BoltInteger::parse(version, input.clone())?;
+        let nanoseconds = BoltInteger::parse(version, input.clone())?;
+        let tz_offset_seconds = BoltInteger::parse(version, input)?;
+        let seconds = if signature == DATE_TIME_UTC {
+            seconds + tz_offset_seconds.clone()
+        } else {
+            seconds
+        };
+        Ok(BoltDateTime {
+            seconds,
+            nanoseconds,
+            tz_offset_seconds,
+        })
+    }
+
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        let (signature, seconds) = if uses_utc_encoding(version) {
+            (DATE_TIME_UTC, self.seconds - self.tz_offset_seconds.clone())
+        } else {
+            (DATE_TIME, self.seconds)
+        };
+        let mut bytes = BytesMut::new();
+        bytes.put_u8(0xB3);
+        bytes.put_u8(signature);
+        bytes.put(seconds.into_bytes(version)?);
+        bytes.put(self.nanoseconds.into_bytes(version)?);
+        bytes.put(self.tz_offset_seconds.into_bytes(version)?);
+        Ok(bytes.freeze())
+    }
+}
+
+impl BoltDateTimeZoneId {
+    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
+        can_parse_struct(input, [DATE_TIME_ZONE_ID, DATE_TIME_ZONE_ID_UTC])
+    }
+
+    pub fn parse(version: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltDateTimeZoneId> {
+        let _marker = input.borrow_mut().get_u8();
+        let signature = input.borrow_mut().get_u8();
+        let seconds = BoltInteger::parse(version, input.clone())?;
+        let nanoseconds = BoltInteger::parse(version, input.clone())?;
+        let tz_id = BoltString::parse(version, input)?;
+        let seconds = if signature == DATE_TIME_ZONE_ID_UTC {
+            (seconds.value + offset_at_utc(&tz_id, seconds.value)?).into()
+        } else {
+            seconds
+        };
+        Ok(BoltDateTimeZoneId {
+            seconds,
+            nanoseconds,
+            tz_id,
+        })
+    }
+
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        let (signature, seconds) = if uses_utc_encoding(version) {
+            let seconds = self.seconds.value - offset_at_local(&self.tz_id, self.seconds.value)?;
+            (DATE_TIME_ZONE_ID_UTC, seconds.into())
+        } else {
+            (DATE_TIME_ZONE_ID, self.seconds)
+        };
+        let mut bytes = BytesMut::new();
+        bytes.put_u8(0xB3);
+        bytes.put_u8(signature);
+        bytes.put(BoltInteger::into_bytes(seconds, version)?);
+        bytes.put(self.nanoseconds.into_bytes(version)?);
+        bytes.put(self.tz_id.into_bytes(version)?);
+        Ok(bytes.freeze())
+    }
+}
+
+/// Fails for seconds and nanoseconds out of the range of chrono
+#[cfg(feature = "chrono")]
+fn utc_date_time(seconds: i64, nanoseconds: i64) -> Result<DateTime<Utc>> {
+    let nanoseconds = u32::try_from(nanoseconds).map_err(|_| Error::ConverstionError)?;
+    DateTime::from_timestamp(seconds, nanoseconds).ok_or(Error::ConverstionError)
+}
+
+/// Fails for offsets of a day or more
+#[cfg(feature = "chrono")]
+pub(crate) fn fixed_offset(seconds: i64) -> Result<FixedOffset> {
+    i32::try_from(seconds)
+        .ok()
+        .and_then(FixedOffset::east_opt)
+        .ok_or(Error::ConverstionError)
+}
+
+#[cfg(feature = "chrono")]
+impl From<(NaiveDateTime, &str)> for BoltDateTimeZoneId {
+    fn from(value: (NaiveDateTime, &str)) -> Self {
+        let seconds = value.0.and_utc().timestamp().into();
+        let nanoseconds = (value.0.and_utc().timestamp_subsec_nanos() as i64).into();
         BoltDateTimeZoneId {
             seconds,
             nanoseconds,
-            tz_id: self.1.into(),
+            tz_id: value.1.into(),
         }
     }
 }
 
-impl TryInto<(NaiveDateTime, String)> for BoltDateTimeZoneId {
+#[cfg(feature = "chrono")]
+impl TryFrom<BoltDateTimeZoneId> for (NaiveDateTime, String) {
     type Error = Error;
 
-    fn try_into(self) -> Result<(NaiveDateTime, String)> {
-        let datetime =
-            NaiveDateTime::from_timestamp(self.seconds.value, self.nanoseconds.value as u32);
-        Ok((datetime, self.tz_id.into()))
+    fn try_from(value: BoltDateTimeZoneId) -> Result<Self> {
+        let datetime = utc_date_time(value.seconds.value, value.nanoseconds.value)?;
+        Ok((datetime.naive_utc(), value.tz_id.into()))
+    }
+}
+
+/// Keeps the name of the zone, so the offset follows its daylight saving time rules
+#[cfg(feature = "chrono")]
+impl From<DateTime<Tz>> for BoltDateTimeZoneId {
+    fn from(value: DateTime<Tz>) -> Self {
+        (value.naive_local(), value.timezone().name()).into()
+    }
+}
+
+/// Fails for zones unknown to chrono-tz, local times repeated when the clocks go back resolve to
+/// the earliest of them
+#[cfg(feature = "chrono")]
+impl TryFrom<BoltDateTimeZoneId> for DateTime<Tz> {
+    type Error = Error;
+
+    fn try_from(value: BoltDateTimeZoneId) -> Result<Self> {
+        let zone = time_zone(&value.tz_id)?;
+        let (local, _) = <(NaiveDateTime, String)>::try_from(value)?;
+        zone.from_local_datetime(&local)
+            .earliest()
+            .ok_or(Error::ConverstionError)
     }
 }
 
-impl Into<BoltLocalDateTime> for NaiveDateTime {
-    fn into(self) -> BoltLocalDateTime {
-        let seconds = self.timestamp().into();
-        let nanoseconds = (self.nanosecond() as i64).into();
+#[cfg(feature = "chrono")]
+impl From<NaiveDateTime> for BoltLocalDateTime {
+    fn from(value: NaiveDateTime) -> Self {
+        let seconds = value.and_utc().timestamp().into();
+        let nanoseconds = (value.nanosecond() as i64).into();
 
         BoltLocalDateTime {
             seconds,
@@ -61,22 +276,22 @@ impl Into<BoltLocalDateTime> for NaiveDateTime {
     }
 }
 
-impl TryInto<NaiveDateTime> for BoltLocalDateTime {
+#[cfg(feature = "chrono")]
+impl TryFrom<BoltLocalDateTime> for NaiveDateTime {
     type Error = Error;
 
-    fn try_into(self) -> Result<NaiveDateTime> {
-        Ok(NaiveDateTime::from_timestamp(
-            self.seconds.value,
-            self.nanoseconds.value as u32,
-        ))
+    fn try_from(value: BoltLocalDateTime) -> Result<Self> {
+        let datetime = utc_date_time(value.seconds.value, value.nanoseconds.value)?;
+        Ok(datetime.naive_utc())
     }
 }
 
-impl Into<BoltDateTime> for DateTime<FixedOffset> {
-    fn into(self) -> BoltDateTime {
-        let seconds = (self.timestamp() + self.offset().fix().local_minus_utc() as i64).into();
-        let nanoseconds = (self.nanosecond() as i64).into();
-        let tz_offset_seconds = self.offset().fix().local_minus_utc().into();
+#[cfg(feature = "chrono")]
+impl From<DateTime<FixedOffset>> for BoltDateTime {
+    fn from(value: DateTime<FixedOffset>) -> Self {
+        let seconds = (value.timestamp() + value.offset().fix().local_minus_utc() as i64).into();
+        let nanoseconds = (value.nanosecond() as i64).into();
+        let tz_offset_seconds = value.offset().fix().local_minus_utc().into();
 
         BoltDateTime {
             seconds,
@@ -86,20 +301,151 @@ impl Into<BoltDateTime> for DateTime<FixedOffset> {
     }
 }
 
-impl TryInto<DateTime<FixedOffset>> for BoltDateTime {
+#[cfg(feature = "chrono")]
+impl TryFrom<BoltDateTime> for DateTime<FixedOffset> {
+    type Error = Error;
+
+    fn try_from(value: BoltDateTime) -> Result<Self> {
+        let offset = fixed_offset(value.tz_offset_seconds.value)?;
+        let seconds = value.seconds.value - value.tz_offset_seconds.value;
+        let datetime = utc_date_time(seconds, value.nanoseconds.value)?;
+        Ok(datetime.with_timezone(&offset))
+    }
+}
+
+#[cfg(feature = "time")]
+fn offset_date_time(seconds: i64, nanoseconds: i64) -> Result<::time::OffsetDateTime> {
+    ::time::OffsetDateTime::from_unix_timestamp(seconds)
+        .and_then(|datetime| datetime.replace_nanosecond(nanoseconds as u32))
+        .map_err(|_| Error::ConverstionError)
+}
+
+#[cfg(feature = "time")]
+fn primitive_date_time(seconds: i64, nanoseconds: i64) -> Result<::time::PrimitiveDateTime> {
+    let datetime = offset_date_time(seconds, nanoseconds)?;
+    Ok(::time::PrimitiveDateTime::new(
+        datetime.date(),
+        datetime.time(),
+    ))
+}
+
+#[cfg(feature = "time")]
+impl From<(::time::PrimitiveDateTime, &str)> for BoltDateTimeZoneId {
+    fn from(value: (::time::PrimitiveDateTime, &str)) -> Self {
+        let datetime = value.0.assume_utc();
+        BoltDateTimeZoneId {
+            seconds: datetime.unix_timestamp().into(),
+            nanoseconds: (datetime.nanosecond() as i64).into(),
+            tz_id: value.1.into(),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltDateTimeZoneId> for (::time::PrimitiveDateTime, String) {
+    type Error = Error;
+
+    fn try_from(value: BoltDateTimeZoneId) -> Result<Self> {
+        let datetime = primitive_date_time(value.seconds.value, value.nanoseconds.value)?;
+        Ok((datetime, value.tz_id.into()))
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<::time::PrimitiveDateTime> for BoltLocalDateTime {
+    fn from(value: ::time::PrimitiveDateTime) -> Self {
+        let datetime = value.assume_utc();
+        BoltLocalDateTime {
+            seconds: datetime.unix_timestamp().into(),
+            nanoseconds: (datetime.nanosecond() as i64).into(),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltLocalDateTime> for ::time::PrimitiveDateTime {
+    type Error = Error;
+
+    fn try_from(value: BoltLocalDateTime) -> Result<Self> {
+        primitive_date_time(value.seconds.value, value.nanoseconds.value)
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<::time::OffsetDateTime> for BoltDateTime {
+    fn from(value: ::time::OffsetDateTime) -> Self {
+        let offset = value.offset().whole_seconds() as i64;
+        BoltDateTime {
+            seconds: (value.unix_timestamp() + offset).into(),
+            nanoseconds: (value.nanosecond() as i64).into(),
+            tz_offset_seconds: offset.into(),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltDateTime> for ::time::OffsetDateTime {
     type Error = Error;
 
-    fn try_into(self) -> Result<DateTime<FixedOffset>> {
-        let seconds = self.seconds.value - self.tz_offset_seconds.value;
-        let datetime = NaiveDateTime::from_timestamp(seconds, self.nanoseconds.value as u32);
-        Ok(DateTime::from_utc(
-            datetime,
-            FixedOffset::east(self.tz_offset_seconds.value as i32),
-        ))
+    fn try_from(value: BoltDateTime) -> Result<Self> {
+        let offset = ::time::UtcOffset::from_whole_seconds(value.tz_offset_seconds.value as i32)
+            .map_err(|_| Error::ConverstionError)?;
+        let seconds = value.seconds.value - value.tz_offset_seconds.value;
+        Ok(offset_date_time(seconds, value.nanoseconds.value)?.to_offset(offset))
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "time"))]
+mod time_tests {
+    use super::*;
+    use ::time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+    fn local() -> PrimitiveDateTime {
+        let date = Date::from_calendar_date(2015, Month::July, 1).unwrap();
+        PrimitiveDateTime::new(date, Time::from_hms_milli(8, 59, 59, 123).unwrap())
+    }
+
+    #[test]
+    fn should_convert_time_datetimes() {
+        let date = Date::from_calendar_date(2015, Month::June, 24).unwrap();
+        let offset = UtcOffset::from_whole_seconds(3600).unwrap();
+        let datetime =
+            PrimitiveDateTime::new(date, Time::from_hms(12, 50, 35).unwrap()).assume_offset(offset);
+        let bytes = Bytes::from_static(&[
+            0xB3, 0x46, 0xCA, 0x55, 0x8A, 0xA7, 0x9B, 0x00, 0xC9, 0x0E, 0x10,
+        ]);
+
+        let bolt: BoltDateTime = datetime.into();
+        assert_eq!(bolt.into_bytes(Version::V4_1).unwrap(), bytes);
+
+        let parsed: OffsetDateTime =
+            BoltDateTime::parse(Version::V4_1, Rc::new(RefCell::new(bytes)))
+                .unwrap()
+                .try_into()
+                .unwrap();
+        assert_eq!(parsed, datetime);
+        assert_eq!(parsed.offset(), offset);
+    }
+
+    #[test]
+    fn should_convert_time_local_datetimes() {
+        let bolt: BoltLocalDateTime = local().into();
+        let parsed: PrimitiveDateTime = bolt.try_into().unwrap();
+
+        assert_eq!(parsed, local());
+    }
+
+    #[test]
+    fn should_convert_time_datetimes_with_zoneid() {
+        let bolt: BoltDateTimeZoneId = (local(), "Europe/Paris").into();
+        let (parsed, zone): (PrimitiveDateTime, String) = bolt.try_into().unwrap();
+
+        assert_eq!(parsed, local());
+        assert_eq!(zone, "Europe/Paris");
+    }
+}
+
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use super::*;
     use crate::version::Version;
@@ -187,12 +533,106 @@ mod tests {
             0x75, 0x72, 0x6F, 0x70, 0x65, 0x2F, 0x50, 0x61, 0x72, 0x69, 0x73,
         ])));
 
-        let (datetime, zone_id) = BoltDateTimeZoneId::parse(Version::V4_1, bytes)
+        let (datetime, zone_id): (NaiveDateTime, String) =
+            BoltDateTimeZoneId::parse(Version::V4_1, bytes)
+                .unwrap()
+                .try_into()
+                .unwrap();
+
+        assert_eq!(datetime.to_string(), "2015-07-01 08:59:60.123");
+        assert_eq!(zone_id, "Europe/Paris");
+    }
+
+    #[test]
+    fn should_serialize_a_utc_datetime() {
+        let date: BoltDateTime = DateTime::parse_from_rfc2822("Wed, 24 Jun 2015 12:50:35 +0100")
+            .unwrap()
+            .into();
+
+        assert_eq!(
+            date.into_bytes(Version::V5_0).unwrap(),
+            Bytes::from_static(&[
+                0xB3, 0x49, 0xCA, 0x55, 0x8A, 0x99, 0x8B, 0x00, 0xC9, 0x0E, 0x10,
+            ])
+        );
+    }
+
+    #[test]
+    fn should_deserialize_a_utc_datetime() {
+        let bytes = Rc::new(RefCell::new(Bytes::from_static(&[
+            0xB3, 0x49, 0xCA, 0x55, 0x8A, 0x99, 0x8B, 0x00, 0xC9, 0x0E, 0x10,
+        ])));
+
+        let datetime: DateTime<FixedOffset> = BoltDateTime::parse(Version::V5_0, bytes)
             .unwrap()
             .try_into()
             .unwrap();
 
-        assert_eq!(datetime.to_string(), "2015-07-01 08:59:60.123");
+        assert_eq!(datetime.to_rfc2822(), "Wed, 24 Jun 2015 12:50:35 +0100");
+    }
+
+    #[test]
+    fn should_round_trip_a_utc_datetime_with_zoneid() {
+        let datetime =
+            NaiveDateTime::parse_from_str("2015-07-01 08:59:59.123", "%Y-%m-%d %H:%M:%S%.f")
+                .unwrap();
+        let date: BoltDateTimeZoneId = (datetime, "Europe/Paris").into();
+
+        let bytes = date.into_bytes(Version::V5_0).unwrap();
+        assert_eq!(bytes[1], DATE_TIME_ZONE_ID_UTC);
+        // 2015-07-01 08:59:59 in Paris (UTC+2) is 06:59:59 UTC
+        assert_eq!(&bytes[2..7], &[0xCA, 0x55, 0x93, 0x8F, 0xEF]);
+
+        let (parsed, zone_id): (NaiveDateTime, String) =
+            BoltDateTimeZoneId::parse(Version::V5_0, Rc::new(RefCell::new(bytes)))
+                .unwrap()
+                .try_into()
+                .unwrap();
+        assert_eq!(parsed, datetime);
         assert_eq!(zone_id, "Europe/Paris");
     }
+
+    #[test]
+    fn should_round_trip_a_datetime_in_a_named_zone() {
+        let zone: Tz = "America/New_York".parse().unwrap();
+        // the day the clocks go forward, before and after the change
+        let winter = zone.with_ymd_and_hms(2021, 3, 14, 1, 30, 0).unwrap();
+        let summer = zone.with_ymd_and_hms(2021, 3, 14, 3, 30, 0).unwrap();
+
+        for datetime in [winter, summer] {
+            let bolt: BoltDateTimeZoneId = datetime.into();
+            let bytes = bolt.into_bytes(Version::V5_0).unwrap();
+            let parsed: DateTime<Tz> =
+                BoltDateTimeZoneId::parse(Version::V5_0, Rc::new(RefCell::new(bytes)))
+                    .unwrap()
+                    .try_into()
+                    .unwrap();
+
+            assert_eq!(parsed, datetime);
+            assert_eq!(parsed.timezone(), zone);
+        }
+        assert_eq!(summer - winter, chrono::Duration::hours(1));
+    }
+
+    #[test]
+    fn should_not_convert_datetimes_out_of_range() {
+        let datetime = |seconds: i64, nanoseconds: i64, tz_offset_seconds: i64| BoltDateTime {
+            seconds: seconds.into(),
+            nanoseconds: nanoseconds.into(),
+            tz_offset_seconds: tz_offset_seconds.into(),
+        };
+
+        for value in [
+            datetime(i64::MAX / 2, 0, 0),
+            datetime(0, -1, 0),
+            datetime(0, 0, 86_400),
+        ] {
+            assert!(DateTime::<FixedOffset>::try_from(value).is_err());
+        }
+        let local = BoltLocalDateTime {
+            seconds: i64::MIN.into(),
+            nanoseconds: 0.into(),
+        };
+        assert!(NaiveDateTime::try_from(local).is_err());
+    }
 }
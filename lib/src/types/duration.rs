@@ -1,3 +1,4 @@
+use crate::duration::IsoDuration;
 use crate::types::*;
 use neo4rs_macros::BoltStruct;
 
@@ -26,10 +27,32 @@ impl BoltDuration {
     }
 }
 
-impl Into<BoltDuration> for std::time::Duration {
-    fn into(self) -> BoltDuration {
-        let seconds = self.as_secs();
-        let nanos = self.subsec_nanos();
+impl From<IsoDuration> for BoltDuration {
+    fn from(duration: IsoDuration) -> Self {
+        BoltDuration::new(
+            duration.months.into(),
+            duration.days.into(),
+            duration.seconds.into(),
+            (duration.nanoseconds as i64).into(),
+        )
+    }
+}
+
+impl From<BoltDuration> for IsoDuration {
+    fn from(duration: BoltDuration) -> Self {
+        IsoDuration::new(
+            duration.months.value,
+            duration.days.value,
+            duration.seconds.value,
+            duration.nanoseconds.value as i32,
+        )
+    }
+}
+
+impl From<std::time::Duration> for BoltDuration {
+    fn from(value: std::time::Duration) -> Self {
+        let seconds = value.as_secs();
+        let nanos = value.subsec_nanos();
         BoltDuration::new(
             0.into(),
             0.into(),
@@ -39,12 +62,12 @@ impl Into<BoltDuration> for std::time::Duration {
     }
 }
 
-impl Into<std::time::Duration> for BoltDuration {
-    fn into(self) -> std::time::Duration {
+impl From<BoltDuration> for std::time::Duration {
+    fn from(value: BoltDuration) -> Self {
         //TODO: clarify month issue
         let seconds =
-            self.seconds.value + (self.days.value * 24 * 3600) + (self.months.value * 2_629_800);
-        std::time::Duration::new(seconds as u64, self.nanoseconds.value as u32)
+            value.seconds.value + (value.days.value * 24 * 3600) + (value.months.value * 2_629_800);
+        std::time::Duration::new(seconds as u64, value.nanoseconds.value as u32)
     }
 }
 
@@ -71,4 +71,13 @@ mod tests {
         let bolt_float: BoltFloat = BoltFloat::parse(Version::V4_1, input).unwrap();
         assert_eq!(bolt_float.value, -1.23);
     }
+
+    #[test]
+    fn should_round_trip_non_finite_floats() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let bytes = BoltFloat::new(value).into_bytes(Version::V4_1).unwrap();
+            let parsed = BoltFloat::parse(Version::V4_1, Rc::new(RefCell::new(bytes))).unwrap();
+            assert_eq!(parsed.value.to_bits(), value.to_bits());
+        }
+    }
 }
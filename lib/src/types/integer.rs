@@ -88,22 +88,22 @@ impl BoltInteger {
     }
 }
 
-impl Into<BoltInteger> for i64 {
-    fn into(self) -> BoltInteger {
-        BoltInteger::new(self)
+impl From<i64> for BoltInteger {
+    fn from(value: i64) -> Self {
+        BoltInteger::new(value)
     }
 }
 
-impl Into<i64> for BoltInteger {
-    fn into(self) -> i64 {
-        self.value
+impl From<BoltInteger> for i64 {
+    fn from(value: BoltInteger) -> Self {
+        value.value
     }
 }
 
 //TODO: use macros
-impl Into<BoltInteger> for i32 {
-    fn into(self) -> BoltInteger {
-        BoltInteger::new(self as i64)
+impl From<i32> for BoltInteger {
+    fn from(value: i32) -> Self {
+        BoltInteger::new(value as i64)
     }
 }
 
@@ -66,9 +66,9 @@ impl IntoIterator for BoltList {
     }
 }
 
-impl Into<Vec<String>> for BoltList {
-    fn into(self) -> Vec<String> {
-        self.value.into_iter().map(|x| x.to_string()).collect()
+impl From<BoltList> for Vec<String> {
+    fn from(value: BoltList) -> Self {
+        value.value.into_iter().map(|x| x.to_string()).collect()
     }
 }
 
@@ -139,9 +139,9 @@ impl BoltList {
     }
 }
 
-impl Into<Vec<BoltType>> for BoltList {
-    fn into(self) -> Vec<BoltType> {
-        self.value
+impl From<BoltList> for Vec<BoltType> {
+    fn from(value: BoltList) -> Self {
+        value.value
     }
 }
 
@@ -177,7 +177,7 @@ mod tests {
 
         assert_eq!(bolt_list.len(), 2);
         match bolt_list.get(0).unwrap() {
-            BoltType::String(s) => assert_eq!(s.value, "a"),
+            BoltType::String(s) => assert_eq!(s.as_str(), "a"),
             _ => unreachable!("error deserialiisation of string in list"),
         }
 
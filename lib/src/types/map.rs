@@ -1,10 +1,10 @@
+use crate::convert::FromBoltValue;
 use crate::errors::*;
 use crate::types::*;
 use crate::version::Version;
 use bytes::*;
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::convert::TryInto;
 use std::iter::FromIterator;
 
 use std::mem;
@@ -39,20 +39,35 @@ impl BoltMap {
         self.value.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// The keys of the map, in no particular order
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.value.keys().map(|key| key.as_str())
+    }
+
+    /// The entries of the map, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BoltType)> {
+        self.value.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
     pub fn put(&mut self, key: BoltString, value: BoltType) {
         self.value.insert(key, value);
     }
 
-    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+    pub fn get<T: FromBoltValue>(&self, key: &str) -> Option<T> {
+        let value = self.value.get(&BoltString::new(key))?;
+        T::from_bolt_value(value.clone()).ok()
+    }
+
+    /// Gets a value which may be null, a missing key is an error rather than `None`
+    pub fn get_opt<T: FromBoltValue>(&self, key: &str) -> Result<Option<T>> {
         match self.value.get(&BoltString::new(key)) {
-            Some(bolt_type) => {
-                if let Ok(value) = TryInto::<T>::try_into(bolt_type.clone()) {
-                    Some(value)
-                } else {
-                    None
-                }
-            }
-            _ => None,
+            Some(BoltType::Null(_)) => Ok(None),
+            Some(bolt_type) => T::from_bolt_value(bolt_type.clone()).map(Some),
+            None => Err(Error::MissingKey(key.to_owned())),
         }
     }
 
@@ -1,12 +1,20 @@
+use crate::convert::FromBoltValue;
+use crate::errors::*;
 use crate::types::*;
-use neo4rs_macros::BoltStruct;
+use crate::version::{Feature, Version};
+use bytes::*;
+use std::cell::RefCell;
+use std::rc::Rc;
 
-#[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB3, 0x4E)]
+pub const SIGNATURE: u8 = 0x4E;
+
+/// A node structure, from bolt 5.0 onwards nodes carry an additional `element_id` field.
+#[derive(Debug, PartialEq, Clone)]
 pub struct BoltNode {
     pub id: BoltInteger,
     pub labels: BoltList,
     pub properties: BoltMap,
+    pub element_id: Option<BoltString>,
 }
 
 impl BoltNode {
@@ -15,19 +23,71 @@ impl BoltNode {
             id,
             labels,
             properties,
+            element_id: None,
         }
     }
 }
 
 impl BoltNode {
-    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+    pub fn get<T: FromBoltValue>(&self, key: &str) -> Option<T> {
         self.properties.get(key)
     }
+
+    pub fn get_opt<T: FromBoltValue>(&self, key: &str) -> Result<Option<T>> {
+        self.properties.get_opt(key)
+    }
+}
+
+impl BoltNode {
+    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
+        let input = input.borrow();
+        input.len() >= 2 && (input[0] == 0xB3 || input[0] == 0xB4) && input[1] == SIGNATURE
+    }
+
+    pub fn parse(version: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltNode> {
+        let marker = input.borrow_mut().get_u8();
+        let _signature = input.borrow_mut().get_u8();
+        let id = BoltInteger::parse(version, input.clone())?;
+        let labels = BoltList::parse(version, input.clone())?;
+        let properties = BoltMap::parse(version, input.clone())?;
+        let element_id = if marker == 0xB4 {
+            Some(BoltString::parse(version, input)?)
+        } else {
+            None
+        };
+        Ok(BoltNode {
+            id,
+            labels,
+            properties,
+            element_id,
+        })
+    }
+
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        let mut bytes = BytesMut::new();
+        if version.supports(Feature::ElementIds) {
+            let id = self.id.value;
+            let element_id = self.element_id.unwrap_or_else(|| id.to_string().into());
+            bytes.put_u8(0xB4);
+            bytes.put_u8(SIGNATURE);
+            bytes.put(self.id.into_bytes(version)?);
+            bytes.put(self.labels.into_bytes(version)?);
+            bytes.put(self.properties.into_bytes(version)?);
+            bytes.put(element_id.into_bytes(version)?);
+        } else {
+            bytes.put_u8(0xB3);
+            bytes.put_u8(SIGNATURE);
+            bytes.put(self.id.into_bytes(version)?);
+            bytes.put(self.labels.into_bytes(version)?);
+            bytes.put(self.properties.into_bytes(version)?);
+        }
+        Ok(bytes.freeze())
+    }
 }
 
-impl Into<BoltType> for BoltNode {
-    fn into(self) -> BoltType {
-        BoltType::Node(self)
+impl From<BoltNode> for BoltType {
+    fn from(value: BoltNode) -> Self {
+        BoltType::Node(value)
     }
 }
 
@@ -35,7 +95,6 @@ impl Into<BoltType> for BoltNode {
 mod tests {
     use super::*;
     use crate::version::Version;
-    use bytes::*;
     use std::cell::RefCell;
     use std::rc::Rc;
 
@@ -61,11 +120,7 @@ mod tests {
         let id = BoltInteger::new(19);
         let labels = vec!["Person".into()].into();
         let properties = vec![("name".into(), "Mark".into())].into_iter().collect();
-        let node = BoltNode {
-            id,
-            labels,
-            properties,
-        };
+        let node = BoltNode::new(id, labels, properties);
 
         let bytes: Bytes = node.into_bytes(Version::V4_1).unwrap();
 
@@ -77,4 +132,37 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn should_deserialize_a_v5_node() {
+        let input = Rc::new(RefCell::new(Bytes::from_static(&[
+            0xB4, 0x4E, 0x13, 0x91, 0x86, 0x50, 0x65, 0x72, 0x73, 0x6F, 0x6E, 0xA1, 0x84, 0x6E,
+            0x61, 0x6D, 0x65, 0x84, 0x4D, 0x61, 0x72, 0x6B, 0x84, 0x34, 0x3A, 0x31, 0x39,
+        ])));
+
+        let node: BoltNode = BoltNode::parse(Version::V5_0, input).unwrap();
+
+        assert_eq!(node.id, BoltInteger::new(19));
+        assert_eq!(node.labels, vec!["Person".into()].into());
+        assert_eq!(node.element_id, Some("4:19".into()));
+    }
+
+    #[test]
+    fn should_serialize_a_v5_node() {
+        let node = BoltNode::new(
+            BoltInteger::new(19),
+            vec!["Person".into()].into(),
+            vec![("name".into(), "Mark".into())].into_iter().collect(),
+        );
+
+        let bytes: Bytes = node.into_bytes(Version::V5_0).unwrap();
+
+        assert_eq!(
+            bytes,
+            Bytes::from_static(&[
+                0xB4, 0x4E, 0x13, 0x91, 0x86, 0x50, 0x65, 0x72, 0x73, 0x6F, 0x6E, 0xA1, 0x84, 0x6E,
+                0x61, 0x6D, 0x65, 0x84, 0x4D, 0x61, 0x72, 0x6B, 0x82, 0x31, 0x39,
+            ])
+        );
+    }
 }
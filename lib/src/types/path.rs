@@ -39,6 +39,51 @@ impl BoltPath {
         }
         ids
     }
+
+    /// The relationships of the path in order, each with the nodes before and after it. The ids
+    /// alternate between a relationship, counted from 1 and negative when it was traversed
+    /// against its direction, and the node reached through it.
+    pub fn segments(&self) -> Vec<(BoltNode, BoltRelation, BoltNode)> {
+        let nodes = self.nodes();
+        let rels = self.rels();
+        let ids = self.ids();
+        let mut segments = Vec::with_capacity(ids.len() / 2);
+        let mut previous = match nodes.first() {
+            Some(node) => node,
+            None => return segments,
+        };
+        for pair in ids.chunks_exact(2) {
+            let (rel_index, node_index) = (pair[0].value, pair[1].value);
+            let rel = usize::try_from(rel_index.unsigned_abs())
+                .ok()
+                .and_then(|index| rels.get(index.checked_sub(1)?));
+            let next = usize::try_from(node_index)
+                .ok()
+                .and_then(|index| nodes.get(index));
+            let (rel, next) = match (rel, next) {
+                (Some(rel), Some(next)) => (rel, next),
+                _ => break,
+            };
+            let (start, end) = if rel_index > 0 {
+                (previous, next)
+            } else {
+                (next, previous)
+            };
+            let relation = BoltRelation {
+                id: rel.id.clone(),
+                start_node_id: start.id.clone(),
+                end_node_id: end.id.clone(),
+                typ: rel.typ.clone(),
+                properties: rel.properties.clone(),
+                element_id: rel.element_id.clone(),
+                start_node_element_id: start.element_id.clone(),
+                end_node_element_id: end.element_id.clone(),
+            };
+            segments.push((previous.clone(), relation, next.clone()));
+            previous = next;
+        }
+        segments
+    }
 }
 
 #[cfg(test)]
@@ -132,4 +177,34 @@ mod tests {
         );
         assert_eq!(ids, vec![22.into(), 42.into()]);
     }
+
+    #[test]
+    fn should_walk_the_segments_of_a_path() {
+        let node = |id: i64| BoltNode::new(id.into(), BoltList::default(), BoltMap::default());
+        let rel =
+            |id: i64| BoltUnboundedRelation::new(id.into(), "KNOWS".into(), BoltMap::default());
+
+        // (1)-[10]->(2)<-[11]-(3)
+        let path = BoltPath {
+            nodes: vec![node(1).into(), node(2).into(), node(3).into()].into(),
+            rels: vec![rel(10).into(), rel(11).into()].into(),
+            ids: vec![1.into(), 1.into(), (-2).into(), 2.into()].into(),
+        };
+
+        let segments: Vec<_> = path
+            .segments()
+            .into_iter()
+            .map(|(start, rel, end)| {
+                (
+                    start.id.value,
+                    rel.id.value,
+                    rel.start_node_id.value,
+                    rel.end_node_id.value,
+                    end.id.value,
+                )
+            })
+            .collect();
+
+        assert_eq!(segments, vec![(1, 10, 1, 2, 2), (2, 11, 3, 2, 3)]);
+    }
 }
@@ -1,22 +1,57 @@
+use crate::convert::FromBoltValue;
+use crate::errors::*;
 use crate::types::*;
-use neo4rs_macros::BoltStruct;
+use crate::version::Version;
+use bytes::*;
+use std::cell::RefCell;
+use std::rc::Rc;
 
-#[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB5, 0x52)]
+pub const SIGNATURE: u8 = 0x52;
+pub const UNBOUNDED_SIGNATURE: u8 = 0x72;
+
+/// A relationship structure, from bolt 5.0 onwards the relationship and both of its nodes
+/// carry an additional element id.
+#[derive(Debug, PartialEq, Clone)]
 pub struct BoltRelation {
     pub id: BoltInteger,
     pub start_node_id: BoltInteger,
     pub end_node_id: BoltInteger,
     pub typ: BoltString,
     pub properties: BoltMap,
+    pub element_id: Option<BoltString>,
+    pub start_node_element_id: Option<BoltString>,
+    pub end_node_element_id: Option<BoltString>,
 }
 
-#[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB3, 0x72)]
+/// A relationship without start/end node information, from bolt 5.0 onwards it carries an
+/// additional element id.
+#[derive(Debug, PartialEq, Clone)]
 pub struct BoltUnboundedRelation {
     pub id: BoltInteger,
     pub typ: BoltString,
     pub properties: BoltMap,
+    pub element_id: Option<BoltString>,
+}
+
+impl BoltRelation {
+    pub fn new(
+        id: BoltInteger,
+        start_node_id: BoltInteger,
+        end_node_id: BoltInteger,
+        typ: BoltString,
+        properties: BoltMap,
+    ) -> Self {
+        BoltRelation {
+            id,
+            start_node_id,
+            end_node_id,
+            typ,
+            properties,
+            element_id: None,
+            start_node_element_id: None,
+            end_node_element_id: None,
+        }
+    }
 }
 
 impl BoltUnboundedRelation {
@@ -25,38 +60,144 @@ impl BoltUnboundedRelation {
             id,
             typ,
             properties,
+            element_id: None,
         }
     }
 }
 
 impl BoltRelation {
-    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+    pub fn get<T: FromBoltValue>(&self, key: &str) -> Option<T> {
         self.properties.get(key)
     }
+
+    pub fn get_opt<T: FromBoltValue>(&self, key: &str) -> Result<Option<T>> {
+        self.properties.get_opt(key)
+    }
 }
 
 impl BoltUnboundedRelation {
-    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+    pub fn get<T: FromBoltValue>(&self, key: &str) -> Option<T> {
         self.properties.get(key)
     }
+
+    pub fn get_opt<T: FromBoltValue>(&self, key: &str) -> Result<Option<T>> {
+        self.properties.get_opt(key)
+    }
+}
+
+fn element_id_or(element_id: Option<BoltString>, id: &BoltInteger) -> BoltString {
+    element_id.unwrap_or_else(|| id.value.to_string().into())
+}
+
+impl BoltRelation {
+    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
+        let input = input.borrow();
+        input.len() >= 2 && (input[0] == 0xB5 || input[0] == 0xB8) && input[1] == SIGNATURE
+    }
+
+    pub fn parse(version: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltRelation> {
+        let marker = input.borrow_mut().get_u8();
+        let _signature = input.borrow_mut().get_u8();
+        let mut relation = BoltRelation::new(
+            BoltInteger::parse(version, input.clone())?,
+            BoltInteger::parse(version, input.clone())?,
+            BoltInteger::parse(version, input.clone())?,
+            BoltString::parse(version, input.clone())?,
+            BoltMap::parse(version, input.clone())?,
+        );
+        if marker == 0xB8 {
+            relation.element_id = Some(BoltString::parse(version, input.clone())?);
+            relation.start_node_element_id = Some(BoltString::parse(version, input.clone())?);
+            relation.end_node_element_id = Some(BoltString::parse(version, input)?);
+        }
+        Ok(relation)
+    }
+
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        let mut bytes = BytesMut::new();
+        let v5 = version >= Version::V5_0;
+        bytes.put_u8(if v5 { 0xB8 } else { 0xB5 });
+        bytes.put_u8(SIGNATURE);
+        let element_ids = if v5 {
+            Some((
+                element_id_or(self.element_id, &self.id),
+                element_id_or(self.start_node_element_id, &self.start_node_id),
+                element_id_or(self.end_node_element_id, &self.end_node_id),
+            ))
+        } else {
+            None
+        };
+        bytes.put(self.id.into_bytes(version)?);
+        bytes.put(self.start_node_id.into_bytes(version)?);
+        bytes.put(self.end_node_id.into_bytes(version)?);
+        bytes.put(self.typ.into_bytes(version)?);
+        bytes.put(self.properties.into_bytes(version)?);
+        if let Some((element_id, start_node_element_id, end_node_element_id)) = element_ids {
+            bytes.put(element_id.into_bytes(version)?);
+            bytes.put(start_node_element_id.into_bytes(version)?);
+            bytes.put(end_node_element_id.into_bytes(version)?);
+        }
+        Ok(bytes.freeze())
+    }
+}
+
+impl BoltUnboundedRelation {
+    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
+        let input = input.borrow();
+        input.len() >= 2
+            && (input[0] == 0xB3 || input[0] == 0xB4)
+            && input[1] == UNBOUNDED_SIGNATURE
+    }
+
+    pub fn parse(version: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltUnboundedRelation> {
+        let marker = input.borrow_mut().get_u8();
+        let _signature = input.borrow_mut().get_u8();
+        let mut relation = BoltUnboundedRelation::new(
+            BoltInteger::parse(version, input.clone())?,
+            BoltString::parse(version, input.clone())?,
+            BoltMap::parse(version, input.clone())?,
+        );
+        if marker == 0xB4 {
+            relation.element_id = Some(BoltString::parse(version, input)?);
+        }
+        Ok(relation)
+    }
+
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        let mut bytes = BytesMut::new();
+        let v5 = version >= Version::V5_0;
+        bytes.put_u8(if v5 { 0xB4 } else { 0xB3 });
+        bytes.put_u8(UNBOUNDED_SIGNATURE);
+        let element_id = if v5 {
+            Some(element_id_or(self.element_id, &self.id))
+        } else {
+            None
+        };
+        bytes.put(self.id.into_bytes(version)?);
+        bytes.put(self.typ.into_bytes(version)?);
+        bytes.put(self.properties.into_bytes(version)?);
+        if let Some(element_id) = element_id {
+            bytes.put(element_id.into_bytes(version)?);
+        }
+        Ok(bytes.freeze())
+    }
 }
 
-impl Into<BoltType> for BoltRelation {
-    fn into(self) -> BoltType {
-        BoltType::Relation(self)
+impl From<BoltRelation> for BoltType {
+    fn from(value: BoltRelation) -> Self {
+        BoltType::Relation(value)
     }
 }
 
-impl Into<BoltType> for BoltUnboundedRelation {
-    fn into(self) -> BoltType {
-        BoltType::UnboundedRelation(self)
+impl From<BoltUnboundedRelation> for BoltType {
+    fn from(value: BoltUnboundedRelation) -> Self {
+        BoltType::UnboundedRelation(value)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use bytes::*;
     use std::cell::RefCell;
     use std::rc::Rc;
 
@@ -68,13 +209,7 @@ mod tests {
         let typ = BoltString::new("rel");
         let properties = vec![("name".into(), "Mark".into())].into_iter().collect();
 
-        let relation = BoltRelation {
-            id,
-            start_node_id,
-            end_node_id,
-            typ,
-            properties,
-        };
+        let relation = BoltRelation::new(id, start_node_id, end_node_id, typ, properties);
 
         let bytes: Bytes = relation.into_bytes(Version::V4_1).unwrap();
 
@@ -141,4 +276,54 @@ mod tests {
             vec![("name".into(), "Mark".into())].into_iter().collect()
         );
     }
+
+    #[test]
+    fn should_deserialize_a_v5_relation() {
+        let input = Rc::new(RefCell::new(Bytes::from_static(&[
+            0xB8, 0x52, 0x2A, 0x01, 0x02, 0x83, 0x72, 0x65, 0x6C, 0xA0, 0x82, 0x34, 0x32, 0x81,
+            0x31, 0x81, 0x32,
+        ])));
+
+        let relation: BoltRelation = BoltRelation::parse(Version::V5_0, input).unwrap();
+
+        assert_eq!(relation.id, BoltInteger::new(42));
+        assert_eq!(relation.typ, BoltString::new("rel"));
+        assert_eq!(relation.element_id, Some("42".into()));
+        assert_eq!(relation.start_node_element_id, Some("1".into()));
+        assert_eq!(relation.end_node_element_id, Some("2".into()));
+    }
+
+    #[test]
+    fn should_serialize_a_v5_relation() {
+        let relation = BoltRelation::new(
+            BoltInteger::new(42),
+            BoltInteger::new(1),
+            BoltInteger::new(2),
+            BoltString::new("rel"),
+            BoltMap::default(),
+        );
+
+        let bytes: Bytes = relation.into_bytes(Version::V5_0).unwrap();
+
+        assert_eq!(
+            bytes,
+            Bytes::from_static(&[
+                0xB8, 0x52, 0x2A, 0x01, 0x02, 0x83, 0x72, 0x65, 0x6C, 0xA0, 0x82, 0x34, 0x32, 0x81,
+                0x31, 0x81, 0x32,
+            ])
+        );
+    }
+
+    #[test]
+    fn should_deserialize_a_v5_unbounded_relation() {
+        let input = Rc::new(RefCell::new(Bytes::from_static(&[
+            0xB4, 0x72, 0x2A, 0x83, 0x72, 0x65, 0x6C, 0xA0, 0x84, 0x35, 0x3A, 0x34, 0x32,
+        ])));
+
+        let relation: BoltUnboundedRelation =
+            BoltUnboundedRelation::parse(Version::V5_0, input).unwrap();
+
+        assert_eq!(relation.id, BoltInteger::new(42));
+        assert_eq!(relation.element_id, Some("5:42".into()));
+    }
 }
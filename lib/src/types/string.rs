@@ -3,8 +3,10 @@ use crate::version::Version;
 use bytes::*;
 use std::cell::RefCell;
 use std::convert::From;
-use std::fmt::Display;
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
 use std::mem;
+use std::ops::Deref;
 use std::rc::Rc;
 
 pub const TINY: u8 = 0x80;
@@ -12,18 +14,26 @@ pub const SMALL: u8 = 0xD0;
 pub const MEDIUM: u8 = 0xD1;
 pub const LARGE: u8 = 0xD2;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+/// A string shared with the buffer of the message it was received in, read as a `&str`
+/// through [`BoltString::as_str`] or `Deref`
+#[derive(PartialEq, Eq, Clone)]
 pub struct BoltString {
-    pub value: String,
+    // always valid utf-8, checked when the string is parsed
+    value: Bytes,
 }
 
 impl BoltString {
     pub fn new(value: &str) -> Self {
         BoltString {
-            value: value.to_string(),
+            value: Bytes::copy_from_slice(value.as_bytes()),
         }
     }
 
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the bytes are copied from a string or validated when they are parsed
+        unsafe { std::str::from_utf8_unchecked(&self.value) }
+    }
+
     pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
         let marker = input.borrow()[0];
         (TINY..=(TINY | 0x0F)).contains(&marker)
@@ -33,9 +43,31 @@ impl BoltString {
     }
 }
 
+impl Deref for BoltString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Hash for BoltString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl Debug for BoltString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BoltString")
+            .field("value", &self.as_str())
+            .finish()
+    }
+}
+
 impl Display for BoltString {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.value)
+        f.write_str(self.as_str())
     }
 }
 
@@ -47,13 +79,15 @@ impl From<&str> for BoltString {
 
 impl From<String> for BoltString {
     fn from(v: String) -> Self {
-        BoltString::new(&v)
+        BoltString {
+            value: Bytes::from(v.into_bytes()),
+        }
     }
 }
 
-impl Into<String> for BoltString {
-    fn into(self) -> String {
-        self.value
+impl From<BoltString> for String {
+    fn from(value: BoltString) -> Self {
+        value.as_str().to_owned()
     }
 }
 
@@ -78,7 +112,7 @@ impl BoltString {
             }
             _ => return Err(Error::StringTooLong),
         };
-        bytes.put_slice(self.value.as_bytes());
+        bytes.put_slice(&self.value);
         Ok(bytes.freeze())
     }
 
@@ -97,10 +131,9 @@ impl BoltString {
                 )))
             }
         };
-        let byte_array = input.split_to(length).to_vec();
-        let string_value = std::string::String::from_utf8(byte_array)
-            .map_err(|e| Error::DeserializationError(e.to_string()))?;
-        Ok(string_value.into())
+        let value = input.split_to(length);
+        std::str::from_utf8(&value).map_err(|e| Error::DeserializationError(e.to_string()))?;
+        Ok(BoltString { value })
     }
 }
 
@@ -202,4 +235,27 @@ mod tests {
         let result: BoltString = BoltString::parse(Version::V4_1, serialized_bytes).unwrap();
         assert_eq!(result, "a".into());
     }
+
+    #[test]
+    fn should_share_the_buffer_of_the_message() {
+        let message = Bytes::from_static(&[0x81, 0x61, 0x83, 0x61, 0x62, 0x63]);
+        let start = message.as_ptr() as usize;
+        let input = Rc::new(RefCell::new(message));
+
+        let first = BoltString::parse(Version::V4_1, input.clone()).unwrap();
+        let second = BoltString::parse(Version::V4_1, input).unwrap();
+
+        assert_eq!(first.as_str(), "a");
+        assert_eq!(second.as_str(), "abc");
+        assert_eq!(second.as_ptr() as usize, start + 3);
+    }
+
+    #[test]
+    fn should_reject_invalid_utf8() {
+        let input = Rc::new(RefCell::new(Bytes::from_static(&[0x82, 0xC3, 0x28])));
+        assert!(matches!(
+            BoltString::parse(Version::V4_1, input),
+            Err(Error::DeserializationError(_))
+        ));
+    }
 }
@@ -1,4 +1,9 @@
+#[cfg(any(feature = "chrono", feature = "time"))]
+use crate::errors::Error;
+#[cfg(feature = "chrono")]
+use crate::types::date_time::fixed_offset;
 use crate::types::*;
+#[cfg(feature = "chrono")]
 use chrono::{FixedOffset, NaiveTime, Offset, Timelike};
 use neo4rs_macros::BoltStruct;
 
@@ -15,49 +20,160 @@ pub struct BoltLocalTime {
     nanoseconds: BoltInteger,
 }
 
-impl Into<BoltTime> for (NaiveTime, FixedOffset) {
-    fn into(self) -> BoltTime {
-        let seconds_from_midnight = self.0.num_seconds_from_midnight() as i64;
-        let nanoseconds = seconds_from_midnight * 1_000_000_000 + self.0.nanosecond() as i64;
+#[cfg(feature = "chrono")]
+impl From<(NaiveTime, FixedOffset)> for BoltTime {
+    fn from(value: (NaiveTime, FixedOffset)) -> Self {
+        let seconds_from_midnight = value.0.num_seconds_from_midnight() as i64;
+        let nanoseconds = seconds_from_midnight * 1_000_000_000 + value.0.nanosecond() as i64;
         BoltTime {
             nanoseconds: nanoseconds.into(),
-            tz_offset_seconds: self.1.fix().local_minus_utc().into(),
+            tz_offset_seconds: value.1.fix().local_minus_utc().into(),
         }
     }
 }
 
-impl Into<(NaiveTime, FixedOffset)> for BoltTime {
-    fn into(self) -> (NaiveTime, FixedOffset) {
-        let nanos = self.nanoseconds.value;
-        let seconds = (nanos / 1_000_000_000) as u32;
-        let nanoseconds = (nanos % 1_000_000_000) as u32;
-        (
-            NaiveTime::from_num_seconds_from_midnight(seconds, nanoseconds),
-            FixedOffset::east(self.tz_offset_seconds.value as i32),
-        )
+#[cfg(feature = "chrono")]
+impl TryFrom<BoltTime> for (NaiveTime, FixedOffset) {
+    type Error = Error;
+
+    fn try_from(value: BoltTime) -> Result<Self> {
+        let offset = fixed_offset(value.tz_offset_seconds.value)?;
+        let time = BoltLocalTime {
+            nanoseconds: value.nanoseconds,
+        };
+        Ok((NaiveTime::try_from(time)?, offset))
     }
 }
 
-impl Into<BoltLocalTime> for NaiveTime {
-    fn into(self) -> BoltLocalTime {
-        let seconds_from_midnight = self.num_seconds_from_midnight() as i64;
-        let nanoseconds = seconds_from_midnight * 1_000_000_000 + self.nanosecond() as i64;
+#[cfg(feature = "chrono")]
+impl From<NaiveTime> for BoltLocalTime {
+    fn from(value: NaiveTime) -> Self {
+        let seconds_from_midnight = value.num_seconds_from_midnight() as i64;
+        let nanoseconds = seconds_from_midnight * 1_000_000_000 + value.nanosecond() as i64;
         BoltLocalTime {
             nanoseconds: nanoseconds.into(),
         }
     }
 }
 
-impl Into<NaiveTime> for BoltLocalTime {
-    fn into(self) -> NaiveTime {
-        let nanos = self.nanoseconds.value;
+#[cfg(feature = "chrono")]
+impl TryFrom<BoltLocalTime> for NaiveTime {
+    type Error = Error;
+
+    fn try_from(value: BoltLocalTime) -> Result<Self> {
+        let nanos = value.nanoseconds.value;
         let seconds = (nanos / 1_000_000_000) as u32;
         let nanoseconds = (nanos % 1_000_000_000) as u32;
-        NaiveTime::from_num_seconds_from_midnight(seconds, nanoseconds)
+        NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanoseconds)
+            .ok_or(Error::ConverstionError)
+    }
+}
+
+#[cfg(feature = "time")]
+fn nanoseconds_from_midnight(time: ::time::Time) -> i64 {
+    let (hour, minute, second, nanosecond) = time.as_hms_nano();
+    let seconds = hour as i64 * 3600 + minute as i64 * 60 + second as i64;
+    seconds * 1_000_000_000 + nanosecond as i64
+}
+
+#[cfg(feature = "time")]
+fn time_from_nanoseconds(nanoseconds: i64) -> Result<::time::Time> {
+    let seconds = nanoseconds / 1_000_000_000;
+    ::time::Time::from_hms_nano(
+        (seconds / 3600) as u8,
+        (seconds / 60 % 60) as u8,
+        (seconds % 60) as u8,
+        (nanoseconds % 1_000_000_000) as u32,
+    )
+    .map_err(|_| Error::ConverstionError)
+}
+
+#[cfg(feature = "time")]
+impl From<(::time::Time, ::time::UtcOffset)> for BoltTime {
+    fn from(value: (::time::Time, ::time::UtcOffset)) -> Self {
+        BoltTime {
+            nanoseconds: nanoseconds_from_midnight(value.0).into(),
+            tz_offset_seconds: (value.1.whole_seconds() as i64).into(),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltTime> for (::time::Time, ::time::UtcOffset) {
+    type Error = Error;
+
+    fn try_from(value: BoltTime) -> Result<Self> {
+        let offset = ::time::UtcOffset::from_whole_seconds(value.tz_offset_seconds.value as i32)
+            .map_err(|_| Error::ConverstionError)?;
+        Ok((time_from_nanoseconds(value.nanoseconds.value)?, offset))
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<::time::Time> for BoltLocalTime {
+    fn from(value: ::time::Time) -> Self {
+        BoltLocalTime {
+            nanoseconds: nanoseconds_from_midnight(value).into(),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<BoltLocalTime> for ::time::Time {
+    type Error = Error;
+
+    fn try_from(value: BoltLocalTime) -> Result<Self> {
+        time_from_nanoseconds(value.nanoseconds.value)
+    }
+}
+
+#[cfg(all(test, feature = "time"))]
+mod time_tests {
+    use super::*;
+    use crate::version::Version;
+    use ::time::{Time, UtcOffset};
+    use bytes::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn should_convert_time_times() {
+        let time = Time::from_hms_nano(7, 8, 9, 100).unwrap();
+        let offset = UtcOffset::from_whole_seconds(2 * 3600).unwrap();
+        let bytes = Bytes::from_static(&[
+            0xB2, 0x54, 0xCB, 0x00, 0x00, 0x17, 0x5D, 0x2F, 0xB8, 0x3A, 0x64, 0xC9, 0x1C, 0x20,
+        ]);
+
+        let bolt: BoltTime = (time, offset).into();
+        assert_eq!(bolt.into_bytes(Version::V4_1).unwrap(), bytes);
+
+        let parsed: (Time, UtcOffset) =
+            BoltTime::parse(Version::V4_1, Rc::new(RefCell::new(bytes)))
+                .unwrap()
+                .try_into()
+                .unwrap();
+        assert_eq!(parsed, (time, offset));
+    }
+
+    #[test]
+    fn should_convert_time_local_times() {
+        let time = Time::from_hms_nano(7, 8, 9, 100).unwrap();
+        let bytes = Bytes::from_static(&[
+            0xB1, 0x74, 0xCB, 0x00, 0x00, 0x17, 0x5D, 0x2F, 0xB8, 0x3A, 0x64,
+        ]);
+
+        let bolt: BoltLocalTime = time.into();
+        assert_eq!(bolt.into_bytes(Version::V4_1).unwrap(), bytes);
+
+        let parsed: Time = BoltLocalTime::parse(Version::V4_1, Rc::new(RefCell::new(bytes)))
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(parsed, time);
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use super::*;
     use crate::version::Version;
@@ -68,7 +184,7 @@ mod tests {
     #[test]
     fn should_serialize_time() {
         let time = NaiveTime::from_hms_nano_opt(7, 8, 9, 100).unwrap();
-        let offset = FixedOffset::east(2 * 3600);
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
 
         let time: BoltTime = (time, offset).into();
 
@@ -86,13 +202,23 @@ mod tests {
             0xB2, 0x54, 0xCB, 0x00, 0x00, 0x17, 0x5D, 0x2F, 0xB8, 0x3A, 0x64, 0xC9, 0x1C, 0x20,
         ])));
 
-        let (time, offset) = BoltTime::parse(Version::V4_1, bytes)
+        let (time, offset): (NaiveTime, FixedOffset) = BoltTime::parse(Version::V4_1, bytes)
             .unwrap()
             .try_into()
             .unwrap();
 
         assert_eq!(time.to_string(), "07:08:09.000000100");
-        assert_eq!(offset, FixedOffset::east(2 * 3600));
+        assert_eq!(offset, FixedOffset::east_opt(2 * 3600).unwrap());
+    }
+
+    #[test]
+    fn should_not_convert_offsets_of_a_day() {
+        let time = BoltTime {
+            nanoseconds: 0.into(),
+            tz_offset_seconds: 86_400.into(),
+        };
+
+        assert!(<(NaiveTime, FixedOffset)>::try_from(time).is_err());
     }
 
     #[test]
@@ -115,11 +241,17 @@ mod tests {
             0xB1, 0x74, 0xCB, 0x00, 0x00, 0x17, 0x5D, 0x2F, 0xB8, 0x3A, 0x64,
         ])));
 
-        let time: NaiveTime = BoltLocalTime::parse(Version::V4_1, bytes)
-            .unwrap()
-            .try_into()
-            .unwrap();
+        let time = NaiveTime::try_from(BoltLocalTime::parse(Version::V4_1, bytes).unwrap());
 
-        assert_eq!(time.to_string(), "07:08:09.000000100");
+        assert_eq!(time.unwrap().to_string(), "07:08:09.000000100");
+    }
+
+    #[test]
+    fn should_not_convert_local_times_past_midnight() {
+        let time = BoltLocalTime {
+            nanoseconds: (24 * 3600 * 1_000_000_000i64).into(),
+        };
+
+        assert!(NaiveTime::try_from(time).is_err());
     }
 }
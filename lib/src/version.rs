@@ -3,32 +3,175 @@ use bytes::{BufMut, Bytes, BytesMut};
 use std::cmp::PartialEq;
 use std::fmt::Debug;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+/// Bolt protocol versions understood by the driver, ordered from oldest to newest.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum Version {
-    V4_1,
+    V3,
     V4,
+    V4_1,
+    V4_2,
+    V4_3,
+    V4_4,
+    V5_0,
+    V5_1,
+    V5_2,
+    V5_3,
+    V5_4,
+    V5_6,
+    V5_7,
+}
+
+/// Capabilities of the driver which depend on the bolt version negotiated with the server, see
+/// [`Graph::supports`](crate::Graph::supports)
+///
+/// The driver checks them itself before sending a message, so older servers don't receive
+/// fields they would fail on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// Queries and transactions on a database other than the default one, from bolt 4.0
+    MultiDatabase,
+    /// Rows pulled in batches of the fetch size, and the results of a transaction told apart by
+    /// their id, from bolt 4.0. Bolt 3 servers send all the rows of a result at once.
+    ResultBatches,
+    /// The routing context sent with HELLO, from bolt 4.1
+    RoutingContext,
+    /// Routing tables fetched with a ROUTE message instead of a procedure, from bolt 4.3
+    RouteMessage,
+    /// Queries and transactions running as another user, from bolt 4.4
+    Impersonation,
+    /// String element ids of nodes and relationships, from bolt 5.0
+    ElementIds,
+    /// Connections authenticating again when the credentials change, from bolt 5.1
+    ReAuthentication,
+    /// Filtering the notifications the server sends, from bolt 5.2
+    NotificationFiltering,
+    /// The driver describing itself and its platform to the server, from bolt 5.3
+    BoltAgent,
+    /// The driver reporting which APIs run queries to servers asking for it, from bolt 5.4
+    Telemetry,
+    /// Notifications reported as GQL statuses, from bolt 5.6
+    GqlStatuses,
+    /// Failures described with a GQL status and the chain of their causes, from bolt 5.7
+    GqlErrors,
+}
+
+impl Feature {
+    /// The first version supporting the feature
+    pub fn since(self) -> Version {
+        match self {
+            Feature::MultiDatabase | Feature::ResultBatches => Version::V4,
+            Feature::RoutingContext => Version::V4_1,
+            Feature::RouteMessage => Version::V4_3,
+            Feature::Impersonation => Version::V4_4,
+            Feature::ElementIds => Version::V5_0,
+            Feature::ReAuthentication => Version::V5_1,
+            Feature::NotificationFiltering => Version::V5_2,
+            Feature::BoltAgent => Version::V5_3,
+            Feature::Telemetry => Version::V5_4,
+            Feature::GqlStatuses => Version::V5_6,
+            Feature::GqlErrors => Version::V5_7,
+        }
+    }
 }
 
+/// The proposal asking servers to answer with the manifest of every version they support
+pub const MANIFEST_V1: [u8; 4] = [0, 0, 1, 0xFF];
+
 impl Version {
+    /// The four version proposals sent during the handshake, most preferred first.
+    ///
+    /// Each proposal is encoded as `[reserved, range, minor, major]`, where `range` is the number
+    /// of consecutive minor versions below `minor` that are also acceptable. Modern servers
+    /// accept the [`MANIFEST_V1`] proposal and list their versions instead, so the best version
+    /// both sides support is picked. The ranges cover 5.7 down to 5.0 and 4.4 down to 4.1
+    /// against servers without the manifest, see [`Version::fallback_versions`] for 4.0.
     pub fn supported_versions() -> Bytes {
         let mut bytes = BytesMut::with_capacity(16);
-        let versions: [u32; 4] = [0x0104, 0x0004, 0, 0];
+        let versions: [u32; 4] = [
+            u32::from_be_bytes(MANIFEST_V1),
+            0x0007_0705,
+            0x0002_0404,
+            0x0104,
+        ];
         for version in versions.iter() {
             bytes.put_u32(*version);
         }
         bytes.freeze()
     }
 
+    /// The proposals of a second handshake with servers that accepted none of the
+    /// [`Version::supported_versions`], which only leaves 4.0 and 3.0. Bolt 3 is spoken by
+    /// Neo4j 3.5, which only has the default database and sends all the rows of a result at
+    /// once.
+    pub fn fallback_versions() -> Bytes {
+        Bytes::from_static(&[0, 0, 0, 4, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0])
+    }
+
+    /// Picks the newest supported version of the `[reserved, range, minor, major]` versions
+    /// listed by the manifest of the server
+    pub fn from_manifest(offered: &[[u8; 4]]) -> Result<Version> {
+        offered
+            .iter()
+            .flat_map(|&[_, range, minor, major]| {
+                (minor.saturating_sub(range)..=minor)
+                    .filter_map(move |minor| Version::parse([0, 0, minor, major]).ok())
+            })
+            .max()
+            .ok_or_else(|| {
+                Error::UnsupportedVersion(format!("no supported version in {:?}", offered))
+            })
+    }
+
+    /// The version encoded like the proposals of the handshake
+    pub fn to_bytes(self) -> [u8; 4] {
+        [0, 0, self.minor(), self.major()]
+    }
+
     pub fn parse(version_bytes: [u8; 4]) -> Result<Version> {
-        match u32::from_be_bytes(version_bytes) {
-            260 => Ok(Version::V4_1),
-            4 => Ok(Version::V4),
-            v => Err(Error::UnsupportedVersion(format!(
+        match (version_bytes[3], version_bytes[2]) {
+            (3, 0) => Ok(Version::V3),
+            (4, 0) => Ok(Version::V4),
+            (4, 1) => Ok(Version::V4_1),
+            (4, 2) => Ok(Version::V4_2),
+            (4, 3) => Ok(Version::V4_3),
+            (4, 4) => Ok(Version::V4_4),
+            (5, 0) => Ok(Version::V5_0),
+            (5, 1) => Ok(Version::V5_1),
+            (5, 2) => Ok(Version::V5_2),
+            (5, 3) => Ok(Version::V5_3),
+            (5, 4) => Ok(Version::V5_4),
+            (5, 6) => Ok(Version::V5_6),
+            (5, 7) => Ok(Version::V5_7),
+            _ => Err(Error::UnsupportedVersion(format!(
                 "version {} is not supported",
-                v
+                u32::from_be_bytes(version_bytes)
             ))),
         }
     }
+
+    pub fn supports(self, feature: Feature) -> bool {
+        self >= feature.since()
+    }
+
+    pub fn major(self) -> u8 {
+        match self {
+            Version::V3 => 3,
+            Version::V4 | Version::V4_1 | Version::V4_2 | Version::V4_3 | Version::V4_4 => 4,
+            _ => 5,
+        }
+    }
+
+    pub fn minor(self) -> u8 {
+        match self {
+            Version::V3 | Version::V4 | Version::V5_0 => 0,
+            Version::V4_1 | Version::V5_1 => 1,
+            Version::V4_2 | Version::V5_2 => 2,
+            Version::V4_3 | Version::V5_3 => 3,
+            Version::V4_4 | Version::V5_4 => 4,
+            Version::V5_6 => 6,
+            Version::V5_7 => 7,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -39,5 +182,66 @@ mod tests {
     async fn should_parse_version() {
         assert_eq!(Version::parse([0, 0, 1, 4]).unwrap(), Version::V4_1);
         assert_eq!(Version::parse([0, 0, 0, 4]).unwrap(), Version::V4);
+        assert_eq!(Version::parse([0, 0, 4, 4]).unwrap(), Version::V4_4);
+        assert_eq!(Version::parse([0, 0, 0, 5]).unwrap(), Version::V5_0);
+        assert_eq!(Version::parse([0, 0, 4, 5]).unwrap(), Version::V5_4);
+        assert_eq!(Version::parse([0, 0, 7, 5]).unwrap(), Version::V5_7);
+        assert_eq!(Version::parse([0, 0, 0, 3]).unwrap(), Version::V3);
+    }
+
+    #[test]
+    fn should_support_the_features_of_older_versions() {
+        assert!(Version::V4_4.supports(Feature::Impersonation));
+        assert!(Version::V5_4.supports(Feature::Impersonation));
+        assert!(!Version::V4_3.supports(Feature::Impersonation));
+        assert!(!Version::V5_0.supports(Feature::ReAuthentication));
+        assert!(Version::V5_2.supports(Feature::NotificationFiltering));
+        assert!(!Version::V5_1.supports(Feature::NotificationFiltering));
+        assert!(!Version::V3.supports(Feature::MultiDatabase));
+        assert!(Version::V4.supports(Feature::ResultBatches));
+    }
+
+    #[tokio::test]
+    async fn should_reject_unknown_version() {
+        assert!(Version::parse([0, 0, 0, 0]).is_err());
+        assert!(Version::parse([0, 0, 4, 3]).is_err());
+        assert!(Version::parse([0, 0, 5, 3]).is_err());
+        // 5.5 was never released
+        assert!(Version::parse([0, 0, 5, 5]).is_err());
+    }
+
+    #[tokio::test]
+    async fn should_propose_version_ranges() {
+        assert_eq!(
+            Version::supported_versions(),
+            Bytes::from_static(&[0, 0, 1, 0xFF, 0, 7, 7, 5, 0, 2, 4, 4, 0, 0, 1, 4])
+        );
+        assert_eq!(
+            &Version::fallback_versions()[..8],
+            &[0, 0, 0, 4, 0, 0, 0, 3]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_pick_the_newest_version_of_the_manifest() {
+        let offered = [[0, 0, 9, 5], [0, 8, 8, 5], [0, 2, 4, 4]];
+        assert_eq!(Version::from_manifest(&offered).unwrap(), Version::V5_7);
+        let offered = [[0, 5, 5, 5], [0, 2, 4, 4]];
+        assert_eq!(Version::from_manifest(&offered).unwrap(), Version::V5_4);
+        assert_eq!(
+            Version::from_manifest(&[[0, 0, 4, 4], [0, 0, 2, 5]]).unwrap(),
+            Version::V5_2
+        );
+        assert!(Version::from_manifest(&[[0, 0, 8, 5], [0, 0, 0, 2]]).is_err());
+        assert_eq!(Version::V5_2.to_bytes(), [0, 0, 2, 5]);
+    }
+
+    #[tokio::test]
+    async fn should_order_versions() {
+        assert!(Version::V3 < Version::V4);
+        assert!(Version::V4 < Version::V4_1);
+        assert!(Version::V4_4 < Version::V5_0);
+        assert_eq!(Version::V5_3.major(), 5);
+        assert_eq!(Version::V5_3.minor(), 3);
     }
 }
@@ -0,0 +1,39 @@
+//! Runs against a Neo4j Aura instance, whose `neo4j+s://` uri and credentials are read from
+//! `NEO4J_AURA_URI`, `NEO4J_AURA_USER` and `NEO4J_AURA_PASSWORD`:
+//!
+//! ```text
+//! cargo test -p neo4rs --test aura -- --ignored
+//! ```
+use neo4rs::*;
+use std::env;
+
+#[tokio::test]
+#[ignore = "needs a Neo4j Aura instance"]
+async fn should_run_queries_on_aura() {
+    let (uri, user, password) = match (
+        env::var("NEO4J_AURA_URI"),
+        env::var("NEO4J_AURA_USER"),
+        env::var("NEO4J_AURA_PASSWORD"),
+    ) {
+        (Ok(uri), Ok(user), Ok(password)) => (uri, user, password),
+        _ => {
+            eprintln!("NEO4J_AURA_URI, NEO4J_AURA_USER or NEO4J_AURA_PASSWORD is not set");
+            return;
+        }
+    };
+    assert!(uri.starts_with("neo4j+s://"), "{} is not an Aura uri", uri);
+
+    let config = config()
+        .uri(&uri)
+        .user(&user)
+        .password(&password)
+        .build()
+        .unwrap();
+    let graph = Graph::connect(config).await.unwrap();
+    graph.verify_connectivity().await.unwrap();
+
+    let mut rows = graph.execute(query("RETURN 1 AS n")).await.unwrap();
+    let row = rows.next().await.unwrap().unwrap();
+    assert_eq!(row.get::<i64>("n").unwrap(), 1);
+    assert!(rows.next().await.unwrap().is_none());
+}
@@ -0,0 +1,113 @@
+use syn::spanned::Spanned;
+use syn::{Attribute, Data, DeriveInput, Field, Fields, Lit, Meta, NestedMeta, Result};
+
+/// The options of a field given with `#[neo4rs(...)]`
+#[derive(Default)]
+pub(crate) struct FieldAttributes {
+    pub(crate) rename: Option<String>,
+    pub(crate) default: bool,
+    pub(crate) id: bool,
+    pub(crate) element_id: bool,
+    pub(crate) labels: bool,
+    pub(crate) key: bool,
+}
+
+impl FieldAttributes {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<FieldAttributes> {
+        let mut attributes = FieldAttributes::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("neo4rs")) {
+            let nested = match attr.parse_meta()? {
+                Meta::List(list) => list.nested,
+                meta => return Err(syn::Error::new(meta.span(), "expected #[neo4rs(...)]")),
+            };
+            for meta in nested {
+                match meta {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                        match nv.lit {
+                            Lit::Str(name) => attributes.rename = Some(name.value()),
+                            lit => return Err(syn::Error::new(lit.span(), "expected a string")),
+                        }
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("default") => {
+                        attributes.default = true
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("id") => {
+                        attributes.id = true
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("element_id") => {
+                        attributes.element_id = true
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("labels") => {
+                        attributes.labels = true
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("key") => {
+                        attributes.key = true
+                    }
+                    meta => return Err(syn::Error::new(meta.span(), "unknown neo4rs attribute")),
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    /// Whether the field receives the identity of a node instead of a property
+    pub(crate) fn is_identity(&self) -> bool {
+        self.id || self.element_id || self.labels
+    }
+
+    /// The column, property or parameter name of the field
+    pub(crate) fn key(&self, field: &Field) -> String {
+        match &self.rename {
+            Some(name) => name.clone(),
+            None => field.ident.as_ref().unwrap().to_string(),
+        }
+    }
+}
+
+/// The options of a struct given with `#[neo4rs(...)]`
+#[derive(Default)]
+pub(crate) struct ContainerAttributes {
+    pub(crate) label: Option<String>,
+}
+
+impl ContainerAttributes {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<ContainerAttributes> {
+        let mut attributes = ContainerAttributes::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("neo4rs")) {
+            let nested = match attr.parse_meta()? {
+                Meta::List(list) => list.nested,
+                meta => return Err(syn::Error::new(meta.span(), "expected #[neo4rs(...)]")),
+            };
+            for meta in nested {
+                match meta {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("label") => {
+                        match nv.lit {
+                            Lit::Str(label) => attributes.label = Some(label.value()),
+                            lit => return Err(syn::Error::new(lit.span(), "expected a string")),
+                        }
+                    }
+                    meta => return Err(syn::Error::new(meta.span(), "unknown neo4rs attribute")),
+                }
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+/// The named fields of the derived struct
+pub(crate) fn named_fields(ast: &DeriveInput) -> Result<Vec<&Field>> {
+    match &ast.data {
+        Data::Struct(structure) => match &structure.fields {
+            Fields::Named(fields) => Ok(fields.named.iter().collect()),
+            Fields::Unit => Ok(Vec::new()),
+            Fields::Unnamed(_) => Err(syn::Error::new(
+                ast.ident.span(),
+                "unnamed fields are not supported",
+            )),
+        },
+        _ => Err(syn::Error::new(
+            ast.ident.span(),
+            "only structs are supported",
+        )),
+    }
+}
@@ -0,0 +1,65 @@
+use crate::attributes::{named_fields, ContainerAttributes, FieldAttributes};
+use crate::from_row::{self, Source};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Result};
+
+pub(crate) fn expand(ast: DeriveInput) -> Result<TokenStream> {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let label = ContainerAttributes::parse(&ast.attrs)?
+        .label
+        .unwrap_or_else(|| name.to_string());
+    let mut key = None;
+    let mut properties = Vec::new();
+    for field in named_fields(&ast)? {
+        let ident = &field.ident;
+        let attributes = FieldAttributes::parse(&field.attrs)?;
+        // the identity of the node isn't one of its properties, it is only read back
+        if attributes.is_identity() {
+            continue;
+        }
+        let property = attributes.key(field);
+        if attributes.key {
+            if key.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "only one field can be the key",
+                ));
+            }
+            key = Some((property.clone(), ident));
+        }
+        properties.push(quote! {
+            properties.put(
+                #property.into(),
+                ::neo4rs::IntoBoltParam::into_bolt_param(::std::clone::Clone::clone(&self.#ident)),
+            );
+        });
+    }
+    let (key, key_ident) = key.ok_or_else(|| {
+        syn::Error::new(
+            name.span(),
+            "an Entity needs a field marked with #[neo4rs(key)]",
+        )
+    })?;
+    let from_node = from_row::expand(ast.clone(), Source::Node)?;
+
+    Ok(quote! {
+        #from_node
+
+        impl #impl_generics ::neo4rs::ogm::Entity for #name #ty_generics #where_clause {
+            const LABEL: &'static str = #label;
+            const KEY: &'static str = #key;
+
+            fn key(&self) -> ::neo4rs::BoltType {
+                ::neo4rs::IntoBoltParam::into_bolt_param(::std::clone::Clone::clone(&self.#key_ident))
+            }
+
+            fn properties(&self) -> ::neo4rs::BoltMap {
+                let mut properties = ::neo4rs::BoltMap::default();
+                #(#properties)*
+                properties
+            }
+        }
+    })
+}
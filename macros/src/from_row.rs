@@ -0,0 +1,71 @@
+use crate::attributes::{named_fields, FieldAttributes};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Result};
+
+/// What the struct is extracted from, a [`Row`] maps columns and a [`Node`] maps properties
+pub(crate) enum Source {
+    Row,
+    Node,
+}
+
+pub(crate) fn expand(ast: DeriveInput, source: Source) -> Result<TokenStream> {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let fields = named_fields(&ast)?
+        .into_iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let attributes = FieldAttributes::parse(&field.attrs)?;
+            let key = attributes.key(field);
+            let value = match source {
+                Source::Node if attributes.id => quote! { value.id() },
+                Source::Node if attributes.element_id => {
+                    quote! { value.element_id().map(::std::borrow::ToOwned::to_owned) }
+                }
+                Source::Node if attributes.labels => quote! { value.labels() },
+                Source::Row if attributes.key => {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        "key is only available when deriving Entity",
+                    ))
+                }
+                Source::Row if attributes.is_identity() => {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        "id, element_id and labels are only available when deriving FromNode",
+                    ))
+                }
+                _ if attributes.default => quote! { value.get(#key).unwrap_or_default() },
+                _ => quote! {
+                    value.get(#key).ok_or_else(|| {
+                        ::neo4rs::Error::DeserializationError(format!(
+                            "{}: missing or mistyped {}",
+                            stringify!(#name),
+                            #key
+                        ))
+                    })?
+                },
+            };
+            Ok(quote! { #ident: #value })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let expanded = match source {
+        Source::Row => quote! {
+            impl #impl_generics ::neo4rs::FromRow for #name #ty_generics #where_clause {
+                fn from_row(value: &::neo4rs::Row) -> ::neo4rs::Result<Self> {
+                    Ok(#name { #(#fields,)* })
+                }
+            }
+        },
+        Source::Node => quote! {
+            impl #impl_generics ::neo4rs::FromNode for #name #ty_generics #where_clause {
+                fn from_node(value: &::neo4rs::Node) -> ::neo4rs::Result<Self> {
+                    Ok(#name { #(#fields,)* })
+                }
+            }
+        },
+    };
+    Ok(expanded)
+}
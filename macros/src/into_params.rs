@@ -0,0 +1,32 @@
+use crate::attributes::{named_fields, FieldAttributes};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Result};
+
+pub(crate) fn expand(ast: DeriveInput) -> Result<TokenStream> {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let params = named_fields(&ast)?
+        .into_iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let attributes = FieldAttributes::parse(&field.attrs)?;
+            if attributes.default || attributes.key || attributes.is_identity() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "only rename is supported when deriving IntoParams",
+                ));
+            }
+            let key = attributes.key(field);
+            Ok(quote! { .param(#key, self.#ident) })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl #impl_generics ::neo4rs::IntoParams for #name #ty_generics #where_clause {
+            fn into_params(self, query: ::neo4rs::Query) -> ::neo4rs::Query {
+                query #(#params)*
+            }
+        }
+    })
+}
@@ -3,6 +3,73 @@ use quote::quote;
 use syn::parse_macro_input;
 use syn::{DeriveInput, MetaList};
 
+mod attributes;
+mod entity;
+mod from_row;
+mod into_params;
+mod query;
+
+/// Implements `neo4rs::FromRow`, extracting every field from the column of the same name
+#[proc_macro_derive(FromRow, attributes(neo4rs))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    from_row::expand(ast, from_row::Source::Row)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `neo4rs::FromNode`, extracting every field from the property of the same name
+#[proc_macro_derive(FromNode, attributes(neo4rs))]
+pub fn derive_from_node(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    from_row::expand(ast, from_row::Source::Node)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `neo4rs::ogm::Entity` and `neo4rs::FromNode`, saving every field as the property
+/// of the same name on a node labelled after the struct, or `#[neo4rs(label = "...")]`. The
+/// field marked with `#[neo4rs(key)]` identifies the node.
+#[proc_macro_derive(Entity, attributes(neo4rs))]
+pub fn derive_entity(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    entity::expand(ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `neo4rs::IntoParams`, passing every field as the parameter of the same name
+#[proc_macro_derive(IntoParams, attributes(neo4rs))]
+pub fn derive_into_params(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    into_params::expand(ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Builds a `neo4rs::Query` from cypher and the values of its `$parameters`, like
+/// `query!("MATCH (p:Person {name: $name}) RETURN p", name)`. A parameter is either given as
+/// `name = value`, or as `name` for a variable of the same name, parameters left out are
+/// captured from the variables of the same name. Values given for parameters the cypher doesn't
+/// have fail to compile.
+#[proc_macro]
+pub fn query(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as query::QueryInput);
+    query::expand(input, false)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Like `query!`, and when the crate keeps verified queries in a `.neo4rs` directory also
+/// checks that the query was verified against a database, see `neo4rs::offline`
+#[proc_macro]
+pub fn query_checked(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as query::QueryInput);
+    query::expand(input, true)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 #[proc_macro_derive(BoltStruct, attributes(signature))]
 pub fn derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
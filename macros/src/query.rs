@@ -0,0 +1,191 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, Ident, LitStr, Result, Token};
+
+/// The cypher of a query and the values of its parameters, either `name = value` or a variable
+/// `name` of the same name as the parameter. Parameters without a value are captured from the
+/// variables of the same name.
+pub(crate) struct QueryInput {
+    cypher: LitStr,
+    params: Vec<(Ident, Expr)>,
+}
+
+impl Parse for QueryInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let cypher = input.parse()?;
+        let mut params = Vec::new();
+        if input.parse::<Option<Token![,]>>()?.is_some() {
+            for param in Punctuated::<Param, Token![,]>::parse_terminated(input)? {
+                params.push((param.name, param.value));
+            }
+        }
+        Ok(QueryInput { cypher, params })
+    }
+}
+
+struct Param {
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for Param {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = Ident::parse_any(input)?;
+        let value = if input.parse::<Option<Token![=]>>()?.is_some() {
+            input.parse()?
+        } else {
+            syn::parse_quote!(#name)
+        };
+        Ok(Param { name, value })
+    }
+}
+
+pub(crate) fn expand(input: QueryInput, checked: bool) -> Result<TokenStream> {
+    let cypher = input.cypher.value();
+    let placeholders = placeholders(&cypher);
+    let mut given = BTreeSet::new();
+    for (name, _) in &input.params {
+        let key = name.unraw().to_string();
+        if !placeholders.contains(&key) {
+            return Err(syn::Error::new_spanned(
+                name,
+                format!("the query has no parameter ${}", key),
+            ));
+        }
+        if !given.insert(key) {
+            return Err(syn::Error::new_spanned(name, "parameter given twice"));
+        }
+    }
+    let mut params: Vec<_> = input
+        .params
+        .iter()
+        .map(|(name, value)| (name.unraw().to_string(), quote! { #value }))
+        .collect();
+    for missing in placeholders.difference(&given) {
+        if syn::parse_str::<Ident>(missing).is_err() {
+            return Err(syn::Error::new_spanned(
+                &input.cypher,
+                format!(
+                    "the parameter ${} can't be captured, give it a value with `{} = ...`",
+                    missing, missing
+                ),
+            ));
+        }
+        let variable = Ident::new(missing, input.cypher.span());
+        params.push((missing.clone(), quote! { #variable }));
+    }
+
+    let verified = if checked {
+        verify(&input.cypher, &cypher, &placeholders)?
+    } else {
+        quote! {}
+    };
+    let params = params
+        .iter()
+        .map(|(key, value)| quote! { .param(#key, #value) });
+    let literal = &input.cypher;
+    Ok(quote! {
+        {
+            #verified
+            ::neo4rs::query(#literal) #(#params)*
+        }
+    })
+}
+
+/// Checks that the query was verified against a database, when the project keeps the verified
+/// queries in a `.neo4rs` directory. The query is recorded for `neo4rs::offline::prepare`
+/// instead when building with `NEO4RS_PREPARE` set.
+fn verify(literal: &LitStr, cypher: &str, placeholders: &BTreeSet<String>) -> Result<TokenStream> {
+    let dir = match std::env::var_os("NEO4RS_OFFLINE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => match std::env::var_os("CARGO_MANIFEST_DIR") {
+            Some(manifest) => PathBuf::from(manifest).join(".neo4rs"),
+            None => return Ok(quote! {}),
+        },
+    };
+    let file_name = format!("query-{:016x}.cypher", checksum(cypher));
+    if std::env::var_os("NEO4RS_PREPARE").is_some() {
+        let pending = dir.join("pending");
+        let names: Vec<_> = placeholders.iter().map(String::as_str).collect();
+        let contents = format!("// params: {}\n{}", names.join(", "), cypher.trim());
+        std::fs::create_dir_all(&pending)
+            .and_then(|_| std::fs::write(pending.join(&file_name), contents))
+            .map_err(|e| {
+                syn::Error::new_spanned(literal, format!("can't record the query: {}", e))
+            })?;
+        return Ok(quote! {});
+    }
+    if !dir.is_dir() {
+        return Ok(quote! {});
+    }
+    let verified = dir.join(&file_name);
+    if !verified.is_file() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            format!(
+                "the query was not verified against a database, build with NEO4RS_PREPARE=1 and \
+                 run neo4rs::offline::prepare to verify it ({} is missing)",
+                verified.display()
+            ),
+        ));
+    }
+    // makes cargo rebuild the crate when the verified query is removed or changed
+    let path = verified.to_string_lossy().into_owned();
+    Ok(quote! { const _: &str = include_str!(#path); })
+}
+
+/// The names of the `$parameters` of a query, outside of strings and comments
+fn placeholders(cypher: &str) -> BTreeSet<String> {
+    let chars: Vec<char> = cypher.chars().collect();
+    let mut names = BTreeSet::new();
+    let mut quote = None;
+    let mut i = 0;
+    while i < chars.len() {
+        match (quote, chars[i]) {
+            (Some(_), '\\') => i += 1,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, c @ ('\'' | '"' | '`')) => quote = Some(c),
+            (None, '/') if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            (None, '/') if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                    i += 1;
+                }
+                i += 1;
+            }
+            (None, '$') => {
+                let name: String = chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_')
+                    .collect();
+                i += name.chars().count();
+                if !name.is_empty() {
+                    names.insert(name);
+                }
+            }
+            (None, _) => {}
+        }
+        i += 1;
+    }
+    names
+}
+
+/// FNV-1a of the trimmed cypher, the same hash `neo4rs::offline::prepare` names the files with
+fn checksum(cypher: &str) -> u64 {
+    cypher
+        .trim()
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}